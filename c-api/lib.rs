@@ -448,9 +448,13 @@ pub extern "C" fn ttfp_get_units_per_em(face: *const ttfp_face) -> u16 {
 
 /// @brief Returns face's x height.
 ///
+/// When OS/2 table is not present or when its version is < 2,
+/// the top of the `x` glyph bounding box is used instead.
+/// In this case the glyph is outlined on each call, so the result should be cached.
+///
 /// This function is affected by variation axes.
 ///
-/// @return x height or 0 when OS/2 table is not present or when its version is < 2.
+/// @return x height or 0 when it is not set and the `x` glyph has no outline.
 #[no_mangle]
 pub extern "C" fn ttfp_get_x_height(face: *const ttfp_face) -> i16 {
     face_from_ptr(face).x_height().unwrap_or(0)
@@ -458,9 +462,13 @@ pub extern "C" fn ttfp_get_x_height(face: *const ttfp_face) -> i16 {
 
 /// @brief Returns face's capital height.
 ///
+/// When OS/2 table is not present or when its version is < 2,
+/// the top of the `H` glyph bounding box is used instead.
+/// In this case the glyph is outlined on each call, so the result should be cached.
+///
 /// This function is affected by variation axes.
 ///
-/// @return capital height or 0 when OS/2 table is not present or when its version is < 2.
+/// @return capital height or 0 when it is not set and the `H` glyph has no outline.
 #[no_mangle]
 pub extern "C" fn ttfp_get_capital_height(face: *const ttfp_face) -> i16 {
     face_from_ptr(face).capital_height().unwrap_or(0)
@@ -552,7 +560,7 @@ pub extern "C" fn ttfp_get_number_of_glyphs(face: *const ttfp_face) -> u16 {
 
 /// @brief Resolves a Glyph ID for a code point.
 ///
-/// All subtable formats except Mixed Coverage (8) are supported.
+/// All subtable formats are supported.
 ///
 /// @param codepoint A valid Unicode codepoint. Otherwise 0 will be returned.
 /// @return Returns 0 when glyph is not present or parsing is failed.
@@ -0,0 +1,230 @@
+//! Table directory parsing for partial font data.
+
+use core::ops::Range;
+
+use crate::{GlyphId, Magic, Tag, TableRecord};
+use crate::parser::{Stream, FromData, LazyArray16, NumFrom, Offset32, Offset};
+use crate::tables::{head, loca, maxp};
+
+// Magic + numTables + searchRange + entrySelector + rangeShift.
+// A collection header has the same size: magic + version + numFonts.
+const HEADER_SIZE: usize = 12;
+
+
+/// A face's table directory.
+///
+/// Unlike [`Face`](struct.Face.html), can be parsed from a prefix of the font data
+/// and doesn't parse any tables. Which is useful for loaders that fetch
+/// only the required parts of a font, like ones that use HTTP range requests.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{GlyphId, TableDirectory, Tag};
+///
+/// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+///
+/// // Fetch a prefix that is long enough to contain the directory.
+/// let mut len = 0;
+/// while let Some(required_len) = TableDirectory::required_len(&data[..len], 0) {
+///     if required_len <= len {
+///         break;
+///     }
+///
+///     len = required_len;
+/// }
+///
+/// let dir = TableDirectory::parse(&data[..len], 0).unwrap();
+/// let head = &data[dir.table_range(Tag::from_bytes(b"head")).unwrap()];
+/// let maxp = &data[dir.table_range(Tag::from_bytes(b"maxp")).unwrap()];
+/// let loca = &data[dir.table_range(Tag::from_bytes(b"loca")).unwrap()];
+/// assert!(dir.glyph_range(head, maxp, loca, GlyphId(1)).is_some());
+/// ```
+#[derive(Clone, Copy)]
+pub struct TableDirectory<'a> {
+    records: LazyArray16<'a, TableRecord>,
+}
+
+impl<'a> TableDirectory<'a> {
+    /// Returns the font data length required to parse a table directory.
+    ///
+    /// `data` is a prefix of the font data, which can be empty.
+    /// When the returned length is larger than the prefix, it should be extended
+    /// to at least this length and the method called again,
+    /// since the directory location may depend on the not yet fetched data.
+    ///
+    /// Returns `None` when the prefix is malformed or the face index is out of bounds.
+    pub fn required_len(data: &[u8], index: u32) -> Option<usize> {
+        if data.len() < HEADER_SIZE {
+            return Some(HEADER_SIZE);
+        }
+
+        let face_offset = match face_offset(data, index) {
+            FaceOffset::Offset(offset) => offset,
+            FaceOffset::Incomplete(len) => return Some(len),
+            FaceOffset::Malformed => return None,
+        };
+
+        let header_end = face_offset.checked_add(HEADER_SIZE)?;
+        if data.len() < header_end {
+            return Some(header_end);
+        }
+
+        let num_tables: u16 = Stream::read_at(data, face_offset + 4)?;
+        header_end.checked_add(usize::from(num_tables) * TableRecord::SIZE)
+    }
+
+    /// Parses a table directory from a prefix of the font data.
+    ///
+    /// Returns `None` when the prefix is shorter than
+    /// [`required_len`](#method.required_len) or malformed.
+    pub fn parse(data: &'a [u8], index: u32) -> Option<Self> {
+        let face_offset = match face_offset(data, index) {
+            FaceOffset::Offset(offset) => offset,
+            _ => return None,
+        };
+
+        let mut s = Stream::new_at(data, face_offset)?;
+        // A face in a font collection can't be another collection.
+        if s.read::<Magic>()? == Magic::FontCollection {
+            return None;
+        }
+
+        let num_tables: u16 = s.read()?;
+        s.advance(6); // searchRange (u16) + entrySelector (u16) + rangeShift (u16)
+        let records = s.read_array16::<TableRecord>(num_tables)?;
+        Some(TableDirectory { records })
+    }
+
+    /// Returns the number of tables.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.records.len()
+    }
+
+    /// Checks that the directory has no tables.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns a table tag by index.
+    #[inline]
+    pub fn table_tag(&self, index: u16) -> Option<Tag> {
+        self.records.get(index).map(|record| record.table_tag)
+    }
+
+    /// Returns a table's byte range in the font data.
+    ///
+    /// The range is not checked against the font data, which may not be fetched yet.
+    ///
+    /// Returns `None` when the table is not present.
+    pub fn table_range(&self, tag: Tag) -> Option<Range<usize>> {
+        let record = self.records.into_iter().find(|record| record.table_tag == tag)?;
+        let start = usize::num_from(record.offset);
+        let end = start.checked_add(usize::num_from(record.length))?;
+        Some(start..end)
+    }
+
+    /// Returns a glyph's byte range in the font data.
+    ///
+    /// Requires the `head`, `maxp` and `loca` tables data.
+    /// Composite glyphs reference other glyphs, which must be resolved separately.
+    ///
+    /// Returns `None` when the `glyf` table is not present,
+    /// any of the provided tables is malformed or when the glyph has no outline.
+    pub fn glyph_range(
+        &self,
+        head: &[u8],
+        maxp: &[u8],
+        loca: &[u8],
+        glyph_id: GlyphId,
+    ) -> Option<Range<usize>> {
+        let glyf = self.table_range(Tag::from_bytes(b"glyf"))?;
+        let head = head::parse(head)?;
+        let number_of_glyphs = maxp::parse(maxp)?;
+        let format = head::index_to_loc_format(head)?;
+        let range = loca::Table::parse(loca, number_of_glyphs, format)?.glyph_range(glyph_id)?;
+        let start = glyf.start.checked_add(range.start)?;
+        let end = glyf.start.checked_add(range.end)?;
+        if end <= glyf.end {
+            Some(start..end)
+        } else {
+            None
+        }
+    }
+}
+
+impl core::fmt::Debug for TableDirectory<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "TableDirectory()")
+    }
+}
+
+
+enum FaceOffset {
+    Offset(usize),
+    Incomplete(usize),
+    Malformed,
+}
+
+// Returns an offset to the face's table directory.
+fn face_offset(data: &[u8], index: u32) -> FaceOffset {
+    match Stream::read_at::<Magic>(data, 0) {
+        Some(Magic::FontCollection) => {}
+        Some(_) => return FaceOffset::Offset(0),
+        None => return FaceOffset::Malformed,
+    }
+
+    let number_of_faces: u32 = match Stream::read_at(data, 8) {
+        Some(n) => n,
+        None => return FaceOffset::Incomplete(HEADER_SIZE),
+    };
+
+    if index >= number_of_faces {
+        return FaceOffset::Malformed;
+    }
+
+    let offset_pos = HEADER_SIZE + usize::num_from(index) * Offset32::SIZE;
+    match Stream::read_at::<Offset32>(data, offset_pos) {
+        Some(offset) => FaceOffset::Offset(offset.to_usize()),
+        None => FaceOffset::Incomplete(offset_pos + Offset32::SIZE),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        assert_eq!(TableDirectory::required_len(&[], 0), Some(12));
+        assert_eq!(TableDirectory::required_len(&data[..12], 0), Some(12 + 7 * 16));
+        assert!(TableDirectory::parse(&data[..12], 0).is_none());
+
+        let dir = TableDirectory::parse(&data[..124], 0).unwrap();
+        assert_eq!(dir.len(), 7);
+        assert_eq!(dir.table_tag(0), Some(Tag::from_bytes(b"cmap")));
+        assert_eq!(dir.table_range(Tag::from_bytes(b"head")), Some(124..178));
+        assert_eq!(dir.table_range(Tag::from_bytes(b"name")), None);
+    }
+
+    #[test]
+    fn collection() {
+        let data = &[
+            0x74, 0x74, 0x63, 0x66, // magic
+            0x00, 0x00, // majorVersion: 0
+            0x00, 0x00, // minorVersion: 0
+            0x00, 0x00, 0x00, 0x02, // numFonts: 2
+            0x00, 0x00, 0x00, 0x14, // offset [0]: 20
+            0x00, 0x00, 0x00, 0x20, // offset [1]: 32
+        ];
+
+        assert_eq!(TableDirectory::required_len(&data[..12], 1), Some(20));
+        assert_eq!(TableDirectory::required_len(data, 0), Some(32));
+        assert_eq!(TableDirectory::required_len(data, 1), Some(44));
+        assert_eq!(TableDirectory::required_len(data, 2), None);
+    }
+}
@@ -0,0 +1,487 @@
+/*!
+Generators of structured random font data.
+
+Types in this module implement [`Arbitrary`](https://docs.rs/arbitrary) and can be
+encoded into well-formed table data, which is more useful for property testing
+than purely random bytes, since most of them would be rejected early by the parser.
+
+Available only with the `arbitrary` feature.
+*/
+
+use core::convert::TryFrom;
+use std::vec::Vec;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{GlyphId, NormalizedCoordinate, Tag};
+
+
+impl<'a> Arbitrary<'a> for GlyphId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(GlyphId(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Tag {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Tag(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for NormalizedCoordinate {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(NormalizedCoordinate::from(u.int_in_range(-16384..=16384)?))
+    }
+}
+
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u16(&mut self, n: u16) {
+        self.0.extend_from_slice(&n.to_be_bytes());
+    }
+
+    fn i16(&mut self, n: i16) {
+        self.0.extend_from_slice(&n.to_be_bytes());
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.0.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+
+/// A `cmap` format 4 segment.
+///
+/// Glyph IDs are always calculated using `idDelta`, so `idRangeOffset` is always zero.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CmapSegment {
+    /// First code point.
+    pub start_code: u16,
+    /// Last code point.
+    pub end_code: u16,
+    /// A delta for all code points in the segment.
+    pub id_delta: i16,
+}
+
+impl<'a> Arbitrary<'a> for CmapSegment {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let start_code: u16 = u.arbitrary()?;
+        let len: u8 = u.arbitrary()?;
+        Ok(CmapSegment {
+            start_code,
+            end_code: start_code.saturating_add(u16::from(len)),
+            id_delta: u.arbitrary()?,
+        })
+    }
+}
+
+
+/// A `cmap` format 4 subtable.
+///
+/// Segments are sorted, do not overlap and the last one is always the `0xFFFF` one.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CmapFormat4Subtable {
+    /// Subtable segments.
+    pub segments: Vec<CmapSegment>,
+}
+
+impl CmapFormat4Subtable {
+    /// Returns a glyph ID for a code point according to segments.
+    pub fn glyph_index(&self, code_point: u16) -> Option<GlyphId> {
+        let segment = self.segments.iter()
+            .find(|s| s.start_code <= code_point && code_point <= s.end_code)?;
+        let id = code_point.wrapping_add(segment.id_delta as u16);
+        if id != 0 { Some(GlyphId(id)) } else { None }
+    }
+
+    /// Encodes the subtable.
+    ///
+    /// Returns `None` when there are too many segments to fit into the `u16` length.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let seg_count = u16::try_from(self.segments.len()).ok()?;
+        let length = seg_count.checked_mul(8)?.checked_add(16)?;
+        let mut w = Writer::default();
+        w.u16(4); // format
+        w.u16(length);
+        w.u16(0); // language
+        w.u16(seg_count * 2); // can't overflow, since `length` didn't
+        // searchRange, entrySelector and rangeShift are ignored by the parser.
+        w.u16(0);
+        w.u16(0);
+        w.u16(0);
+        for segment in &self.segments {
+            w.u16(segment.end_code);
+        }
+        w.u16(0); // reservedPad
+        for segment in &self.segments {
+            w.u16(segment.start_code);
+        }
+        for segment in &self.segments {
+            w.i16(segment.id_delta);
+        }
+        for _ in &self.segments {
+            w.u16(0); // idRangeOffset
+        }
+        Some(w.0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for CmapFormat4Subtable {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut segments: Vec<CmapSegment> = u.arbitrary()?;
+        segments.sort_by_key(|s| s.start_code);
+
+        // Remove overlapping segments and the ones reserved for the last segment.
+        let mut next_code = 0;
+        segments.retain(|s| {
+            let keep = s.start_code >= next_code && s.end_code < 0xFFFF;
+            if keep {
+                next_code = s.end_code + 1;
+            }
+
+            keep
+        });
+
+        // Fits into the u16 length.
+        segments.truncate(8000);
+
+        segments.push(CmapSegment { start_code: 0xFFFF, end_code: 0xFFFF, id_delta: 1 });
+        Ok(CmapFormat4Subtable { segments })
+    }
+}
+
+
+/// A `hmtx` long horizontal metrics record.
+#[derive(Clone, Copy, PartialEq, Arbitrary, Debug)]
+pub struct HorizontalMetrics {
+    /// Advance width.
+    pub advance: u16,
+    /// Left side bearing.
+    pub lsb: i16,
+}
+
+
+/// A `hmtx` table.
+#[derive(Clone, PartialEq, Debug)]
+pub struct HmtxTable {
+    /// Long horizontal metrics. Always has at least one record.
+    pub metrics: Vec<HorizontalMetrics>,
+    /// Left side bearings of the remaining glyphs.
+    pub bearings: Vec<i16>,
+}
+
+impl HmtxTable {
+    /// Returns a `numberOfHMetrics` value for `hhea`.
+    ///
+    /// Returns `None` when there are too many records to fit into `u16`.
+    pub fn number_of_h_metrics(&self) -> Option<u16> {
+        u16::try_from(self.metrics.len()).ok()
+    }
+
+    /// Returns a `numGlyphs` value for `maxp`.
+    ///
+    /// Returns `None` when there are too many records to fit into `u16`.
+    pub fn number_of_glyphs(&self) -> Option<u16> {
+        u16::try_from(self.metrics.len().checked_add(self.bearings.len())?).ok()
+    }
+
+    /// Encodes the table.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        for metrics in &self.metrics {
+            w.u16(metrics.advance);
+            w.i16(metrics.lsb);
+        }
+        for bearing in &self.bearings {
+            w.i16(*bearing);
+        }
+        w.0
+    }
+}
+
+impl<'a> Arbitrary<'a> for HmtxTable {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut metrics: Vec<HorizontalMetrics> = u.arbitrary()?;
+        if metrics.is_empty() {
+            metrics.push(u.arbitrary()?);
+        }
+        metrics.truncate(0x8000);
+
+        let mut bearings: Vec<i16> = u.arbitrary()?;
+        bearings.truncate(0x7FFF);
+
+        Ok(HmtxTable { metrics, bearings })
+    }
+}
+
+
+/// A `name` record.
+#[derive(Clone, PartialEq, Arbitrary, Debug)]
+pub struct NameRecord {
+    /// Platform ID.
+    pub platform_id: u16,
+    /// Platform-specific encoding ID.
+    pub encoding_id: u16,
+    /// Language ID.
+    pub language_id: u16,
+    /// Name ID.
+    pub name_id: u16,
+    /// Raw string data.
+    pub name: Vec<u8>,
+}
+
+
+/// A `name` table of format 0.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NameTable {
+    /// Name records.
+    pub records: Vec<NameRecord>,
+}
+
+impl NameTable {
+    /// Encodes the table.
+    ///
+    /// Returns `None` when records or strings do not fit into `u16` offsets.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let count = u16::try_from(self.records.len()).ok()?;
+        let mut w = Writer::default();
+        w.u16(0); // format
+        w.u16(count);
+        w.u16(count.checked_mul(12)?.checked_add(6)?); // storage offset
+
+        let mut offset: u16 = 0;
+        for record in &self.records {
+            let len = u16::try_from(record.name.len()).ok()?;
+            w.u16(record.platform_id);
+            w.u16(record.encoding_id);
+            w.u16(record.language_id);
+            w.u16(record.name_id);
+            w.u16(len);
+            w.u16(offset);
+            offset = offset.checked_add(len)?;
+        }
+
+        for record in &self.records {
+            w.0.extend_from_slice(&record.name);
+        }
+
+        Some(w.0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for NameTable {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut records: Vec<NameRecord> = u.arbitrary()?;
+        // Fits into u16 offsets.
+        records.truncate(256);
+        for record in &mut records {
+            record.name.truncate(255);
+        }
+
+        Ok(NameTable { records })
+    }
+}
+
+
+/// A table in a table directory.
+#[derive(Clone, PartialEq, Arbitrary, Debug)]
+pub struct TableRecord {
+    /// Table tag.
+    pub tag: Tag,
+    /// Table data.
+    pub data: Vec<u8>,
+}
+
+
+/// A font with a table directory.
+///
+/// Tags are unique and sorted. Tables are 4-byte aligned.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FontData {
+    /// Font tables.
+    pub tables: Vec<TableRecord>,
+}
+
+impl FontData {
+    /// Encodes the font.
+    ///
+    /// Returns `None` when tables do not fit into `u16` count or `u32` offsets.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let num_tables = u16::try_from(self.tables.len()).ok()?;
+        let mut w = Writer::default();
+        w.u32(0x00010000); // magic
+        w.u16(num_tables);
+        // searchRange, entrySelector and rangeShift are ignored by the parser.
+        w.u16(0);
+        w.u16(0);
+        w.u16(0);
+
+        let mut offset = 12 + u32::from(num_tables) * 16;
+        for table in &self.tables {
+            w.u32(table.tag.0);
+            w.u32(0); // checkSum
+            let len = u32::try_from(table.data.len()).ok()?;
+            w.u32(offset);
+            w.u32(len);
+            offset = offset.checked_add(len.checked_add(3)? & !3)?;
+        }
+
+        for table in &self.tables {
+            w.0.extend_from_slice(&table.data);
+            while w.0.len() % 4 != 0 {
+                w.0.push(0);
+            }
+        }
+
+        Some(w.0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for FontData {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut tables: Vec<TableRecord> = u.arbitrary()?;
+        tables.sort_by_key(|t| t.tag);
+        tables.dedup_by_key(|t| t.tag);
+        Ok(FontData { tables })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroU16;
+
+    use super::*;
+    use crate::tables::{cmap, hmtx, name};
+    use crate::{Face, TableDirectory};
+
+    // Runs `f` with a set of pseudo-random inputs.
+    fn check(mut f: impl FnMut(&mut Unstructured) -> Result<()>) {
+        let mut state = 0x2545F491u32;
+        for _ in 0..200 {
+            let data: Vec<u8> = (0..4096).map(|_| {
+                // xorshift32
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            }).collect();
+
+            f(&mut Unstructured::new(&data)).unwrap();
+        }
+    }
+
+    #[test]
+    fn cmap_format4() {
+        check(|u| {
+            let subtable: CmapFormat4Subtable = u.arbitrary()?;
+            let mut data = vec![
+                0x00, 0x00, // version: 0
+                0x00, 0x01, // number of tables: 1
+                0x00, 0x03, // platform ID: Windows
+                0x00, 0x01, // encoding ID: Unicode BMP
+                0x00, 0x00, 0x00, 0x0C, // offset: 12
+            ];
+            data.extend_from_slice(&subtable.to_bytes().unwrap());
+
+            let parsed = cmap::parse(&data).unwrap().next().unwrap();
+            for segment in &subtable.segments {
+                for c in &[segment.start_code, segment.end_code] {
+                    assert_eq!(parsed.glyph_index(u32::from(*c)), subtable.glyph_index(*c));
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn hmtx() {
+        check(|u| {
+            let table: HmtxTable = u.arbitrary()?;
+            let data = table.to_bytes();
+            let parsed = hmtx::Table::parse(
+                &data,
+                NonZeroU16::new(table.number_of_h_metrics().unwrap()).unwrap(),
+                NonZeroU16::new(table.number_of_glyphs().unwrap()).unwrap(),
+            ).unwrap();
+
+            for (i, metrics) in table.metrics.iter().enumerate() {
+                assert_eq!(parsed.advance(GlyphId(i as u16)), Some(metrics.advance));
+                assert_eq!(parsed.side_bearing(GlyphId(i as u16)), Some(metrics.lsb));
+            }
+
+            for (i, bearing) in table.bearings.iter().enumerate() {
+                let id = GlyphId((table.metrics.len() + i) as u16);
+                assert_eq!(parsed.side_bearing(id), Some(*bearing));
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn names() {
+        check(|u| {
+            let table: NameTable = u.arbitrary()?;
+            let data = table.to_bytes().unwrap();
+            let names = name::parse(&data).unwrap();
+            assert_eq!(names.count(), table.records.len());
+            for (parsed, record) in names.zip(&table.records) {
+                assert_eq!(parsed.name_id(), record.name_id);
+                assert_eq!(parsed.name(), &record.name[..]);
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn table_directory() {
+        check(|u| {
+            let font: FontData = u.arbitrary()?;
+            let data = font.to_bytes().unwrap();
+            let dir = TableDirectory::parse(&data, 0).unwrap();
+            assert_eq!(usize::from(dir.len()), font.tables.len());
+            for table in &font.tables {
+                let range = dir.table_range(table.tag).unwrap();
+                assert_eq!(&data[range], &table.data[..]);
+            }
+
+            // Must not panic.
+            let _ = Face::from_slice(&data, 0);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn too_large() {
+        let segment = CmapSegment { start_code: 0, end_code: 0, id_delta: 0 };
+        let subtable = CmapFormat4Subtable { segments: vec![segment; 8189] };
+        assert!(subtable.to_bytes().is_some());
+        let subtable = CmapFormat4Subtable { segments: vec![segment; 8190] };
+        assert!(subtable.to_bytes().is_none());
+
+        let record = NameRecord {
+            platform_id: 0,
+            encoding_id: 0,
+            language_id: 0,
+            name_id: 0,
+            name: vec![0; 0x7FFF],
+        };
+        let table = NameTable { records: vec![record.clone(); 2] };
+        assert!(table.to_bytes().is_some());
+        let table = NameTable { records: vec![record; 3] };
+        assert!(table.to_bytes().is_none());
+
+        let table = HmtxTable {
+            metrics: vec![HorizontalMetrics { advance: 0, lsb: 0 }; 0x8000],
+            bearings: vec![0; 0x8000],
+        };
+        assert_eq!(table.number_of_h_metrics(), Some(0x8000));
+        assert_eq!(table.number_of_glyphs(), None);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::parser::Stream;
+
+
+/// A [Device Table](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#device-and-variationindex-tables).
+///
+/// Either a hinting device, which stores per-ppem pixel adjustments,
+/// or a variation index, which references deltas in the GDEF item variation store.
+#[derive(Clone, Copy)]
+pub struct Device<'a> {
+    data: &'a [u8],
+    first: u16,
+    second: u16,
+    format: u16,
+}
+
+impl<'a> Device<'a> {
+    /// Parses a device table.
+    ///
+    /// Returns `None` for unknown formats.
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let first: u16 = s.read()?;
+        let second: u16 = s.read()?;
+        let format: u16 = s.read()?;
+        match format {
+            1..=3 | 0x8000 => {}
+            _ => return None,
+        }
+
+        Some(Device { data, first, second, format })
+    }
+
+    /// Returns raw device table data.
+    ///
+    /// Since device tables do not store their length, the data continues
+    /// until the end of the parent table.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Checks that this is a hinting device.
+    #[inline]
+    pub fn is_hinting(&self) -> bool {
+        self.format != 0x8000
+    }
+
+    /// Returns a range of sizes, in ppem, covered by a hinting device.
+    #[inline]
+    pub fn sizes(&self) -> Option<core::ops::RangeInclusive<u16>> {
+        if self.is_hinting() {
+            Some(self.first..=self.second)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an adjustment in pixels for the specified size.
+    ///
+    /// Returns `None` for variation devices and sizes outside of the covered range.
+    pub fn pixel_delta(&self, ppem: u16) -> Option<i8> {
+        if !self.sizes()?.contains(&ppem) {
+            return None;
+        }
+
+        let bits = 1u16 << self.format; // 2, 4 or 8
+        let per_word = 16 / bits;
+        let index = ppem - self.first;
+        let word: u16 = Stream::read_at(self.data, 6 + usize::from(index / per_word) * 2)?;
+        let shift = 16 - bits * (index % per_word + 1);
+        let mask = (1u16 << bits) - 1;
+        let value = (word >> shift) & mask;
+        // Sign-extend.
+        let value = if value >= (1 << (bits - 1)) {
+            i16::from(value as u8) - (1 << bits)
+        } else {
+            i16::from(value as u8)
+        };
+
+        Some(value as i8)
+    }
+
+    /// Returns an adjustment in font units for the specified size.
+    ///
+    /// Returns `None` for variation devices and sizes outside of the covered range.
+    pub fn delta(&self, ppem: u16, units_per_em: u16) -> Option<f32> {
+        let pixels = self.pixel_delta(ppem)?;
+        Some(f32::from(pixels) * f32::from(units_per_em) / f32::from(ppem))
+    }
+
+    /// Returns outer and inner indices into the GDEF item variation store.
+    ///
+    /// Returns `None` for hinting devices.
+    ///
+    /// Use [`Face::glyph_variation_delta`](struct.Face.html#method.glyph_variation_delta)
+    /// to resolve them.
+    #[inline]
+    pub fn variation_index(&self) -> Option<(u16, u16)> {
+        if self.is_hinting() {
+            None
+        } else {
+            Some((self.first, self.second))
+        }
+    }
+}
+
+impl core::fmt::Debug for Device<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Device")
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hinting() {
+        let data = &[
+            0x00, 0x0B, // start size: 11
+            0x00, 0x0F, // end size: 15
+            0x00, 0x01, // delta format: 2-bit
+            0b01_11_00_10, 0b01_00_00_00, // deltas: 1, -1, 0, -2, 1
+        ];
+
+        let device = Device::parse(data).unwrap();
+        assert!(device.is_hinting());
+        assert_eq!(device.sizes(), Some(11..=15));
+        assert_eq!(device.pixel_delta(10), None);
+        assert_eq!(device.pixel_delta(11), Some(1));
+        assert_eq!(device.pixel_delta(12), Some(-1));
+        assert_eq!(device.pixel_delta(13), Some(0));
+        assert_eq!(device.pixel_delta(14), Some(-2));
+        assert_eq!(device.pixel_delta(15), Some(1));
+        assert_eq!(device.pixel_delta(16), None);
+        assert_eq!(device.delta(12, 1200), Some(-100.0));
+        assert_eq!(device.variation_index(), None);
+    }
+
+    #[test]
+    fn hinting_8bit() {
+        let data = &[
+            0x00, 0x0C, // start size: 12
+            0x00, 0x0C, // end size: 12
+            0x00, 0x03, // delta format: 8-bit
+            0xFD, 0x00, // deltas: -3
+        ];
+
+        let device = Device::parse(data).unwrap();
+        assert_eq!(device.pixel_delta(12), Some(-3));
+    }
+
+    #[test]
+    fn variation_index() {
+        let data = &[
+            0x00, 0x01, // outer index: 1
+            0x00, 0x02, // inner index: 2
+            0x80, 0x00, // format: variation index
+        ];
+
+        let device = Device::parse(data).unwrap();
+        assert!(!device.is_hinting());
+        assert_eq!(device.variation_index(), Some((1, 2)));
+        assert_eq!(device.pixel_delta(1), None);
+    }
+
+    #[test]
+    fn unknown_format() {
+        assert!(Device::parse(&[0x00, 0x01, 0x00, 0x02, 0x00, 0x04]).is_none());
+    }
+}
@@ -0,0 +1,1465 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2
+
+use core::fmt;
+
+use crate::{GlyphId, Tag};
+use crate::ggg::CoverageTable;
+use crate::parser::{Stream, FromData, Offset, Offset16, Offset32, LazyArray16, U24};
+
+
+#[derive(Clone, Copy)]
+struct TagRecord {
+    tag: Tag,
+    offset: Offset16,
+}
+
+impl FromData for TagRecord {
+    const SIZE: usize = 6;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(TagRecord {
+            tag: s.read::<Tag>()?,
+            offset: s.read::<Offset16>()?,
+        })
+    }
+}
+
+
+// A list of records with tags and offsets to subtables.
+#[derive(Clone, Copy, Default)]
+struct RecordList<'a> {
+    data: &'a [u8],
+    records: LazyArray16<'a, TagRecord>,
+}
+
+impl<'a> RecordList<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let count: u16 = s.read()?;
+        let records = s.read_array16::<TagRecord>(count)?;
+        Some(RecordList { data, records })
+    }
+
+    #[inline]
+    fn len(&self) -> u16 {
+        self.records.len()
+    }
+
+    // An invalid offset results in an empty subtable, so the iteration is not interrupted.
+    #[inline]
+    fn get(&self, index: u16) -> Option<(Tag, &'a [u8])> {
+        let record = self.records.get(index)?;
+        Some((record.tag, self.data.get(record.offset.to_usize()..).unwrap_or_default()))
+    }
+
+    #[inline]
+    fn find(&self, tag: Tag) -> Option<(Tag, &'a [u8])> {
+        let index = self.records.into_iter().position(|r| r.tag == tag)?;
+        self.get(index as u16)
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum TableKind {
+    Substitution,
+    Positioning,
+}
+
+
+/// A [GSUB](https://docs.microsoft.com/en-us/typography/opentype/spec/gsub) or
+/// [GPOS](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos) table.
+///
+/// Both tables share the same layout of script, feature and lookup lists.
+#[derive(Clone, Copy)]
+pub struct LayoutTable<'a> {
+    scripts: RecordList<'a>,
+    features: RecordList<'a>,
+    lookups: LookupList<'a>,
+}
+
+impl<'a> LayoutTable<'a> {
+    pub(crate) fn parse(data: &'a [u8], kind: TableKind) -> Option<Self> {
+        let mut s = Stream::new(data);
+
+        let major_version: u16 = s.read()?;
+        let minor_version: u16 = s.read()?;
+        if !(major_version == 1 && (minor_version == 0 || minor_version == 1)) {
+            return None;
+        }
+
+        let script_list_offset: Offset16 = s.read()?;
+        let feature_list_offset: Option<Offset16> = s.read()?;
+        let lookup_list_offset: Option<Offset16> = s.read()?;
+
+        let features = match feature_list_offset {
+            Some(offset) => RecordList::parse(data.get(offset.to_usize()..)?)?,
+            None => RecordList::default(),
+        };
+
+        let lookups = match lookup_list_offset {
+            Some(offset) => LookupList::parse(data.get(offset.to_usize()..)?, kind)?,
+            None => LookupList::default(),
+        };
+
+        Some(LayoutTable {
+            scripts: RecordList::parse(data.get(script_list_offset.to_usize()..)?)?,
+            features,
+            lookups,
+        })
+    }
+
+    /// Returns an iterator over [scripts](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#script-list-table-and-script-record).
+    #[inline]
+    pub fn scripts(&self) -> Scripts<'a> {
+        Scripts { list: self.scripts, index: 0 }
+    }
+
+    /// Returns a script by tag.
+    #[inline]
+    pub fn script(&self, tag: Tag) -> Option<Script<'a>> {
+        self.scripts.find(tag).map(|(tag, data)| Script { tag, data })
+    }
+
+    /// Returns an iterator over [features](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#feature-list-table).
+    ///
+    /// Multiple features can have the same tag. Usually, for different language systems.
+    #[inline]
+    pub fn features(&self) -> Features<'a> {
+        Features { list: self.features, index: 0 }
+    }
+
+    /// Returns a feature at index.
+    ///
+    /// Language systems reference features by index.
+    #[inline]
+    pub fn feature_at(&self, index: u16) -> Option<Feature<'a>> {
+        self.features.get(index).map(|(tag, data)| Feature { tag, data })
+    }
+
+    /// Returns an iterator over unique feature tags.
+    ///
+    /// Unlike [`features`](#method.features), each tag is yielded only once,
+    /// in the order of its first appearance in the feature list.
+    /// Useful for checking which features, like `liga` or `smcp`, a face supports.
+    #[inline]
+    pub fn feature_tags(&self) -> FeatureTags<'a> {
+        FeatureTags { list: self.features, index: 0, is_sorted: true }
+    }
+
+    /// Returns an iterator over [lookups](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#lookup-list-table).
+    ///
+    /// Lookups are yielded in index order. Malformed lookups are skipped.
+    #[inline]
+    pub fn lookups(&self) -> Lookups<'a> {
+        Lookups { list: self.lookups, index: 0 }
+    }
+
+    /// Returns a lookup at index.
+    ///
+    /// Features and contextual lookups reference lookups by index.
+    #[inline]
+    pub fn lookup_at(&self, index: u16) -> Option<Lookup<'a>> {
+        self.lookups.get(index)
+    }
+
+    /// Returns the number of lookups.
+    #[inline]
+    pub fn lookups_count(&self) -> u16 {
+        self.lookups.offsets.len()
+    }
+
+    fn feature_covers(&self, feature: Feature, glyph_id: GlyphId) -> bool {
+        feature.lookup_indices().into_iter()
+            .filter_map(|idx| self.lookup_at(idx))
+            .any(|lookup| lookup.covers(glyph_id))
+    }
+
+    // Checks that a feature with the same tag that precedes `index` covers the glyph.
+    fn is_covered_before(&self, tag: Tag, index: u16, glyph_id: GlyphId) -> bool {
+        (0..index)
+            .filter_map(|idx| self.feature_at(idx))
+            .any(|f| f.tag() == tag && self.feature_covers(f, glyph_id))
+    }
+}
+
+impl fmt::Debug for LayoutTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LayoutTable()")
+    }
+}
+
+
+/// A [script table](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#script-table-and-language-system-record).
+#[derive(Clone, Copy)]
+pub struct Script<'a> {
+    tag: Tag,
+    data: &'a [u8],
+}
+
+impl<'a> Script<'a> {
+    /// Returns script's tag.
+    #[inline]
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns script's default language system.
+    ///
+    /// The default language system has the `dflt` tag.
+    #[inline]
+    pub fn default_language(&self) -> Option<LanguageSystem<'a>> {
+        let offset: Option<Offset16> = Stream::new(self.data).read()?;
+        let data = self.data.get(offset?.to_usize()..)?;
+        LanguageSystem::parse(Tag::from_bytes(b"dflt"), data)
+    }
+
+    /// Returns an iterator over script's language systems.
+    ///
+    /// Doesn't include the default language system.
+    #[inline]
+    pub fn languages(&self) -> LanguageSystems<'a> {
+        let list = self.data.get(Offset16::SIZE..)
+            .and_then(RecordList::parse)
+            .map(|list| RecordList { data: self.data, records: list.records })
+            .unwrap_or_default();
+        LanguageSystems { list, index: 0 }
+    }
+
+    /// Returns a language system by tag.
+    ///
+    /// Returns the default language system for the `dflt` tag,
+    /// unless the script has an explicit one.
+    #[inline]
+    pub fn language(&self, tag: Tag) -> Option<LanguageSystem<'a>> {
+        match self.languages().find(|lang| lang.tag() == tag) {
+            Some(lang) => Some(lang),
+            None if tag == Tag::from_bytes(b"dflt") => self.default_language(),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Debug for Script<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Script")
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+
+/// An iterator over layout table's scripts.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Scripts<'a> {
+    list: RecordList<'a>,
+    index: u16,
+}
+
+impl<'a> Iterator for Scripts<'a> {
+    type Item = Script<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tag, data) = self.list.get(self.index)?;
+        self.index += 1;
+        Some(Script { tag, data })
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        usize::from(self.list.len().saturating_sub(self.index))
+    }
+}
+
+
+/// A [language system table](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#language-system-table).
+#[derive(Clone, Copy, Debug)]
+pub struct LanguageSystem<'a> {
+    tag: Tag,
+    required_feature_index: Option<u16>,
+    feature_indices: LazyArray16<'a, u16>,
+}
+
+impl<'a> LanguageSystem<'a> {
+    fn parse(tag: Tag, data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        s.skip::<Offset16>(); // lookupOrderOffset
+        let required_feature_index: u16 = s.read()?;
+        let count: u16 = s.read()?;
+        let feature_indices = s.read_array16::<u16>(count)?;
+        Some(LanguageSystem {
+            tag,
+            required_feature_index: if required_feature_index != 0xFFFF {
+                Some(required_feature_index)
+            } else {
+                None
+            },
+            feature_indices,
+        })
+    }
+
+    /// Returns language system's tag.
+    #[inline]
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns an index of a feature that must be always applied.
+    #[inline]
+    pub fn required_feature_index(&self) -> Option<u16> {
+        self.required_feature_index
+    }
+
+    /// Returns indices of features available for this language system.
+    ///
+    /// Use `LayoutTable::feature_at()` to resolve them.
+    /// Doesn't include the required feature.
+    #[inline]
+    pub fn feature_indices(&self) -> LazyArray16<'a, u16> {
+        self.feature_indices
+    }
+}
+
+
+/// An iterator over script's language systems.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct LanguageSystems<'a> {
+    list: RecordList<'a>,
+    index: u16,
+}
+
+impl<'a> Iterator for LanguageSystems<'a> {
+    type Item = LanguageSystem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (tag, data) = self.list.get(self.index)?;
+            self.index += 1;
+
+            // Skip malformed language systems.
+            if let Some(lang) = LanguageSystem::parse(tag, data) {
+                return Some(lang);
+            }
+        }
+    }
+}
+
+
+/// A [feature table](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#feature-table).
+#[derive(Clone, Copy)]
+pub struct Feature<'a> {
+    tag: Tag,
+    data: &'a [u8],
+}
+
+impl<'a> Feature<'a> {
+    /// Returns feature's tag.
+    #[inline]
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns indices of lookups referenced by this feature.
+    #[inline]
+    pub fn lookup_indices(&self) -> LazyArray16<'a, u16> {
+        let mut s = Stream::new(self.data);
+        s.skip::<Offset16>(); // featureParamsOffset
+        let count: u16 = try_opt_or!(s.read(), LazyArray16::default());
+        s.read_array16::<u16>(count).unwrap_or_default()
+    }
+
+    /// Returns [stylistic set parameters](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/features_pt#ss01---ss20).
+    ///
+    /// Returns `None` when the feature is not `ss01`..`ss20`
+    /// or doesn't have parameters.
+    #[inline]
+    pub fn stylistic_set_params(&self) -> Option<StylisticSetParams> {
+        if !is_numbered_tag(self.tag, b"ss", 1..=20) {
+            return None;
+        }
+
+        let mut s = Stream::new(self.params()?);
+        Some(StylisticSetParams {
+            version: s.read()?,
+            ui_name_id: s.read()?,
+        })
+    }
+
+    /// Returns [character variant parameters](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/features_ae#cv01-cv99).
+    ///
+    /// Returns `None` when the feature is not `cv01`..`cv99`
+    /// or doesn't have parameters.
+    #[inline]
+    pub fn character_variant_params(&self) -> Option<CharacterVariantParams<'a>> {
+        if !is_numbered_tag(self.tag, b"cv", 1..=99) {
+            return None;
+        }
+
+        let non_zero = |id: u16| if id != 0 { Some(id) } else { None };
+
+        let mut s = Stream::new(self.params()?);
+        let version: u16 = s.read()?;
+        let label_name_id: u16 = s.read()?;
+        let tooltip_name_id: u16 = s.read()?;
+        let sample_text_name_id: u16 = s.read()?;
+        let parameters_count: u16 = s.read()?;
+        let first_parameter_name_id: u16 = s.read()?;
+        let chars_count: u16 = s.read()?;
+        let characters = s.read_array16::<U24>(chars_count)?;
+        Some(CharacterVariantParams {
+            version,
+            label_name_id: non_zero(label_name_id),
+            tooltip_name_id: non_zero(tooltip_name_id),
+            sample_text_name_id: non_zero(sample_text_name_id),
+            parameters_count,
+            first_parameter_name_id: if parameters_count != 0 {
+                non_zero(first_parameter_name_id)
+            } else {
+                None
+            },
+            characters,
+        })
+    }
+
+    fn params(&self) -> Option<&'a [u8]> {
+        let offset: Option<Offset16> = Stream::new(self.data).read()?;
+        self.data.get(offset?.to_usize()..)
+    }
+}
+
+impl fmt::Debug for Feature<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Feature")
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+
+// Checks for tags like `ss01` or `cv99`.
+fn is_numbered_tag(tag: Tag, prefix: &[u8; 2], range: core::ops::RangeInclusive<u8>) -> bool {
+    let b = tag.to_bytes();
+    if &b[..2] != prefix || !b[2].is_ascii_digit() || !b[3].is_ascii_digit() {
+        return false;
+    }
+
+    range.contains(&((b[2] - b'0') * 10 + (b[3] - b'0')))
+}
+
+
+/// [Stylistic set](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/features_pt#ss01---ss20)
+/// feature parameters.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub struct StylisticSetParams {
+    /// Parameters version. Must be 0.
+    pub version: u16,
+
+    /// The `name` table ID of the stylistic set's user-interface name.
+    pub ui_name_id: u16,
+}
+
+
+/// [Character variant](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/features_ae#cv01-cv99)
+/// feature parameters.
+///
+/// All name IDs reference the `name` table.
+#[derive(Clone, Copy, Debug)]
+pub struct CharacterVariantParams<'a> {
+    /// Parameters version. Must be 0.
+    pub version: u16,
+
+    /// The user-interface label name ID.
+    pub label_name_id: Option<u16>,
+
+    /// The tooltip text name ID.
+    pub tooltip_name_id: Option<u16>,
+
+    /// The sample text name ID.
+    pub sample_text_name_id: Option<u16>,
+
+    /// The number of named parameters.
+    ///
+    /// Parameter names use consecutive name IDs starting from `first_parameter_name_id`.
+    pub parameters_count: u16,
+
+    /// The first parameter's user-interface label name ID.
+    pub first_parameter_name_id: Option<u16>,
+
+    characters: LazyArray16<'a, U24>,
+}
+
+impl<'a> CharacterVariantParams<'a> {
+    /// Returns an iterator over Unicode characters for which this feature provides variants.
+    ///
+    /// Invalid code points are skipped.
+    #[inline]
+    pub fn characters(&self) -> impl Iterator<Item = char> + 'a {
+        self.characters.into_iter().filter_map(|c| core::char::from_u32(c.0))
+    }
+}
+
+
+/// An iterator over layout table's features.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Features<'a> {
+    list: RecordList<'a>,
+    index: u16,
+}
+
+impl<'a> Iterator for Features<'a> {
+    type Item = Feature<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tag, data) = self.list.get(self.index)?;
+        self.index += 1;
+        Some(Feature { tag, data })
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        usize::from(self.list.len().saturating_sub(self.index))
+    }
+}
+
+
+/// An iterator over layout table's unique feature tags.
+///
+/// Feature records must be sorted by tag, so duplicates are usually adjacent
+/// and each step is O(1). For an unsorted feature list each step falls back
+/// to rescanning the preceding records, which is O(n).
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct FeatureTags<'a> {
+    list: RecordList<'a>,
+    index: u16,
+    is_sorted: bool,
+}
+
+impl<'a> Iterator for FeatureTags<'a> {
+    type Item = Tag;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (tag, _) = self.list.get(self.index)?;
+            let index = self.index;
+            self.index += 1;
+
+            if self.is_sorted && index > 0 {
+                let (prev_tag, _) = self.list.get(index - 1)?;
+                if prev_tag < tag {
+                    return Some(tag);
+                } else if prev_tag == tag {
+                    continue;
+                }
+
+                self.is_sorted = false;
+            }
+
+            let is_duplicate = !self.is_sorted && (0..index)
+                .filter_map(|idx| self.list.get(idx))
+                .any(|(prev_tag, _)| prev_tag == tag);
+            if !is_duplicate {
+                return Some(tag);
+            }
+        }
+    }
+}
+
+
+/// An iterator over tags of features that reference a glyph.
+///
+/// `GSUB` features are yielded first. Each tag is yielded only once.
+///
+/// Feature records must be sorted by tag, so features with the same tag are adjacent
+/// and are skipped once the tag was yielded. For an unsorted feature list each step
+/// falls back to checking the coverage of the preceding features with the same tag.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphFeatures<'a> {
+    gsub: Option<LayoutTable<'a>>,
+    gpos: Option<LayoutTable<'a>>,
+    glyph_id: GlyphId,
+    is_gpos: bool,
+    index: u16,
+    prev_tag: Option<Tag>,
+    last_yielded_tag: Option<Tag>,
+    is_sorted: bool,
+}
+
+impl<'a> GlyphFeatures<'a> {
+    pub(crate) fn new(
+        gsub: Option<LayoutTable<'a>>,
+        gpos: Option<LayoutTable<'a>>,
+        glyph_id: GlyphId,
+    ) -> Self {
+        GlyphFeatures {
+            gsub,
+            gpos,
+            glyph_id,
+            is_gpos: false,
+            index: 0,
+            prev_tag: None,
+            last_yielded_tag: None,
+            is_sorted: true,
+        }
+    }
+}
+
+impl<'a> Iterator for GlyphFeatures<'a> {
+    type Item = Tag;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let feature = if self.is_gpos { self.gpos } else { self.gsub }
+                .and_then(|table| Some((table, table.feature_at(self.index)?)));
+
+            let (table, feature) = match feature {
+                Some(v) => v,
+                None if !self.is_gpos => {
+                    self.is_gpos = true;
+                    self.index = 0;
+                    self.prev_tag = None;
+                    self.last_yielded_tag = None;
+                    self.is_sorted = true;
+                    continue;
+                }
+                None => return None,
+            };
+
+            let index = self.index;
+            self.index += 1;
+
+            let tag = feature.tag();
+            if self.prev_tag.map(|prev_tag| prev_tag > tag).unwrap_or(false) {
+                self.is_sorted = false;
+            }
+            self.prev_tag = Some(tag);
+
+            if self.is_sorted && self.last_yielded_tag == Some(tag) {
+                continue;
+            }
+
+            if !table.feature_covers(feature, self.glyph_id)
+                || (!self.is_sorted && table.is_covered_before(tag, index, self.glyph_id))
+            {
+                continue;
+            }
+
+            if self.is_gpos {
+                if let Some(gsub) = self.gsub {
+                    if gsub.is_covered_before(tag, gsub.features.len(), self.glyph_id) {
+                        continue;
+                    }
+                }
+            }
+
+            self.last_yielded_tag = Some(tag);
+            return Some(tag);
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, Default)]
+struct LookupList<'a> {
+    data: &'a [u8],
+    offsets: LazyArray16<'a, Offset16>,
+    table: Option<TableKind>,
+}
+
+impl<'a> LookupList<'a> {
+    fn parse(data: &'a [u8], table: TableKind) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let count: u16 = s.read()?;
+        let offsets = s.read_array16::<Offset16>(count)?;
+        Some(LookupList { data, offsets, table: Some(table) })
+    }
+
+    #[inline]
+    fn get(&self, index: u16) -> Option<Lookup<'a>> {
+        let offset = self.offsets.get(index)?;
+        Lookup::parse(self.data.get(offset.to_usize()..)?, self.table?)
+    }
+}
+
+
+/// A set of lookup indices.
+///
+/// Covers the whole `u16` range, so it takes 8 KiB and should be kept on the stack.
+#[derive(Clone)]
+pub(crate) struct LookupIndexSet {
+    words: [u64; 1024],
+    // A range of words that may have bits set.
+    start: usize,
+    end: usize,
+}
+
+impl LookupIndexSet {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        LookupIndexSet { words: [0; 1024], start: 1024, end: 0 }
+    }
+
+    /// Inserts an index and returns `true` when it wasn't in the set already.
+    #[inline]
+    pub(crate) fn insert(&mut self, index: u16) -> bool {
+        let word = usize::from(index / 64);
+        let mask = 1 << (index % 64);
+        let is_new = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        self.start = self.start.min(word);
+        self.end = self.end.max(word + 1);
+        is_new
+    }
+}
+
+impl IntoIterator for LookupIndexSet {
+    type Item = u16;
+    type IntoIter = LookupIndexSetIter;
+
+    /// Returns indices in ascending order.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let word = self.start;
+        LookupIndexSetIter { set: self, word, bits: 0 }
+    }
+}
+
+pub(crate) struct LookupIndexSetIter {
+    set: LookupIndexSet,
+    word: usize,
+    // Not yet yielded bits of the word before `word`.
+    bits: u64,
+}
+
+impl Iterator for LookupIndexSetIter {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bits == 0 {
+            if self.word >= self.set.end {
+                return None;
+            }
+
+            self.bits = self.set.words[self.word];
+            self.word += 1;
+        }
+
+        let bit = self.bits.trailing_zeros();
+        self.bits &= self.bits - 1;
+        // `word` is at most 1024, so the index always fits into `u16`.
+        Some(((self.word - 1) * 64) as u16 + bit as u16)
+    }
+}
+
+
+/// [Lookup flags](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#lookupFlags).
+#[repr(transparent)]
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Hash)]
+pub struct LookupFlags(pub u16);
+
+#[allow(missing_docs)]
+impl LookupFlags {
+    #[inline] pub fn right_to_left(self) -> bool { self.0 & 0x0001 != 0 }
+    #[inline] pub fn ignore_base_glyphs(self) -> bool { self.0 & 0x0002 != 0 }
+    #[inline] pub fn ignore_ligatures(self) -> bool { self.0 & 0x0004 != 0 }
+    #[inline] pub fn ignore_marks(self) -> bool { self.0 & 0x0008 != 0 }
+    #[inline] pub fn use_mark_filtering_set(self) -> bool { self.0 & 0x0010 != 0 }
+
+    /// Returns a mark attachment class to filter marks by.
+    ///
+    /// Returns `None` when marks are not filtered by class.
+    #[inline]
+    pub fn mark_attachment_type(self) -> Option<u8> {
+        let class = (self.0 >> 8) as u8;
+        if class != 0 { Some(class) } else { None }
+    }
+}
+
+
+/// A [lookup table](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#lookup-table).
+#[derive(Clone, Copy)]
+pub struct Lookup<'a> {
+    data: &'a [u8],
+    table: TableKind,
+    kind: u16,
+    flags: LookupFlags,
+    mark_filtering_set: Option<u16>,
+    is_extension: bool,
+    subtable_offsets: LazyArray16<'a, Offset16>,
+}
+
+impl<'a> Lookup<'a> {
+    fn parse(data: &'a [u8], table: TableKind) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let mut kind: u16 = s.read()?;
+        let flags = LookupFlags(s.read()?);
+        let count: u16 = s.read()?;
+        let subtable_offsets = s.read_array16::<Offset16>(count)?;
+        let mark_filtering_set = if flags.use_mark_filtering_set() {
+            Some(s.read()?)
+        } else {
+            None
+        };
+
+        let extension_kind = match table {
+            TableKind::Substitution => 7,
+            TableKind::Positioning => 9,
+        };
+
+        // All extension subtables must have the same type,
+        // so we can resolve it once using the first one.
+        let is_extension = kind == extension_kind;
+        if is_extension {
+            let offset = subtable_offsets.get(0)?;
+            let mut s = Stream::new_at(data, offset.to_usize())?;
+            s.skip::<u16>(); // format
+            kind = s.read()?;
+        }
+
+        Some(Lookup {
+            data,
+            table,
+            kind,
+            flags,
+            mark_filtering_set,
+            is_extension,
+            subtable_offsets,
+        })
+    }
+
+    /// Returns lookup flags.
+    #[inline]
+    pub fn flags(&self) -> LookupFlags {
+        self.flags
+    }
+
+    /// Returns an index into `GDEF` mark glyph sets.
+    ///
+    /// Set only when `LookupFlags::use_mark_filtering_set` is set.
+    /// Can be passed to `Face::is_mark_glyph`.
+    #[inline]
+    pub fn mark_filtering_set(&self) -> Option<u16> {
+        self.mark_filtering_set
+    }
+
+    /// Returns lookup type.
+    ///
+    /// The meaning depends on the table.
+    /// See [GSUB](https://docs.microsoft.com/en-us/typography/opentype/spec/gsub#table-organization)
+    /// and [GPOS](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#table-organization)
+    /// for details.
+    ///
+    /// Extension lookups report the type of the wrapped subtables.
+    #[inline]
+    pub fn kind(&self) -> u16 {
+        self.kind
+    }
+
+    /// Returns the number of subtables.
+    #[inline]
+    pub fn subtables_count(&self) -> u16 {
+        self.subtable_offsets.len()
+    }
+
+    /// Returns an iterator over subtables data.
+    ///
+    /// Extension subtables are resolved automatically.
+    #[inline]
+    pub fn subtables(&self) -> LookupSubtables<'a> {
+        LookupSubtables { lookup: *self, index: 0 }
+    }
+
+    /// Returns subtable's data.
+    ///
+    /// Subtable's data starts at its format field and continues until the end of the table,
+    /// since subtables do not store their length.
+    ///
+    /// Extension subtables are resolved automatically.
+    #[inline]
+    pub fn subtable(&self, index: u16) -> Option<&'a [u8]> {
+        let offset = self.subtable_offsets.get(index)?;
+        let data = self.data.get(offset.to_usize()..)?;
+        if !self.is_extension {
+            return Some(data);
+        }
+
+        let mut s = Stream::new(data);
+        let format: u16 = s.read()?;
+        if format != 1 {
+            return None;
+        }
+
+        s.skip::<u16>(); // extensionLookupType
+        let offset: Offset32 = s.read()?;
+        data.get(offset.to_usize()..)
+    }
+
+    // Checks that any of lookup's subtables references the glyph via a coverage table.
+    //
+    // Only coverage tables of input glyphs are checked.
+    // In case of mark attachment, both mark and base coverage tables are checked.
+    pub(crate) fn covers(&self, glyph_id: GlyphId) -> bool {
+        (0..self.subtables_count())
+            .filter_map(|idx| self.subtable(idx))
+            .any(|data| self.subtable_covers(data, glyph_id).unwrap_or(false))
+    }
+
+    fn subtable_covers(&self, data: &[u8], glyph_id: GlyphId) -> Option<bool> {
+        let covers = |offset: Offset16| {
+            data.get(offset.to_usize()..)
+                .map(|data| CoverageTable::new(data).contains(glyph_id))
+                .unwrap_or(false)
+        };
+
+        let (context_kind, chained_context_kind) = match self.table {
+            TableKind::Substitution => (5, 6),
+            TableKind::Positioning => (7, 8),
+        };
+
+        let mut s = Stream::new(data);
+        let format: u16 = s.read()?;
+
+        if format == 3 && self.kind == context_kind {
+            let count: u16 = s.read()?;
+            s.skip::<u16>(); // seqLookupCount
+            let offsets = s.read_array16::<Offset16>(count)?;
+            return Some(offsets.into_iter().any(covers));
+        }
+
+        if format == 3 && self.kind == chained_context_kind {
+            let backtrack_count: u16 = s.read()?;
+            s.advance(usize::from(backtrack_count) * Offset16::SIZE);
+            let input_count: u16 = s.read()?;
+            let offsets = s.read_array16::<Offset16>(input_count)?;
+            return Some(offsets.into_iter().any(covers));
+        }
+
+        if covers(s.read()?) {
+            return Some(true);
+        }
+
+        // Mark-to-base, mark-to-ligature and mark-to-mark attachments
+        // have a second coverage table.
+        if self.table == TableKind::Positioning && (4..=6).contains(&self.kind) {
+            return Some(covers(s.read()?));
+        }
+
+        Some(false)
+    }
+}
+
+
+impl fmt::Debug for Lookup<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lookup")
+            .field("kind", &self.kind)
+            .field("flags", &self.flags)
+            .field("mark_filtering_set", &self.mark_filtering_set)
+            .finish()
+    }
+}
+
+
+/// An iterator over layout table's lookups.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Lookups<'a> {
+    list: LookupList<'a>,
+    index: u16,
+}
+
+impl<'a> Iterator for Lookups<'a> {
+    type Item = Lookup<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.list.offsets.len() {
+            let index = self.index;
+            self.index += 1;
+            if let Some(lookup) = self.list.get(index) {
+                return Some(lookup);
+            }
+        }
+
+        None
+    }
+}
+
+
+/// An iterator over lookup's subtables.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct LookupSubtables<'a> {
+    lookup: Lookup<'a>,
+    index: u16,
+}
+
+impl<'a> Iterator for LookupSubtables<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.lookup.subtables_count() {
+            let index = self.index;
+            self.index += 1;
+            if let Some(data) = self.lookup.subtable(index) {
+                return Some(data);
+            }
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x00, // feature list offset: 0
+            0x00, 0x00, // lookup list offset: 0
+            // Script list
+            0x00, 0x02, // count: 2
+            b'D', b'F', b'L', b'T', // tag
+            0x00, 0x0E, // offset: 14
+            b'l', b'a', b't', b'n', // tag
+            0x00, 0x0E, // offset: 14
+            // Script table
+            0x00, 0x00, // default language system offset: 0
+            0x00, 0x00, // language system count: 0
+        ];
+
+        let table = LayoutTable::parse(data, TableKind::Substitution).unwrap();
+        assert_eq!(table.scripts().count(), 2);
+        let tags: [Tag; 2] = [
+            table.scripts().next().unwrap().tag(),
+            table.scripts().nth(1).unwrap().tag(),
+        ];
+        assert_eq!(tags, [Tag::from_bytes(b"DFLT"), Tag::from_bytes(b"latn")]);
+        assert!(table.script(Tag::from_bytes(b"latn")).is_some());
+        assert!(table.script(Tag::from_bytes(b"cyrl")).is_none());
+    }
+
+    #[test]
+    fn language_systems() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x2C, // feature list offset: 44
+            0x00, 0x00, // lookup list offset: 0
+            // Script list
+            0x00, 0x01, // count: 1
+            b'l', b'a', b't', b'n', // tag
+            0x00, 0x08, // offset: 8
+            // Script table
+            0x00, 0x0A, // default language system offset: 10
+            0x00, 0x01, // language system count: 1
+            b'T', b'R', b'K', b' ', // tag
+            0x00, 0x12, // offset: 18
+            // Default language system
+            0x00, 0x00, // lookup order offset: 0
+            0xFF, 0xFF, // required feature index: none
+            0x00, 0x01, // feature index count: 1
+            0x00, 0x00, // feature index [0]: 0
+            // Turkish language system
+            0x00, 0x00, // lookup order offset: 0
+            0x00, 0x01, // required feature index: 1
+            0x00, 0x01, // feature index count: 1
+            0x00, 0x00, // feature index [0]: 0
+            // Feature list
+            0x00, 0x02, // count: 2
+            b'l', b'i', b'g', b'a', // tag
+            0x00, 0x0E, // offset: 14
+            b'l', b'o', b'c', b'l', // tag
+            0x00, 0x14, // offset: 20
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x01, // lookup index count: 1
+            0x00, 0x03, // lookup index [0]: 3
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x00, // lookup index count: 0
+        ];
+
+        let table = LayoutTable::parse(data, TableKind::Substitution).unwrap();
+        let script = table.script(Tag::from_bytes(b"latn")).unwrap();
+
+        let dflt = script.default_language().unwrap();
+        assert_eq!(dflt.tag(), Tag::from_bytes(b"dflt"));
+        assert_eq!(dflt.required_feature_index(), None);
+        assert_eq!(dflt.feature_indices().get(0), Some(0));
+
+        assert_eq!(script.languages().count(), 1);
+        let trk = script.language(Tag::from_bytes(b"TRK ")).unwrap();
+        assert_eq!(trk.required_feature_index(), Some(1));
+
+        let locl = table.feature_at(trk.required_feature_index().unwrap()).unwrap();
+        assert_eq!(locl.tag(), Tag::from_bytes(b"locl"));
+        assert!(locl.lookup_indices().is_empty());
+
+        let liga = table.feature_at(0).unwrap();
+        assert_eq!(liga.tag(), Tag::from_bytes(b"liga"));
+        assert_eq!(liga.lookup_indices().get(0), Some(3));
+
+        assert_eq!(table.features().count(), 2);
+        assert!(table.feature_at(2).is_none());
+    }
+
+    #[test]
+    fn feature_tags() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x0C, // feature list offset: 12
+            0x00, 0x00, // lookup list offset: 0
+            // Script list
+            0x00, 0x00, // count: 0
+            // Feature list
+            0x00, 0x03, // count: 3
+            b'l', b'i', b'g', b'a', // tag
+            0x00, 0x14, // offset: 20
+            b's', b'm', b'c', b'p', // tag
+            0x00, 0x14, // offset: 20
+            b'l', b'i', b'g', b'a', // tag
+            0x00, 0x14, // offset: 20
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x00, // lookup index count: 0
+        ];
+
+        let table = LayoutTable::parse(data, TableKind::Substitution).unwrap();
+        assert_eq!(table.features().count(), 3);
+
+        let mut tags = table.feature_tags();
+        assert_eq!(tags.next(), Some(Tag::from_bytes(b"liga")));
+        assert_eq!(tags.next(), Some(Tag::from_bytes(b"smcp")));
+        assert_eq!(tags.next(), None);
+    }
+
+    #[test]
+    fn sorted_feature_tags() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x0C, // feature list offset: 12
+            0x00, 0x00, // lookup list offset: 0
+            // Script list
+            0x00, 0x00, // count: 0
+            // Feature list
+            0x00, 0x04, // count: 4
+            b'k', b'e', b'r', b'n', // tag
+            0x00, 0x1A, // offset: 26
+            b'l', b'i', b'g', b'a', // tag
+            0x00, 0x1A, // offset: 26
+            b'l', b'i', b'g', b'a', // tag
+            0x00, 0x1A, // offset: 26
+            b's', b'm', b'c', b'p', // tag
+            0x00, 0x1A, // offset: 26
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x00, // lookup index count: 0
+        ];
+
+        let table = LayoutTable::parse(data, TableKind::Substitution).unwrap();
+        let mut tags = table.feature_tags();
+        assert_eq!(tags.next(), Some(Tag::from_bytes(b"kern")));
+        assert_eq!(tags.next(), Some(Tag::from_bytes(b"liga")));
+        assert_eq!(tags.next(), Some(Tag::from_bytes(b"smcp")));
+        assert_eq!(tags.next(), None);
+    }
+
+    #[test]
+    fn stylistic_set_params() {
+        let data = &[
+            0x00, 0x04, // feature params offset: 4
+            0x00, 0x00, // lookup index count: 0
+            // Feature params
+            0x00, 0x00, // version: 0
+            0x01, 0x00, // UI name ID: 256
+        ];
+
+        let feature = Feature { tag: Tag::from_bytes(b"ss03"), data };
+        assert_eq!(
+            feature.stylistic_set_params(),
+            Some(StylisticSetParams { version: 0, ui_name_id: 256 })
+        );
+
+        let feature = Feature { tag: Tag::from_bytes(b"ss21"), data };
+        assert_eq!(feature.stylistic_set_params(), None);
+
+        let feature = Feature { tag: Tag::from_bytes(b"liga"), data };
+        assert_eq!(feature.stylistic_set_params(), None);
+    }
+
+    #[test]
+    fn character_variant_params() {
+        let data = &[
+            0x00, 0x04, // feature params offset: 4
+            0x00, 0x00, // lookup index count: 0
+            // Feature params
+            0x00, 0x00, // version: 0
+            0x01, 0x00, // label name ID: 256
+            0x00, 0x00, // tooltip name ID: none
+            0x01, 0x01, // sample text name ID: 257
+            0x00, 0x02, // number of named parameters: 2
+            0x01, 0x02, // first parameter name ID: 258
+            0x00, 0x03, // character count: 3
+            0x00, 0x00, 0x61, // character [0]: a
+            0x11, 0x00, 0x00, // character [1]: invalid
+            0x01, 0xF6, 0x00, // character [2]: U+1F600
+        ];
+
+        let feature = Feature { tag: Tag::from_bytes(b"cv07"), data };
+        let params = feature.character_variant_params().unwrap();
+        assert_eq!(params.label_name_id, Some(256));
+        assert_eq!(params.tooltip_name_id, None);
+        assert_eq!(params.sample_text_name_id, Some(257));
+        assert_eq!(params.parameters_count, 2);
+        assert_eq!(params.first_parameter_name_id, Some(258));
+
+        let mut chars = params.characters();
+        assert_eq!(chars.next(), Some('a'));
+        assert_eq!(chars.next(), Some('\u{1F600}'));
+        assert_eq!(chars.next(), None);
+
+        let feature = Feature { tag: Tag::from_bytes(b"ss07"), data };
+        assert!(feature.character_variant_params().is_none());
+    }
+
+    #[test]
+    fn glyph_features() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x0C, // feature list offset: 12
+            0x00, 0x2C, // lookup list offset: 44
+            // Script list
+            0x00, 0x00, // count: 0
+            // Feature list
+            0x00, 0x03, // count: 3
+            b'l', b'o', b'c', b'l', // tag
+            0x00, 0x14, // offset: 20
+            b'l', b'o', b'c', b'l', // tag
+            0x00, 0x14, // offset: 20
+            b's', b'm', b'c', b'p', // tag
+            0x00, 0x1A, // offset: 26
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x01, // lookup index count: 1
+            0x00, 0x00, // lookup index [0]: 0
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x01, // lookup index count: 1
+            0x00, 0x01, // lookup index [0]: 1
+            // Lookup list
+            0x00, 0x02, // count: 2
+            0x00, 0x06, // offset [0]: 6
+            0x00, 0x1A, // offset [1]: 26
+            // Lookup 0
+            0x00, 0x01, // type: single substitution
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // offset [0]: 8
+            // Single substitution subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x06, // coverage offset: 6
+            0x00, 0x0A, // delta: 10
+            // Coverage table
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // glyph [0]: 5
+            // Lookup 1
+            0x00, 0x05, // type: context substitution
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // offset [0]: 8
+            // Context substitution subtable
+            0x00, 0x03, // format: 3
+            0x00, 0x02, // glyph count: 2
+            0x00, 0x00, // lookup count: 0
+            0x00, 0x0A, // coverage offset [0]: 10
+            0x00, 0x0A, // coverage offset [1]: 10
+            // Coverage table
+            0x00, 0x02, // format: 2
+            0x00, 0x01, // count: 1
+            0x00, 0x04, // start glyph: 4
+            0x00, 0x06, // end glyph: 6
+            0x00, 0x00, // start coverage index: 0
+        ];
+
+        let gsub = LayoutTable::parse(data, TableKind::Substitution);
+        assert!(gsub.is_some());
+
+        let mut iter = GlyphFeatures::new(gsub, None, GlyphId(5));
+        assert_eq!(iter.next(), Some(Tag::from_bytes(b"locl")));
+        assert_eq!(iter.next(), Some(Tag::from_bytes(b"smcp")));
+        assert_eq!(iter.next(), None);
+
+        let mut iter = GlyphFeatures::new(gsub, None, GlyphId(6));
+        assert_eq!(iter.next(), Some(Tag::from_bytes(b"smcp")));
+        assert_eq!(iter.next(), None);
+
+        // The same table as GPOS. Lookup types are different, but still have coverages.
+        let iter = GlyphFeatures::new(gsub, gsub, GlyphId(5));
+        assert_eq!(iter.count(), 2);
+
+        assert_eq!(GlyphFeatures::new(gsub, None, GlyphId(7)).count(), 0);
+
+        // An unsorted feature list: `locl`, `smcp`, `locl`.
+        let mut data = data.to_vec();
+        data[20..26].copy_from_slice(&[b's', b'm', b'c', b'p', 0x00, 0x1A]);
+        data[26..32].copy_from_slice(&[b'l', b'o', b'c', b'l', 0x00, 0x14]);
+        let gsub = LayoutTable::parse(&data, TableKind::Substitution);
+        let tags: std::vec::Vec<Tag> = GlyphFeatures::new(gsub, None, GlyphId(5)).collect();
+        assert_eq!(tags, [Tag::from_bytes(b"locl"), Tag::from_bytes(b"smcp")]);
+    }
+
+    #[test]
+    fn lookup_flags() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x00, // feature list offset: 0
+            0x00, 0x0C, // lookup list offset: 12
+            // Script list
+            0x00, 0x00, // count: 0
+            // Lookup list
+            0x00, 0x02, // count: 2
+            0x00, 0x06, // offset [0]: 6
+            0x00, 0x0C, // offset [1]: 12
+            // Lookup 0
+            0x00, 0x01, // type: single substitution
+            0x03, 0x09, // flags: RIGHT_TO_LEFT | IGNORE_MARKS | mark attachment type 3
+            0x00, 0x00, // subtable count: 0
+            // Lookup 1
+            0x00, 0x01, // type: single substitution
+            0x00, 0x10, // flags: USE_MARK_FILTERING_SET
+            0x00, 0x00, // subtable count: 0
+            0x00, 0x02, // mark filtering set: 2
+        ];
+
+        let table = LayoutTable::parse(data, TableKind::Substitution).unwrap();
+
+        let lookup = table.lookup_at(0).unwrap();
+        let flags = lookup.flags();
+        assert!(flags.right_to_left());
+        assert!(flags.ignore_marks());
+        assert!(!flags.ignore_base_glyphs());
+        assert!(!flags.ignore_ligatures());
+        assert_eq!(flags.mark_attachment_type(), Some(3));
+        assert_eq!(lookup.mark_filtering_set(), None);
+
+        let lookup = table.lookup_at(1).unwrap();
+        assert!(lookup.flags().use_mark_filtering_set());
+        assert_eq!(lookup.flags().mark_attachment_type(), None);
+        assert_eq!(lookup.mark_filtering_set(), Some(2));
+
+        assert!(table.lookup_at(2).is_none());
+        assert_eq!(table.lookups_count(), 2);
+        assert_eq!(table.lookups().count(), 2);
+    }
+
+    #[test]
+    fn lookup_subtables() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x00, // feature list offset: 0
+            0x00, 0x0C, // lookup list offset: 12
+            // Script list
+            0x00, 0x00, // count: 0
+            // Lookup list
+            0x00, 0x02, // count: 2
+            0x00, 0x06, // offset [0]: 6
+            0x00, 0x14, // offset [1]: 20
+            // Lookup 0
+            0x00, 0x04, // type: ligature substitution
+            0x00, 0x00, // flags: 0
+            0x00, 0x02, // subtable count: 2
+            0x00, 0x0A, // offset [0]: 10
+            0x00, 0x0C, // offset [1]: 12
+            // Subtable 0
+            0x00, 0x01,
+            // Subtable 1
+            0x00, 0x02,
+            // Lookup 1
+            0x00, 0x07, // type: extension substitution
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // offset [0]: 8
+            // Extension subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x06, // extension lookup type: chained context substitution
+            0x00, 0x00, 0x00, 0x08, // offset: 8
+            // Subtable 0
+            0x00, 0x03,
+        ];
+
+        let table = LayoutTable::parse(data, TableKind::Substitution).unwrap();
+
+        let mut lookups = table.lookups();
+        let lookup = lookups.next().unwrap();
+        assert_eq!(lookup.kind(), 4);
+        assert_eq!(lookup.subtables_count(), 2);
+        let mut subtables = lookup.subtables();
+        assert_eq!(subtables.next().map(|data| data[1]), Some(1));
+        assert_eq!(subtables.next().map(|data| data[1]), Some(2));
+        assert!(subtables.next().is_none());
+
+        let lookup = lookups.next().unwrap();
+        assert_eq!(lookup.kind(), 6);
+        assert_eq!(lookup.subtable(0), Some(&[0x00, 0x03][..]));
+        assert!(lookups.next().is_none());
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let data = &[
+            0x00, 0x02, 0x00, 0x00, // version: 2.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x00, // feature list offset: 0
+            0x00, 0x00, // lookup list offset: 0
+            0x00, 0x00, // count: 0
+        ];
+
+        assert!(LayoutTable::parse(data, TableKind::Substitution).is_none());
+    }
+
+    #[test]
+    fn lookup_index_set() {
+        let set = LookupIndexSet::new();
+        assert_eq!(set.into_iter().count(), 0);
+
+        let mut set = LookupIndexSet::new();
+        assert!(set.insert(700));
+        assert!(set.insert(5));
+        assert!(!set.insert(5));
+        assert!(set.insert(0xFFFF));
+        assert!(set.insert(0));
+        assert!(set.insert(63));
+        assert!(set.insert(64));
+        let indices: std::vec::Vec<u16> = set.into_iter().collect();
+        assert_eq!(indices, [0, 5, 63, 64, 700, 0xFFFF]);
+    }
+}
@@ -3,6 +3,12 @@
 use crate::GlyphId;
 use crate::parser::*;
 
+mod device;
+mod layout_table;
+
+pub use device::*;
+pub use layout_table::*;
+
 
 #[derive(Clone, Copy)]
 struct RangeRecord {
@@ -44,20 +50,27 @@ impl<'a> CoverageTable<'a> {
     }
 
     pub fn contains(&self, glyph_id: GlyphId) -> bool {
+        self.get(glyph_id).is_some()
+    }
+
+    /// Returns glyph's coverage index.
+    pub fn get(&self, glyph_id: GlyphId) -> Option<u16> {
         let mut s = Stream::new(self.data);
-        let format: u16 = try_opt_or!(s.read(), false);
+        let format: u16 = s.read()?;
 
         match format {
             1 => {
-                let count = try_opt_or!(s.read::<u16>(), false);
-                s.read_array16::<GlyphId>(count).unwrap().binary_search(&glyph_id).is_some()
+                let count = s.read::<u16>()?;
+                s.read_array16::<GlyphId>(count)?.binary_search(&glyph_id).map(|(idx, _)| idx)
             }
             2 => {
-                let count = try_opt_or!(s.read::<u16>(), false);
-                let records = try_opt_or!(s.read_array16::<RangeRecord>(count), false);
-                records.into_iter().any(|r| r.range().contains(&glyph_id))
+                let count = s.read::<u16>()?;
+                let records = s.read_array16::<RangeRecord>(count)?;
+                let record = records.into_iter().find(|r| r.range().contains(&glyph_id))?;
+                // `value` is a start coverage index.
+                record.value.checked_add(glyph_id.0 - record.start_glyph_id.0)
             }
-            _ => false,
+            _ => None,
         }
     }
 }
@@ -0,0 +1,1965 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/tt_instructions
+//
+// The behavior of undocumented edge cases mimics the FreeType's v35 interpreter.
+
+use std::vec::Vec;
+
+use super::math::*;
+
+
+// Not defined in the spec, so we are using our own values.
+const MAX_CALL_DEPTH: usize = 32;
+const MAX_INSTRUCTIONS: u32 = 1_000_000;
+// FreeType adds the same amount to `maxStackElements`, because fonts often underestimate it.
+const EXTRA_STACK_ELEMENTS: usize = 32;
+
+pub const FLAG_ON_CURVE: u8 = 0x01;
+const FLAG_TOUCHED_X: u8 = 0x02;
+const FLAG_TOUCHED_Y: u8 = 0x04;
+const FLAG_TOUCHED_BOTH: u8 = FLAG_TOUCHED_X | FLAG_TOUCHED_Y;
+
+
+/// A point in 26.6 or font units.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+
+/// A points storage.
+///
+/// The glyph zone also contains four phantom points at the end.
+#[derive(Clone, Default, Debug)]
+pub struct Zone {
+    /// Unscaled points.
+    pub orus: Vec<Point>,
+    /// Scaled points.
+    pub org: Vec<Point>,
+    /// Hinted points.
+    pub cur: Vec<Point>,
+    pub flags: Vec<u8>,
+    /// Contour end points.
+    pub contours: Vec<u16>,
+}
+
+impl Zone {
+    pub fn new_twilight(len: u16) -> Self {
+        let len = usize::from(len);
+        Zone {
+            orus: vec![Point::default(); len],
+            org: vec![Point::default(); len],
+            cur: vec![Point::default(); len],
+            flags: vec![0; len],
+            contours: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.cur.len()
+    }
+}
+
+
+/// A unit vector in 2.14.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Vector {
+    x: i32,
+    y: i32,
+}
+
+const X_AXIS: Vector = Vector { x: 0x4000, y: 0 };
+const Y_AXIS: Vector = Vector { x: 0, y: 0x4000 };
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RoundState {
+    ToHalfGrid,
+    ToGrid,
+    ToDoubleGrid,
+    DownToGrid,
+    UpToGrid,
+    Off,
+    Super,
+    Super45,
+}
+
+
+#[derive(Clone, Copy, Debug)]
+pub struct GraphicsState {
+    auto_flip: bool,
+    control_value_cutin: i32,
+    delta_base: i32,
+    delta_shift: i32,
+    dual_vector: Vector,
+    proj_vector: Vector,
+    free_vector: Vector,
+    pub instruct_control: i32,
+    loop_counter: i32,
+    min_distance: i32,
+    round_state: RoundState,
+    period: i32,
+    phase: i32,
+    threshold: i32,
+    rp0: usize,
+    rp1: usize,
+    rp2: usize,
+    single_width_cutin: i32,
+    single_width_value: i32,
+    gep0: u8,
+    gep1: u8,
+    gep2: u8,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        GraphicsState {
+            auto_flip: true,
+            control_value_cutin: 68, // 17/16 pixels
+            delta_base: 9,
+            delta_shift: 3,
+            dual_vector: X_AXIS,
+            proj_vector: X_AXIS,
+            free_vector: X_AXIS,
+            instruct_control: 0,
+            loop_counter: 1,
+            min_distance: 64,
+            round_state: RoundState::ToGrid,
+            period: 64,
+            phase: 0,
+            threshold: 0,
+            rp0: 0,
+            rp1: 0,
+            rp2: 0,
+            single_width_cutin: 0,
+            single_width_value: 0,
+            gep0: 1,
+            gep1: 1,
+            gep2: 1,
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Program {
+    Font,
+    ControlValue,
+    Glyph,
+}
+
+
+#[derive(Clone, Copy, Debug)]
+struct Definition {
+    program: Program,
+    start: usize,
+}
+
+
+#[derive(Clone, Copy, Debug)]
+struct CallFrame {
+    caller_program: Program,
+    caller_ip: usize,
+    count: i32,
+    definition: Definition,
+}
+
+
+pub struct Exec<'a> {
+    pub gs: GraphicsState,
+    pub default_gs: GraphicsState,
+    pub cvt: Vec<i32>,
+    pub storage: Vec<i32>,
+    pub twilight: Zone,
+    pub glyph: Zone,
+    pub ppem: i32,
+    /// Font units to 26.6 scale in 16.16.
+    ///
+    /// Composite glyph programs operate on already scaled points, so it is set to 1.0 for them.
+    pub scale: i32,
+    fpgm: &'a [u8],
+    prep: &'a [u8],
+    pub glyph_program: &'a [u8],
+    functions: Vec<Option<Definition>>,
+    instructions: Vec<(u8, Definition)>,
+    stack: Vec<i32>,
+    max_stack: usize,
+    call_stack: Vec<CallFrame>,
+    program: Program,
+    ip: usize,
+    next_ip: usize,
+    opcode: u8,
+    f_dot_p: i32,
+}
+
+impl<'a> Exec<'a> {
+    pub fn new(
+        fpgm: &'a [u8],
+        prep: &'a [u8],
+        max_stack: u16,
+        max_storage: u16,
+        max_function_defs: u16,
+        max_twilight_points: u16,
+    ) -> Self {
+        Exec {
+            gs: GraphicsState::default(),
+            default_gs: GraphicsState::default(),
+            cvt: Vec::new(),
+            storage: vec![0; usize::from(max_storage)],
+            twilight: Zone::new_twilight(max_twilight_points),
+            glyph: Zone::default(),
+            ppem: 0,
+            scale: 0x10000,
+            fpgm,
+            prep,
+            glyph_program: &[],
+            functions: vec![None; usize::from(max_function_defs)],
+            instructions: Vec::new(),
+            stack: Vec::new(),
+            max_stack: usize::from(max_stack) + EXTRA_STACK_ELEMENTS,
+            call_stack: Vec::new(),
+            program: Program::Font,
+            ip: 0,
+            next_ip: 0,
+            opcode: 0,
+            f_dot_p: 0x4000,
+        }
+    }
+
+    /// Executes a program.
+    ///
+    /// Returns `None` on error.
+    pub fn run(&mut self, program: Program) -> Option<()> {
+        self.gs = self.default_gs;
+        self.gs.gep0 = 1;
+        self.gs.gep1 = 1;
+        self.gs.gep2 = 1;
+        self.gs.dual_vector = X_AXIS;
+        self.gs.proj_vector = X_AXIS;
+        self.gs.free_vector = X_AXIS;
+        self.gs.round_state = RoundState::ToGrid;
+        self.gs.loop_counter = 1;
+        self.compute_f_dot_p();
+
+        self.stack.clear();
+        self.call_stack.clear();
+        self.program = program;
+        self.ip = 0;
+
+        let mut count = 0;
+        loop {
+            let code = self.code(self.program);
+            if self.ip >= code.len() {
+                if self.call_stack.is_empty() {
+                    return Some(());
+                }
+
+                // A function without ENDF.
+                return None;
+            }
+
+            count += 1;
+            if count > MAX_INSTRUCTIONS {
+                return None;
+            }
+
+            self.opcode = code[self.ip];
+            self.next_ip = self.ip.checked_add(opcode_len(code, self.ip)?)?;
+            self.execute()?;
+            self.ip = self.next_ip;
+        }
+    }
+
+    #[inline]
+    fn code(&self, program: Program) -> &'a [u8] {
+        match program {
+            Program::Font => self.fpgm,
+            Program::ControlValue => self.prep,
+            Program::Glyph => self.glyph_program,
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<i32> {
+        self.stack.pop()
+    }
+
+    #[inline]
+    fn push(&mut self, n: i32) -> Option<()> {
+        if self.stack.len() >= self.max_stack {
+            return None;
+        }
+
+        self.stack.push(n);
+        Some(())
+    }
+
+    #[inline]
+    fn zone(&self, n: u8) -> &Zone {
+        if n == 0 { &self.twilight } else { &self.glyph }
+    }
+
+    #[inline]
+    fn zone_mut(&mut self, n: u8) -> &mut Zone {
+        if n == 0 { &mut self.twilight } else { &mut self.glyph }
+    }
+
+    #[inline]
+    fn point(&self, zone: u8, index: usize) -> Option<Point> {
+        self.zone(zone).cur.get(index).copied()
+    }
+
+    #[inline]
+    fn org_point(&self, zone: u8, index: usize) -> Option<Point> {
+        self.zone(zone).org.get(index).copied()
+    }
+
+    #[inline]
+    fn orus_point(&self, zone: u8, index: usize) -> Option<Point> {
+        self.zone(zone).orus.get(index).copied()
+    }
+
+    fn compute_f_dot_p(&mut self) {
+        let pv = self.gs.proj_vector;
+        let fv = self.gs.free_vector;
+        let n = (i64::from(pv.x) * i64::from(fv.x) + i64::from(pv.y) * i64::from(fv.y)) >> 14;
+        self.f_dot_p = if n.abs() < 0x400 { 0x4000 } else { n as i32 };
+    }
+
+    #[inline]
+    fn project(&self, p1: Point, p2: Point) -> i32 {
+        let v = self.gs.proj_vector;
+        dot_fix14(p1.x.wrapping_sub(p2.x), p1.y.wrapping_sub(p2.y), v.x, v.y)
+    }
+
+    #[inline]
+    fn dual_project(&self, p1: Point, p2: Point) -> i32 {
+        let v = self.gs.dual_vector;
+        dot_fix14(p1.x.wrapping_sub(p2.x), p1.y.wrapping_sub(p2.y), v.x, v.y)
+    }
+
+    /// Moves a point along the freedom vector, so its projection changes by `distance`.
+    fn move_point(&mut self, zone: u8, index: usize, distance: i32, touch: bool) -> Option<()> {
+        let fv = self.gs.free_vector;
+        let f_dot_p = self.f_dot_p;
+        let z = self.zone_mut(zone);
+        let p = z.cur.get_mut(index)?;
+        if fv.x != 0 {
+            p.x = p.x.wrapping_add(mul_div(distance, fv.x, f_dot_p));
+            if touch {
+                z.flags[index] |= FLAG_TOUCHED_X;
+            }
+        }
+
+        if fv.y != 0 {
+            p.y = p.y.wrapping_add(mul_div(distance, fv.y, f_dot_p));
+            if touch {
+                z.flags[index] |= FLAG_TOUCHED_Y;
+            }
+        }
+
+        Some(())
+    }
+
+    fn move_original(&mut self, zone: u8, index: usize, distance: i32) -> Option<()> {
+        let fv = self.gs.free_vector;
+        let f_dot_p = self.f_dot_p;
+        let p = self.zone_mut(zone).org.get_mut(index)?;
+        if fv.x != 0 {
+            p.x = p.x.wrapping_add(mul_div(distance, fv.x, f_dot_p));
+        }
+
+        if fv.y != 0 {
+            p.y = p.y.wrapping_add(mul_div(distance, fv.y, f_dot_p));
+        }
+
+        Some(())
+    }
+
+    fn shift_point(&mut self, zone: u8, index: usize, dx: i32, dy: i32, touch: bool) -> Option<()> {
+        let fv = self.gs.free_vector;
+        let z = self.zone_mut(zone);
+        let p = z.cur.get_mut(index)?;
+        if fv.x != 0 {
+            p.x = p.x.wrapping_add(dx);
+            if touch {
+                z.flags[index] |= FLAG_TOUCHED_X;
+            }
+        }
+
+        if fv.y != 0 {
+            p.y = p.y.wrapping_add(dy);
+            if touch {
+                z.flags[index] |= FLAG_TOUCHED_Y;
+            }
+        }
+
+        Some(())
+    }
+
+    fn round(&self, distance: i32) -> i32 {
+        match self.gs.round_state {
+            RoundState::ToHalfGrid => {
+                if distance >= 0 {
+                    (distance & -64).wrapping_add(32).max(0)
+                } else {
+                    (distance.wrapping_neg() & -64).wrapping_add(32).wrapping_neg().min(0)
+                }
+            }
+            RoundState::ToGrid => {
+                if distance >= 0 {
+                    (distance.wrapping_add(32) & -64).max(0)
+                } else {
+                    (distance.wrapping_neg().wrapping_add(32) & -64).wrapping_neg().min(0)
+                }
+            }
+            RoundState::ToDoubleGrid => {
+                if distance >= 0 {
+                    (distance.wrapping_add(16) & -32).max(0)
+                } else {
+                    (distance.wrapping_neg().wrapping_add(16) & -32).wrapping_neg().min(0)
+                }
+            }
+            RoundState::DownToGrid => {
+                if distance >= 0 {
+                    (distance & -64).max(0)
+                } else {
+                    (distance.wrapping_neg() & -64).wrapping_neg().min(0)
+                }
+            }
+            RoundState::UpToGrid => {
+                if distance >= 0 {
+                    (distance.wrapping_add(63) & -64).max(0)
+                } else {
+                    (distance.wrapping_neg().wrapping_add(63) & -64).wrapping_neg().min(0)
+                }
+            }
+            RoundState::Off => distance,
+            RoundState::Super => {
+                let gs = &self.gs;
+                let offset = gs.threshold.wrapping_sub(gs.phase);
+                let mask = gs.period.wrapping_neg();
+                if distance >= 0 {
+                    let v = distance.wrapping_add(offset) & mask;
+                    let v = v.wrapping_add(gs.phase);
+                    if v < 0 { gs.phase } else { v }
+                } else {
+                    let v = (offset.wrapping_sub(distance) & mask).wrapping_neg();
+                    let v = v.wrapping_sub(gs.phase);
+                    if v > 0 { gs.phase.wrapping_neg() } else { v }
+                }
+            }
+            RoundState::Super45 => {
+                let gs = &self.gs;
+                if gs.period == 0 {
+                    return distance;
+                }
+
+                let offset = gs.threshold.wrapping_sub(gs.phase);
+                if distance >= 0 {
+                    let v = distance.wrapping_add(offset).wrapping_div(gs.period).wrapping_mul(gs.period);
+                    let v = v.wrapping_add(gs.phase);
+                    if v < 0 { gs.phase } else { v }
+                } else {
+                    let v = offset.wrapping_sub(distance).wrapping_div(gs.period).wrapping_mul(gs.period);
+                    let v = v.wrapping_neg().wrapping_sub(gs.phase);
+                    if v > 0 { gs.phase.wrapping_neg() } else { v }
+                }
+            }
+        }
+    }
+
+    fn set_super_round(&mut self, grid_period: i32, selector: i32) {
+        let period = match selector & 0xC0 {
+            0x00 => grid_period / 2,
+            0x80 => grid_period * 2,
+            _ => grid_period,
+        };
+
+        let phase = match selector & 0x30 {
+            0x00 => 0,
+            0x10 => period / 4,
+            0x20 => period / 2,
+            _ => period * 3 / 4,
+        };
+
+        let threshold = if selector & 0x0F == 0 {
+            period - 1
+        } else {
+            ((selector & 0x0F) - 4) * period / 8
+        };
+
+        // Convert to 26.6.
+        self.gs.period = (period >> 8).max(1);
+        self.gs.phase = phase >> 8;
+        self.gs.threshold = threshold >> 8;
+    }
+
+    #[inline]
+    fn read_cvt(&self, index: i32) -> i32 {
+        // Out of bounds access is allowed and returns zero.
+        usize_from(index).and_then(|i| self.cvt.get(i)).copied().unwrap_or(0)
+    }
+
+    #[inline]
+    fn scale_funits(&self, n: i32) -> i32 {
+        mul_fix(n, self.scale)
+    }
+
+    fn set_vector_to_line(&self, p1_index: i32, p2_index: i32, mut perpendicular: bool) -> Option<Vector> {
+        let p1 = self.point(self.gs.gep1, usize_from(p2_index)?)?;
+        let p2 = self.point(self.gs.gep2, usize_from(p1_index)?)?;
+        let mut a = p1.x.wrapping_sub(p2.x);
+        let mut b = p1.y.wrapping_sub(p2.y);
+        if a == 0 && b == 0 {
+            // Fallback to the x-axis.
+            a = 0x4000;
+            perpendicular = false;
+        }
+
+        if perpendicular {
+            let c = b;
+            b = a;
+            a = c.wrapping_neg();
+        }
+
+        normalize(a, b).map(|(x, y)| Vector { x, y })
+    }
+
+    /// Returns the reference point displacement used by SHP, SHC and SHZ.
+    fn point_displacement(&self) -> Option<(i32, i32, u8, usize)> {
+        let (zone, index) = if self.opcode & 1 != 0 {
+            (self.gs.gep0, self.gs.rp1)
+        } else {
+            (self.gs.gep1, self.gs.rp2)
+        };
+
+        let z = self.zone(zone);
+        let d = self.project(*z.cur.get(index)?, *z.org.get(index)?);
+        let fv = self.gs.free_vector;
+        let dx = mul_div(d, fv.x, self.f_dot_p);
+        let dy = mul_div(d, fv.y, self.f_dot_p);
+        Some((dx, dy, zone, index))
+    }
+
+    fn skip_branch(&mut self, stop_at_else: bool) -> Option<()> {
+        let code = self.code(self.program);
+        let mut nesting = 1;
+        let mut ip = self.next_ip;
+        while ip < code.len() {
+            let opcode = code[ip];
+            let len = opcode_len(code, ip)?;
+            match opcode {
+                0x58 => nesting += 1, // IF
+                0x1B if nesting == 1 && stop_at_else => { // ELSE
+                    self.next_ip = ip + len;
+                    return Some(());
+                }
+                0x59 => { // EIF
+                    nesting -= 1;
+                    if nesting == 0 {
+                        self.next_ip = ip + len;
+                        return Some(());
+                    }
+                }
+                _ => {}
+            }
+
+            ip += len;
+        }
+
+        None
+    }
+
+    fn skip_definition(&mut self) -> Option<()> {
+        let code = self.code(self.program);
+        let mut ip = self.next_ip;
+        while ip < code.len() {
+            let opcode = code[ip];
+            let len = opcode_len(code, ip)?;
+            match opcode {
+                0x2C | 0x89 => return None, // Nested FDEF or IDEF.
+                0x2D => { // ENDF
+                    self.next_ip = ip + len;
+                    return Some(());
+                }
+                _ => {}
+            }
+
+            ip += len;
+        }
+
+        None
+    }
+
+    fn call(&mut self, definition: Definition, count: i32) -> Option<()> {
+        if count <= 0 {
+            return Some(());
+        }
+
+        if self.call_stack.len() >= MAX_CALL_DEPTH {
+            return None;
+        }
+
+        self.call_stack.push(CallFrame {
+            caller_program: self.program,
+            caller_ip: self.next_ip,
+            count,
+            definition,
+        });
+
+        self.program = definition.program;
+        self.next_ip = definition.start;
+        Some(())
+    }
+
+    fn define(&mut self) -> Option<Definition> {
+        // Definitions are allowed only in `fpgm` and `prep`.
+        if self.program == Program::Glyph {
+            return None;
+        }
+
+        let definition = Definition { program: self.program, start: self.next_ip };
+        self.skip_definition()?;
+        Some(definition)
+    }
+
+    fn execute(&mut self) -> Option<()> {
+        let opcode = self.opcode;
+        match opcode {
+            // SVTCA
+            0x00 | 0x01 => {
+                let v = if opcode & 1 != 0 { X_AXIS } else { Y_AXIS };
+                self.gs.proj_vector = v;
+                self.gs.dual_vector = v;
+                self.gs.free_vector = v;
+                self.compute_f_dot_p();
+            }
+            // SPVTCA
+            0x02 | 0x03 => {
+                let v = if opcode & 1 != 0 { X_AXIS } else { Y_AXIS };
+                self.gs.proj_vector = v;
+                self.gs.dual_vector = v;
+                self.compute_f_dot_p();
+            }
+            // SFVTCA
+            0x04 | 0x05 => {
+                self.gs.free_vector = if opcode & 1 != 0 { X_AXIS } else { Y_AXIS };
+                self.compute_f_dot_p();
+            }
+            // SPVTL
+            0x06 | 0x07 => {
+                let p1 = self.pop()?;
+                let p2 = self.pop()?;
+                let v = self.set_vector_to_line(p1, p2, opcode & 1 != 0)?;
+                self.gs.proj_vector = v;
+                self.gs.dual_vector = v;
+                self.compute_f_dot_p();
+            }
+            // SFVTL
+            0x08 | 0x09 => {
+                let p1 = self.pop()?;
+                let p2 = self.pop()?;
+                self.gs.free_vector = self.set_vector_to_line(p1, p2, opcode & 1 != 0)?;
+                self.compute_f_dot_p();
+            }
+            // SPVFS
+            0x0A => {
+                let y = self.pop()?;
+                let x = self.pop()?;
+                let (x, y) = normalize(i32::from(x as i16), i32::from(y as i16))?;
+                self.gs.proj_vector = Vector { x, y };
+                self.gs.dual_vector = self.gs.proj_vector;
+                self.compute_f_dot_p();
+            }
+            // SFVFS
+            0x0B => {
+                let y = self.pop()?;
+                let x = self.pop()?;
+                let (x, y) = normalize(i32::from(x as i16), i32::from(y as i16))?;
+                self.gs.free_vector = Vector { x, y };
+                self.compute_f_dot_p();
+            }
+            // GPV
+            0x0C => {
+                self.push(self.gs.proj_vector.x)?;
+                self.push(self.gs.proj_vector.y)?;
+            }
+            // GFV
+            0x0D => {
+                self.push(self.gs.free_vector.x)?;
+                self.push(self.gs.free_vector.y)?;
+            }
+            // SFVTPV
+            0x0E => {
+                self.gs.free_vector = self.gs.proj_vector;
+                self.compute_f_dot_p();
+            }
+            // ISECT
+            0x0F => {
+                let b1 = usize_from(self.pop()?)?;
+                let b0 = usize_from(self.pop()?)?;
+                let a1 = usize_from(self.pop()?)?;
+                let a0 = usize_from(self.pop()?)?;
+                let point = usize_from(self.pop()?)?;
+
+                let b0 = self.point(self.gs.gep0, b0)?;
+                let b1 = self.point(self.gs.gep0, b1)?;
+                let a0 = self.point(self.gs.gep1, a0)?;
+                let a1 = self.point(self.gs.gep1, a1)?;
+
+                let dbx = b1.x.wrapping_sub(b0.x);
+                let dby = b1.y.wrapping_sub(b0.y);
+                let dax = a1.x.wrapping_sub(a0.x);
+                let day = a1.y.wrapping_sub(a0.y);
+                let dx = b0.x.wrapping_sub(a0.x);
+                let dy = b0.y.wrapping_sub(a0.y);
+
+                let discriminant = mul_div(dax, -dby, 0x40).wrapping_add(mul_div(day, dbx, 0x40));
+                let dot_product = mul_div(dax, dbx, 0x40).wrapping_add(mul_div(day, dby, 0x40));
+
+                let p = if 19 * i64::from(discriminant).abs() > i64::from(dot_product).abs() {
+                    let v = mul_div(dx, -dby, 0x40).wrapping_add(mul_div(dy, dbx, 0x40));
+                    Point {
+                        x: a0.x.wrapping_add(mul_div(v, dax, discriminant)),
+                        y: a0.y.wrapping_add(mul_div(v, day, discriminant)),
+                    }
+                } else {
+                    // Take the middle of the middles.
+                    Point {
+                        x: ((i64::from(a0.x) + i64::from(a1.x) + i64::from(b0.x) + i64::from(b1.x)) / 4) as i32,
+                        y: ((i64::from(a0.y) + i64::from(a1.y) + i64::from(b0.y) + i64::from(b1.y)) / 4) as i32,
+                    }
+                };
+
+                let z = self.zone_mut(self.gs.gep2);
+                *z.cur.get_mut(point)? = p;
+                z.flags[point] |= FLAG_TOUCHED_BOTH;
+            }
+            // SRP0
+            0x10 => self.gs.rp0 = usize_from(self.pop()?)?,
+            // SRP1
+            0x11 => self.gs.rp1 = usize_from(self.pop()?)?,
+            // SRP2
+            0x12 => self.gs.rp2 = usize_from(self.pop()?)?,
+            // SZP0
+            0x13 => self.gs.gep0 = zone_from(self.pop()?)?,
+            // SZP1
+            0x14 => self.gs.gep1 = zone_from(self.pop()?)?,
+            // SZP2
+            0x15 => self.gs.gep2 = zone_from(self.pop()?)?,
+            // SZPS
+            0x16 => {
+                let zone = zone_from(self.pop()?)?;
+                self.gs.gep0 = zone;
+                self.gs.gep1 = zone;
+                self.gs.gep2 = zone;
+            }
+            // SLOOP
+            0x17 => {
+                let n = self.pop()?;
+                if n < 0 {
+                    return None;
+                }
+
+                self.gs.loop_counter = n.min(0xFFFF);
+            }
+            // RTG
+            0x18 => self.gs.round_state = RoundState::ToGrid,
+            // RTHG
+            0x19 => self.gs.round_state = RoundState::ToHalfGrid,
+            // SMD
+            0x1A => self.gs.min_distance = self.pop()?,
+            // ELSE
+            0x1B => self.skip_branch(false)?,
+            // JMPR
+            0x1C => {
+                let offset = self.pop()?;
+                self.jump(offset)?;
+            }
+            // SCVTCI
+            0x1D => self.gs.control_value_cutin = self.pop()?,
+            // SSWCI
+            0x1E => self.gs.single_width_cutin = self.pop()?,
+            // SSW
+            0x1F => {
+                let n = self.pop()?;
+                self.gs.single_width_value = self.scale_funits(n);
+            }
+            // DUP
+            0x20 => {
+                let n = *self.stack.last()?;
+                self.push(n)?;
+            }
+            // POP
+            0x21 => {
+                self.pop()?;
+            }
+            // CLEAR
+            0x22 => self.stack.clear(),
+            // SWAP
+            0x23 => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                self.push(a)?;
+                self.push(b)?;
+            }
+            // DEPTH
+            0x24 => {
+                let n = self.stack.len() as i32;
+                self.push(n)?;
+            }
+            // CINDEX
+            0x25 => {
+                let k = usize_from(self.pop()?)?;
+                let index = self.stack.len().checked_sub(k)?;
+                let n = *self.stack.get(index)?;
+                self.push(n)?;
+            }
+            // MINDEX
+            0x26 => {
+                let k = usize_from(self.pop()?)?;
+                let index = self.stack.len().checked_sub(k)?;
+                if index >= self.stack.len() {
+                    return None;
+                }
+
+                let n = self.stack.remove(index);
+                self.push(n)?;
+            }
+            // ALIGNPTS
+            0x27 => {
+                let p2 = usize_from(self.pop()?)?;
+                let p1 = usize_from(self.pop()?)?;
+                let d = self.project(self.point(self.gs.gep0, p2)?, self.point(self.gs.gep1, p1)?) / 2;
+                self.move_point(self.gs.gep1, p1, d, true)?;
+                self.move_point(self.gs.gep0, p2, -d, true)?;
+            }
+            // UTP
+            0x29 => {
+                let point = usize_from(self.pop()?)?;
+                let mut mask = 0xFF;
+                if self.gs.free_vector.x != 0 {
+                    mask &= !FLAG_TOUCHED_X;
+                }
+
+                if self.gs.free_vector.y != 0 {
+                    mask &= !FLAG_TOUCHED_Y;
+                }
+
+                let zone = self.gs.gep0;
+                *self.zone_mut(zone).flags.get_mut(point)? &= mask;
+            }
+            // LOOPCALL
+            0x2A => {
+                let f = usize_from(self.pop()?)?;
+                let count = self.pop()?;
+                let definition = (*self.functions.get(f)?)?;
+                self.call(definition, count)?;
+            }
+            // CALL
+            0x2B => {
+                let f = usize_from(self.pop()?)?;
+                let definition = (*self.functions.get(f)?)?;
+                self.call(definition, 1)?;
+            }
+            // FDEF
+            0x2C => {
+                let f = usize_from(self.pop()?)?;
+                let definition = self.define()?;
+                *self.functions.get_mut(f)? = Some(definition);
+            }
+            // ENDF
+            0x2D => {
+                let mut frame = self.call_stack.pop()?;
+                if frame.count > 1 {
+                    frame.count -= 1;
+                    self.program = frame.definition.program;
+                    self.next_ip = frame.definition.start;
+                    self.call_stack.push(frame);
+                } else {
+                    self.program = frame.caller_program;
+                    self.next_ip = frame.caller_ip;
+                }
+            }
+            // MDAP
+            0x2E | 0x2F => {
+                let point = usize_from(self.pop()?)?;
+                let zone = self.gs.gep0;
+                let p = self.point(zone, point)?;
+                let distance = if opcode & 1 != 0 {
+                    let d = self.project(p, Point::default());
+                    self.round(d).wrapping_sub(d)
+                } else {
+                    0
+                };
+
+                self.move_point(zone, point, distance, true)?;
+                self.gs.rp0 = point;
+                self.gs.rp1 = point;
+            }
+            // IUP
+            0x30 | 0x31 => self.interpolate_untouched(opcode & 1 != 0),
+            // SHP
+            0x32 | 0x33 => {
+                let (dx, dy, _, _) = self.point_displacement()?;
+                while self.gs.loop_counter > 0 {
+                    let point = usize_from(self.pop()?)?;
+                    self.shift_point(self.gs.gep2, point, dx, dy, true)?;
+                    self.gs.loop_counter -= 1;
+                }
+
+                self.gs.loop_counter = 1;
+            }
+            // SHC
+            0x34 | 0x35 => {
+                let contour = usize_from(self.pop()?)?;
+                let (dx, dy, ref_zone, ref_point) = self.point_displacement()?;
+                let zone = self.gs.gep2;
+                let z = self.zone(zone);
+                let start = if contour == 0 {
+                    0
+                } else {
+                    usize::from(*z.contours.get(contour - 1)?) + 1
+                };
+                let end = usize::from(*z.contours.get(contour)?) + 1;
+                for i in start..end.min(z.len()) {
+                    if ref_zone != zone || ref_point != i {
+                        self.shift_point(zone, i, dx, dy, true)?;
+                    }
+                }
+            }
+            // SHZ
+            0x36 | 0x37 => {
+                let e = self.pop()?;
+                zone_from(e)?;
+                let (dx, dy, ref_zone, ref_point) = self.point_displacement()?;
+                let zone = self.gs.gep2;
+                let z = self.zone(zone);
+                // Phantom points are not moved.
+                let end = if zone == 0 {
+                    z.len()
+                } else {
+                    z.contours.last().map(|n| usize::from(*n) + 1).unwrap_or(0)
+                };
+
+                for i in 0..end.min(z.len()) {
+                    if ref_zone != zone || ref_point != i {
+                        self.shift_point(zone, i, dx, dy, false)?;
+                    }
+                }
+            }
+            // SHPIX
+            0x38 => {
+                let d = self.pop()?;
+                let dx = mul_fix14(d, self.gs.free_vector.x);
+                let dy = mul_fix14(d, self.gs.free_vector.y);
+                while self.gs.loop_counter > 0 {
+                    let point = usize_from(self.pop()?)?;
+                    self.shift_point(self.gs.gep2, point, dx, dy, true)?;
+                    self.gs.loop_counter -= 1;
+                }
+
+                self.gs.loop_counter = 1;
+            }
+            // IP
+            0x39 => self.interpolate_point()?,
+            // MSIRP
+            0x3A | 0x3B => {
+                let distance = self.pop()?;
+                let point = usize_from(self.pop()?)?;
+                let rp0 = self.gs.rp0;
+                if self.gs.gep1 == 0 {
+                    let org = self.org_point(self.gs.gep0, rp0)?;
+                    let zone = self.gs.gep1;
+                    *self.zone_mut(zone).org.get_mut(point)? = org;
+                    self.move_original(zone, point, distance)?;
+                    let z = self.zone_mut(zone);
+                    z.cur[point] = z.org[point];
+                }
+
+                let d = self.project(self.point(self.gs.gep1, point)?, self.point(self.gs.gep0, rp0)?);
+                self.move_point(self.gs.gep1, point, distance.wrapping_sub(d), true)?;
+                self.gs.rp1 = rp0;
+                self.gs.rp2 = point;
+                if opcode & 1 != 0 {
+                    self.gs.rp0 = point;
+                }
+            }
+            // ALIGNRP
+            0x3C => {
+                let rp0 = self.point(self.gs.gep0, self.gs.rp0)?;
+                while self.gs.loop_counter > 0 {
+                    let point = usize_from(self.pop()?)?;
+                    let d = self.project(self.point(self.gs.gep1, point)?, rp0);
+                    self.move_point(self.gs.gep1, point, d.wrapping_neg(), true)?;
+                    self.gs.loop_counter -= 1;
+                }
+
+                self.gs.loop_counter = 1;
+            }
+            // RTDG
+            0x3D => self.gs.round_state = RoundState::ToDoubleGrid,
+            // MIAP
+            0x3E | 0x3F => {
+                let cvt_index = self.pop()?;
+                let point = usize_from(self.pop()?)?;
+                let mut distance = self.read_cvt(cvt_index);
+                let zone = self.gs.gep0;
+                if zone == 0 {
+                    let p = Point {
+                        x: mul_fix14(distance, self.gs.free_vector.x),
+                        y: mul_fix14(distance, self.gs.free_vector.y),
+                    };
+                    let z = self.zone_mut(zone);
+                    *z.org.get_mut(point)? = p;
+                    z.cur[point] = p;
+                }
+
+                let org_dist = self.project(self.point(zone, point)?, Point::default());
+                if opcode & 1 != 0 {
+                    if (i64::from(distance) - i64::from(org_dist)).abs()
+                        > i64::from(self.gs.control_value_cutin)
+                    {
+                        distance = org_dist;
+                    }
+
+                    distance = self.round(distance);
+                }
+
+                self.move_point(zone, point, distance.wrapping_sub(org_dist), true)?;
+                self.gs.rp0 = point;
+                self.gs.rp1 = point;
+            }
+            // NPUSHB
+            0x40 => {
+                let code = self.code(self.program);
+                let n = usize::from(code[self.ip + 1]);
+                for i in 0..n {
+                    self.push(i32::from(code[self.ip + 2 + i]))?;
+                }
+            }
+            // NPUSHW
+            0x41 => {
+                let code = self.code(self.program);
+                let n = usize::from(code[self.ip + 1]);
+                for i in 0..n {
+                    let offset = self.ip + 2 + i * 2;
+                    self.push(i32::from(i16::from_be_bytes([code[offset], code[offset + 1]])))?;
+                }
+            }
+            // WS
+            0x42 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                // Out of bounds writes are ignored.
+                if let Some(n) = usize_from(index).and_then(|i| self.storage.get_mut(i)) {
+                    *n = value;
+                }
+            }
+            // RS
+            0x43 => {
+                let index = self.pop()?;
+                // Out of bounds reads return zero.
+                let n = usize_from(index).and_then(|i| self.storage.get(i)).copied().unwrap_or(0);
+                self.push(n)?;
+            }
+            // WCVTP
+            0x44 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                if let Some(n) = usize_from(index).and_then(|i| self.cvt.get_mut(i)) {
+                    *n = value;
+                }
+            }
+            // RCVT
+            0x45 => {
+                let index = self.pop()?;
+                let n = self.read_cvt(index);
+                self.push(n)?;
+            }
+            // GC
+            0x46 | 0x47 => {
+                let point = usize_from(self.pop()?)?;
+                let zone = self.gs.gep2;
+                let n = if opcode & 1 != 0 {
+                    self.dual_project(self.org_point(zone, point)?, Point::default())
+                } else {
+                    self.project(self.point(zone, point)?, Point::default())
+                };
+                self.push(n)?;
+            }
+            // SCFS
+            0x48 => {
+                let value = self.pop()?;
+                let point = usize_from(self.pop()?)?;
+                let zone = self.gs.gep2;
+                let k = self.project(self.point(zone, point)?, Point::default());
+                self.move_point(zone, point, value.wrapping_sub(k), true)?;
+                if zone == 0 {
+                    let z = self.zone_mut(zone);
+                    z.org[point] = z.cur[point];
+                }
+            }
+            // MD
+            0x49 | 0x4A => {
+                let k = usize_from(self.pop()?)?;
+                let l = usize_from(self.pop()?)?;
+                let d = if opcode & 1 != 0 {
+                    match (self.point(self.gs.gep0, l), self.point(self.gs.gep1, k)) {
+                        (Some(p1), Some(p2)) => self.project(p1, p2),
+                        _ => 0,
+                    }
+                } else if self.gs.gep0 == 0 || self.gs.gep1 == 0 {
+                    match (self.org_point(self.gs.gep0, l), self.org_point(self.gs.gep1, k)) {
+                        (Some(p1), Some(p2)) => self.dual_project(p1, p2),
+                        _ => 0,
+                    }
+                } else {
+                    match (self.orus_point(self.gs.gep0, l), self.orus_point(self.gs.gep1, k)) {
+                        (Some(p1), Some(p2)) => self.scale_funits(self.dual_project(p1, p2)),
+                        _ => 0,
+                    }
+                };
+                self.push(d)?;
+            }
+            // MPPEM, MPS
+            0x4B | 0x4C => self.push(self.ppem)?,
+            // FLIPON
+            0x4D => self.gs.auto_flip = true,
+            // FLIPOFF
+            0x4E => self.gs.auto_flip = false,
+            // DEBUG
+            0x4F => {
+                self.pop()?;
+            }
+            // LT, LTEQ, GT, GTEQ, EQ, NEQ
+            0x50..=0x55 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let n = match opcode {
+                    0x50 => a < b,
+                    0x51 => a <= b,
+                    0x52 => a > b,
+                    0x53 => a >= b,
+                    0x54 => a == b,
+                    _ => a != b,
+                };
+                self.push(n as i32)?;
+            }
+            // ODD
+            0x56 => {
+                let n = self.pop()?;
+                let n = self.round(n) & 127 == 64;
+                self.push(n as i32)?;
+            }
+            // EVEN
+            0x57 => {
+                let n = self.pop()?;
+                let n = self.round(n) & 127 == 0;
+                self.push(n as i32)?;
+            }
+            // IF
+            0x58 => {
+                if self.pop()? == 0 {
+                    self.skip_branch(true)?;
+                }
+            }
+            // EIF
+            0x59 => {}
+            // AND
+            0x5A => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push((a != 0 && b != 0) as i32)?;
+            }
+            // OR
+            0x5B => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push((a != 0 || b != 0) as i32)?;
+            }
+            // NOT
+            0x5C => {
+                let a = self.pop()?;
+                self.push((a == 0) as i32)?;
+            }
+            // DELTAP1, DELTAP2, DELTAP3
+            0x5D | 0x71 | 0x72 => {
+                let n = self.pop()?;
+                let base = match opcode {
+                    0x5D => 0,
+                    0x71 => 16,
+                    _ => 32,
+                };
+
+                for _ in 0..n {
+                    let point = self.pop()?;
+                    let arg = self.pop()?;
+                    if let Some(d) = self.delta_value(arg, base) {
+                        if let Some(point) = usize_from(point) {
+                            if point < self.zone(self.gs.gep0).len() {
+                                self.move_point(self.gs.gep0, point, d, true)?;
+                            }
+                        }
+                    }
+                }
+            }
+            // SDB
+            0x5E => self.gs.delta_base = self.pop()?,
+            // SDS
+            0x5F => {
+                let n = self.pop()?;
+                if !(0..=6).contains(&n) {
+                    return None;
+                }
+
+                self.gs.delta_shift = n;
+            }
+            // ADD
+            0x60 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(a.wrapping_add(b))?;
+            }
+            // SUB
+            0x61 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(a.wrapping_sub(b))?;
+            }
+            // DIV
+            0x62 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                if b == 0 {
+                    return None;
+                }
+
+                self.push(mul_div_no_round(a, 64, b))?;
+            }
+            // MUL
+            0x63 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(mul_div(a, b, 64))?;
+            }
+            // ABS
+            0x64 => {
+                let a = self.pop()?;
+                self.push(a.wrapping_abs())?;
+            }
+            // NEG
+            0x65 => {
+                let a = self.pop()?;
+                self.push(a.wrapping_neg())?;
+            }
+            // FLOOR
+            0x66 => {
+                let a = self.pop()?;
+                self.push(a & -64)?;
+            }
+            // CEILING
+            0x67 => {
+                let a = self.pop()?;
+                self.push(a.wrapping_add(63) & -64)?;
+            }
+            // ROUND
+            0x68..=0x6B => {
+                let a = self.pop()?;
+                let n = self.round(a);
+                self.push(n)?;
+            }
+            // NROUND
+            0x6C..=0x6F => {
+                // Engine compensation is always zero.
+            }
+            // WCVTF
+            0x70 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                let value = self.scale_funits(value);
+                if let Some(n) = usize_from(index).and_then(|i| self.cvt.get_mut(i)) {
+                    *n = value;
+                }
+            }
+            // DELTAC1, DELTAC2, DELTAC3
+            0x73..=0x75 => {
+                let n = self.pop()?;
+                let base = match opcode {
+                    0x73 => 0,
+                    0x74 => 16,
+                    _ => 32,
+                };
+
+                for _ in 0..n {
+                    let index = self.pop()?;
+                    let arg = self.pop()?;
+                    if let Some(d) = self.delta_value(arg, base) {
+                        if let Some(n) = usize_from(index).and_then(|i| self.cvt.get_mut(i)) {
+                            *n = n.wrapping_add(d);
+                        }
+                    }
+                }
+            }
+            // SROUND
+            0x76 => {
+                let n = self.pop()?;
+                self.set_super_round(0x4000, n);
+                self.gs.round_state = RoundState::Super;
+            }
+            // S45ROUND
+            0x77 => {
+                let n = self.pop()?;
+                self.set_super_round(0x2D41, n);
+                self.gs.round_state = RoundState::Super45;
+            }
+            // JROT, JROF
+            0x78 | 0x79 => {
+                let condition = self.pop()?;
+                let offset = self.pop()?;
+                if (condition != 0) == (opcode == 0x78) {
+                    self.jump(offset)?;
+                }
+            }
+            // ROFF
+            0x7A => self.gs.round_state = RoundState::Off,
+            // RUTG
+            0x7C => self.gs.round_state = RoundState::UpToGrid,
+            // RDTG
+            0x7D => self.gs.round_state = RoundState::DownToGrid,
+            // SANGW, AA
+            0x7E | 0x7F => {
+                self.pop()?;
+            }
+            // FLIPPT
+            0x80 => {
+                while self.gs.loop_counter > 0 {
+                    let point = usize_from(self.pop()?)?;
+                    *self.glyph.flags.get_mut(point)? ^= FLAG_ON_CURVE;
+                    self.gs.loop_counter -= 1;
+                }
+
+                self.gs.loop_counter = 1;
+            }
+            // FLIPRGON, FLIPRGOFF
+            0x81 | 0x82 => {
+                let end = usize_from(self.pop()?)?;
+                let start = usize_from(self.pop()?)?;
+                if end >= self.glyph.len() || start >= self.glyph.len() {
+                    return None;
+                }
+
+                for i in start..=end {
+                    if opcode == 0x81 {
+                        self.glyph.flags[i] |= FLAG_ON_CURVE;
+                    } else {
+                        self.glyph.flags[i] &= !FLAG_ON_CURVE;
+                    }
+                }
+            }
+            // SCANCTRL, SCANTYPE
+            0x85 | 0x8D => {
+                // We do not perform scan conversion.
+                self.pop()?;
+            }
+            // SDPVTL
+            0x86 | 0x87 => {
+                let p1 = usize_from(self.pop()?)?;
+                let p2 = usize_from(self.pop()?)?;
+
+                let mut perpendicular = opcode & 1 != 0;
+                let v1 = self.org_point(self.gs.gep1, p2)?;
+                let v2 = self.org_point(self.gs.gep2, p1)?;
+                let mut a = v1.x.wrapping_sub(v2.x);
+                let mut b = v1.y.wrapping_sub(v2.y);
+                if a == 0 && b == 0 {
+                    a = 0x4000;
+                    perpendicular = false;
+                }
+
+                if perpendicular {
+                    let c = b;
+                    b = a;
+                    a = c.wrapping_neg();
+                }
+
+                let (x, y) = normalize(a, b)?;
+                self.gs.dual_vector = Vector { x, y };
+
+                let v1 = self.point(self.gs.gep1, p2)?;
+                let v2 = self.point(self.gs.gep2, p1)?;
+                let mut a = v1.x.wrapping_sub(v2.x);
+                let mut b = v1.y.wrapping_sub(v2.y);
+                if a == 0 && b == 0 {
+                    a = 0x4000;
+                    perpendicular = false;
+                }
+
+                if perpendicular {
+                    let c = b;
+                    b = a;
+                    a = c.wrapping_neg();
+                }
+
+                let (x, y) = normalize(a, b)?;
+                self.gs.proj_vector = Vector { x, y };
+                self.compute_f_dot_p();
+            }
+            // GETINFO
+            0x88 => {
+                let selector = self.pop()?;
+                let mut n = 0;
+                if selector & 1 != 0 {
+                    n = 35; // Interpreter version.
+                }
+
+                self.push(n)?;
+            }
+            // IDEF
+            0x89 => {
+                let op = self.pop()?;
+                let definition = self.define()?;
+                let op = op as u8;
+                if let Some(d) = self.instructions.iter_mut().find(|d| d.0 == op) {
+                    d.1 = definition;
+                } else {
+                    self.instructions.push((op, definition));
+                }
+            }
+            // ROLL
+            0x8A => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                let c = self.pop()?;
+                self.push(b)?;
+                self.push(a)?;
+                self.push(c)?;
+            }
+            // MAX
+            0x8B => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(a.max(b))?;
+            }
+            // MIN
+            0x8C => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(a.min(b))?;
+            }
+            // INSTCTRL
+            0x8E => {
+                let selector = self.pop()?;
+                let value = self.pop()?;
+                if !(1..=3).contains(&selector) {
+                    return None;
+                }
+
+                // Allowed only in the `prep` program.
+                if self.program == Program::ControlValue {
+                    let k = 1 << (selector - 1);
+                    let l = if value != 0 { k } else { 0 };
+                    self.gs.instruct_control = (self.gs.instruct_control & !k) | l;
+                }
+            }
+            // PUSHB
+            0xB0..=0xB7 => {
+                let code = self.code(self.program);
+                let n = usize::from(opcode - 0xB0 + 1);
+                for i in 0..n {
+                    self.push(i32::from(code[self.ip + 1 + i]))?;
+                }
+            }
+            // PUSHW
+            0xB8..=0xBF => {
+                let code = self.code(self.program);
+                let n = usize::from(opcode - 0xB8 + 1);
+                for i in 0..n {
+                    let offset = self.ip + 1 + i * 2;
+                    self.push(i32::from(i16::from_be_bytes([code[offset], code[offset + 1]])))?;
+                }
+            }
+            // MDRP
+            0xC0..=0xDF => self.move_direct_relative_point()?,
+            // MIRP
+            0xE0..=0xFF => self.move_indirect_relative_point()?,
+            _ => {
+                // An instruction defined by IDEF.
+                let definition = self.instructions.iter().find(|d| d.0 == opcode)?.1;
+                self.call(definition, 1)?;
+            }
+        }
+
+        Some(())
+    }
+
+    fn jump(&mut self, offset: i32) -> Option<()> {
+        let ip = self.ip as i64 + i64::from(offset);
+        if ip < 0 {
+            return None;
+        }
+
+        self.next_ip = ip as usize;
+        Some(())
+    }
+
+    fn delta_value(&self, arg: i32, base: i32) -> Option<i32> {
+        // `delta_base` can be set to any value by SDB.
+        let ppem = ((arg & 0xF0) >> 4).wrapping_add(base).wrapping_add(self.gs.delta_base);
+        if ppem != self.ppem {
+            return None;
+        }
+
+        let mut step = (arg & 0xF) - 8;
+        if step >= 0 {
+            step += 1;
+        }
+
+        Some(step * (1 << (6 - self.gs.delta_shift)))
+    }
+
+    fn interpolate_point(&mut self) -> Option<()> {
+        let twilight = self.gs.gep0 == 0 || self.gs.gep1 == 0 || self.gs.gep2 == 0;
+        let rp1 = self.gs.rp1;
+        let rp2 = self.gs.rp2;
+
+        let base = (
+            if twilight {
+                self.org_point(self.gs.gep0, rp1)
+            } else {
+                self.orus_point(self.gs.gep0, rp1)
+            },
+            self.point(self.gs.gep0, rp1),
+        );
+
+        let (old_range, cur_range) = match base {
+            (Some(orus_base), Some(cur_base)) => {
+                let rp2_orus = if twilight {
+                    self.org_point(self.gs.gep1, rp2)
+                } else {
+                    self.orus_point(self.gs.gep1, rp2)
+                };
+
+                match (rp2_orus, self.point(self.gs.gep1, rp2)) {
+                    (Some(orus), Some(cur)) => {
+                        (self.dual_project(orus, orus_base), self.project(cur, cur_base))
+                    }
+                    _ => (0, 0),
+                }
+            }
+            _ => (0, 0),
+        };
+
+        let orus_base = base.0.unwrap_or_default();
+        let cur_base = base.1.unwrap_or_default();
+
+        while self.gs.loop_counter > 0 {
+            let point = usize_from(self.pop()?)?;
+            let zone = self.gs.gep2;
+            let org_dist = if twilight {
+                self.dual_project(self.org_point(zone, point)?, orus_base)
+            } else {
+                self.dual_project(self.orus_point(zone, point)?, orus_base)
+            };
+            let cur_dist = self.project(self.point(zone, point)?, cur_base);
+
+            let new_dist = if org_dist != 0 {
+                if old_range != 0 {
+                    mul_div(org_dist, cur_range, old_range)
+                } else {
+                    cur_dist
+                }
+            } else {
+                0
+            };
+
+            self.move_point(zone, point, new_dist.wrapping_sub(cur_dist), true)?;
+            self.gs.loop_counter -= 1;
+        }
+
+        self.gs.loop_counter = 1;
+        Some(())
+    }
+
+    fn move_direct_relative_point(&mut self) -> Option<()> {
+        let opcode = self.opcode;
+        let point = usize_from(self.pop()?)?;
+        let rp0 = self.gs.rp0;
+
+        let mut org_dist = if self.gs.gep0 == 0 || self.gs.gep1 == 0 {
+            self.dual_project(self.org_point(self.gs.gep1, point)?, self.org_point(self.gs.gep0, rp0)?)
+        } else {
+            let d = self.dual_project(
+                self.orus_point(self.gs.gep1, point)?,
+                self.orus_point(self.gs.gep0, rp0)?,
+            );
+            self.scale_funits(d)
+        };
+
+        let swv = self.gs.single_width_value;
+        let swci = self.gs.single_width_cutin;
+        if swci > 0 && org_dist < swv.wrapping_add(swci) && org_dist > swv.wrapping_sub(swci) {
+            org_dist = if org_dist >= 0 { swv } else { swv.wrapping_neg() };
+        }
+
+        let mut distance = if opcode & 4 != 0 {
+            self.round(org_dist)
+        } else {
+            org_dist
+        };
+
+        if opcode & 8 != 0 {
+            distance = self.apply_min_distance(org_dist, distance);
+        }
+
+        let cur_dist = self.project(self.point(self.gs.gep1, point)?, self.point(self.gs.gep0, rp0)?);
+        self.move_point(self.gs.gep1, point, distance.wrapping_sub(cur_dist), true)?;
+
+        self.gs.rp1 = rp0;
+        self.gs.rp2 = point;
+        if opcode & 16 != 0 {
+            self.gs.rp0 = point;
+        }
+
+        Some(())
+    }
+
+    fn move_indirect_relative_point(&mut self) -> Option<()> {
+        let opcode = self.opcode;
+        let cvt_index = self.pop()?;
+        let point = usize_from(self.pop()?)?;
+        let rp0 = self.gs.rp0;
+
+        // cvt[-1] is always zero.
+        let mut cvt_dist = if cvt_index == -1 { 0 } else { self.read_cvt(cvt_index) };
+
+        let swv = self.gs.single_width_value;
+        if (i64::from(cvt_dist) - i64::from(swv)).abs() < i64::from(self.gs.single_width_cutin) {
+            cvt_dist = if cvt_dist >= 0 { swv } else { swv.wrapping_neg() };
+        }
+
+        if self.gs.gep1 == 0 {
+            let rp0_org = self.org_point(self.gs.gep0, rp0)?;
+            let p = Point {
+                x: rp0_org.x.wrapping_add(mul_fix14(cvt_dist, self.gs.free_vector.x)),
+                y: rp0_org.y.wrapping_add(mul_fix14(cvt_dist, self.gs.free_vector.y)),
+            };
+            let z = self.zone_mut(0);
+            *z.org.get_mut(point)? = p;
+            z.cur[point] = p;
+        }
+
+        let org_dist = self.dual_project(self.org_point(self.gs.gep1, point)?, self.org_point(self.gs.gep0, rp0)?);
+        let cur_dist = self.project(self.point(self.gs.gep1, point)?, self.point(self.gs.gep0, rp0)?);
+
+        if self.gs.auto_flip && (org_dist ^ cvt_dist) < 0 {
+            cvt_dist = cvt_dist.wrapping_neg();
+        }
+
+        let mut distance = if opcode & 4 != 0 {
+            // Cut-in test is performed only when both points are in the same zone.
+            if self.gs.gep0 == self.gs.gep1
+                && (i64::from(cvt_dist) - i64::from(org_dist)).abs() > i64::from(self.gs.control_value_cutin)
+            {
+                cvt_dist = org_dist;
+            }
+
+            self.round(cvt_dist)
+        } else {
+            cvt_dist
+        };
+
+        if opcode & 8 != 0 {
+            distance = self.apply_min_distance(org_dist, distance);
+        }
+
+        self.move_point(self.gs.gep1, point, distance.wrapping_sub(cur_dist), true)?;
+
+        self.gs.rp1 = rp0;
+        self.gs.rp2 = point;
+        if opcode & 16 != 0 {
+            self.gs.rp0 = point;
+        }
+
+        Some(())
+    }
+
+    #[inline]
+    fn apply_min_distance(&self, org_dist: i32, distance: i32) -> i32 {
+        let min = self.gs.min_distance;
+        if org_dist >= 0 {
+            if distance < min { min } else { distance }
+        } else if distance > min.wrapping_neg() {
+            min.wrapping_neg()
+        } else {
+            distance
+        }
+    }
+
+    fn interpolate_untouched(&mut self, x_axis: bool) {
+        let mask = if x_axis { FLAG_TOUCHED_X } else { FLAG_TOUCHED_Y };
+        let z = &mut self.glyph;
+        // Phantom points are not interpolated.
+        let points_count = z.len().saturating_sub(4);
+
+        let mut point = 0;
+        for contour in 0..z.contours.len() {
+            let end = usize::from(z.contours[contour]);
+            if end >= points_count || point > end {
+                break;
+            }
+
+            let first_point = point;
+            while point <= end && z.flags[point] & mask == 0 {
+                point += 1;
+            }
+
+            if point <= end {
+                let first_touched = point;
+                let mut cur_touched = point;
+                point += 1;
+
+                while point <= end {
+                    if z.flags[point] & mask != 0 {
+                        iup_interpolate(z, x_axis, cur_touched + 1, point - 1, cur_touched, point);
+                        cur_touched = point;
+                    }
+
+                    point += 1;
+                }
+
+                if cur_touched == first_touched {
+                    iup_shift(z, x_axis, first_point, end, cur_touched);
+                } else {
+                    iup_interpolate(z, x_axis, cur_touched + 1, end, cur_touched, first_touched);
+                    if first_touched > first_point {
+                        iup_interpolate(z, x_axis, first_point, first_touched - 1, cur_touched, first_touched);
+                    }
+                }
+            }
+
+            point = end + 1;
+        }
+    }
+}
+
+#[inline]
+fn coord(p: &Point, x_axis: bool) -> i32 {
+    if x_axis { p.x } else { p.y }
+}
+
+#[inline]
+fn coord_mut(p: &mut Point, x_axis: bool) -> &mut i32 {
+    if x_axis { &mut p.x } else { &mut p.y }
+}
+
+fn iup_shift(z: &mut Zone, x_axis: bool, p1: usize, p2: usize, reference: usize) {
+    let delta = coord(&z.cur[reference], x_axis).wrapping_sub(coord(&z.org[reference], x_axis));
+    if delta == 0 {
+        return;
+    }
+
+    for i in p1..=p2 {
+        if i != reference {
+            let c = coord_mut(&mut z.cur[i], x_axis);
+            *c = c.wrapping_add(delta);
+        }
+    }
+}
+
+fn iup_interpolate(
+    z: &mut Zone,
+    x_axis: bool,
+    p1: usize,
+    p2: usize,
+    mut ref1: usize,
+    mut ref2: usize,
+) {
+    if p1 > p2 {
+        return;
+    }
+
+    let mut orus1 = coord(&z.orus[ref1], x_axis);
+    let mut orus2 = coord(&z.orus[ref2], x_axis);
+    if orus1 > orus2 {
+        core::mem::swap(&mut orus1, &mut orus2);
+        core::mem::swap(&mut ref1, &mut ref2);
+    }
+
+    let org1 = coord(&z.org[ref1], x_axis);
+    let org2 = coord(&z.org[ref2], x_axis);
+    let cur1 = coord(&z.cur[ref1], x_axis);
+    let cur2 = coord(&z.cur[ref2], x_axis);
+    let delta1 = cur1.wrapping_sub(org1);
+    let delta2 = cur2.wrapping_sub(org2);
+
+    if cur1 == cur2 || orus1 == orus2 {
+        for i in p1..=p2 {
+            let x = coord(&z.org[i], x_axis);
+            let x = if x <= org1 {
+                x.wrapping_add(delta1)
+            } else if x >= org2 {
+                x.wrapping_add(delta2)
+            } else {
+                cur1
+            };
+
+            *coord_mut(&mut z.cur[i], x_axis) = x;
+        }
+    } else {
+        let scale = div_fix(cur2.wrapping_sub(cur1), orus2.wrapping_sub(orus1));
+        for i in p1..=p2 {
+            let x = coord(&z.org[i], x_axis);
+            let x = if x <= org1 {
+                x.wrapping_add(delta1)
+            } else if x >= org2 {
+                x.wrapping_add(delta2)
+            } else {
+                let orus = coord(&z.orus[i], x_axis);
+                cur1.wrapping_add(mul_fix(orus.wrapping_sub(orus1), scale))
+            };
+
+            *coord_mut(&mut z.cur[i], x_axis) = x;
+        }
+    }
+}
+
+/// Returns the instruction length, including inline data.
+fn opcode_len(code: &[u8], ip: usize) -> Option<usize> {
+    let opcode = *code.get(ip)?;
+    let len = match opcode {
+        0x40 => 2 + usize::from(*code.get(ip + 1)?), // NPUSHB
+        0x41 => 2 + usize::from(*code.get(ip + 1)?) * 2, // NPUSHW
+        0xB0..=0xB7 => 1 + usize::from(opcode - 0xB0 + 1), // PUSHB
+        0xB8..=0xBF => 1 + usize::from(opcode - 0xB8 + 1) * 2, // PUSHW
+        _ => 1,
+    };
+
+    // Make sure that inline data is not truncated.
+    if ip + len > code.len() {
+        return None;
+    }
+
+    Some(len)
+}
+
+#[inline]
+fn usize_from(n: i32) -> Option<usize> {
+    if n >= 0 { Some(n as usize) } else { None }
+}
+
+#[inline]
+fn zone_from(n: i32) -> Option<u8> {
+    match n {
+        0 => Some(0),
+        1 => Some(1),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exec(fpgm: &[u8]) -> Option<Exec> {
+        let mut exec = Exec::new(fpgm, &[], 16, 4, 4, 4);
+        exec.ppem = 12;
+        exec.run(Program::Font)?;
+        Some(exec)
+    }
+
+    #[test]
+    fn arithmetic() {
+        let exec = exec(&[
+            0xB2, 0x00, 0x40, 0x80, // PUSHB[2] 0 64 128
+            0x60, // ADD
+            0x42, // WS
+        ]).unwrap();
+        assert_eq!(exec.storage[0], 192);
+    }
+
+    #[test]
+    fn function_call() {
+        let exec = exec(&[
+            0xB0, 0x01, // PUSHB[0] 1
+            0x2C, // FDEF
+            0xB1, 0x02, 0x07, // PUSHB[1] 2 7
+            0x42, // WS
+            0x2D, // ENDF
+            0xB0, 0x01, // PUSHB[0] 1
+            0x2B, // CALL
+        ]).unwrap();
+        assert_eq!(exec.storage[2], 7);
+    }
+
+    #[test]
+    fn if_else() {
+        let exec = exec(&[
+            0xB0, 0x00, // PUSHB[0] 0
+            0x58, // IF
+            0xB1, 0x01, 0x01, // PUSHB[1] 1 1
+            0x42, // WS
+            0x1B, // ELSE
+            0xB1, 0x01, 0x02, // PUSHB[1] 1 2
+            0x42, // WS
+            0x59, // EIF
+        ]).unwrap();
+        assert_eq!(exec.storage[1], 2);
+    }
+
+    #[test]
+    fn stack_underflow() {
+        assert!(exec(&[0x21]).is_none()); // POP
+    }
+
+    #[test]
+    fn truncated_push() {
+        assert!(exec(&[0xB1, 0x01]).is_none()); // PUSHB[1] 1
+    }
+
+    #[test]
+    fn hostile_values() {
+        let mut prep = std::vec![
+            0xB8, 0x80, 0x00, // PUSHW[0] -32768
+        ];
+        // Doubles the value 16 times, producing i32::MIN.
+        for _ in 0..16 {
+            prep.extend_from_slice(&[0x20, 0x60]); // DUP ADD
+        }
+
+        prep.extend_from_slice(&[
+            0x20, 0x68, 0x21, // DUP ROUND[0] POP (to grid)
+            0x3D, 0x20, 0x68, 0x21, // RTDG DUP ROUND[0] POP
+            0x19, 0x20, 0x68, 0x21, // RTHG DUP ROUND[0] POP
+            0x7D, 0x20, 0x68, 0x21, // RDTG DUP ROUND[0] POP
+            0x7C, 0x20, 0x68, 0x21, // RUTG DUP ROUND[0] POP
+            0xB0, 0x48, 0x76, 0x20, 0x68, 0x21, // PUSHB[0] 72 SROUND DUP ROUND[0] POP
+            0xB0, 0x48, 0x77, 0x20, 0x68, 0x21, // PUSHB[0] 72 S45ROUND DUP ROUND[0] POP
+            0x20, 0x5E, // DUP SDB
+            0xB2, 0xF0, 0x00, 0x01, // PUSHB[2] 240 0 1
+            0x73, // DELTAC1
+            0xB0, 0x01, 0x61, // PUSHB[0] 1 SUB (i32::MAX)
+            0x18, 0x20, 0x68, 0x21, // RTG DUP ROUND[0] POP
+            0x5E, // SDB
+            0xB2, 0xF0, 0x00, 0x01, // PUSHB[2] 240 0 1
+            0x73, // DELTAC1
+        ]);
+
+        let mut exec = Exec::new(&[], &prep, 16, 4, 4, 4);
+        exec.ppem = 12;
+        exec.cvt = std::vec![64];
+        assert!(exec.run(Program::ControlValue).is_some());
+        assert_eq!(exec.cvt[0], 64);
+    }
+
+    #[test]
+    fn infinite_loop() {
+        assert!(exec(&[
+            0xB8, 0xFF, 0xFD, // PUSHW[0] -3
+            0x1C, // JMPR
+        ]).is_none());
+    }
+}
@@ -0,0 +1,126 @@
+// Fixed-point arithmetic used by the interpreter.
+//
+// 26.6 is used for coordinates, 16.16 for scales and 2.14 for unit vectors.
+// All functions are saturating, since fonts can produce arbitrary values.
+
+const I32_MIN: i32 = -0x8000_0000;
+const I32_MAX: i32 = 0x7FFF_FFFF;
+
+#[inline]
+fn saturate(n: i64) -> i32 {
+    n.max(i64::from(I32_MIN)).min(i64::from(I32_MAX)) as i32
+}
+
+/// Computes `a * b / c` with rounding.
+#[inline]
+pub fn mul_div(a: i32, b: i32, c: i32) -> i32 {
+    if c == 0 {
+        return if (a ^ b) < 0 { I32_MIN } else { I32_MAX };
+    }
+
+    let n = i64::from(a) * i64::from(b);
+    let c = i64::from(c);
+    let q = (n.abs() + c.abs() / 2) / c.abs();
+    if (n < 0) != (c < 0) {
+        saturate(-q)
+    } else {
+        saturate(q)
+    }
+}
+
+/// Computes `a * b / c` without rounding.
+#[inline]
+pub fn mul_div_no_round(a: i32, b: i32, c: i32) -> i32 {
+    if c == 0 {
+        return if (a ^ b) < 0 { I32_MIN } else { I32_MAX };
+    }
+
+    saturate(i64::from(a) * i64::from(b) / i64::from(c))
+}
+
+/// Multiplies by a 16.16 value.
+#[inline]
+pub fn mul_fix(a: i32, b: i32) -> i32 {
+    mul_div(a, b, 0x10000)
+}
+
+/// Divides producing a 16.16 value.
+#[inline]
+pub fn div_fix(a: i32, b: i32) -> i32 {
+    mul_div(a, 0x10000, b)
+}
+
+/// Multiplies by a 2.14 value.
+#[inline]
+pub fn mul_fix14(a: i32, b: i32) -> i32 {
+    mul_div(a, b, 0x4000)
+}
+
+/// Computes a dot product with a 2.14 vector.
+#[inline]
+pub fn dot_fix14(ax: i32, ay: i32, bx: i32, by: i32) -> i32 {
+    let n = i64::from(ax) * i64::from(bx) + i64::from(ay) * i64::from(by);
+    saturate((n + 0x2000 - i64::from(n < 0)) >> 14)
+}
+
+/// Normalizes a vector to a 2.14 unit vector.
+///
+/// Returns `None` for a zero vector.
+pub fn normalize(x: i32, y: i32) -> Option<(i32, i32)> {
+    if x == 0 && y == 0 {
+        return None;
+    }
+
+    // Length in 2.14 units.
+    let x = i128::from(x);
+    let y = i128::from(y);
+    let len = isqrt(((x * x + y * y) as u128) << 28) as i128;
+    if len == 0 {
+        return None;
+    }
+
+    let nx = ((x << 28) + if x < 0 { -len / 2 } else { len / 2 }) / len;
+    let ny = ((y << 28) + if y < 0 { -len / 2 } else { len / 2 }) / len;
+    Some((nx as i32, ny as i32))
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 4 {
+        return u128::from(n != 0);
+    }
+
+    // Newton's method.
+    let mut x = n;
+    let mut y = x / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+
+    x
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounding() {
+        assert_eq!(mul_div(3, 1, 2), 2);
+        assert_eq!(mul_div(-3, 1, 2), -2);
+        assert_eq!(mul_div_no_round(3, 1, 2), 1);
+        assert_eq!(mul_fix(64, 0x8000), 32);
+        assert_eq!(div_fix(1, 2), 0x8000);
+        assert_eq!(mul_fix14(64, 0x2000), 32);
+        assert_eq!(dot_fix14(64, 64, 0x4000, 0), 64);
+    }
+
+    #[test]
+    fn normalization() {
+        assert_eq!(normalize(10, 0), Some((0x4000, 0)));
+        assert_eq!(normalize(0, -3), Some((0, -0x4000)));
+        assert_eq!(normalize(1, 1), Some((0x2D41, 0x2D41)));
+        assert_eq!(normalize(0, 0), None);
+    }
+}
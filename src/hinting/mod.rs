@@ -0,0 +1,374 @@
+//! A TrueType bytecode interpreter.
+
+use core::num::NonZeroU16;
+use std::vec::Vec;
+
+use crate::{FaceTables, GlyphId, OutlineBuilder, BBox};
+use crate::parser::Stream;
+use crate::tables::glyf::{self, Transform, CompositeGlyphIter, MAX_COMPONENTS};
+
+mod interpreter;
+mod math;
+
+use interpreter::{Exec, GraphicsState, Point, Program, Zone, FLAG_ON_CURVE};
+use math::*;
+
+
+#[derive(Clone, Default, Debug)]
+struct Outline {
+    points: Vec<Point>,
+    flags: Vec<u8>,
+    contours: Vec<u16>,
+    /// Left/right and top/bottom phantom points.
+    phantoms: [Point; 4],
+}
+
+
+/// A TrueType glyph hinter.
+///
+/// Executes `fpgm`, `prep` and glyph programs, emulating FreeType's v35 interpreter.
+///
+/// The font and control value programs are executed once, on creation.
+/// Glyph programs are executed per `outline_glyph` call.
+///
+/// Variation axes are ignored. Available only with the `hinting` feature.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{Face, GlyphId, Hinter, OutlineBuilder};
+///
+/// struct Dummy;
+/// impl OutlineBuilder for Dummy {
+///     fn move_to(&mut self, _: f32, _: f32) {}
+///     fn line_to(&mut self, _: f32, _: f32) {}
+///     fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+///     fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+///     fn close(&mut self) {}
+/// }
+///
+/// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+/// let face = Face::from_slice(&data, 0).unwrap();
+/// let mut hinter = Hinter::new(&face, 16).unwrap();
+/// assert!(hinter.outline_glyph(GlyphId(1), &mut Dummy).is_some());
+/// ```
+pub struct Hinter<'a> {
+    tables: FaceTables<'a>,
+    exec: Exec<'a>,
+    cvt: Vec<i32>,
+    storage: Vec<i32>,
+    twilight: Zone,
+}
+
+impl<'a> Hinter<'a> {
+    /// Creates a new `Hinter` for the specified pixels per em.
+    ///
+    /// Returns `None` when:
+    ///
+    /// - face doesn't have `glyf`, `loca` or version 1.0 `maxp` tables
+    /// - `ppem` is zero
+    /// - `fpgm` or `prep` programs are malformed
+    pub fn new(face: &FaceTables<'a>, ppem: u16) -> Option<Self> {
+        face.glyf?;
+        face.loca?;
+
+        if ppem == 0 {
+            return None;
+        }
+
+        let profile = face.maximum_profile()?;
+        let units_per_em = i32::from(face.units_per_em()?);
+
+        let mut exec = Exec::new(
+            face.font_program().unwrap_or(&[]),
+            face.control_value_program().unwrap_or(&[]),
+            profile.max_stack_elements,
+            profile.max_storage,
+            profile.max_function_defs,
+            // Plus four phantom points.
+            profile.max_twilight_points.saturating_add(4),
+        );
+
+        exec.ppem = i32::from(ppem);
+        exec.scale = div_fix(i32::from(ppem) * 64, units_per_em);
+        if let Some(cvt) = face.control_values() {
+            exec.cvt = cvt.into_iter().map(|n| mul_fix(i32::from(n), exec.scale)).collect();
+        }
+
+        exec.run(Program::Font)?;
+        exec.run(Program::ControlValue)?;
+
+        // `prep` can modify the default graphics state,
+        // unless the second bit of `INSTCTRL` is set.
+        exec.default_gs = if exec.gs.instruct_control & 2 != 0 {
+            let mut gs = GraphicsState::default();
+            gs.instruct_control = exec.gs.instruct_control;
+            gs
+        } else {
+            exec.gs
+        };
+
+        Some(Hinter {
+            tables: face.clone(),
+            cvt: exec.cvt.clone(),
+            storage: exec.storage.clone(),
+            twilight: exec.twilight.clone(),
+            exec,
+        })
+    }
+
+    /// Returns the pixels per em this hinter was created for.
+    #[inline]
+    pub fn ppem(&self) -> u16 {
+        self.exec.ppem as u16
+    }
+
+    /// Outlines a hinted glyph.
+    ///
+    /// Unlike `Face::outline_glyph`, coordinates are in pixels and not in font units.
+    /// The outline is positioned so the left phantom point is at the origin.
+    ///
+    /// Glyph programs errors are ignored, like in FreeType,
+    /// so the outline can be partially hinted.
+    ///
+    /// Returns `None` when glyph has no outline or on error.
+    pub fn outline_glyph(&mut self, glyph_id: GlyphId, builder: &mut dyn OutlineBuilder) -> Option<()> {
+        let outline = self.load_glyph(glyph_id, 0)?;
+        if outline.points.is_empty() {
+            return None;
+        }
+
+        let dx = outline.phantoms[0].x;
+        let mut b = glyf::Builder::new(Transform::default(), BBox::new(), builder);
+        let mut start = 0;
+        for end in &outline.contours {
+            let end = usize::from(*end);
+            for i in start..=end {
+                let p = outline.points[i];
+                b.push_point(
+                    (p.x - dx) as f32 / 64.0,
+                    p.y as f32 / 64.0,
+                    outline.flags[i] & FLAG_ON_CURVE != 0,
+                    i == end,
+                );
+            }
+
+            start = end + 1;
+        }
+
+        Some(())
+    }
+
+    fn load_glyph(&mut self, glyph_id: GlyphId, depth: u8) -> Option<Outline> {
+        if depth >= MAX_COMPONENTS {
+            return None;
+        }
+
+        let range = self.tables.loca?.glyph_range(glyph_id)?;
+        let data = self.tables.glyf?.get(range)?;
+
+        let mut s = Stream::new(data);
+        let number_of_contours: i16 = s.read().unwrap_or(0);
+        let x_min: i16 = s.read().unwrap_or(0);
+        s.skip::<i16>(); // y_min
+        s.skip::<i16>(); // x_max
+        let y_max: i16 = s.read().unwrap_or(0);
+
+        let phantoms = self.phantom_points(glyph_id, x_min, y_max);
+
+        if number_of_contours > 0 {
+            // u16 casting is safe, since we already checked that the value is positive.
+            let number_of_contours = NonZeroU16::new(number_of_contours as u16)?;
+            let mut zone = Zone::default();
+            for point in glyf::parse_simple_outline(s.tail()?, number_of_contours)? {
+                zone.orus.push(Point { x: i32::from(point.x), y: i32::from(point.y) });
+                zone.flags.push(if point.on_curve_point { FLAG_ON_CURVE } else { 0 });
+                if point.last_point {
+                    zone.contours.push(zone.orus.len() as u16 - 1);
+                }
+            }
+
+            zone.orus.extend_from_slice(&phantoms);
+            zone.flags.extend_from_slice(&[0; 4]);
+            let scale = self.exec.scale;
+            zone.org = zone.orus.iter()
+                .map(|p| Point { x: mul_fix(p.x, scale), y: mul_fix(p.y, scale) })
+                .collect();
+
+            let instructions = glyf::glyph_instructions(self.tables.loca?, self.tables.glyf?, glyph_id);
+            Some(self.hint(zone, instructions.unwrap_or(&[]), scale))
+        } else if number_of_contours < 0 {
+            let mut outline = Outline::default();
+            let scale = self.exec.scale;
+            outline.phantoms = [
+                Point { x: mul_fix(phantoms[0].x, scale), y: 0 },
+                Point { x: mul_fix(phantoms[1].x, scale), y: 0 },
+                Point { x: 0, y: mul_fix(phantoms[2].y, scale) },
+                Point { x: 0, y: mul_fix(phantoms[3].y, scale) },
+            ];
+
+            let mut iter = CompositeGlyphIter::new(s.tail()?);
+            for comp in iter.by_ref() {
+                let mut child = match self.load_glyph(comp.glyph_id, depth + 1) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                let ts = comp.transform;
+                let has_transform = ts.a != 1.0 || ts.b != 0.0 || ts.c != 0.0 || ts.d != 1.0;
+                if has_transform {
+                    for p in &mut child.points {
+                        let x = p.x as f32;
+                        let y = p.y as f32;
+                        p.x = round_f32(ts.a * x + ts.c * y);
+                        p.y = round_f32(ts.b * x + ts.d * y);
+                    }
+                }
+
+                let (dx, dy) = if let Some((parent, child_point)) = comp.point_numbers {
+                    let p1 = *outline.points.get(usize::from(parent))?;
+                    let p2 = *child.points.get(usize::from(child_point))?;
+                    (p1.x.wrapping_sub(p2.x), p1.y.wrapping_sub(p2.y))
+                } else {
+                    let mut dx = mul_fix(round_f32(ts.e), scale);
+                    let mut dy = mul_fix(round_f32(ts.f), scale);
+                    if comp.flags.round_xy_to_grid() {
+                        dx = (dx + 32) & -64;
+                        dy = (dy + 32) & -64;
+                    }
+
+                    (dx, dy)
+                };
+
+                if comp.flags.use_my_metrics() {
+                    outline.phantoms = child.phantoms;
+                }
+
+                let offset = outline.points.len() as u16;
+                for p in &child.points {
+                    outline.points.push(Point { x: p.x.wrapping_add(dx), y: p.y.wrapping_add(dy) });
+                }
+
+                outline.flags.extend_from_slice(&child.flags);
+                outline.contours.extend(child.contours.iter().map(|n| n.wrapping_add(offset)));
+            }
+
+            match iter.instructions() {
+                Some(instructions) if !instructions.is_empty() && !outline.points.is_empty() => {
+                    // Composite glyph programs operate on already hinted points.
+                    let mut points = outline.points;
+                    points.extend_from_slice(&outline.phantoms);
+                    let mut flags: Vec<u8> = outline.flags.iter().map(|f| f & FLAG_ON_CURVE).collect();
+                    flags.extend_from_slice(&[0; 4]);
+                    let zone = Zone {
+                        orus: points.clone(),
+                        org: points,
+                        cur: Vec::new(),
+                        flags,
+                        contours: outline.contours,
+                    };
+                    Some(self.hint(zone, instructions, 0x10000))
+                }
+                _ => {
+                    round_phantoms(&mut outline.phantoms);
+                    Some(outline)
+                }
+            }
+        } else {
+            // An empty glyph.
+            let scale = self.exec.scale;
+            let mut outline = Outline::default();
+            for (p, funits) in outline.phantoms.iter_mut().zip(phantoms.iter()) {
+                *p = Point { x: mul_fix(funits.x, scale), y: mul_fix(funits.y, scale) };
+            }
+
+            round_phantoms(&mut outline.phantoms);
+            Some(outline)
+        }
+    }
+
+    /// Returns phantom points in font units.
+    fn phantom_points(&self, glyph_id: GlyphId, x_min: i16, y_max: i16) -> [Point; 4] {
+        let advance = i32::from(self.tables.glyph_hor_advance(glyph_id).unwrap_or(0));
+        let lsb = i32::from(self.tables.glyph_hor_side_bearing(glyph_id).unwrap_or(0));
+
+        let ascender = i32::from(self.tables.ascender());
+        let descender = i32::from(self.tables.descender());
+        let ver_advance = self.tables.glyph_ver_advance(glyph_id)
+            .map(i32::from)
+            .unwrap_or(ascender - descender);
+        let tsb = self.tables.glyph_ver_side_bearing(glyph_id)
+            .map(i32::from)
+            .unwrap_or(ascender - i32::from(y_max));
+
+        let left = i32::from(x_min) - lsb;
+        let top = i32::from(y_max) + tsb;
+        [
+            Point { x: left, y: 0 },
+            Point { x: left + advance, y: 0 },
+            Point { x: 0, y: top },
+            Point { x: 0, y: top - ver_advance },
+        ]
+    }
+
+    /// Runs a glyph program on a zone with scaled `org` points and four phantom points.
+    fn hint(&mut self, mut zone: Zone, instructions: &'a [u8], scale: i32) -> Outline {
+        zone.cur = zone.org.clone();
+
+        let len = zone.cur.len();
+        zone.cur[len - 4].x = (zone.cur[len - 4].x + 32) & -64;
+        zone.cur[len - 3].x = (zone.cur[len - 3].x + 32) & -64;
+        zone.cur[len - 2].y = (zone.cur[len - 2].y + 32) & -64;
+        zone.cur[len - 1].y = (zone.cur[len - 1].y + 32) & -64;
+
+        // Glyph programs are disabled by the first bit of `INSTCTRL`.
+        let enabled = self.exec.default_gs.instruct_control & 1 == 0;
+        if enabled && !instructions.is_empty() {
+            self.exec.cvt.clone_from(&self.cvt);
+            self.exec.storage.clone_from(&self.storage);
+            self.exec.twilight.clone_from(&self.twilight);
+            self.exec.glyph_program = instructions;
+            let font_scale = self.exec.scale;
+            self.exec.scale = scale;
+            core::mem::swap(&mut self.exec.glyph, &mut zone);
+
+            // Errors are ignored, the same way FreeType does in non-pedantic mode.
+            let _ = self.exec.run(Program::Glyph);
+
+            core::mem::swap(&mut self.exec.glyph, &mut zone);
+            self.exec.scale = font_scale;
+        }
+
+        let mut phantoms = [Point::default(); 4];
+        phantoms.copy_from_slice(&zone.cur[len - 4..]);
+        zone.cur.truncate(len - 4);
+        zone.flags.truncate(len - 4);
+        Outline {
+            points: zone.cur,
+            flags: zone.flags,
+            contours: zone.contours,
+            phantoms,
+        }
+    }
+
+}
+
+impl core::fmt::Debug for Hinter<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Hinter({})", self.exec.ppem)
+    }
+}
+
+#[inline]
+fn round_phantoms(phantoms: &mut [Point; 4]) {
+    phantoms[0].x = (phantoms[0].x + 32) & -64;
+    phantoms[1].x = (phantoms[1].x + 32) & -64;
+    phantoms[2].y = (phantoms[2].y + 32) & -64;
+    phantoms[3].y = (phantoms[3].y + 32) & -64;
+}
+
+// We can't use `round()` in `no_std`, so this is the next best thing.
+#[inline]
+fn round_f32(n: f32) -> i32 {
+    if n >= 0.0 { (n + 0.5) as i32 } else { (n - 0.5) as i32 }
+}
@@ -7,7 +7,7 @@ A high-level, safe, zero-allocation TrueType font parser.
   Basically, no direct access to font tables.
 - Zero heap allocations.
 - Zero unsafe.
-- Zero dependencies.
+- Zero dependencies, except for the optional `arbitrary` one.
 - `no_std`/WASM compatible.
 - Fast.
 - Stateless. All parsing methods are immutable methods.
@@ -50,7 +50,14 @@ macro_rules! try_opt_or {
 }
 
 pub mod parser;
+mod directory;
+#[cfg(all(feature = "arbitrary", feature = "std"))] pub mod generators;
 mod ggg;
+#[cfg(feature = "hinting")] mod hinting;
+mod outline;
+#[cfg(feature = "outline-cache")] mod outline_cache;
+#[cfg(feature = "rasterizer")] mod raster;
+mod scripts;
 mod tables;
 #[cfg(feature = "variable-fonts")] mod var_store;
 
@@ -59,14 +66,37 @@ mod writer;
 
 use tables::*;
 use parser::{Stream, FromData, NumFrom, TryNumFrom, LazyArray16, LazyArrayIter16, Offset32, Offset};
-use head::IndexToLocationFormat;
 
-#[cfg(feature = "variable-fonts")] pub use fvar::{VariationAxes, VariationAxis};
-pub use gdef::GlyphClass;
+#[cfg(feature = "variable-fonts")] pub use fvar::{VariationAxes, VariationAxis, NamedInstances, NamedInstance};
+#[cfg(feature = "variable-fonts")] pub use var_store::{DeltaSetIndexMap, DeltaSet, ItemVariationData};
+#[cfg(feature = "variable-fonts")] pub use var_store::{ItemVariationStore, RegionAxisCoordinates, VariationRegionList};
+pub use colr::{ColorGlyphLayer, ColorGlyphLayers, ColorLine, ColorStop, ColorStops, CompositeMode};
+pub use colr::{GradientExtend, LinearGradient, Paint, Painter, RadialGradient, SweepGradient, Transform};
+pub use cpal::{PaletteFlags, RgbaColor};
+pub use directory::TableDirectory;
+pub use dsig::{Signature, Signatures};
+pub use ebsc::{BitmapScale, BitmapScales};
+pub use gdef::{GlyphClass, LigatureCaret, LigatureCarets};
+pub use gpos::{Anchor, MarkToBaseAttachment, PairAdjustment, ValueRecord};
+pub use graphite::GraphiteTables;
+pub use gsub::GlyphAlternates;
+pub use head::{IndexToLocationFormat, MacStyle};
+pub use hhea::CaretSlope;
 pub use ggg::*;
+#[cfg(feature = "hinting")] pub use hinting::Hinter;
+pub use math::{MathConstants, MathGlyphAssembly, MathGlyphConstruction, MathGlyphPart};
+pub use math::{MathGlyphPartFlags, MathGlyphVariant, MathValue};
+pub use maxp::MaximumProfile;
+pub use merg::MergeEntry;
 pub use name::*;
 pub use os2::*;
-pub use tables::{cmap, kern};
+pub use outline::{FlattenBuilder, Winding, WindingBuilder};
+#[cfg(feature = "outline-cache")] pub use outline_cache::OutlineCache;
+#[cfg(feature = "rasterizer")] pub use raster::{GlyphMask, Rasterizer};
+pub use pclt::PclInfo;
+pub use scripts::SupportedScripts;
+pub use stat::{AxisValue, AxisValueFlags, AxisValueRecord, AxisValues, DesignAxes, DesignAxis};
+pub use tables::{cmap, kern, kerx};
 
 
 /// A type-safe wrapper for glyph ID.
@@ -333,6 +363,93 @@ impl Rect {
 }
 
 
+/// Glyph outline statistics.
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub struct GlyphStats {
+    /// Number of contours.
+    ///
+    /// In case of a composite glyph, contours of all components are counted.
+    pub number_of_contours: u16,
+
+    /// Number of points, including control points.
+    ///
+    /// In case of a composite glyph, points of all components are counted.
+    pub number_of_points: u16,
+
+    /// Indicates that a glyph is composed from other glyphs.
+    ///
+    /// Always `false` for CFF-based glyphs.
+    pub is_composite: bool,
+}
+
+
+/// Glyph properties yielded by the `Glyphs` iterator.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphInfo {
+    /// Glyph ID.
+    pub id: GlyphId,
+
+    /// Horizontal advance.
+    ///
+    /// Affected by variation axes.
+    pub advance: Option<u16>,
+
+    /// Tight bounding box.
+    ///
+    /// Affected by variation axes.
+    /// `None` for empty glyphs.
+    pub bounding_box: Option<Rect>,
+
+    /// Class from the `GDEF` table.
+    pub class: Option<GlyphClass>,
+}
+
+
+/// An iterator over all face's glyphs.
+///
+/// Glyphs are yielded in ID order.
+/// Each property is resolved on demand, so nothing is allocated.
+#[derive(Clone, Copy, Debug)]
+pub struct Glyphs<'a> {
+    tables: &'a FaceTables<'a>,
+    index: u16,
+}
+
+impl<'a> Iterator for Glyphs<'a> {
+    type Item = GlyphInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.tables.number_of_glyphs() {
+            return None;
+        }
+
+        let id = GlyphId(self.index);
+        self.index += 1;
+
+        Some(GlyphInfo {
+            id,
+            advance: self.tables.glyph_hor_advance(id),
+            bounding_box: self.tables.glyph_bounding_box(id),
+            class: self.tables.glyph_class(id),
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = usize::from(self.tables.number_of_glyphs() - self.index);
+        (n, Some(n))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        usize::from(self.tables.number_of_glyphs() - self.index)
+    }
+}
+
+impl ExactSizeIterator for Glyphs<'_> {}
+
+
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct BBox {
     x_min: f32,
@@ -413,6 +530,29 @@ impl OutlineBuilder for DummyOutline {
 }
 
 
+struct StatsOutline(GlyphStats);
+impl OutlineBuilder for StatsOutline {
+    fn move_to(&mut self, _: f32, _: f32) {
+        self.0.number_of_contours = self.0.number_of_contours.saturating_add(1);
+        self.0.number_of_points = self.0.number_of_points.saturating_add(1);
+    }
+
+    fn line_to(&mut self, _: f32, _: f32) {
+        self.0.number_of_points = self.0.number_of_points.saturating_add(1);
+    }
+
+    fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {
+        self.0.number_of_points = self.0.number_of_points.saturating_add(2);
+    }
+
+    fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {
+        self.0.number_of_points = self.0.number_of_points.saturating_add(3);
+    }
+
+    fn close(&mut self) {}
+}
+
+
 /// A glyph raster image format.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -452,6 +592,19 @@ pub struct RasterGlyphImage<'a> {
     pub data: &'a [u8],
 }
 
+/// Checks that a bitmap strike is a better match for `requested` pixels per em
+/// than the `best` one found so far.
+///
+/// The best strike is the smallest one not smaller than requested
+/// or the largest one otherwise.
+pub(crate) fn is_better_strike(best: Option<u16>, ppem: u16, requested: u16) -> bool {
+    match best {
+        Some(best) if best < requested => ppem > best,
+        Some(best) => requested <= ppem && ppem < best,
+        None => true,
+    }
+}
+
 
 /// A table name.
 #[repr(C)]
@@ -512,14 +665,47 @@ impl FromData for TableRecord {
     }
 }
 
+impl TableRecord {
+    // Tables must be 4-byte aligned, but some tools do not align them
+    // or include the padding in the table length, even for the last table,
+    // which makes it longer than the font data itself.
+    // We accept both, but only as long as the missing padding is shorter than 4 bytes.
+    #[inline]
+    fn data<'a>(&self, data: &'a [u8]) -> Option<&'a [u8]> {
+        let offset = usize::num_from(self.offset);
+        let end = offset.checked_add(usize::num_from(self.length))?;
+        if end > data.len() && end - data.len() < 4 {
+            data.get(offset..)
+        } else {
+            data.get(offset..end)
+        }
+    }
+
+    #[inline]
+    fn is_well_formed(&self, data: &[u8]) -> bool {
+        let end = usize::num_from(self.offset).checked_add(usize::num_from(self.length));
+        self.offset & 3 == 0 && end.map(|end| end <= data.len()).unwrap_or(false)
+    }
+}
+
 
 #[cfg(feature = "variable-fonts")]
 const MAX_VAR_COORDS: usize = 32;
 
+// Code points with the Unicode `White_Space` property.
+const WHITESPACE_CHARS: &[char] = &[
+    '\u{0009}', '\u{000A}', '\u{000B}', '\u{000C}', '\u{000D}', '\u{0020}', '\u{0085}',
+    '\u{00A0}', '\u{1680}', '\u{2000}', '\u{2001}', '\u{2002}', '\u{2003}', '\u{2004}',
+    '\u{2005}', '\u{2006}', '\u{2007}', '\u{2008}', '\u{2009}', '\u{200A}', '\u{2028}',
+    '\u{2029}', '\u{202F}', '\u{205F}', '\u{3000}',
+];
+
 #[cfg(feature = "variable-fonts")]
 #[derive(Clone, Default)]
 struct VarCoords {
     data: [NormalizedCoordinate; MAX_VAR_COORDS],
+    // Coordinates before `avar` mapping.
+    unmapped: [NormalizedCoordinate; MAX_VAR_COORDS],
     len: u8,
 }
 
@@ -539,6 +725,8 @@ impl VarCoords {
 
 
 /// A list of font face parsing errors.
+///
+/// New variants can be added in minor releases, so matching must include a wildcard arm.
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum FaceParsingError {
     /// An attempt to read out of bounds detected.
@@ -552,14 +740,32 @@ pub enum FaceParsingError {
     /// The face index is larger than the number of faces in the font.
     FaceIndexOutOfBounds,
 
-    /// The `head` table is missing or malformed.
+    /// The `head` table is missing.
     NoHeadTable,
 
-    /// The `hhea` table is missing or malformed.
+    /// The `hhea` table is missing.
     NoHheaTable,
 
-    /// The `maxp` table is missing or malformed.
+    /// The `maxp` table is missing.
     NoMaxpTable,
+
+    /// A table record has an offset and length
+    /// that cannot be represented on the current platform.
+    MalformedTableRecord {
+        /// The table tag.
+        tag: Tag,
+        /// The table offset from the start of the font data.
+        offset: u32,
+        /// The table length.
+        length: u32,
+    },
+
+    /// A required table with the specified tag is present, but malformed.
+    MalformedTable(Tag),
+
+    // Prevents exhaustive matching, since `#[non_exhaustive]` requires Rust 1.40.
+    #[doc(hidden)]
+    __NonExhaustive,
 }
 
 impl core::fmt::Display for FaceParsingError {
@@ -568,9 +774,15 @@ impl core::fmt::Display for FaceParsingError {
             FaceParsingError::MalformedFont => write!(f, "malformed font"),
             FaceParsingError::UnknownMagic => write!(f, "unknown magic"),
             FaceParsingError::FaceIndexOutOfBounds => write!(f, "face index is out of bounds"),
-            FaceParsingError::NoHeadTable => write!(f, "the head table is missing or malformed"),
-            FaceParsingError::NoHheaTable => write!(f, "the hhea table is missing or malformed"),
-            FaceParsingError::NoMaxpTable => write!(f, "the maxp table is missing or malformed"),
+            FaceParsingError::NoHeadTable => write!(f, "the head table is missing"),
+            FaceParsingError::NoHheaTable => write!(f, "the hhea table is missing"),
+            FaceParsingError::NoMaxpTable => write!(f, "the maxp table is missing"),
+            FaceParsingError::MalformedTableRecord { tag, offset, length } => {
+                write!(f, "the {} table record with offset {} and length {} is malformed",
+                       tag, offset, length)
+            }
+            FaceParsingError::MalformedTable(tag) => write!(f, "the {} table is malformed", tag),
+            FaceParsingError::__NonExhaustive => write!(f, "unknown error"),
         }
     }
 }
@@ -616,25 +828,45 @@ pub struct FaceTables<'a> {
     cblc: Option<&'a [u8]>,
     cff1: Option<cff1::Metadata<'a>>,
     cmap: Option<cmap::Subtables<'a>>,
+    colr: Option<colr::Table<'a>>,
+    cpal: Option<cpal::Table<'a>>,
+    cvt_: Option<LazyArray16<'a, i16>>,
+    dsig: Option<dsig::Table<'a>>,
+    ebsc: Option<ebsc::Table<'a>>,
+    feat: Option<&'a [u8]>,
+    fpgm: Option<&'a [u8]>,
     gdef: Option<gdef::Table<'a>>,
+    gpos: Option<LayoutTable<'a>>,
+    gsub: Option<LayoutTable<'a>>,
+    glat: Option<&'a [u8]>,
+    gloc: Option<&'a [u8]>,
     glyf: Option<&'a [u8]>,
     head: &'a [u8],
     hhea: &'a [u8],
     hmtx: Option<hmtx::Table<'a>>,
     kern: Option<kern::Subtables<'a>>,
+    kerx: Option<kerx::Subtables<'a>>,
     loca: Option<loca::Table<'a>>,
+    math: Option<math::Table<'a>>,
+    maxp: &'a [u8],
+    merg: Option<merg::Table<'a>>,
     name: Option<name::Names<'a>>,
     os_2: Option<os2::Table<'a>>,
+    pclt: Option<&'a [u8]>,
     post: Option<post::Table<'a>>,
+    prep: Option<&'a [u8]>,
     vhea: Option<&'a [u8]>,
     vmtx: Option<hmtx::Table<'a>>,
     sbix: Option<&'a [u8]>,
+    silf: Option<&'a [u8]>,
+    stat: Option<stat::Table<'a>>,
     svg_: Option<&'a [u8]>,
     vorg: Option<vorg::Table<'a>>,
 
     // Variable font tables.
     #[cfg(feature = "variable-fonts")] avar: Option<avar::Table<'a>>,
     #[cfg(feature = "variable-fonts")] cff2: Option<cff2::Metadata<'a>>,
+    #[cfg(feature = "variable-fonts")] cvar: Option<cvar::Table<'a>>,
     #[cfg(feature = "variable-fonts")] fvar: Option<fvar::Table<'a>>,
     #[cfg(feature = "variable-fonts")] gvar: Option<gvar::Table<'a>>,
     #[cfg(feature = "variable-fonts")] hvar: Option<hvar::Table<'a>>,
@@ -717,10 +949,15 @@ impl<'a> Face<'a> {
     /// Useful if you want to parse the data manually.
     pub fn table_data(&self, tag: Tag) -> Option<&'a [u8]> {
         let (_, table) = self.table_records.binary_search_by(|record| record.table_tag.cmp(&tag))?;
-        let offset = usize::num_from(table.offset);
-        let length = usize::num_from(table.length);
-        let end = offset.checked_add(length)?;
-        self.font_data.get(offset..end)
+        table.data(self.font_data)
+    }
+
+    /// Checks that all tables are 4-byte aligned and fit into the font data.
+    ///
+    /// Unaligned tables and tables with a length that includes a missing padding
+    /// are still parsed, but this indicates a font produced by a non-conforming tool.
+    pub fn has_well_formed_table_layout(&self) -> bool {
+        self.table_records.into_iter().all(|record| record.is_well_formed(self.font_data))
     }
 }
 
@@ -737,23 +974,43 @@ impl<'a> FaceTables<'a> {
             cblc: None,
             cff1: None,
             cmap: None,
+            colr: None,
+            cpal: None,
+            cvt_: None,
+            dsig: None,
+            ebsc: None,
+            feat: None,
+            fpgm: None,
             gdef: None,
+            gpos: None,
+            gsub: None,
+            glat: None,
+            gloc: None,
             glyf: None,
             head: &[],
             hhea: &[],
             hmtx: None,
             kern: None,
+            kerx: None,
             loca: None,
+            math: None,
+            maxp: &[],
+            merg: None,
             name: None,
             os_2: None,
+            pclt: None,
             post: None,
+            prep: None,
             vhea: None,
             vmtx: None,
             sbix: None,
+            silf: None,
+            stat: None,
             svg_: None,
             vorg: None,
             #[cfg(feature = "variable-fonts")] avar: None,
             #[cfg(feature = "variable-fonts")] cff2: None,
+            #[cfg(feature = "variable-fonts")] cvar: None,
             #[cfg(feature = "variable-fonts")] fvar: None,
             #[cfg(feature = "variable-fonts")] gvar: None,
             #[cfg(feature = "variable-fonts")] hvar: None,
@@ -765,6 +1022,7 @@ impl<'a> FaceTables<'a> {
 
         let mut number_of_glyphs = None;
         let mut hmtx = None;
+        let mut kerx = None;
         let mut vmtx = None;
         let mut loca = None;
 
@@ -776,32 +1034,62 @@ impl<'a> FaceTables<'a> {
                 b"CFF " => face.cff1 = table_data.and_then(|data| cff1::parse_metadata(data)),
                 #[cfg(feature = "variable-fonts")]
                 b"CFF2" => face.cff2 = table_data.and_then(|data| cff2::parse_metadata(data)),
+                b"COLR" => face.colr = table_data.and_then(colr::Table::parse),
+                b"CPAL" => face.cpal = table_data.and_then(cpal::Table::parse),
+                b"DSIG" => face.dsig = table_data.and_then(dsig::Table::parse),
+                b"EBSC" => face.ebsc = table_data.and_then(ebsc::Table::parse),
+                b"Feat" => face.feat = table_data.and_then(graphite::parse_feat),
                 b"GDEF" => face.gdef = table_data.and_then(|data| gdef::Table::parse(data)),
+                b"GPOS" => face.gpos = table_data.and_then(|data| LayoutTable::parse(data, ggg::TableKind::Positioning)),
+                b"GSUB" => face.gsub = table_data.and_then(|data| LayoutTable::parse(data, ggg::TableKind::Substitution)),
+                b"Glat" => face.glat = table_data.and_then(graphite::parse_glat),
+                b"Gloc" => face.gloc = table_data.and_then(graphite::parse_gloc),
                 #[cfg(feature = "variable-fonts")]
                 b"HVAR" => face.hvar = table_data.and_then(|data| hvar::Table::parse(data)),
+                b"MATH" => face.math = table_data.and_then(math::Table::parse),
+                b"MERG" => face.merg = table_data.and_then(merg::Table::parse),
                 #[cfg(feature = "variable-fonts")]
                 b"MVAR" => face.mvar = table_data.and_then(|data| mvar::Table::parse(data)),
                 b"OS/2" => face.os_2 = table_data.and_then(|data| os2::Table::parse(data)),
+                b"PCLT" => face.pclt = table_data,
+                b"STAT" => face.stat = table_data.and_then(stat::Table::parse),
                 b"SVG " => face.svg_ = table_data,
+                b"Silf" => face.silf = table_data.and_then(graphite::parse_silf),
                 b"VORG" => face.vorg = table_data.and_then(|data| vorg::Table::parse(data)),
                 #[cfg(feature = "variable-fonts")]
                 b"VVAR" => face.vvar = table_data.and_then(|data| hvar::Table::parse(data)),
                 #[cfg(feature = "variable-fonts")]
                 b"avar" => face.avar = table_data.and_then(|data| avar::Table::parse(data)),
                 b"cmap" => face.cmap = table_data.and_then(|data| cmap::parse(data)),
+                b"cvt " => face.cvt_ = table_data.and_then(cvt::parse),
+                #[cfg(feature = "variable-fonts")]
+                b"cvar" => face.cvar = table_data.and_then(cvar::Table::parse),
+                b"fpgm" => face.fpgm = table_data,
                 #[cfg(feature = "variable-fonts")]
                 b"fvar" => face.fvar = table_data.and_then(|data| fvar::Table::parse(data)),
                 b"glyf" => face.glyf = table_data,
                 #[cfg(feature = "variable-fonts")]
                 b"gvar" => face.gvar = table_data.and_then(|data| gvar::Table::parse(data)),
-                b"head" => face.head = table_data.and_then(|data| head::parse(data)).unwrap_or_default(),
-                b"hhea" => face.hhea = table_data.and_then(|data| hhea::parse(data)).unwrap_or_default(),
+                b"head" => {
+                    face.head = table_data.and_then(head::parse)
+                        .ok_or(FaceParsingError::MalformedTable(table_tag))?;
+                }
+                b"hhea" => {
+                    face.hhea = table_data.and_then(hhea::parse)
+                        .ok_or(FaceParsingError::MalformedTable(table_tag))?;
+                }
                 b"hmtx" => hmtx = table_data,
                 b"kern" => face.kern = table_data.and_then(|data| kern::parse(data)),
+                b"kerx" => kerx = table_data,
                 b"loca" => loca = table_data,
-                b"maxp" => number_of_glyphs = table_data.and_then(|data| maxp::parse(data)),
+                b"maxp" => {
+                    number_of_glyphs = Some(table_data.and_then(maxp::parse)
+                        .ok_or(FaceParsingError::MalformedTable(table_tag))?);
+                    face.maxp = table_data.unwrap_or_default();
+                }
                 b"name" => face.name = table_data.and_then(|data| name::parse(data)),
                 b"post" => face.post = table_data.and_then(|data| post::Table::parse(data)),
+                b"prep" => face.prep = table_data,
                 b"sbix" => face.sbix = table_data,
                 b"vhea" => face.vhea = table_data.and_then(|data| vhea::parse(data)),
                 b"vmtx" => vmtx = table_data,
@@ -834,6 +1122,8 @@ impl<'a> FaceTables<'a> {
             }
         }
 
+        face.kerx = kerx.and_then(|data| kerx::parse(data, face.number_of_glyphs));
+
         if let (Some(vhea), Some(data)) = (face.vhea, vmtx) {
             if let Some(number_of_v_metrics) = vhea::num_of_long_ver_metrics(vhea) {
                 face.vmtx = hmtx::Table::parse(data, number_of_v_metrics, face.number_of_glyphs);
@@ -933,6 +1223,242 @@ impl<'a> FaceTables<'a> {
         self.name.unwrap_or_default()
     }
 
+    /// Returns a stylistic set's user-interface name.
+    ///
+    /// English names are preferred.
+    ///
+    /// Returns `None` when feature is not a stylistic set,
+    /// has no parameters or the name is not found.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn stylistic_set_name(&self, feature: &Feature) -> Option<std::string::String> {
+        self.localized_name(feature.stylistic_set_params()?.ui_name_id)
+    }
+
+    /// Returns a name string by [Name ID](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids).
+    ///
+    /// English names are preferred. Otherwise, the first Unicode name is returned,
+    /// falling back to the first Macintosh Roman one.
+    ///
+    /// Useful for resolving name IDs from other tables,
+    /// like `CharacterVariantParams::label_name_id`.
+    #[cfg(feature = "std")]
+    pub fn localized_name(&self, name_id: u16) -> Option<std::string::String> {
+        const ENGLISH_US: u16 = 0x0409;
+
+        let mut fallback = None;
+        let mut mac_fallback = None;
+        for name in self.names() {
+            if name.name_id() != name_id {
+                continue;
+            }
+
+            if !name.is_unicode() {
+                if mac_fallback.is_none() {
+                    mac_fallback = name.to_string();
+                }
+
+                continue;
+            }
+
+            if name.platform_id() == PlatformId::Windows && name.language_id() == ENGLISH_US {
+                return name.to_string();
+            }
+
+            if fallback.is_none() {
+                fallback = name.to_string();
+            }
+        }
+
+        fallback.or(mac_fallback)
+    }
+
+    /// Returns face's family name.
+    ///
+    /// The *Typographic Family Name* (ID 16) is preferred,
+    /// falling back to the *Family Name* (ID 1).
+    /// Names are resolved using [`localized_name`](#method.localized_name).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn family_name(&self) -> Option<std::string::String> {
+        self.localized_name(name_id::TYPOGRAPHIC_FAMILY)
+            .or_else(|| self.localized_name(name_id::FAMILY))
+    }
+
+    /// Returns face's subfamily name, like *Bold Italic*.
+    ///
+    /// The *Typographic Subfamily Name* (ID 17) is preferred,
+    /// falling back to the *Subfamily Name* (ID 2).
+    /// Names are resolved using [`localized_name`](#method.localized_name).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn subfamily_name(&self) -> Option<std::string::String> {
+        self.localized_name(name_id::TYPOGRAPHIC_SUBFAMILY)
+            .or_else(|| self.localized_name(name_id::SUBFAMILY))
+    }
+
+    /// Returns face's *Full Name* (ID 4).
+    ///
+    /// Names are resolved using [`localized_name`](#method.localized_name).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn full_name(&self) -> Option<std::string::String> {
+        self.localized_name(name_id::FULL_NAME)
+    }
+
+    /// Returns face's *PostScript Name* (ID 6).
+    ///
+    /// Names are resolved using [`localized_name`](#method.localized_name).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn post_script_name(&self) -> Option<std::string::String> {
+        self.localized_name(name_id::POST_SCRIPT_NAME)
+    }
+
+    /// Returns an iterator over [style attributes](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/stat) design axes.
+    #[inline]
+    pub fn style_axes(&self) -> DesignAxes<'a> {
+        self.stat.map(|stat| stat.axes).unwrap_or_default()
+    }
+
+    /// Returns an iterator over [style attributes](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/stat) axis values.
+    #[inline]
+    pub fn style_axis_values(&self) -> AxisValues<'a> {
+        self.stat.map(|stat| stat.values).unwrap_or_default()
+    }
+
+    /// Returns a name ID used when all axis values of a style name are elided.
+    ///
+    /// Returns `None` when face doesn't have a `STAT` table
+    /// or it's version 1.0, in which case the *Subfamily Name* (ID 2) should be used.
+    #[inline]
+    pub fn elided_fallback_name_id(&self) -> Option<u16> {
+        self.stat?.elided_fallback_name_id
+    }
+
+    /// Returns name IDs that compose a style name, like *Condensed Bold*,
+    /// for the specified user-space coordinates using the `STAT` table.
+    ///
+    /// Variation axes without a coordinate use the default value from `fvar`.
+    /// Name IDs are sorted according to axis ordering, with elidable names removed.
+    ///
+    /// Returns `None` when face doesn't have a `STAT` table.
+    #[cfg(feature = "std")]
+    pub fn style_name_ids(&self, coordinates: &[(Tag, f32)]) -> Option<std::vec::Vec<u16>> {
+        let stat = self.stat?;
+
+        #[allow(unused_mut)]
+        let mut coordinates = coordinates.to_vec();
+        #[cfg(feature = "variable-fonts")] {
+            for axis in self.variation_axes() {
+                if !coordinates.iter().any(|(tag, _)| *tag == axis.tag) {
+                    coordinates.push((axis.tag, axis.def_value));
+                }
+            }
+        }
+
+        Some(stat.style_name_ids(&coordinates))
+    }
+
+    /// Returns a style name, like *Condensed Bold*,
+    /// for the specified user-space coordinates using the `STAT` table.
+    ///
+    /// Names are resolved using [`localized_name`](#method.localized_name)
+    /// and joined with spaces. See [`style_name_ids`](#method.style_name_ids) for details.
+    #[cfg(feature = "std")]
+    pub fn style_name(&self, coordinates: &[(Tag, f32)]) -> Option<std::string::String> {
+        let names: Option<std::vec::Vec<_>> = self.style_name_ids(coordinates)?
+            .into_iter()
+            .map(|id| self.localized_name(id))
+            .collect();
+        Some(names?.join(" "))
+    }
+
+    /// Returns a name string by [Name ID](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids)
+    /// and a [BCP 47](https://tools.ietf.org/html/bcp47) language tag, like `ja` or `zh-TW`.
+    ///
+    /// Tags are compared case-insensitively. A tag without a region, like `ja`,
+    /// also matches names with one, like `ja-JP`, unless an exact match exists.
+    /// See [`Name::language_tag`](struct.Name.html#method.language_tag)
+    /// for supported language IDs.
+    #[cfg(feature = "std")]
+    pub fn name_by_id_and_language(
+        &self,
+        name_id: u16,
+        language: &str,
+    ) -> Option<std::string::String> {
+        let mut fallback = None;
+        for name in self.names() {
+            if name.name_id() != name_id {
+                continue;
+            }
+
+            let tag = match name.language_tag() {
+                Some(tag) => tag,
+                None => continue,
+            };
+
+            if tag.eq_ignore_ascii_case(language) {
+                if let Some(s) = name.to_string() {
+                    return Some(s);
+                }
+            } else if fallback.is_none() && !language.contains('-') {
+                let primary = tag.split('-').next().unwrap_or("");
+                if primary.eq_ignore_ascii_case(language) {
+                    fallback = name.to_string();
+                }
+            }
+        }
+
+        fallback
+    }
+
+    /// Checks that two faces belong to the same family.
+    ///
+    /// Faces are grouped using the *WWS Family Name* (ID 21), which separates faces
+    /// that differ not only in weight, width and slope, like optical sizes.
+    /// When a face doesn't have one or is marked as WWS-conformant in `OS/2`,
+    /// the *Typographic Family Name* (ID 16) is used instead,
+    /// falling back to the *Family Name* (ID 1).
+    /// Names are compared case-insensitively.
+    ///
+    /// When both faces have a `STAT` table, their design axes must also have
+    /// the same tags in the same order, as recommended for all faces of a family.
+    /// Axis values and the elided fallback name are not compared,
+    /// since they are expected to differ between faces.
+    ///
+    /// Returns `false` when any of the faces has no family name.
+    #[cfg(feature = "std")]
+    pub fn is_same_family(&self, other: &FaceTables<'_>) -> bool {
+        let same_name = match (self.wws_family_name(), other.wws_family_name()) {
+            (Some(a), Some(b)) => a.to_lowercase() == b.to_lowercase(),
+            _ => false,
+        };
+
+        if !same_name {
+            return false;
+        }
+
+        if self.stat.is_some() && other.stat.is_some() {
+            self.style_axes().map(|axis| axis.tag).eq(other.style_axes().map(|axis| axis.tag))
+        } else {
+            true
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn wws_family_name(&self) -> Option<std::string::String> {
+        let is_wws = self.os_2.map(|os_2| os_2.is_wws()).unwrap_or(false);
+        let wws_name = if is_wws { None } else { self.localized_name(name_id::WWS_FAMILY) };
+        wws_name
+            .or_else(|| self.localized_name(name_id::TYPOGRAPHIC_FAMILY))
+            .or_else(|| self.localized_name(name_id::FAMILY))
+    }
+
     /// Checks that face is marked as *Regular*.
     ///
     /// Returns `false` when OS/2 table is not present.
@@ -1004,6 +1530,30 @@ impl<'a> FaceTables<'a> {
         try_opt_or!(self.os_2, Width::default()).width()
     }
 
+    /// Returns Unicode ranges considered functional by the face.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn unicode_ranges(&self) -> Option<UnicodeRanges> {
+        self.os_2.map(|os_2| os_2.unicode_ranges())
+    }
+
+    /// Returns code page character ranges considered functional by the face.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 1.
+    #[inline]
+    pub fn code_page_ranges(&self) -> Option<CodePageRanges> {
+        self.os_2.and_then(|os_2| os_2.code_page_ranges())
+    }
+
+    /// Returns face's embedding permissions.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn embedding_permissions(&self) -> Option<EmbeddingPermissions> {
+        self.os_2.map(|os_2| os_2.embedding_permissions())
+    }
+
     /// Returns face's italic angle.
     ///
     /// Returns `None` when `post` table is not present.
@@ -1012,40 +1562,56 @@ impl<'a> FaceTables<'a> {
         self.post.map(|table| table.italic_angle())
     }
 
+    /// Checks that face has the `USE_TYPO_METRICS` flag set in `OS/2`.
+    ///
+    /// When set, typographic metrics should be used for line layout
+    /// instead of Windows ones.
+    ///
+    /// Returns `false` when OS/2 table is not present or its version is lower than 4.
+    #[inline]
+    pub fn is_use_typo_metrics(&self) -> bool {
+        try_opt_or!(self.os_2, false).is_use_typo_metrics()
+    }
+
     /// Returns a horizontal face ascender.
     ///
+    /// Uses `OS/2` typographic metrics when `USE_TYPO_METRICS` is set,
+    /// Windows metrics otherwise and falls back to `hhea` when `OS/2` is not present.
+    ///
     /// This method is affected by variation axes.
     #[inline]
     pub fn ascender(&self) -> i16 {
         if let Some(os_2) = self.os_2 {
             if os_2.is_use_typo_metrics() {
-                let v = os_2.typo_ascender();
-                self.apply_metrics_variation(Tag::from_bytes(b"hasc"), v)
-            } else {
-                let v = os_2.windows_ascender();
-                self.apply_metrics_variation(Tag::from_bytes(b"hcla"), v)
+                if let Some(v) = os_2.typo_ascender() {
+                    return self.apply_metrics_variation(Tag::from_bytes(b"hasc"), v);
+                }
+            } else if let Some(v) = os_2.windows_ascender() {
+                return self.apply_metrics_variation(Tag::from_bytes(b"hcla"), v);
             }
-        } else {
-            hhea::ascender(self.hhea)
         }
+
+        hhea::ascender(self.hhea)
     }
 
     /// Returns a horizontal face descender.
     ///
+    /// Uses the same metrics as [`ascender`](#method.ascender).
+    ///
     /// This method is affected by variation axes.
     #[inline]
     pub fn descender(&self) -> i16 {
         if let Some(os_2) = self.os_2 {
             if os_2.is_use_typo_metrics() {
-                let v = os_2.typo_descender();
-                self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), v)
-            } else {
-                let v = os_2.windows_descender();
-                self.apply_metrics_variation(Tag::from_bytes(b"hcld"), v)
+                if let Some(v) = os_2.typo_descender() {
+                    return self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), v);
+                }
+            } else if let Some(v) = os_2.windows_descender() {
+                return self.apply_metrics_variation(Tag::from_bytes(b"hcld"), v);
             }
-        } else {
-            hhea::descender(self.hhea)
         }
+
+        hhea::descender(self.hhea)
     }
 
     /// Returns face's height.
@@ -1058,19 +1624,21 @@ impl<'a> FaceTables<'a> {
 
     /// Returns a horizontal face line gap.
     ///
+    /// Uses the `OS/2` typographic line gap when `USE_TYPO_METRICS` is set
+    /// and the `hhea` one otherwise, since Windows metrics have no line gap.
+    ///
     /// This method is affected by variation axes.
     #[inline]
     pub fn line_gap(&self) -> i16 {
         if let Some(os_2) = self.os_2 {
             if os_2.is_use_typo_metrics() {
-                let v = os_2.typo_line_gap();
-                self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), v)
-            } else {
-                hhea::line_gap(self.hhea)
+                if let Some(v) = os_2.typo_line_gap() {
+                    return self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), v);
+                }
             }
-        } else {
-            hhea::line_gap(self.hhea)
         }
+
+        hhea::line_gap(self.hhea)
     }
 
     /// Returns a horizontal typographic face ascender.
@@ -1080,13 +1648,11 @@ impl<'a> FaceTables<'a> {
     ///
     /// This method is affected by variation axes.
     ///
-    /// Returns `None` when OS/2 table is not present.
+    /// Returns `None` when OS/2 table is not present or is a short Apple version 0 table.
     #[inline]
     pub fn typographic_ascender(&self) -> Option<i16> {
-        self.os_2.map(|table| {
-            let v = table.typo_ascender();
-            self.apply_metrics_variation(Tag::from_bytes(b"hasc"), v)
-        })
+        self.os_2.and_then(|os_2| os_2.typo_ascender())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hasc"), v))
     }
 
     /// Returns a horizontal typographic face descender.
@@ -1096,13 +1662,11 @@ impl<'a> FaceTables<'a> {
     ///
     /// This method is affected by variation axes.
     ///
-    /// Returns `None` when OS/2 table is not present.
+    /// Returns `None` when OS/2 table is not present or is a short Apple version 0 table.
     #[inline]
     pub fn typographic_descender(&self) -> Option<i16> {
-        self.os_2.map(|table| {
-            let v = table.typo_descender();
-            self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), v)
-        })
+        self.os_2.and_then(|os_2| os_2.typo_descender())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), v))
     }
 
     /// Returns a horizontal typographic face line gap.
@@ -1112,92 +1676,308 @@ impl<'a> FaceTables<'a> {
     ///
     /// This method is affected by variation axes.
     ///
-    /// Returns `None` when OS/2 table is not present.
+    /// Returns `None` when OS/2 table is not present or is a short Apple version 0 table.
     #[inline]
     pub fn typographic_line_gap(&self) -> Option<i16> {
-        self.os_2.map(|table| {
-            let v = table.typo_line_gap();
-            self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), v)
-        })
+        self.os_2.and_then(|os_2| os_2.typo_line_gap())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), v))
     }
 
-    /// Returns a vertical face ascender.
-    ///
-    /// This method is affected by variation axes.
+    /// Returns the maximum advance width from the `hhea` table.
     #[inline]
-    pub fn vertical_ascender(&self) -> Option<i16> {
-        self.vhea.map(vhea::ascender)
-            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vasc"), v))
+    pub fn max_advance_width(&self) -> u16 {
+        hhea::advance_width_max(self.hhea)
     }
 
-    /// Returns a vertical face descender.
+    /// Returns the minimum left side bearing from the `hhea` table.
     ///
-    /// This method is affected by variation axes.
+    /// Only glyphs with contours are taken into account.
     #[inline]
-    pub fn vertical_descender(&self) -> Option<i16> {
-        self.vhea.map(vhea::descender)
-            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vdsc"), v))
+    pub fn min_left_side_bearing(&self) -> i16 {
+        hhea::min_left_side_bearing(self.hhea)
     }
 
-    /// Returns a vertical face height.
+    /// Returns the minimum right side bearing from the `hhea` table.
     ///
-    /// This method is affected by variation axes.
+    /// Only glyphs with contours are taken into account.
     #[inline]
-    pub fn vertical_height(&self) -> Option<i16> {
-        Some(self.vertical_ascender()? - self.vertical_descender()?)
+    pub fn min_right_side_bearing(&self) -> i16 {
+        hhea::min_right_side_bearing(self.hhea)
     }
 
-    /// Returns a vertical face line gap.
+    /// Returns the maximum horizontal glyph extent from the `hhea` table.
     ///
-    /// This method is affected by variation axes.
+    /// Calculated as `max(lsb + (xMax - xMin))`.
     #[inline]
-    pub fn vertical_line_gap(&self) -> Option<i16> {
-        self.vhea.map(vhea::line_gap)
-            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vlgp"), v))
+    pub fn max_x_extent(&self) -> i16 {
+        hhea::x_max_extent(self.hhea)
     }
 
-    /// Returns face's units per EM.
+    /// Returns a horizontal caret slope.
     ///
-    /// Returns `None` when value is not in a 16..=16384 range.
+    /// Used to draw the text cursor for italic faces.
+    ///
+    /// This method is affected by variation axes.
     #[inline]
-    pub fn units_per_em(&self) -> Option<u16> {
-        head::units_per_em(self.head)
+    pub fn caret_slope(&self) -> CaretSlope {
+        let mut slope = hhea::caret_slope(self.hhea);
+
+        if self.is_variable() {
+            self.apply_metrics_variation_to(Tag::from_bytes(b"hcrs"), &mut slope.rise);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"hcrn"), &mut slope.run);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"hcof"), &mut slope.offset);
+        }
+
+        slope
     }
 
-    /// Returns face's x height.
+    /// Returns a horizontal Windows face ascender.
+    ///
+    /// Prefer `Face::ascender` unless you explicitly want this. This is a more
+    /// low-level alternative.
     ///
     /// This method is affected by variation axes.
     ///
-    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    /// Returns `None` when OS/2 table is not present or is a short Apple version 0 table.
     #[inline]
-    pub fn x_height(&self) -> Option<i16> {
-        self.os_2.and_then(|os_2| os_2.x_height())
-            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"xhgt"), v))
+    pub fn windows_ascender(&self) -> Option<i16> {
+        self.os_2.and_then(|os_2| os_2.windows_ascender())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hcla"), v))
     }
 
-    /// Returns face's capital height.
+    /// Returns a horizontal Windows face descender.
+    ///
+    /// Unlike the `usWinDescent` value, is negative for descents below the baseline.
+    ///
+    /// Prefer `Face::descender` unless you explicitly want this. This is a more
+    /// low-level alternative.
     ///
     /// This method is affected by variation axes.
     ///
-    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    /// Returns `None` when OS/2 table is not present or is a short Apple version 0 table.
     #[inline]
-    pub fn capital_height(&self) -> Option<i16> {
-        self.os_2.and_then(|os_2| os_2.cap_height())
-            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"cpht"), v))
+    pub fn windows_descender(&self) -> Option<i16> {
+        self.os_2.and_then(|os_2| os_2.windows_descender())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hcld"), v))
     }
 
-    /// Returns face's underline metrics.
+    /// Returns a horizontal face ascender from the `hhea` table.
     ///
-    /// This method is affected by variation axes.
+    /// Prefer `Face::ascender` unless you explicitly want this. This is a more
+    /// low-level alternative.
     ///
-    /// Returns `None` when `post` table is not present.
+    /// This method is affected by variation axes.
     #[inline]
-    pub fn underline_metrics(&self) -> Option<LineMetrics> {
-        let mut metrics = self.post?.underline_metrics();
+    pub fn horizontal_header_ascender(&self) -> i16 {
+        self.apply_metrics_variation(Tag::from_bytes(b"hasc"), hhea::ascender(self.hhea))
+    }
 
-        if self.is_variable() {
-            self.apply_metrics_variation_to(Tag::from_bytes(b"undo"), &mut metrics.position);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"unds"), &mut metrics.thickness);
+    /// Returns a horizontal face descender from the `hhea` table.
+    ///
+    /// Prefer `Face::descender` unless you explicitly want this. This is a more
+    /// low-level alternative.
+    ///
+    /// This method is affected by variation axes.
+    #[inline]
+    pub fn horizontal_header_descender(&self) -> i16 {
+        self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), hhea::descender(self.hhea))
+    }
+
+    /// Returns a horizontal face line gap from the `hhea` table.
+    ///
+    /// Prefer `Face::line_gap` unless you explicitly want this. This is a more
+    /// low-level alternative.
+    ///
+    /// This method is affected by variation axes.
+    #[inline]
+    pub fn horizontal_header_line_gap(&self) -> i16 {
+        self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), hhea::line_gap(self.hhea))
+    }
+
+    /// Returns a vertical face ascender.
+    ///
+    /// This method is affected by variation axes.
+    #[inline]
+    pub fn vertical_ascender(&self) -> Option<i16> {
+        self.vhea.map(vhea::ascender)
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vasc"), v))
+    }
+
+    /// Returns a vertical face descender.
+    ///
+    /// This method is affected by variation axes.
+    #[inline]
+    pub fn vertical_descender(&self) -> Option<i16> {
+        self.vhea.map(vhea::descender)
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vdsc"), v))
+    }
+
+    /// Returns a vertical face height.
+    ///
+    /// This method is affected by variation axes.
+    #[inline]
+    pub fn vertical_height(&self) -> Option<i16> {
+        Some(self.vertical_ascender()? - self.vertical_descender()?)
+    }
+
+    /// Returns a vertical face line gap.
+    ///
+    /// This method is affected by variation axes.
+    #[inline]
+    pub fn vertical_line_gap(&self) -> Option<i16> {
+        self.vhea.map(vhea::line_gap)
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vlgp"), v))
+    }
+
+    /// Returns face's units per EM.
+    ///
+    /// Returns `None` when value is not in a 16..=16384 range.
+    #[inline]
+    pub fn units_per_em(&self) -> Option<u16> {
+        head::units_per_em(self.head)
+    }
+
+    /// Returns face's revision, as set by the font manufacturer.
+    #[inline]
+    pub fn font_revision(&self) -> f32 {
+        head::font_revision(self.head).unwrap_or(0.0)
+    }
+
+    /// Returns face's Mac style flags.
+    #[inline]
+    pub fn mac_style(&self) -> MacStyle {
+        head::mac_style(self.head).unwrap_or_default()
+    }
+
+    /// Returns face's `loca` table offsets format.
+    ///
+    /// Returns `None` when the value is invalid.
+    #[inline]
+    pub fn index_to_location_format(&self) -> Option<IndexToLocationFormat> {
+        head::index_to_loc_format(self.head)
+    }
+
+    /// Returns the smallest readable size in pixels.
+    #[inline]
+    pub fn lowest_recommended_ppem(&self) -> u16 {
+        head::lowest_rec_ppem(self.head).unwrap_or(0)
+    }
+
+    /// Checks that the baseline for the face is at `y=0`.
+    #[inline]
+    pub fn is_baseline_at_zero(&self) -> bool {
+        self.head_flag(0)
+    }
+
+    /// Checks that the left sidebearing point is at `x=0`.
+    ///
+    /// Relevant only for TrueType rasterizers.
+    #[inline]
+    pub fn is_left_side_bearing_at_zero(&self) -> bool {
+        self.head_flag(1)
+    }
+
+    /// Checks that TrueType instructions may alter advance widths.
+    ///
+    /// When set, advance widths might not scale linearly.
+    #[inline]
+    pub fn instructions_may_alter_advance(&self) -> bool {
+        self.head_flag(4)
+    }
+
+    #[inline]
+    fn head_flag(&self, bit: u8) -> bool {
+        head::flags(self.head).map(|flags| (flags >> bit) & 1 != 0).unwrap_or(false)
+    }
+
+    /// Returns face's x height.
+    ///
+    /// When OS/2 table is not present or when its version is < 2,
+    /// the top of the `x` glyph bounding box, i.e. its `y_max`, is used instead.
+    /// In this case the glyph is outlined on each call, so the result should be cached
+    /// instead of being queried repeatedly.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when the x height is not set and the `x` glyph has no outline.
+    pub fn x_height(&self) -> Option<i16> {
+        match self.os_2.and_then(|os_2| os_2.x_height()) {
+            Some(v) => Some(self.apply_metrics_variation(Tag::from_bytes(b"xhgt"), v)),
+            None => self.glyph_height('x'),
+        }
+    }
+
+    /// Returns face's capital height.
+    ///
+    /// When OS/2 table is not present or when its version is < 2,
+    /// the top of the `H` glyph bounding box, i.e. its `y_max`, is used instead.
+    /// In this case the glyph is outlined on each call, so the result should be cached
+    /// instead of being queried repeatedly.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when the capital height is not set and the `H` glyph has no outline.
+    pub fn capital_height(&self) -> Option<i16> {
+        match self.os_2.and_then(|os_2| os_2.cap_height()) {
+            Some(v) => Some(self.apply_metrics_variation(Tag::from_bytes(b"cpht"), v)),
+            None => self.glyph_height('H'),
+        }
+    }
+
+    // Measures a glyph above the baseline.
+    fn glyph_height(&self, c: char) -> Option<i16> {
+        let glyph_id = self.glyph_index(c)?;
+        Some(self.outline_glyph(glyph_id, &mut DummyOutline)?.y_max)
+    }
+
+    /// Returns a code point used as a substitute for missing characters.
+    ///
+    /// Zero indicates that glyph 0 should be used.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn default_character(&self) -> Option<u16> {
+        self.os_2.and_then(|os_2| os_2.default_char())
+    }
+
+    /// Returns a code point used as a word break character, usually a space.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn break_character(&self) -> Option<u16> {
+        self.os_2.and_then(|os_2| os_2.break_char())
+    }
+
+    /// Returns the maximum length of a target glyph context for any feature in the face.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn max_context(&self) -> Option<u16> {
+        self.os_2.and_then(|os_2| os_2.max_context())
+    }
+
+    /// Returns a point size range the face was designed for.
+    ///
+    /// The lower value is inclusive and the upper is exclusive.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 5.
+    #[inline]
+    pub fn optical_point_size_range(&self) -> Option<core::ops::Range<f32>> {
+        self.os_2.and_then(|os_2| os_2.optical_point_size_range())
+    }
+
+    /// Returns face's underline metrics.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when `post` table is not present.
+    #[inline]
+    pub fn underline_metrics(&self) -> Option<LineMetrics> {
+        let mut metrics = self.post?.underline_metrics();
+
+        if self.is_variable() {
+            self.apply_metrics_variation_to(Tag::from_bytes(b"undo"), &mut metrics.position);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"unds"), &mut metrics.thickness);
         }
 
         Some(metrics)
@@ -1268,12 +2048,82 @@ impl<'a> FaceTables<'a> {
         self.number_of_glyphs.get()
     }
 
+    /// Returns an iterator over all glyphs with their metrics.
+    ///
+    /// Since the bounding box is resolved for each glyph,
+    /// iterating over a `CFF` based face involves outlining all glyphs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+    /// let face = ttf_parser::Face::from_slice(&data, 0).unwrap();
+    /// let widest = face.glyphs().filter_map(|info| info.advance).max();
+    /// assert_eq!(widest, Some(600));
+    /// ```
+    #[inline]
+    pub fn glyphs(&self) -> Glyphs<'_> {
+        Glyphs { tables: self, index: 0 }
+    }
+
+    /// Returns face's [maximum profile](https://docs.microsoft.com/en-us/typography/opentype/spec/maxp).
+    ///
+    /// Useful for preallocating buffers before outlining or hinting.
+    ///
+    /// Returns `None` when `maxp` table version is not 1.0,
+    /// which is the case for fonts with CFF outlines.
+    #[inline]
+    pub fn maximum_profile(&self) -> Option<MaximumProfile> {
+        maxp::profile(self.maxp)
+    }
+
+    /// Returns face's [Graphite](https://graphite.sil.org/) tables.
+    ///
+    /// Returns `None` when any of the `Silf`, `Glat` or `Gloc` tables
+    /// is not present or has an unsupported version.
+    #[inline]
+    pub fn graphite_tables(&self) -> Option<GraphiteTables<'a>> {
+        Some(GraphiteTables {
+            glat: self.glat?,
+            gloc: self.gloc?,
+            silf: self.silf?,
+            feat: self.feat,
+        })
+    }
+
+    /// Returns face's [PCL 5 table](https://docs.microsoft.com/en-us/typography/opentype/spec/pclt).
+    ///
+    /// A legacy table. Prefer `OS/2` based methods like
+    /// [`x_height()`](#method.x_height) when possible.
+    ///
+    /// Returns `None` when `PCLT` table is not present or invalid.
+    #[inline]
+    pub fn pcl_info(&self) -> Option<PclInfo> {
+        pclt::parse(self.pclt?)
+    }
+
     /// Returns an iterator over
     /// [character to glyph index mapping](https://docs.microsoft.com/en-us/typography/opentype/spec/cmap).
     ///
-    /// This is a more low-level alternative to `Face::glyph_index`.
+    /// This is a more low-level alternative to `Face::glyph_index`,
+    /// which allows using a specific subtable, like a non-Unicode one.
     ///
     /// An iterator can be empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ttf_parser::{Face, PlatformId};
+    ///
+    /// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+    /// let face = Face::from_slice(&data, 0).unwrap();
+    /// // Prefer the Windows Symbol subtable when present.
+    /// let subtable = face.character_mapping_subtables().find(|s| s.is_symbol())
+    ///     .or_else(|| face.character_mapping_subtables().find(|s| s.is_unicode()))
+    ///     .unwrap();
+    /// assert_eq!(subtable.platform_id(), PlatformId::Unicode);
+    /// assert!(subtable.glyph_index(u32::from('A')).is_some());
+    /// ```
     #[inline]
     pub fn character_mapping_subtables(&self) -> cmap::Subtables {
         self.cmap.unwrap_or_default()
@@ -1283,22 +2133,62 @@ impl<'a> FaceTables<'a> {
     ///
     /// Returns `None` instead of `0` when glyph is not found.
     ///
-    /// All subtable formats except Mixed Coverage (8) are supported.
+    /// All subtable formats are supported.
+    /// Segmented Coverage (12) subtables are preferred over other ones,
+    /// since they cover the whole Unicode range.
+    /// Macintosh Roman subtables are used only when there are no Unicode ones,
+    /// which is the case for some legacy Mac fonts.
     ///
     /// If you need a more low-level control, prefer `Face::character_mapping_subtables`.
     #[inline]
     pub fn glyph_index(&self, c: char) -> Option<GlyphId> {
-        for encoding in self.character_mapping_subtables() {
-            if !encoding.is_unicode() {
-                continue;
-            }
-
-            if let Some(id) = encoding.glyph_index(u32::from(c)) {
-                return Some(id);
-            }
+        let mut subtables = self.unicode_subtables().peekable();
+        if subtables.peek().is_none() {
+            let code = cmap::encode_mac_roman(c)?;
+            return self.character_mapping_subtables()
+                .filter(|e| e.is_mac_roman())
+                .find_map(|e| e.glyph_index(u32::from(code)));
         }
 
-        None
+        subtables.find_map(|e| e.glyph_index(u32::from(c)))
+    }
+
+    // Returns Unicode subtables, with full repertoire ones first.
+    fn unicode_subtables(&self) -> impl Iterator<Item = cmap::Subtable<'_>> + '_ {
+        let is_full_repertoire = |e: &cmap::Subtable| e.format() == cmap::Format::SegmentedCoverage;
+        let subtables = self.character_mapping_subtables().filter(|e| e.is_unicode());
+        subtables.clone().filter(is_full_repertoire)
+            .chain(subtables.filter(move |e| !is_full_repertoire(e)))
+    }
+
+    /// Calls `f` for all characters mapped by the face.
+    ///
+    /// Unlike `Face::glyph_index`, which can fallback to other subtables,
+    /// uses only the preferred Unicode subtable or the Macintosh Roman one
+    /// when there are no Unicode subtables.
+    /// Characters mapped to glyph ID `0` are skipped.
+    ///
+    /// If you need a more low-level control, prefer `Face::character_mapping_subtables`.
+    pub fn codepoints<F: FnMut(char)>(&self, mut f: F) {
+        let subtable = self.unicode_subtables()
+            .find(|e| e.format() != cmap::Format::UnicodeVariationSequences);
+        if let Some(subtable) = subtable {
+            subtable.codepoints(|code| {
+                if let Some(c) = core::char::from_u32(code) {
+                    if subtable.glyph_index(code).is_some() {
+                        f(c);
+                    }
+                }
+            });
+        } else if let Some(subtable) = self.character_mapping_subtables().find(|e| e.is_mac_roman()) {
+            subtable.codepoints(|code| {
+                if let Ok(c) = core::convert::TryFrom::try_from(code) {
+                    if subtable.glyph_index(code).is_some() {
+                        f(cmap::decode_mac_roman(c));
+                    }
+                }
+            });
+        }
     }
 
     /// Resolves a variation of a Glyph ID from two code points.
@@ -1331,12 +2221,12 @@ impl<'a> FaceTables<'a> {
             if self.is_variable() {
                 // Ignore variation offset when `hvar` is not set.
                 if let Some(hvar_data) = self.hvar {
-                    // We can't use `round()` in `no_std`, so this is the next best thing.
-                    advance += hvar::glyph_advance_offset(hvar_data, glyph_id, self.coords())? + 0.5;
+                    // A glyph without a delta-set is simply not varied.
+                    advance += hvar::glyph_advance_offset(hvar_data, glyph_id, self.coords()).unwrap_or(0.0);
                 }
             }
 
-            u16::try_num_from(advance)
+            round_metric(advance)
         }
 
         #[cfg(not(feature = "variable-fonts"))] {
@@ -1355,12 +2245,12 @@ impl<'a> FaceTables<'a> {
             if self.is_variable() {
                 // Ignore variation offset when `vvar` is not set.
                 if let Some(vvar_data) = self.vvar {
-                    // We can't use `round()` in `no_std`, so this is the next best thing.
-                    advance += hvar::glyph_advance_offset(vvar_data, glyph_id, self.coords())? + 0.5;
+                    // A glyph without a delta-set is simply not varied.
+                    advance += hvar::glyph_advance_offset(vvar_data, glyph_id, self.coords()).unwrap_or(0.0);
                 }
             }
 
-            u16::try_num_from(advance)
+            round_metric(advance)
         }
 
         #[cfg(not(feature = "variable-fonts"))] {
@@ -1379,12 +2269,12 @@ impl<'a> FaceTables<'a> {
             if self.is_variable() {
                 // Ignore variation offset when `hvar` is not set.
                 if let Some(hvar_data) = self.hvar {
-                    // We can't use `round()` in `no_std`, so this is the next best thing.
-                    bearing += hvar::glyph_side_bearing_offset(hvar_data, glyph_id, self.coords())? + 0.5;
+                    // A glyph without a delta-set is simply not varied.
+                    bearing += hvar::glyph_side_bearing_offset(hvar_data, glyph_id, self.coords()).unwrap_or(0.0);
                 }
             }
 
-            i16::try_num_from(bearing)
+            round_metric(bearing)
         }
 
         #[cfg(not(feature = "variable-fonts"))] {
@@ -1403,12 +2293,12 @@ impl<'a> FaceTables<'a> {
             if self.is_variable() {
                 // Ignore variation offset when `vvar` is not set.
                 if let Some(vvar_data) = self.vvar {
-                    // We can't use `round()` in `no_std`, so this is the next best thing.
-                    bearing += hvar::glyph_side_bearing_offset(vvar_data, glyph_id, self.coords())? + 0.5;
+                    // A glyph without a delta-set is simply not varied.
+                    bearing += hvar::glyph_side_bearing_offset(vvar_data, glyph_id, self.coords()).unwrap_or(0.0);
                 }
             }
 
-            i16::try_num_from(bearing)
+            round_metric(bearing)
         }
 
         #[cfg(not(feature = "variable-fonts"))] {
@@ -1418,8 +2308,26 @@ impl<'a> FaceTables<'a> {
 
     /// Returns glyph's vertical origin according to
     /// [Vertical Origin Table](https://docs.microsoft.com/en-us/typography/opentype/spec/vorg).
+    ///
+    /// This method is affected by variation axes.
     pub fn glyph_y_origin(&self, glyph_id: GlyphId) -> Option<i16> {
-        self.vorg.map(|vorg| vorg.glyph_y_origin(glyph_id))
+        #[cfg(feature = "variable-fonts")] {
+            let mut origin = f32::from(self.vorg?.glyph_y_origin(glyph_id));
+
+            if self.is_variable() {
+                // Ignore variation offset when `vvar` is not set.
+                if let Some(vvar_data) = self.vvar {
+                    // A glyph without a delta-set is simply not varied.
+                    origin += hvar::glyph_y_origin_offset(vvar_data, glyph_id, self.coords()).unwrap_or(0.0);
+                }
+            }
+
+            round_metric(origin)
+        }
+
+        #[cfg(not(feature = "variable-fonts"))] {
+            self.vorg.map(|vorg| vorg.glyph_y_origin(glyph_id))
+        }
     }
 
     /// Returns glyph's name.
@@ -1440,6 +2348,27 @@ impl<'a> FaceTables<'a> {
         None
     }
 
+    /// Returns a glyph ID by its name.
+    ///
+    /// Uses the `post` and `CFF` tables as sources.
+    ///
+    /// This method is relatively slow, since all glyph names have to be checked.
+    ///
+    /// Returns `None` when no glyph has this name.
+    pub fn glyph_index_by_name(&self, name: &str) -> Option<GlyphId> {
+        if let Some(id) = self.post.and_then(|post| post.glyph_index_by_name(name)) {
+            return Some(id);
+        }
+
+        if let Some(ref cff1) = self.cff1 {
+            return (0..self.number_of_glyphs())
+                .map(GlyphId)
+                .find(|id| cff1::glyph_name(cff1, *id) == Some(name));
+        }
+
+        None
+    }
+
     /// Checks that face has
     /// [Glyph Class Definition Table](
     /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#glyph-class-definition-table).
@@ -1447,6 +2376,96 @@ impl<'a> FaceTables<'a> {
         try_opt_or!(self.gdef, false).has_glyph_classes()
     }
 
+    /// Returns face's [Glyph Substitution Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/gsub).
+    #[inline]
+    pub fn substitution_table(&self) -> Option<LayoutTable<'a>> {
+        self.gsub
+    }
+
+    /// Returns face's [Glyph Positioning Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos).
+    #[inline]
+    pub fn positioning_table(&self) -> Option<LayoutTable<'a>> {
+        self.gpos
+    }
+
+    /// Returns an iterator over `GPOS` pair adjustment lookups of the `kern` feature.
+    ///
+    /// Lookups are yielded in the order they should be applied.
+    /// Lookups shared by multiple `kern` features are yielded only once.
+    /// Use [`PairAdjustment::parse`](struct.PairAdjustment.html#method.parse)
+    /// to parse lookup's subtables.
+    #[inline]
+    pub fn kerning_lookups(&self) -> impl Iterator<Item = Lookup<'a>> + 'a {
+        gpos::kerning_lookups(self.gpos)
+    }
+
+    /// Returns a horizontal kerning value for a pair of glyphs
+    /// from the `GPOS` `kern` feature.
+    ///
+    /// Sums the first glyph's advance adjustments of all `kern` pair adjustment lookups,
+    /// ignoring lookup flags, scripts and language systems.
+    /// This is enough for a simple text layout, but not a replacement for a shaping engine.
+    ///
+    /// Returns `None` when no lookup covers the pair.
+    #[inline]
+    pub fn glyphs_pair_adjustment(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        gpos::glyphs_kerning(self.gpos, left, right)
+    }
+
+    /// Returns base and mark anchors for a pair of glyphs
+    /// from the `GPOS` `mark` feature.
+    ///
+    /// Uses the first mark-to-base attachment subtable that covers both glyphs,
+    /// ignoring lookup flags, scripts and language systems.
+    /// The mark should be positioned so its anchor matches the base one.
+    ///
+    /// Returns `None` when no subtable covers the pair.
+    #[inline]
+    pub fn glyphs_mark_to_base_anchors(
+        &self,
+        base: GlyphId,
+        mark: GlyphId,
+    ) -> Option<(Anchor<'a>, Anchor<'a>)> {
+        gpos::mark_to_base_anchors(self.gpos, base, mark)
+    }
+
+    /// Returns an iterator over glyph's alternates.
+    ///
+    /// Alternates are collected from single and alternate substitution lookups
+    /// of the `GSUB` [`aalt`](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/features_ae#tag-aalt) feature.
+    /// This is the list that glyph palettes usually show.
+    #[inline]
+    pub fn glyph_alternates(&self, glyph_id: GlyphId) -> GlyphAlternates<'a> {
+        GlyphAlternates::new(self.gsub, glyph_id)
+    }
+
+    /// Returns an iterator over tags of `GSUB` and `GPOS` features that reference a glyph.
+    ///
+    /// A feature references a glyph when one of its lookups has a coverage table
+    /// that contains it. Only coverage tables of input glyphs are checked,
+    /// so glyphs produced by substitutions are not reported.
+    ///
+    /// This method is relatively slow, since each lookup has to be checked.
+    #[inline]
+    pub fn glyph_features(&self, glyph_id: GlyphId) -> GlyphFeatures<'a> {
+        GlyphFeatures::new(self.gsub, self.gpos, glyph_id)
+    }
+
+    /// Returns an iterator over scripts supported by the face.
+    ///
+    /// Combines script tags from `GSUB` and `GPOS` tables with a `cmap` coverage heuristic:
+    /// a script is considered supported when a few of its representative letters are mapped.
+    /// The heuristic covers only the most widespread scripts.
+    ///
+    /// Each script is reported only once. `DFLT` is never reported.
+    #[inline]
+    pub fn scripts(&self) -> SupportedScripts<'_> {
+        SupportedScripts::new(self)
+    }
+
     /// Returns glyph's class according to
     /// [Glyph Class Definition Table](
     /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#glyph-class-definition-table).
@@ -1477,17 +2496,74 @@ impl<'a> FaceTables<'a> {
         try_opt_or!(self.gdef, false).is_mark_glyph(glyph_id, set_index)
     }
 
-    /// Returns glyph's variation delta at a specified index according to
-    /// [Item Variation Store Table](
-    /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#item-variation-store-table).
-    #[cfg(feature = "variable-fonts")]
+    /// Returns the number of
+    /// [mark glyph sets](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#mark-glyph-sets-table).
     #[inline]
-    pub fn glyph_variation_delta(&self, outer_index: u16, inner_index: u16) -> Option<f32> {
-        self.gdef.and_then(|gdef|
-            gdef.variation_delta(outer_index, inner_index, self.coordinates.as_slice()))
+    pub fn mark_glyph_sets_count(&self) -> u16 {
+        try_opt_or!(self.gdef, 0).mark_glyph_sets_count()
     }
 
-    /// Returns a iterator over kerning subtables.
+    /// Checks that a lookup should skip a glyph according to its flags.
+    ///
+    /// Uses `GDEF` glyph classes, mark attachment classes and mark glyph sets
+    /// to implement `IgnoreBaseGlyphs`, `IgnoreLigatures`, `IgnoreMarks`,
+    /// `MarkAttachmentType` and `UseMarkFilteringSet`.
+    ///
+    /// Returns `false` when `GDEF` is not present.
+    #[inline]
+    pub fn lookup_ignores_glyph(&self, lookup: &Lookup, glyph_id: GlyphId) -> bool {
+        try_opt_or!(self.gdef, false)
+            .ignores_glyph(glyph_id, lookup.flags(), lookup.mark_filtering_set())
+    }
+
+    /// Returns glyph's merge class according to
+    /// [Merge Table](https://docs.microsoft.com/en-us/typography/opentype/spec/merg).
+    ///
+    /// All glyphs not assigned to a class fall into Class 0.
+    pub fn glyph_merge_class(&self, glyph_id: GlyphId) -> Class {
+        try_opt_or!(self.merg, Class(0)).glyph_class(glyph_id)
+    }
+
+    /// Returns a merge entry for a pair of adjacent glyphs according to
+    /// [Merge Table](https://docs.microsoft.com/en-us/typography/opentype/spec/merg).
+    ///
+    /// `first` and `second` are in the logical order.
+    ///
+    /// Returns `None` when `MERG` table is not present.
+    pub fn glyphs_merge_entry(&self, first: GlyphId, second: GlyphId) -> Option<MergeEntry> {
+        let merg = self.merg?;
+        merg.entry(merg.glyph_class(first), merg.glyph_class(second))
+    }
+
+    /// Returns an iterator over ligature glyph's carets according to
+    /// [Ligature Caret List Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#ligature-caret-list-table).
+    ///
+    /// Variation deltas are applied using the current variation coordinates.
+    pub fn glyph_ligature_carets(&self, glyph_id: GlyphId) -> LigatureCarets<'_> {
+        let carets = self.gdef.and_then(|gdef| gdef.ligature_carets(glyph_id)).unwrap_or_default();
+
+        #[cfg(feature = "variable-fonts")] {
+            carets.with_coordinates(self.coords())
+        }
+
+        #[cfg(not(feature = "variable-fonts"))] {
+            carets
+        }
+    }
+
+    /// Returns glyph's variation delta at a specified index according to
+    /// [Item Variation Store Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#item-variation-store-table).
+    #[cfg(feature = "variable-fonts")]
+    #[inline]
+    pub fn glyph_variation_delta(&self, outer_index: u16, inner_index: u16) -> Option<f32> {
+        self.gdef.and_then(|gdef|
+            gdef.variation_delta(outer_index, inner_index, self.coordinates.as_slice()))
+    }
+
+    /// Returns a iterator over kerning subtables.
     ///
     /// Supports both
     /// [OpenType](https://docs.microsoft.com/en-us/typography/opentype/spec/kern)
@@ -1498,6 +2574,63 @@ impl<'a> FaceTables<'a> {
         self.kern.unwrap_or_default()
     }
 
+    /// Returns a iterator over
+    /// [Extended Kerning Table](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html)
+    /// subtables.
+    pub fn extended_kerning_subtables(&self) -> kerx::Subtables<'a> {
+        self.kerx.unwrap_or_default()
+    }
+
+    /// Returns a horizontal kerning value for a pair of glyphs.
+    ///
+    /// Accumulates values from all horizontal subtables in the
+    /// [Extended Kerning Table](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html)
+    /// when present, and in the
+    /// [Kerning Table](https://docs.microsoft.com/en-us/typography/opentype/spec/kern) otherwise.
+    /// Override subtables replace the accumulated value.
+    /// Subtables with minimum values, cross-stream values, variations or a state machine
+    /// are ignored. Use [`kerning_subtables`](#method.kerning_subtables) and
+    /// [`extended_kerning_subtables`](#method.extended_kerning_subtables) to process them.
+    ///
+    /// Returns `None` when no subtable has a value for this pair.
+    pub fn glyphs_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        if self.kerx.is_some() {
+            let mut kerning = None;
+            for subtable in self.extended_kerning_subtables() {
+                if !subtable.is_horizontal() || subtable.has_cross_stream()
+                    || subtable.is_variable() || subtable.has_state_machine()
+                {
+                    continue;
+                }
+
+                if let Some(value) = subtable.glyphs_kerning(left, right) {
+                    kerning = Some(kerning.unwrap_or(0i16).saturating_add(value));
+                }
+            }
+
+            return kerning;
+        }
+
+        let mut kerning = None;
+        for subtable in self.kerning_subtables() {
+            if !subtable.is_horizontal() || subtable.is_minimum() || subtable.has_cross_stream()
+                || subtable.is_variable() || subtable.has_state_machine()
+            {
+                continue;
+            }
+
+            if let Some(value) = subtable.glyphs_kerning(left, right) {
+                kerning = Some(if subtable.is_override() {
+                    value
+                } else {
+                    kerning.unwrap_or(0i16).saturating_add(value)
+                });
+            }
+        }
+
+        kerning
+    }
+
     /// Outlines a glyph and returns its tight bounding box.
     ///
     /// **Warning**: since `ttf-parser` is a pull parser,
@@ -1579,6 +2712,94 @@ impl<'a> FaceTables<'a> {
         None
     }
 
+    /// Outlines a glyph in pixels for the specified size.
+    ///
+    /// Same as [`outline_glyph()`](#method.outline_glyph), but all coordinates
+    /// are multiplied by `pixels_per_em / units_per_em`.
+    /// When `flip_y` is set, the Y axis points down, like in most graphics libraries,
+    /// while the origin stays at the baseline.
+    ///
+    /// The returned bounding box is in the same scaled units as the outline,
+    /// rounded outwards to whole pixels, and is flipped as well when `flip_y` is set.
+    /// Coordinates that do not fit into `i16` are clamped.
+    ///
+    /// Returns `None` when `pixels_per_em` is not a positive number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    /// use ttf_parser;
+    ///
+    /// struct Builder(String);
+    ///
+    /// impl ttf_parser::OutlineBuilder for Builder {
+    ///     fn move_to(&mut self, x: f32, y: f32) {
+    ///         write!(&mut self.0, "M {} {} ", x, y).unwrap();
+    ///     }
+    ///
+    ///     fn line_to(&mut self, x: f32, y: f32) {
+    ///         write!(&mut self.0, "L {} {} ", x, y).unwrap();
+    ///     }
+    ///
+    ///     fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+    ///         write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+    ///     }
+    ///
+    ///     fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+    ///         write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+    ///     }
+    ///
+    ///     fn close(&mut self) {
+    ///         write!(&mut self.0, "Z ").unwrap();
+    ///     }
+    /// }
+    ///
+    /// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+    /// let face = ttf_parser::Face::from_slice(&data, 0).unwrap();
+    /// let mut builder = Builder(String::new());
+    /// let bbox = face.outline_glyph_scaled(ttf_parser::GlyphId(1), 2000.0, true, &mut builder).unwrap();
+    /// // 1000 units per em.
+    /// assert!(builder.0.starts_with("M 346 -534 L 738 -534 "));
+    /// assert!(bbox.y_min <= -534);
+    /// ```
+    pub fn outline_glyph_scaled(
+        &self,
+        glyph_id: GlyphId,
+        pixels_per_em: f32,
+        flip_y: bool,
+        builder: &mut dyn OutlineBuilder,
+    ) -> Option<Rect> {
+        if !pixels_per_em.is_finite() || pixels_per_em <= 0.0 {
+            return None;
+        }
+
+        let scale = pixels_per_em / f32::from(self.units_per_em()?);
+        let mut builder = outline::ScaleBuilder {
+            builder,
+            x_scale: scale,
+            y_scale: if flip_y { -scale } else { scale },
+        };
+
+        let bbox = self.outline_glyph(glyph_id, &mut builder)?;
+        Some(scale_rect(bbox, builder.x_scale, builder.y_scale))
+    }
+
+    /// Rasterizes a glyph into an anti-aliased coverage mask.
+    ///
+    /// The mask is just large enough to contain the glyph at the specified size.
+    ///
+    /// Returns `None` when glyph has no outline, when it's larger than 16 ems
+    /// or when the mask would be larger than 8192 pixels in any dimension.
+    ///
+    /// This method is affected by variation axes.
+    /// Available only with the `rasterizer` feature.
+    #[cfg(feature = "rasterizer")]
+    #[inline]
+    pub fn rasterize_glyph(&self, glyph_id: GlyphId, pixels_per_em: f32) -> Option<GlyphMask> {
+        raster::rasterize_glyph(self, glyph_id, pixels_per_em)
+    }
+
     /// Returns a tight glyph bounding box.
     ///
     /// Unless the current face has a `glyf` table, this is just a shorthand for `outline_glyph()`
@@ -1611,6 +2832,227 @@ impl<'a> FaceTables<'a> {
         self.outline_glyph(glyph_id, &mut DummyOutline)
     }
 
+    /// Returns a tight glyph bounding box at the specified variation coordinates.
+    ///
+    /// Unlike [`glyph_bounding_box()`](#method.glyph_bounding_box), doesn't depend
+    /// on the current variation coordinates, so bounding boxes of multiple instances
+    /// can be calculated without modifying the face.
+    /// The bounding box is calculated from the outline with variation deltas applied,
+    /// since the bounding box stored in the `glyf` table is valid only for the default instance.
+    ///
+    /// `coordinates` must be normalized, one per axis, with `avar` mapping already applied,
+    /// just like [`variation_coordinates()`](#method.variation_coordinates) returns.
+    ///
+    /// Returns `None` when the number of coordinates doesn't match the number of axes.
+    ///
+    /// For non-variable faces this is the same as `glyph_bounding_box()`.
+    #[cfg(feature = "variable-fonts")]
+    pub fn glyph_bounding_box_at(
+        &self,
+        glyph_id: GlyphId,
+        coordinates: &[NormalizedCoordinate],
+    ) -> Option<Rect> {
+        if coordinates.len() != self.coords().len() {
+            return None;
+        }
+
+        if let Some(ref gvar_table) = self.gvar {
+            return gvar::outline(self.loca?, self.glyf?, gvar_table, coordinates, glyph_id,
+                                 &mut DummyOutline);
+        }
+
+        if let Some(ref metadata) = self.cff2 {
+            return cff2::outline(metadata, coordinates, glyph_id, &mut DummyOutline);
+        }
+
+        self.glyph_bounding_box(glyph_id)
+    }
+
+    /// Returns glyph's outline statistics.
+    ///
+    /// In case of a `glyf` table, only the glyph header and contour endpoints are parsed,
+    /// so this method is much cheaper than `outline_glyph()`.
+    /// `CFF` and `CFF2` glyphs have to be outlined, since they do not store such data.
+    ///
+    /// Variation axes are ignored, because they cannot affect the number of points.
+    ///
+    /// Returns `None` when glyph has no outline or on error.
+    #[inline]
+    pub fn glyph_stats(&self, glyph_id: GlyphId) -> Option<GlyphStats> {
+        if let Some(glyf_table) = self.glyf {
+            return glyf::glyph_stats(self.loca?, glyf_table, glyph_id);
+        }
+
+        let mut builder = StatsOutline(GlyphStats {
+            number_of_contours: 0,
+            number_of_points: 0,
+            is_composite: false,
+        });
+        self.outline_glyph(glyph_id, &mut builder)?;
+        Some(builder.0)
+    }
+
+    /// Checks that glyph has no outline.
+    ///
+    /// Useful to skip rasterization of spaces and control glyphs.
+    ///
+    /// In case of a `glyf` table, only `loca` is checked, so this method is very cheap.
+    /// `CFF` and `CFF2` glyphs have to be outlined, since even an empty charstring
+    /// contains at least an `endchar` operator.
+    /// Malformed `CFF` glyphs are reported as empty.
+    ///
+    /// Glyph images from `sbix`, `CBDT` and `SVG` tables are ignored.
+    ///
+    /// Returns `None` when glyph ID is out of range or face has no outlines.
+    #[inline]
+    pub fn glyph_is_empty(&self, glyph_id: GlyphId) -> Option<bool> {
+        if glyph_id.0 >= self.number_of_glyphs() {
+            return None;
+        }
+
+        if self.glyf.is_some() {
+            // An empty `loca` range indicates a glyph without an outline.
+            return Some(self.loca?.glyph_range(glyph_id).is_none());
+        }
+
+        #[cfg(feature = "variable-fonts")] {
+            if self.cff2.is_some() {
+                return Some(self.outline_glyph(glyph_id, &mut DummyOutline).is_none());
+            }
+        }
+
+        if self.cff1.is_some() {
+            return Some(self.outline_glyph(glyph_id, &mut DummyOutline).is_none());
+        }
+
+        None
+    }
+
+    /// Checks that the `.notdef` glyph (glyph 0) is present and has an outline.
+    ///
+    /// The spec requires `.notdef` to be a visible glyph,
+    /// so an empty one usually indicates a broken or subsetted font.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+    /// let face = ttf_parser::Face::from_slice(&data, 0).unwrap();
+    /// assert!(face.has_notdef_outline());
+    /// ```
+    #[inline]
+    pub fn has_notdef_outline(&self) -> bool {
+        self.glyph_is_empty(GlyphId(0)) == Some(false)
+    }
+
+    /// Checks that glyph is a space-like glyph.
+    ///
+    /// A space-like glyph has no outline and is mapped from at least one
+    /// code point with the Unicode `White_Space` property, like U+0020 or U+00A0.
+    ///
+    /// Since there is no reverse mapping, each whitespace code point is looked up,
+    /// so prefer calling this method once per glyph and caching the result.
+    #[inline]
+    pub fn is_whitespace_glyph(&self, glyph_id: GlyphId) -> bool {
+        let is_mapped = WHITESPACE_CHARS.iter()
+            .any(|c| self.glyph_index(*c) == Some(glyph_id));
+        is_mapped && self.glyph_is_empty(glyph_id) == Some(true)
+    }
+
+    /// Checks that glyph has the `OVERLAP_SIMPLE` or `OVERLAP_COMPOUND` flag set.
+    ///
+    /// Such glyphs have overlapping contours or components and should be
+    /// rasterized using the non-zero winding rule with overlap-aware anti-aliasing.
+    /// In case of a composite glyph, referenced glyphs are checked too.
+    ///
+    /// Only `glyf` based faces store such flags.
+    ///
+    /// Returns `None` when face doesn't have a `glyf` table,
+    /// glyph has no outline or on error.
+    #[inline]
+    pub fn glyph_has_overlap(&self, glyph_id: GlyphId) -> Option<bool> {
+        glyf::glyph_has_overlap(self.loca?, self.glyf?, glyph_id)
+    }
+
+    /// Returns glyph's TrueType hinting instructions.
+    ///
+    /// In case of a composite glyph, instructions are present only when
+    /// one of the components has the `WE_HAVE_INSTRUCTIONS` flag set.
+    ///
+    /// The bytecode is returned as is. Interpreting it is up to the caller.
+    ///
+    /// Returns `None` when face doesn't have a `glyf` table
+    /// or glyph doesn't have instructions.
+    #[inline]
+    pub fn glyph_instructions(&self, glyph_id: GlyphId) -> Option<&'a [u8]> {
+        glyf::glyph_instructions(self.loca?, self.glyf?, glyph_id)
+    }
+
+    /// Returns face's [Control Value Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/cvt).
+    ///
+    /// Values are in font units.
+    #[inline]
+    pub fn control_values(&self) -> Option<LazyArray16<'a, i16>> {
+        self.cvt_
+    }
+
+    /// Returns a [CVT variations](https://docs.microsoft.com/en-us/typography/opentype/spec/cvar)
+    /// delta for a control value at `index` at the current variation coordinates.
+    ///
+    /// The delta is not rounded and should be added to the value from `control_values()`.
+    ///
+    /// Returns `None` when face is not variable, doesn't have a `cvt ` or `cvar` table,
+    /// `index` is out of bounds or the `cvar` table is malformed.
+    #[cfg(feature = "variable-fonts")]
+    #[inline]
+    pub fn control_value_delta(&self, index: u16) -> Option<f32> {
+        if index >= self.cvt_?.len() {
+            return None;
+        }
+
+        self.cvar?.value_delta(self.coords(), index)
+    }
+
+    /// Returns face's [Font Program](https://docs.microsoft.com/en-us/typography/opentype/spec/fpgm).
+    ///
+    /// The bytecode is returned as is. Interpreting it is up to the caller.
+    #[inline]
+    pub fn font_program(&self) -> Option<&'a [u8]> {
+        self.fpgm
+    }
+
+    /// Returns face's [Control Value Program](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/prep).
+    ///
+    /// The bytecode is returned as is. Interpreting it is up to the caller.
+    #[inline]
+    pub fn control_value_program(&self) -> Option<&'a [u8]> {
+        self.prep
+    }
+
+    /// Returns an iterator over face's [Digital Signatures](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/dsig).
+    ///
+    /// An iterator can be empty.
+    ///
+    /// Signatures are not verified.
+    #[inline]
+    pub fn digital_signatures(&self) -> Signatures<'a> {
+        self.dsig.map(|dsig| dsig.signatures).unwrap_or_default()
+    }
+
+    /// Returns face's [Digital Signature](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/dsig) flags.
+    ///
+    /// Bit 0 indicates that the face cannot be resigned.
+    ///
+    /// Returns `None` when `DSIG` table is not present or invalid.
+    #[inline]
+    pub fn digital_signature_flags(&self) -> Option<u16> {
+        self.dsig.map(|dsig| dsig.flags)
+    }
+
     /// Returns a bounding box that large enough to enclose any glyph from the face.
     #[inline]
     pub fn global_bounding_box(&self) -> Rect {
@@ -1653,6 +3095,84 @@ impl<'a> FaceTables<'a> {
         None
     }
 
+    /// Returns [Math Constants](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathconstants-table).
+    ///
+    /// Returns `None` when `MATH` table is not present or has no constants.
+    #[inline]
+    pub fn math_constants(&self) -> Option<MathConstants<'a>> {
+        self.math?.constants
+    }
+
+    /// Returns glyph's italics correction from the [`MATH` table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathitalicscorrectioninfo-table).
+    #[inline]
+    pub fn math_italics_correction(&self, glyph_id: GlyphId) -> Option<MathValue<'a>> {
+        self.math?.italic_correction(glyph_id)
+    }
+
+    /// Returns glyph's top accent attachment position from the [`MATH` table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathtopaccentattachment-table).
+    ///
+    /// When not set, accents should be centered horizontally on the glyph.
+    #[inline]
+    pub fn math_top_accent_attachment(&self, glyph_id: GlyphId) -> Option<MathValue<'a>> {
+        self.math?.top_accent_attachment(glyph_id)
+    }
+
+    /// Checks that glyph is an [extended shape](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/math#extended-shape-coverage-table).
+    ///
+    /// Extended shapes, like tall delimiters, get different script positioning.
+    #[inline]
+    pub fn is_math_extended_shape(&self, glyph_id: GlyphId) -> bool {
+        self.math.map(|math| math.is_extended_shape(glyph_id)).unwrap_or(false)
+    }
+
+    /// Returns the minimum overlap of connecting glyphs during glyph construction
+    /// from the [`MATH` table](https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathvariants-table).
+    #[inline]
+    pub fn math_min_connector_overlap(&self) -> Option<u16> {
+        self.math?.min_connector_overlap()
+    }
+
+    /// Returns glyph's vertical size variants and assembly from the [`MATH` table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathvariants-table).
+    ///
+    /// Used to stretch delimiters, radicals and other operators vertically.
+    #[inline]
+    pub fn math_vertical_construction(&self, glyph_id: GlyphId) -> Option<MathGlyphConstruction<'a>> {
+        self.math?.vertical_construction(glyph_id)
+    }
+
+    /// Returns glyph's horizontal size variants and assembly from the [`MATH` table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathvariants-table).
+    ///
+    /// Used to stretch accents, arrows and other operators horizontally.
+    #[inline]
+    pub fn math_horizontal_construction(&self, glyph_id: GlyphId) -> Option<MathGlyphConstruction<'a>> {
+        self.math?.horizontal_construction(glyph_id)
+    }
+
+    /// Returns an iterator over [Embedded Bitmap Scaling](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/ebsc) records.
+    ///
+    /// An iterator can be empty.
+    #[inline]
+    pub fn bitmap_scales(&self) -> BitmapScales<'a> {
+        self.ebsc.map(|ebsc| ebsc.scales()).unwrap_or_default()
+    }
+
+    /// Returns a bitmap scale for the specified ppem size.
+    ///
+    /// Glyphs of this size should be produced by scaling bitmaps of the substitute size.
+    ///
+    /// Returns `None` when `EBSC` table is not present or has no such size.
+    #[inline]
+    pub fn bitmap_scale(&self, ppem_x: u8, ppem_y: u8) -> Option<BitmapScale> {
+        self.ebsc?.find(ppem_x, ppem_y)
+    }
+
     /// Returns a reference to a glyph's SVG image.
     ///
     /// A font can define a glyph using a raster or a vector image instead of a simple outline.
@@ -1662,6 +3182,9 @@ impl<'a> FaceTables<'a> {
     /// or even decompressed (in case of SVGZ) by the caller.
     /// We don't validate or preprocess it in any way.
     ///
+    /// A single SVG document can define multiple glyphs. In which case,
+    /// the required glyph is an element with the `glyph{glyph_id}` ID.
+    ///
     /// Also, a font can contain both: images and outlines. So when this method returns `None`
     /// you should also try `outline_glyph()` afterwards.
     #[inline]
@@ -1669,6 +3192,113 @@ impl<'a> FaceTables<'a> {
         self.svg_.and_then(|svg_data| svg::parse(svg_data, glyph_id))
     }
 
+    /// Checks that face has a [Color Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/colr) entry for the glyph.
+    #[inline]
+    pub fn is_color_glyph(&self, glyph_id: GlyphId) -> bool {
+        self.colr.map(|colr| colr.contains(glyph_id)).unwrap_or(false)
+    }
+
+    /// Returns glyph's color layers from the [Color Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/colr), from bottom to top.
+    ///
+    /// Only `COLR` version 0 glyphs are supported. Use `paint_color_glyph()` to handle both.
+    ///
+    /// Each layer is a glyph outline filled with a color from the `CPAL` table,
+    /// which can be resolved using `palette_color()`.
+    ///
+    /// Returns `None` when face doesn't have a `COLR` table or glyph is not a color glyph.
+    #[inline]
+    pub fn color_glyph_layers(&self, glyph_id: GlyphId) -> Option<ColorGlyphLayers<'a>> {
+        self.colr?.layers(glyph_id)
+    }
+
+    /// Returns a color from the [Color Palette Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/cpal).
+    ///
+    /// Palette 0 is the default one.
+    ///
+    /// Returns `None` when face doesn't have a `CPAL` table, index is out of bounds
+    /// or `palette_entry` is `0xFFFF`, i.e. the text foreground color.
+    #[inline]
+    pub fn palette_color(&self, palette_index: u16, palette_entry: u16) -> Option<RgbaColor> {
+        self.cpal?.get(palette_index, palette_entry)
+    }
+
+    /// Returns a number of palettes in the [Color Palette Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/cpal).
+    ///
+    /// Returns 0 when face doesn't have a `CPAL` table.
+    #[inline]
+    pub fn palettes_count(&self) -> u16 {
+        self.cpal.map(|cpal| cpal.palettes()).unwrap_or(0)
+    }
+
+    /// Returns a number of colors in each palette.
+    ///
+    /// Returns 0 when face doesn't have a `CPAL` table.
+    #[inline]
+    pub fn palette_entries_count(&self) -> u16 {
+        self.cpal.map(|cpal| cpal.palette_entries()).unwrap_or(0)
+    }
+
+    /// Returns all colors of the palette at `palette_index`.
+    #[inline]
+    pub fn palette(&self, palette_index: u16) -> Option<LazyArray16<'a, RgbaColor>> {
+        self.cpal?.palette(palette_index)
+    }
+
+    /// Returns palette's type flags.
+    ///
+    /// Palettes from a version 0 `CPAL` table have no flags set.
+    #[inline]
+    pub fn palette_flags(&self, palette_index: u16) -> Option<PaletteFlags> {
+        self.cpal?.palette_flags(palette_index)
+    }
+
+    /// Returns palette's name ID in the `name` table.
+    #[inline]
+    pub fn palette_name_id(&self, palette_index: u16) -> Option<u16> {
+        self.cpal?.palette_name_id(palette_index)
+    }
+
+    /// Returns palette entry's name ID in the `name` table.
+    ///
+    /// Entry names are shared by all palettes.
+    #[inline]
+    pub fn palette_entry_name_id(&self, palette_entry: u16) -> Option<u16> {
+        self.cpal?.palette_entry_name_id(palette_entry)
+    }
+
+    /// Paints a color glyph from the [Color Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/colr).
+    ///
+    /// Both `COLR` version 0 layers and version 1 paint graphs are supported.
+    /// Colors are resolved using the `CPAL` palette at `palette_index`, while
+    /// `foreground_color` is used for the text foreground color entries.
+    ///
+    /// Variable paints are painted using their default values.
+    ///
+    /// Returns `None` when face doesn't have a `COLR` table, glyph is not a color glyph
+    /// or the paint graph is malformed. In the latter case, painting can stop midway,
+    /// but all the `Painter` push/pop calls are still balanced.
+    #[inline]
+    pub fn paint_color_glyph(
+        &self,
+        glyph_id: GlyphId,
+        palette_index: u16,
+        foreground_color: RgbaColor,
+        painter: &mut dyn Painter,
+    ) -> Option<()> {
+        let palette = colr::Palette {
+            table: self.cpal,
+            index: palette_index,
+            foreground: foreground_color,
+        };
+
+        self.colr?.paint(glyph_id, palette, painter)
+    }
+
     /// Returns an iterator over variation axes.
     #[cfg(feature = "variable-fonts")]
     #[inline]
@@ -1676,6 +3306,15 @@ impl<'a> FaceTables<'a> {
         self.fvar.map(|fvar| fvar.axes()).unwrap_or_default()
     }
 
+    /// Returns an iterator over named instances.
+    ///
+    /// Coordinates can be passed to `Face::set_variation` as is.
+    #[cfg(feature = "variable-fonts")]
+    #[inline]
+    pub fn named_instances(&self) -> NamedInstances<'a> {
+        self.fvar.map(|fvar| fvar.named_instances()).unwrap_or_default()
+    }
+
     /// Sets a variation axis coordinate.
     ///
     /// This is the only mutable method in the library.
@@ -1697,20 +3336,42 @@ impl<'a> FaceTables<'a> {
                 return None;
             }
 
-            self.coordinates.data[idx] = a.normalized_value(value);
+            self.coordinates.unmapped[idx] = a.normalized_value(value);
         } else {
             return None;
         }
 
-        // TODO: optimize
+        // `avar` must be applied to the unmapped coordinates of all axes,
+        // since a mapped value can depend on other axes.
+        self.coordinates.data = self.coordinates.unmapped;
         if let Some(avar) = self.avar {
-            // Ignore error.
-            let _ = avar.map_coordinates(self.coordinates.as_mut_slice());
+            // A malformed `avar` is ignored and must not leave coordinates half-mapped.
+            if avar.map_coordinates(self.coordinates.as_mut_slice()).is_none() {
+                self.coordinates.data = self.coordinates.unmapped;
+            }
         }
 
         Some(())
     }
 
+    /// Returns table's [Item Variation Store](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#item-variation-store).
+    ///
+    /// Supports `avar`, `CFF2`, `GDEF`, `HVAR`, `MVAR` and `VVAR`.
+    /// Returns `None` for other tables or when the table has no variation store.
+    #[cfg(feature = "variable-fonts")]
+    pub fn item_variation_store(&self, name: TableName) -> Option<ItemVariationStore<'a>> {
+        match name {
+            TableName::AxisVariations               => self.avar?.variation_store(),
+            TableName::CompactFontFormat2           => self.cff2.map(|t| t.variation_store()),
+            TableName::GlyphDefinition              => self.gdef?.variation_store(),
+            TableName::HorizontalMetricsVariations  => self.hvar.map(|t| t.variation_store()),
+            TableName::MetricsVariations            => self.mvar.map(|t| t.variation_store()),
+            TableName::VerticalMetricsVariations    => self.vvar.map(|t| t.variation_store()),
+            _ => None,
+        }
+    }
+
     /// Returns the current normalized variation coordinates.
     #[cfg(feature = "variable-fonts")]
     #[inline]
@@ -1743,8 +3404,7 @@ impl<'a> FaceTables<'a> {
     fn apply_metrics_variation_to(&self, tag: Tag, value: &mut i16) {
         if self.is_variable() {
             let v = f32::from(*value) + self.metrics_var_offset(tag);
-            // TODO: Should probably round it, but f32::round is not available in core.
-            if let Some(v) = i16::try_num_from(v) {
+            if let Some(v) = round_metric(v) {
                 *value = v;
             }
         }
@@ -1762,6 +3422,56 @@ impl<'a> FaceTables<'a> {
     }
 }
 
+/// Rounds a varied metric to the nearest integer, with halves rounded away from zero.
+///
+/// We can't use `round()` in `no_std`, so this is the next best thing.
+#[cfg(feature = "variable-fonts")]
+#[inline]
+fn round_metric<T: TryNumFrom<f32>>(n: f32) -> Option<T> {
+    T::try_num_from(if n >= 0.0 { n + 0.5 } else { n - 0.5 })
+}
+
+/// Scales a bounding box, rounding it outwards to whole units.
+///
+/// A negative scale flips the corresponding axis.
+/// Values that do not fit into `i16` are clamped.
+fn scale_rect(rect: Rect, x_scale: f32, y_scale: f32) -> Rect {
+    let (x_min, x_max) = scale_range(rect.x_min, rect.x_max, x_scale);
+    let (y_min, y_max) = scale_range(rect.y_min, rect.y_max, y_scale);
+    Rect { x_min, y_min, x_max, y_max }
+}
+
+fn scale_range(min: i16, max: i16, scale: f32) -> (i16, i16) {
+    let a = f32::from(min) * scale;
+    let b = f32::from(max) * scale;
+    let (min, max) = if a <= b { (a, b) } else { (b, a) };
+
+    // `try_num_from` truncates toward zero, so we have to fix up
+    // the rounding direction manually. We can't use `floor()` in `no_std`.
+    let mut min_i = clamp_to_i16(min);
+    if f32::from(min_i) > min {
+        min_i = min_i.saturating_sub(1);
+    }
+
+    let mut max_i = clamp_to_i16(max);
+    if f32::from(max_i) < max {
+        max_i = max_i.saturating_add(1);
+    }
+
+    (min_i, max_i)
+}
+
+// Unlike `f32_bound`, accepts infinite values, which can be produced by a huge scale.
+fn clamp_to_i16(n: f32) -> i16 {
+    if n <= f32::from(core::i16::MIN) {
+        core::i16::MIN
+    } else if n >= f32::from(core::i16::MAX) {
+        core::i16::MAX
+    } else {
+        i16::try_num_from(n).unwrap_or(0)
+    }
+}
+
 struct DefaultTableProvider<'a> {
     data: &'a [u8],
     tables: LazyArrayIter16<'a, TableRecord>,
@@ -1773,13 +3483,13 @@ impl<'a> Iterator for DefaultTableProvider<'a> {
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         self.tables.next().map(|table| {
-            Ok((table.table_tag, {
-                let offset = usize::num_from(table.offset);
-                let length = usize::num_from(table.length);
-                let end = offset.checked_add(length).ok_or(FaceParsingError::MalformedFont)?;
-                let range = offset..end;
-                self.data.get(range)
-            }))
+            usize::num_from(table.offset).checked_add(usize::num_from(table.length))
+                .ok_or(FaceParsingError::MalformedTableRecord {
+                    tag: table.table_tag,
+                    offset: table.offset,
+                    length: table.length,
+                })?;
+            Ok((table.table_tag, table.data(self.data)))
         })
     }
 }
@@ -1885,4 +3595,997 @@ mod tests {
         assert_eq!(Face::from_slice(data, std::u32::MAX).unwrap_err(),
                    FaceParsingError::FaceIndexOutOfBounds);
     }
+
+    // Fonts from `benches/fonts` are not included in the published crate,
+    // so tests that rely on them are skipped when they are missing.
+    fn bench_font(name: &str) -> Option<std::vec::Vec<u8>> {
+        std::fs::read(std::format!("benches/fonts/{}", name)).ok()
+    }
+
+    #[test]
+    fn same_family() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        let other_data = match bench_font("SourceSansVariable-Roman.ttf") {
+            Some(data) => data,
+            None => return,
+        };
+        let other_face = Face::from_slice(&other_data, 0).unwrap();
+        let other_data2 = match bench_font("SourceSansVariable-Roman.otf") {
+            Some(data) => data,
+            None => return,
+        };
+        let other_face2 = Face::from_slice(&other_data2, 0).unwrap();
+
+        // `demo.ttf` doesn't have a `name` table.
+        assert!(!face.is_same_family(&face));
+        assert!(other_face.is_same_family(&other_face2));
+        assert!(!other_face.is_same_family(&face));
+
+        let stat_data = &[
+            0x00, 0x01, 0x00, 0x01, // version: 1.1
+            0x00, 0x08, // design axis size: 8
+            0x00, 0x01, // design axis count: 1
+            0x00, 0x00, 0x00, 0x14, // design axes offset: 20
+            0x00, 0x00, // axis value count: 0
+            0x00, 0x00, 0x00, 0x1C, // axis value offsets offset: 28
+            0x00, 0x02, // elided fallback name ID: 2
+            // Axis record
+            0x77, 0x64, 0x74, 0x68, // tag: wdth
+            0x01, 0x00, // name ID: 256
+            0x00, 0x00, // ordering: 0
+        ];
+
+        // Same family names, but different design axes.
+        let mut other_face3 = other_face2.clone();
+        other_face3.stat = stat::Table::parse(stat_data);
+        assert!(other_face3.stat.is_some());
+        assert!(!other_face.is_same_family(&other_face3));
+
+        // Axes are compared only when both faces have a `STAT` table.
+        other_face3.stat = None;
+        assert!(other_face.is_same_family(&other_face3));
+    }
+
+    #[test]
+    #[cfg(feature = "variable-fonts")]
+    fn variable_outline() {
+        let data = match bench_font("SourceSansVariable-Roman.ttf") {
+            Some(data) => data,
+            None => return,
+        };
+        let mut face = Face::from_slice(&data, 0).unwrap();
+        let glyph_id = face.glyph_index('o').unwrap();
+
+        let default_bbox = face.outline_glyph(glyph_id, &mut DummyOutline).unwrap();
+        assert_eq!(face.glyph_bounding_box(glyph_id), Some(default_bbox));
+
+        face.set_variation(Tag::from_bytes(b"wght"), 900.0).unwrap();
+        let black_bbox = face.outline_glyph(glyph_id, &mut DummyOutline).unwrap();
+        assert!(black_bbox.width() > default_bbox.width());
+
+        // Resetting to the default instance must restore the original outline.
+        let default_wght = face.variation_axes().next().unwrap().def_value;
+        face.set_variation(Tag::from_bytes(b"wght"), default_wght).unwrap();
+        assert!(!face.has_non_default_variation_coordinates());
+        assert_eq!(face.outline_glyph(glyph_id, &mut DummyOutline), Some(default_bbox));
+    }
+
+    #[test]
+    #[cfg(feature = "variable-fonts")]
+    fn variable_advances() {
+        let data = match bench_font("SourceSansVariable-Roman.ttf") {
+            Some(data) => data,
+            None => return,
+        };
+        let mut face = Face::from_slice(&data, 0).unwrap();
+        let glyph_id = face.glyph_index('o').unwrap();
+
+        let default_advance = face.glyph_hor_advance(glyph_id).unwrap();
+        let default_bearing = face.glyph_hor_side_bearing(glyph_id).unwrap();
+
+        face.set_variation(Tag::from_bytes(b"wght"), 900.0).unwrap();
+        let black_advance = face.glyph_hor_advance(glyph_id).unwrap();
+        assert_eq!(black_advance, 560);
+        assert!(black_advance > default_advance);
+        // `HVAR` in this font has no LSB mapping, so bearings are not varied.
+        assert_eq!(face.glyph_hor_side_bearing(glyph_id), Some(default_bearing));
+    }
+
+    #[test]
+    #[cfg(feature = "variable-fonts")]
+    fn metric_rounding() {
+        assert_eq!(round_metric::<i16>(-10.3), Some(-10));
+        assert_eq!(round_metric::<i16>(-10.5), Some(-11));
+        assert_eq!(round_metric::<i16>(-9.7), Some(-10));
+        assert_eq!(round_metric::<u16>(10.5), Some(11));
+        assert_eq!(round_metric::<u16>(10.4), Some(10));
+        assert_eq!(round_metric::<u16>(-1.0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "variable-fonts")]
+    fn variable_metrics() {
+        let data = match bench_font("SourceSansVariable-Roman.ttf") {
+            Some(data) => data,
+            None => return,
+        };
+        let mut face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.x_height(), Some(478));
+        assert_eq!(face.strikeout_metrics(), Some(LineMetrics { position: 286, thickness: 50 }));
+
+        face.set_variation(Tag::from_bytes(b"wght"), 900.0).unwrap();
+        assert_eq!(face.x_height(), Some(500));
+        assert_eq!(face.strikeout_metrics(), Some(LineMetrics { position: 299, thickness: 50 }));
+        // Not present in `MVAR`.
+        assert_eq!(face.ascender(), 1024);
+        assert_eq!(face.capital_height(), Some(660));
+    }
+
+    #[test]
+    #[cfg(feature = "variable-fonts")]
+    fn style_names() {
+        use std::string::String;
+
+        let data = match bench_font("SourceSansVariable-Roman.ttf") {
+            Some(data) => data,
+            None => return,
+        };
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.style_axes().count(), 2);
+        assert_eq!(face.style_axis_values().count(), 8);
+        assert_eq!(face.elided_fallback_name_id(), Some(2));
+
+        let wght = Tag::from_bytes(b"wght");
+        assert_eq!(face.style_name(&[(wght, 700.0)]), Some(String::from("Bold")));
+        assert_eq!(face.style_name(&[(wght, 320.0)]), Some(String::from("Light")));
+        // *Regular* and *Roman* are elidable.
+        assert_eq!(face.style_name_ids(&[(wght, 400.0)]), Some(vec![2]));
+        // The default `wght` value is 200.
+        assert_eq!(face.style_name(&[]), Some(String::from("ExtraLight")));
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.style_name_ids(&[]), None);
+    }
+
+    #[test]
+    fn family_names() {
+        let name = &[
+            0x00, 0x00, // format: 0
+            0x00, 0x04, // count: 4
+            0x00, 0x36, // storage offset: 54
+            // Name record [0]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x04, 0x09, // language ID: English (US)
+            0x00, 0x01, // name ID: family
+            0x00, 0x02, // length: 2
+            0x00, 0x00, // offset: 0
+            // Name record [1]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x04, 0x09, // language ID: English (US)
+            0x00, 0x02, // name ID: subfamily
+            0x00, 0x02, // length: 2
+            0x00, 0x02, // offset: 2
+            // Name record [2]
+            0x00, 0x01, // platform ID: Macintosh
+            0x00, 0x00, // encoding ID: Roman
+            0x00, 0x00, // language ID: English
+            0x00, 0x06, // name ID: PostScript name
+            0x00, 0x01, // length: 1
+            0x00, 0x04, // offset: 4
+            // Name record [3]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x04, 0x09, // language ID: English (US)
+            0x00, 0x10, // name ID: typographic family
+            0x00, 0x02, // length: 2
+            0x00, 0x05, // offset: 5
+            // Storage
+            0x00, b'F', // "F"
+            0x00, b'S', // "S"
+            b'P', // "P"
+            0x00, b'T', // "T"
+        ];
+
+        use std::string::String;
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_table(&data, b"name", name);
+        assert_eq!(face.family_name(), Some(String::from("T")));
+        assert_eq!(face.subfamily_name(), Some(String::from("S")));
+        assert_eq!(face.full_name(), None);
+        assert_eq!(face.post_script_name(), Some(String::from("P")));
+    }
+
+    #[test]
+    fn name_by_id_and_language() {
+        let name = &[
+            0x00, 0x00, // format: 0
+            0x00, 0x02, // count: 2
+            0x00, 0x1E, // storage offset: 30
+            // Name record [0]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x04, 0x09, // language ID: English (US)
+            0x00, 0x01, // name ID: family
+            0x00, 0x02, // length: 2
+            0x00, 0x00, // offset: 0
+            // Name record [1]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x04, 0x11, // language ID: Japanese (Japan)
+            0x00, 0x01, // name ID: family
+            0x00, 0x02, // length: 2
+            0x00, 0x02, // offset: 2
+            // Storage
+            0x00, b'A', // "A"
+            0x30, 0x42, // "あ"
+        ];
+
+        use std::string::String;
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_table(&data, b"name", name);
+        assert_eq!(face.name_by_id_and_language(name_id::FAMILY, "ja-JP"), Some(String::from("あ")));
+        assert_eq!(face.name_by_id_and_language(name_id::FAMILY, "ja"), Some(String::from("あ")));
+        assert_eq!(face.name_by_id_and_language(name_id::FAMILY, "EN-us"), Some(String::from("A")));
+        assert_eq!(face.name_by_id_and_language(name_id::FAMILY, "en-GB"), None);
+        assert_eq!(face.name_by_id_and_language(name_id::FULL_NAME, "en"), None);
+    }
+
+    #[test]
+    fn line_metrics_selection() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert!(!face.is_use_typo_metrics());
+        assert_eq!(face.windows_ascender(), None);
+        assert_eq!((face.ascender(), face.descender(), face.line_gap()), (1024, -400, 0));
+
+        let mut os_2 = vec![0; 96];
+        os_2[1] = 4; // version
+        os_2[68..78].copy_from_slice(&[
+            0x03, 0x20, // typographic ascender: 800
+            0xFF, 0x38, // typographic descender: -200
+            0x00, 0x64, // typographic line gap: 100
+            0x03, 0x84, // Windows ascent: 900
+            0x01, 0x2C, // Windows descent: 300
+        ]);
+
+        let face = with_table(&data, b"OS/2", &os_2);
+        assert_eq!(face.windows_ascender(), Some(900));
+        assert_eq!(face.windows_descender(), Some(-300));
+        assert_eq!(face.typographic_line_gap(), Some(100));
+        assert_eq!(face.horizontal_header_ascender(), 1024);
+        assert_eq!((face.ascender(), face.descender(), face.line_gap()), (900, -300, 0));
+
+        // Set `USE_TYPO_METRICS`.
+        os_2[62..64].copy_from_slice(&[0x00, 0x80]);
+        let face = with_table(&data, b"OS/2", &os_2);
+        assert!(face.is_use_typo_metrics());
+        assert_eq!((face.ascender(), face.descender(), face.line_gap()), (800, -200, 100));
+    }
+
+    #[test]
+    fn measured_heights() {
+        let cmap = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x01, // number of tables: 1
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x00, 0x00, 0x00, 0x0C, // offset: 12
+            // Format 4 subtable
+            0x00, 0x04, // format: 4
+            0x00, 0x28, // subtable size: 40
+            0x00, 0x00, // language ID: 0
+            0x00, 0x06, // 2 x segCount: 6
+            0x00, 0x04, // search range: 4
+            0x00, 0x01, // entry selector: 1
+            0x00, 0x02, // range shift: 2
+            // End character codes
+            0x00, 0x48, // char code [0]: 72
+            0x00, 0x78, // char code [1]: 120
+            0xFF, 0xFF, // char code [2]: 65535
+            0x00, 0x00, // reserved: 0
+            // Start character codes
+            0x00, 0x48, // char code [0]: 72
+            0x00, 0x78, // char code [1]: 120
+            0xFF, 0xFF, // char code [2]: 65535
+            // Deltas
+            0xFF, 0xB9, // delta [0]: -71
+            0xFF, 0x89, // delta [1]: -119
+            0x00, 0x01, // delta [2]: 1
+            // Offsets into Glyph index array
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, // offset [1]: 0
+            0x00, 0x00, // offset [2]: 0
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        let tables = [b"head", b"hhea", b"maxp", b"loca", b"glyf"].iter()
+            .map(|tag| Ok((Tag::from_bytes(tag), face.table_data(Tag::from_bytes(tag)))))
+            .chain(core::iter::once(Ok((Tag::from_bytes(b"cmap"), Some(&cmap[..])))));
+        let face = FaceTables::from_table_provider(tables).unwrap();
+
+        // Both characters are mapped to a glyph with bbox 6,0..541,656.
+        assert_eq!(face.x_height(), Some(656));
+        assert_eq!(face.capital_height(), Some(656));
+    }
+
+    #[test]
+    fn glyph_index_by_name() {
+        for name in &["SourceSansPro-Regular.ttf", "SourceSansPro-Regular.otf"] {
+            let data = match bench_font(name) {
+                Some(data) => data,
+                None => return,
+            };
+            let face = Face::from_slice(&data, 0).unwrap();
+            let id = face.glyph_index('a').unwrap();
+            assert_eq!(face.glyph_name(id), Some("a"));
+            assert_eq!(face.glyph_index_by_name("a"), Some(id));
+            assert_eq!(face.glyph_index_by_name("no-such-glyph"), None);
+        }
+    }
+
+    #[test]
+    fn vertical_metrics() {
+        let mut vhea = vec![0; 36];
+        vhea[..10].copy_from_slice(&[
+            0x00, 0x01, 0x10, 0x00, // version: 1.1
+            0x01, 0xF4, // ascender: 500
+            0xFE, 0x0C, // descender: -500
+            0x00, 0x00, // line gap: 0
+        ]);
+        vhea[34..].copy_from_slice(&[0x00, 0x01]); // number of long vertical metrics: 1
+        let vmtx = &[
+            0x03, 0xE8, // advance height [0]: 1000
+            0x00, 0x64, // top side bearing [0]: 100
+            0x00, 0x32, // top side bearing [1]: 50
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.glyph_ver_advance(GlyphId(0)), None);
+
+        let tables = [b"head", b"hhea", b"maxp"].iter()
+            .map(|tag| Ok((Tag::from_bytes(tag), face.table_data(Tag::from_bytes(tag)))))
+            .chain(core::iter::once(Ok((Tag::from_bytes(b"vhea"), Some(&vhea[..])))))
+            .chain(core::iter::once(Ok((Tag::from_bytes(b"vmtx"), Some(&vmtx[..])))));
+        let face = FaceTables::from_table_provider(tables).unwrap();
+        assert_eq!(face.vertical_ascender(), Some(500));
+        assert_eq!(face.vertical_descender(), Some(-500));
+        assert_eq!(face.vertical_height(), Some(1000));
+        assert_eq!(face.glyph_ver_advance(GlyphId(0)), Some(1000));
+        assert_eq!(face.glyph_ver_side_bearing(GlyphId(0)), Some(100));
+        // Glyphs past the long metrics reuse the last advance.
+        assert_eq!(face.glyph_ver_advance(GlyphId(1)), Some(1000));
+        assert_eq!(face.glyph_ver_side_bearing(GlyphId(1)), Some(50));
+        assert_eq!(face.glyph_ver_advance(GlyphId(2)), None);
+    }
+
+    #[test]
+    fn malformed_head() {
+        use std::string::ToString;
+
+        let mut data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        // Set the `head` table length to 53.
+        let record = 12 + 2 * TableRecord::SIZE;
+        assert_eq!(&data[record..record + 4], b"head");
+        data[record + 12..record + 16].copy_from_slice(&[0x00, 0x00, 0x00, 0x35]);
+
+        let err = Face::from_slice(&data, 0).unwrap_err();
+        assert_eq!(err, FaceParsingError::MalformedTable(Tag::from_bytes(b"head")));
+        assert_eq!(err.to_string(), "the head table is malformed");
+
+        // Can only be triggered on 32-bit platforms.
+        let err = FaceParsingError::MalformedTableRecord {
+            tag: Tag::from_bytes(b"glyf"),
+            offset: 0xFFFF_FFF0,
+            length: 0x20,
+        };
+        assert_eq!(err.to_string(),
+                   "the glyf table record with offset 4294967280 and length 32 is malformed");
+    }
+
+    #[test]
+    fn missing_padding() {
+        let mut data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert!(face.has_well_formed_table_layout());
+
+        // Make the last table longer than the font data by 2 bytes.
+        let num_tables = usize::from(Stream::read_at::<u16>(&data, 4).unwrap());
+        let (record_offset, table_offset) = (0..num_tables)
+            .map(|i| 12 + i * TableRecord::SIZE)
+            .map(|offset| (offset, Stream::read_at::<TableRecord>(&data, offset).unwrap().offset))
+            .max_by_key(|&(_, table_offset)| table_offset)
+            .unwrap();
+        let length = data.len() as u32 - table_offset + 2;
+        data[record_offset + 12..record_offset + 16].copy_from_slice(&length.to_be_bytes());
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert!(!face.has_well_formed_table_layout());
+        let tag = Stream::read_at::<TableRecord>(&data, record_offset).unwrap().table_tag;
+        assert_eq!(face.table_data(tag).map(|d| d.len()), Some(length as usize - 2));
+
+        // But not by 4 or more.
+        let length = length + 2;
+        data[record_offset + 12..record_offset + 16].copy_from_slice(&length.to_be_bytes());
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.table_data(tag), None);
+    }
+
+    // Keeps only the `head`, `hhea` and `maxp` tables of the demo font
+    // and adds the specified ones.
+    fn with_tables<'a>(data: &'a [u8], tables: &[(&[u8; 4], &'a [u8])]) -> FaceTables<'a> {
+        let face = Face::from_slice(data, 0).unwrap();
+        let tables = [b"head", b"hhea", b"maxp"].iter()
+            .map(|tag| Ok((Tag::from_bytes(tag), face.table_data(Tag::from_bytes(tag)))))
+            .chain(tables.iter().map(|(tag, table)| Ok((Tag::from_bytes(tag), Some(*table)))));
+        FaceTables::from_table_provider(tables).unwrap()
+    }
+
+    fn with_table<'a>(data: &'a [u8], tag: &[u8; 4], table: &'a [u8]) -> FaceTables<'a> {
+        with_tables(data, &[(tag, table)])
+    }
+
+    // Glyph 0 is a triangle and glyph 1 is empty.
+    const LOCA: &[u8] = &[
+        0x00, 0x00, // offset [0]: 0
+        0x00, 0x0B, // offset [1]: 22
+        0x00, 0x0B, // offset [2]: 22
+    ];
+
+    const GLYF: &[u8] = &[
+        0x00, 0x01, // number of contours: 1
+        0x00, 0x0A, 0x00, 0x05, 0x00, 0x3C, 0x00, 0x2D, // bbox: 10, 5, 60, 45
+        0x00, 0x02, // end point [0]: 2
+        0x00, 0x00, // instructions length: 0
+        0x37, // flags [0]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+        0x37, // flags [1]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+        0x33, // flags [2]: ON_CURVE_POINT | X_SHORT | X_IS_POSITIVE | Y_IS_SAME
+        0x0A, // x [0]: +10
+        0x14, // x [1]: +20
+        0x1E, // x [2]: +30
+        0x05, // y [0]: +5
+        0x28, // y [1]: +40
+    ];
+
+    #[test]
+    fn glyph_is_empty() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_tables(&data, &[(b"loca", LOCA), (b"glyf", GLYF)]);
+        assert_eq!(face.glyph_is_empty(GlyphId(0)), Some(false));
+        assert_eq!(face.glyph_is_empty(GlyphId(1)), Some(true));
+        // Out of range.
+        assert_eq!(face.glyph_is_empty(GlyphId(2)), None);
+
+        // No outlines at all.
+        let face = with_tables(&data, &[]);
+        assert_eq!(face.glyph_is_empty(GlyphId(0)), None);
+    }
+
+    #[test]
+    #[cfg(feature = "variable-fonts")]
+    fn bounding_box_at() {
+        let fvar = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x10, // axes array offset: 16
+            0x00, 0x02, // reserved: 2
+            0x00, 0x01, // axis count: 1
+            0x00, 0x14, // axis size: 20
+            0x00, 0x00, // instance count: 0
+            0x00, 0x08, // instance size: 8
+            // Axis [0]
+            b'w', b'g', b'h', b't', // tag
+            0x00, 0x64, 0x00, 0x00, // min value: 100
+            0x01, 0x90, 0x00, 0x00, // default value: 400
+            0x03, 0x84, 0x00, 0x00, // max value: 900
+            0x00, 0x00, // flags: 0
+            0x01, 0x00, // name ID: 256
+        ];
+
+        let gvar = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x01, // axis count: 1
+            0x00, 0x00, // shared tuple count: 0
+            0x00, 0x00, 0x00, 0x00, // shared tuples offset: 0
+            0x00, 0x02, // glyph count: 2
+            0x00, 0x00, // flags: short offsets
+            0x00, 0x00, 0x00, 0x1A, // glyph variation data array offset: 26
+            // Glyph variation data offsets
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x08, // offset [1]: 16
+            0x00, 0x08, // offset [2]: 16
+            // Glyph variation data [0]
+            0x00, 0x01, // tuple variation count: 1
+            0x00, 0x0A, // data offset: 10
+            // Tuple variation header [0]
+            0x00, 0x06, // variation data size: 6
+            0x80, 0x00, // tuple index: EMBEDDED_PEAK_TUPLE
+            0x40, 0x00, // peak tuple: 1.0
+            // Serialized data, all points
+            0x02, 0x0A, 0x0A, 0x0A, // x deltas [0..3]: 10
+            0x83, // x deltas [3..7]: 0 (phantom points)
+            0x86, // y deltas [0..7]: 0
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_tables(&data, &[
+            (b"loca", LOCA), (b"glyf", GLYF), (b"fvar", fvar), (b"gvar", gvar),
+        ]);
+
+        let default_bbox = Rect { x_min: 10, y_min: 5, x_max: 60, y_max: 45 };
+        let coords = [NormalizedCoordinate::from(0.0)];
+        assert_eq!(face.glyph_bounding_box_at(GlyphId(0), &coords), Some(default_bbox));
+
+        // Each point is moved by 10 units to the right.
+        let coords = [NormalizedCoordinate::from(1.0)];
+        assert_eq!(face.glyph_bounding_box_at(GlyphId(0), &coords),
+                   Some(Rect { x_min: 20, y_min: 5, x_max: 70, y_max: 45 }));
+        let coords = [NormalizedCoordinate::from(0.5)];
+        assert_eq!(face.glyph_bounding_box_at(GlyphId(0), &coords),
+                   Some(Rect { x_min: 15, y_min: 5, x_max: 65, y_max: 45 }));
+        // The current coordinates are not affected.
+        assert_eq!(face.glyph_bounding_box(GlyphId(0)), Some(default_bbox));
+
+        assert_eq!(face.glyph_bounding_box_at(GlyphId(1), &coords), None);
+        // The number of coordinates doesn't match the number of axes.
+        assert_eq!(face.glyph_bounding_box_at(GlyphId(0), &[]), None);
+
+        // Non-variable faces accept only an empty list of coordinates.
+        let face = with_tables(&data, &[(b"loca", LOCA), (b"glyf", GLYF)]);
+        assert_eq!(face.glyph_bounding_box_at(GlyphId(0), &[]), Some(default_bbox));
+        assert_eq!(face.glyph_bounding_box_at(GlyphId(0), &coords), None);
+    }
+
+    #[test]
+    fn outline_glyph_scaled() {
+        struct Builder(std::vec::Vec<(f32, f32)>);
+
+        impl OutlineBuilder for Builder {
+            fn move_to(&mut self, x: f32, y: f32) { self.0.push((x, y)); }
+            fn line_to(&mut self, x: f32, y: f32) { self.0.push((x, y)); }
+            fn quad_to(&mut self, _: f32, _: f32, x: f32, y: f32) { self.0.push((x, y)); }
+            fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, x: f32, y: f32) {
+                self.0.push((x, y));
+            }
+            fn close(&mut self) {}
+        }
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_tables(&data, &[(b"loca", LOCA), (b"glyf", GLYF)]);
+
+        // 1000 units per em, so everything is doubled.
+        let mut builder = Builder(std::vec::Vec::new());
+        let bbox = face.outline_glyph_scaled(GlyphId(0), 2000.0, false, &mut builder);
+        assert_eq!(builder.0, [(20.0, 10.0), (60.0, 90.0), (120.0, 90.0), (20.0, 10.0)]);
+        assert_eq!(bbox, Some(Rect { x_min: 20, y_min: 10, x_max: 120, y_max: 90 }));
+
+        let mut builder = Builder(std::vec::Vec::new());
+        let bbox = face.outline_glyph_scaled(GlyphId(0), 2000.0, true, &mut builder);
+        assert_eq!(builder.0, [(20.0, -10.0), (60.0, -90.0), (120.0, -90.0), (20.0, -10.0)]);
+        assert_eq!(bbox, Some(Rect { x_min: 20, y_min: -90, x_max: 120, y_max: -10 }));
+
+        // Fractional coordinates are rounded outwards.
+        let mut builder = Builder(std::vec::Vec::new());
+        let bbox = face.outline_glyph_scaled(GlyphId(0), 100.0, true, &mut builder);
+        assert_eq!(bbox, Some(Rect { x_min: 1, y_min: -5, x_max: 6, y_max: 0 }));
+
+        // The scaled bounding box doesn't fit into `i16`, so it's clamped,
+        // while the outline itself is not affected.
+        let mut builder = Builder(std::vec::Vec::new());
+        let bbox = face.outline_glyph_scaled(GlyphId(0), 1_000_000.0, true, &mut builder);
+        assert_eq!(builder.0[0], (10_000.0, -5_000.0));
+        assert_eq!(bbox, Some(Rect { x_min: 10_000, y_min: -32768, x_max: 32767, y_max: -5_000 }));
+
+        let mut builder = Builder(std::vec::Vec::new());
+        let bbox = face.outline_glyph_scaled(GlyphId(0), core::f32::MAX, false, &mut builder);
+        assert_eq!(bbox, Some(Rect { x_min: 32767, y_min: 32767, x_max: 32767, y_max: 32767 }));
+
+        let mut builder = Builder(std::vec::Vec::new());
+        assert_eq!(face.outline_glyph_scaled(GlyphId(0), 0.0, false, &mut builder), None);
+        assert_eq!(face.outline_glyph_scaled(GlyphId(0), -10.0, false, &mut builder), None);
+        assert_eq!(face.outline_glyph_scaled(GlyphId(0), core::f32::NAN, false, &mut builder), None);
+        assert!(builder.0.is_empty());
+        // Empty glyph.
+        assert_eq!(face.outline_glyph_scaled(GlyphId(1), 10.0, false, &mut builder), None);
+    }
+
+    #[test]
+    fn supplementary_character_via_cmap_format8() {
+        let mut cmap = vec![
+            0x00, 0x00, // version: 0
+            0x00, 0x01, // number of tables: 1
+            0x00, 0x00, // platform ID: Unicode
+            0x00, 0x04, // encoding ID: Unicode full repertoire
+            0x00, 0x00, 0x00, 0x0C, // offset: 12
+            // Subtable
+            0x00, 0x08, // format: 8
+            0x00, 0x00, // reserved
+            0x00, 0x00, 0x20, 0x1C, // length: 8220
+            0x00, 0x00, 0x00, 0x00, // language: 0
+        ];
+
+        // Make only 0xD83D the first half of a 32-bit code.
+        let is32_offset = cmap.len();
+        cmap.extend(std::iter::repeat(0x00).take(8192));
+        cmap[is32_offset + 0xD83D / 8] = 0x80 >> (0xD83D % 8);
+
+        cmap.extend(&[
+            0x00, 0x00, 0x00, 0x01, // number of groups: 1
+            // Group [0]
+            0xD8, 0x3D, 0xDE, 0x00, // start char code: U+1F600
+            0xD8, 0x3D, 0xDE, 0x01, // end char code: U+1F601
+            0x00, 0x00, 0x00, 0x01, // start glyph ID: 1
+        ]);
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_table(&data, b"cmap", &cmap);
+        assert_eq!(face.glyph_index('\u{1F600}'), Some(GlyphId(1)));
+        assert_eq!(face.glyph_index('\u{1F601}'), Some(GlyphId(2)));
+        assert_eq!(face.glyph_index('\u{1F602}'), None);
+
+        let mut chars = std::vec::Vec::new();
+        face.codepoints(|c| chars.push(c));
+        assert_eq!(chars, ['\u{1F600}', '\u{1F601}']);
+    }
+
+    #[test]
+    fn strike_selection() {
+        assert!(is_better_strike(None, 10, 20));
+        // Larger strikes are preferred when the best one is too small.
+        assert!(is_better_strike(Some(10), 15, 20));
+        assert!(is_better_strike(Some(10), 30, 20));
+        assert!(!is_better_strike(Some(15), 10, 20));
+        // Smaller strikes are preferred when the best one is large enough.
+        assert!(is_better_strike(Some(30), 20, 20));
+        assert!(is_better_strike(Some(30), 25, 20));
+        assert!(!is_better_strike(Some(25), 30, 20));
+        assert!(!is_better_strike(Some(25), 15, 20));
+        assert!(!is_better_strike(Some(20), 20, 20));
+    }
+
+    #[test]
+    fn notdef_and_whitespace() {
+        let cmap = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x01, // number of tables: 1
+            0x00, 0x00, // platform ID: Unicode
+            0x00, 0x03, // encoding ID: Unicode 2.0 BMP
+            0x00, 0x00, 0x00, 0x0C, // offset: 12
+            // Format 4 subtable
+            0x00, 0x04, // format: 4
+            0x00, 0x20, // subtable size: 32
+            0x00, 0x00, // language ID: 0
+            0x00, 0x04, // 2 x segCount: 4
+            0x00, 0x02, // search range: 2
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x02, // range shift: 2
+            // End character codes
+            0x00, 0x20, // char code [0]: 32
+            0xFF, 0xFF, // char code [1]: 65535
+            0x00, 0x00, // reserved: 0
+            // Start character codes
+            0x00, 0x20, // char code [0]: 32
+            0xFF, 0xFF, // char code [1]: 65535
+            // Deltas
+            0xFF, 0xE1, // delta [0]: -31
+            0x00, 0x01, // delta [1]: 1
+            // Offsets into Glyph index array
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, // offset [1]: 0
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_tables(&data, &[(b"loca", LOCA), (b"glyf", GLYF), (b"cmap", cmap)]);
+        assert!(face.has_notdef_outline());
+        // Mapped from U+0020 and empty.
+        assert!(face.is_whitespace_glyph(GlyphId(1)));
+        // Not mapped from a whitespace and not empty.
+        assert!(!face.is_whitespace_glyph(GlyphId(0)));
+
+        // Swap glyphs, so `.notdef` is empty and U+0020 is mapped to a triangle.
+        let loca = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, // offset [1]: 0
+            0x00, 0x0B, // offset [2]: 22
+        ];
+        let face = with_tables(&data, &[(b"loca", loca), (b"glyf", GLYF), (b"cmap", cmap)]);
+        assert!(!face.has_notdef_outline());
+        assert!(!face.is_whitespace_glyph(GlyphId(0)));
+        assert!(!face.is_whitespace_glyph(GlyphId(1)));
+
+        // No outlines at all.
+        let face = with_tables(&data, &[(b"cmap", cmap)]);
+        assert!(!face.has_notdef_outline());
+        assert!(!face.is_whitespace_glyph(GlyphId(1)));
+    }
+
+    #[test]
+    fn glyphs() {
+        let hmtx = &[
+            0x02, 0x58, // advance width [0]: 600
+            0x00, 0x0A, // side bearing [0]: 10
+            0x00, 0xFA, // advance width [1]: 250
+            0x00, 0x00, // side bearing [1]: 0
+        ];
+
+        let gdef = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0C, // glyph class def offset: 12
+            0x00, 0x00, // attach list offset: 0
+            0x00, 0x00, // lig caret list offset: 0
+            0x00, 0x00, // mark attach class def offset: 0
+            // Class definition
+            0x00, 0x01, // format: 1
+            0x00, 0x00, // start glyph: 0
+            0x00, 0x01, // count: 1
+            0x00, 0x01, // class [0]: base
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = with_tables(&data, &[
+            (b"loca", LOCA), (b"glyf", GLYF), (b"hmtx", hmtx), (b"GDEF", gdef),
+        ]);
+
+        let mut glyphs = face.glyphs();
+        assert_eq!(glyphs.size_hint(), (2, Some(2)));
+        assert_eq!(glyphs.next(), Some(GlyphInfo {
+            id: GlyphId(0),
+            advance: Some(600),
+            bounding_box: Some(Rect { x_min: 10, y_min: 5, x_max: 60, y_max: 45 }),
+            class: Some(GlyphClass::Base),
+        }));
+        assert_eq!(glyphs.len(), 1);
+        assert_eq!(glyphs.next(), Some(GlyphInfo {
+            id: GlyphId(1),
+            advance: Some(250),
+            bounding_box: None,
+            class: None,
+        }));
+        assert_eq!(glyphs.next(), None);
+        assert_eq!(glyphs.count(), 0);
+    }
+
+    fn with_cmap<'a>(data: &'a [u8], cmap: &'a [u8]) -> FaceTables<'a> {
+        with_table(data, b"cmap", cmap)
+    }
+
+    #[test]
+    fn prefer_segmented_coverage() {
+        let cmap = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x02, // number of tables: 2
+            0x00, 0x00, // platform ID: Unicode
+            0x00, 0x03, // encoding ID: Unicode 2.0 BMP
+            0x00, 0x00, 0x00, 0x14, // offset: 20
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x0A, // encoding ID: Unicode full repertoire
+            0x00, 0x00, 0x00, 0x34, // offset: 52
+            // Format 4 subtable
+            0x00, 0x04, // format: 4
+            0x00, 0x20, // subtable size: 32
+            0x00, 0x00, // language ID: 0
+            0x00, 0x04, // 2 x segCount: 4
+            0x00, 0x02, // search range: 2
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x02, // range shift: 2
+            // End character codes
+            0x00, 0x42, // char code [0]: 66
+            0xFF, 0xFF, // char code [1]: 65535
+            0x00, 0x00, // reserved: 0
+            // Start character codes
+            0x00, 0x41, // char code [0]: 65
+            0xFF, 0xFF, // char code [1]: 65535
+            // Deltas
+            0xFF, 0xC0, // delta [0]: -64
+            0x00, 0x01, // delta [1]: 1
+            // Offsets into Glyph index array
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, // offset [1]: 0
+            // Format 12 subtable
+            0x00, 0x0C, // format: 12
+            0x00, 0x00, // reserved
+            0x00, 0x00, 0x00, 0x1C, // length: 28
+            0x00, 0x00, 0x00, 0x00, // language: 0
+            0x00, 0x00, 0x00, 0x01, // number of groups: 1
+            // Group [0]
+            0x00, 0x00, 0x00, 0x41, // start char code: 65
+            0x00, 0x00, 0x00, 0x41, // end char code: 65
+            0x00, 0x00, 0x00, 0x03, // start glyph ID: 3
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let tables = with_cmap(&data, cmap);
+        assert_eq!(tables.glyph_index('A'), Some(GlyphId(3)));
+        // Other subtables are still used as a fallback.
+        assert_eq!(tables.glyph_index('B'), Some(GlyphId(2)));
+        assert_eq!(tables.glyph_index('C'), None);
+    }
+
+    #[test]
+    fn mac_roman_fallback() {
+        let cmap = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x01, // number of tables: 1
+            0x00, 0x01, // platform ID: Macintosh
+            0x00, 0x00, // encoding ID: Roman
+            0x00, 0x00, 0x00, 0x0C, // offset: 12
+            // Format 6 subtable
+            0x00, 0x06, // format: 6
+            0x00, 0x0E, // length: 14
+            0x00, 0x00, // language: independent
+            0x00, 0x80, // first code: 128
+            0x00, 0x02, // count: 2
+            0x00, 0x03, // glyph ID [0]: 3
+            0x00, 0x04, // glyph ID [1]: 4
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let tables = with_cmap(&data, cmap);
+        assert_eq!(tables.glyph_index('Ä'), Some(GlyphId(3)));
+        assert_eq!(tables.glyph_index('Å'), Some(GlyphId(4)));
+        assert_eq!(tables.glyph_index('A'), None);
+        assert_eq!(tables.glyph_index('€'), None);
+
+        let mut chars = std::vec::Vec::new();
+        tables.codepoints(|c| chars.push(c));
+        assert_eq!(chars, ['Ä', 'Å']);
+    }
+
+    #[test]
+    fn codepoints() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        let mut chars = std::vec::Vec::new();
+        face.codepoints(|c| chars.push(c));
+        assert_eq!(chars, ['A']);
+    }
+
+    #[test]
+    fn glyphs_kerning() {
+        let kern = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x03, // number of tables: 3
+            // Subtable 1
+            0x00, 0x00, // version: 0
+            0x00, 0x1A, // length: 26
+            0x00, // format: 0
+            0x01, // coverage: horizontal
+            0x00, 0x02, // number of pairs: 2
+            0x00, 0x0C, // search range: 12
+            0x00, 0x01, // entry selector: 1
+            0x00, 0x00, // range shift: 0
+            0x00, 0x01, 0x00, 0x02, // pair [0]: 1, 2
+            0xFF, 0xCE, // value [0]: -50
+            0x00, 0x01, 0x00, 0x03, // pair [1]: 1, 3
+            0xFF, 0xF6, // value [1]: -10
+            // Subtable 2
+            0x00, 0x00, // version: 0
+            0x00, 0x14, // length: 20
+            0x00, // format: 0
+            0x01, // coverage: horizontal
+            0x00, 0x01, // number of pairs: 1
+            0x00, 0x06, // search range: 6
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x00, // range shift: 0
+            0x00, 0x01, 0x00, 0x02, // pair [0]: 1, 2
+            0xFF, 0xEC, // value [0]: -20
+            // Subtable 3
+            0x00, 0x00, // version: 0
+            0x00, 0x1A, // length: 26
+            0x00, // format: 0
+            0x09, // coverage: horizontal | override
+            0x00, 0x02, // number of pairs: 2
+            0x00, 0x0C, // search range: 12
+            0x00, 0x01, // entry selector: 1
+            0x00, 0x00, // range shift: 0
+            0x00, 0x01, 0x00, 0x03, // pair [0]: 1, 3
+            0x00, 0x05, // value [0]: 5
+            0x00, 0x02, 0x00, 0x01, // pair [1]: 2, 1
+            0x00, 0x00, // value [1]: 0
+            // Not a subtable, since the number of tables is 3.
+            0x00, 0x00, // version: 0
+            0x00, 0x14, // length: 20
+            0x00, // format: 0
+            0x01, // coverage: horizontal
+            0x00, 0x01, // number of pairs: 1
+            0x00, 0x06, // search range: 6
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x00, // range shift: 0
+            0x00, 0x01, 0x00, 0x02, // pair [0]: 1, 2
+            0xFF, 0x9C, // value [0]: -100
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let tables = with_table(&data, b"kern", kern);
+        assert_eq!(tables.kerning_subtables().count(), 3);
+        assert_eq!(tables.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-70));
+        assert_eq!(tables.glyphs_kerning(GlyphId(1), GlyphId(3)), Some(5));
+        assert_eq!(tables.glyphs_kerning(GlyphId(2), GlyphId(1)), Some(0));
+        assert_eq!(tables.glyphs_kerning(GlyphId(2), GlyphId(3)), None);
+    }
+
+    #[test]
+    fn glyphs_pair_adjustment() {
+        let gpos = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x0C, // feature list offset: 12
+            0x00, 0x22, // lookup list offset: 34
+            // Script list
+            0x00, 0x00, // count: 0
+            // Feature list
+            0x00, 0x02, // count: 2
+            b'k', b'e', b'r', b'n', // tag [0]
+            0x00, 0x0E, // offset [0]: 14
+            b'k', b'e', b'r', b'n', // tag [1]
+            0x00, 0x0E, // offset [1]: 14
+            // Feature
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x02, // lookup index count: 2
+            0x00, 0x00, // lookup index [0]: 0
+            0x00, 0x01, // lookup index [1]: 1
+            // Lookup list
+            0x00, 0x02, // count: 2
+            0x00, 0x06, // offset [0]: 6
+            0x00, 0x34, // offset [1]: 52
+            // Lookup [0]
+            0x00, 0x02, // type: pair adjustment
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // subtable offset [0]: 8
+            // Pair adjustment subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x0E, // coverage offset: 14
+            0x00, 0x04, // value format 1: x advance
+            0x00, 0x00, // value format 2: none
+            0x00, 0x02, // pair set count: 2
+            0x00, 0x16, // pair set offset [0]: 22
+            0x00, 0x20, // pair set offset [1]: 32
+            0x00, 0x01, // coverage format: 1
+            0x00, 0x02, // count: 2
+            0x00, 0x01, // glyph [0]: 1
+            0x00, 0x02, // glyph [1]: 2
+            0x00, 0x02, // pair set [0] count: 2
+            0x00, 0x02, // second glyph [0]: 2
+            0xFF, 0xCE, // x advance [0]: -50
+            0x00, 0x03, // second glyph [1]: 3
+            0xFF, 0xF6, // x advance [1]: -10
+            0x00, 0x01, // pair set [1] count: 1
+            0x00, 0x01, // second glyph [0]: 1
+            0x00, 0x14, // x advance [0]: 20
+            // Lookup [1]
+            0x00, 0x02, // type: pair adjustment
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // subtable offset [0]: 8
+            // Pair adjustment subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x0C, // coverage offset: 12
+            0x00, 0x04, // value format 1: x advance
+            0x00, 0x00, // value format 2: none
+            0x00, 0x01, // pair set count: 1
+            0x00, 0x12, // pair set offset [0]: 18
+            0x00, 0x01, // coverage format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x01, // glyph [0]: 1
+            0x00, 0x01, // pair set [0] count: 1
+            0x00, 0x02, // second glyph [0]: 2
+            0xFF, 0xFB, // x advance [0]: -5
+        ];
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let tables = with_table(&data, b"GPOS", gpos);
+        // Both `kern` features reference the same lookups.
+        assert_eq!(tables.kerning_lookups().count(), 2);
+        assert_eq!(tables.glyphs_pair_adjustment(GlyphId(1), GlyphId(2)), Some(-55));
+        assert_eq!(tables.glyphs_pair_adjustment(GlyphId(1), GlyphId(3)), Some(-10));
+        assert_eq!(tables.glyphs_pair_adjustment(GlyphId(2), GlyphId(1)), Some(20));
+        assert_eq!(tables.glyphs_pair_adjustment(GlyphId(3), GlyphId(3)), None);
+    }
 }
@@ -0,0 +1,433 @@
+//! Outline processing utilities.
+
+use crate::OutlineBuilder;
+
+
+/// A contour winding direction.
+///
+/// Font units are Y-up, so a positive signed area means a counter-clockwise contour.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub enum Winding {
+    /// Negative signed area.
+    ///
+    /// TrueType outer contours must be clockwise.
+    Clockwise,
+
+    /// Positive signed area.
+    ///
+    /// CFF outer contours must be counter-clockwise.
+    CounterClockwise,
+}
+
+impl Winding {
+    /// Returns a winding direction for a signed area.
+    ///
+    /// Returns `None` for a zero or NaN area.
+    #[inline]
+    pub fn from_area(area: f32) -> Option<Self> {
+        if area > 0.0 {
+            Some(Winding::CounterClockwise)
+        } else if area < 0.0 {
+            Some(Winding::Clockwise)
+        } else {
+            None
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    #[inline]
+    pub fn lerp(self, other: Point, t: f32) -> Point {
+        Point {
+            x: self.x + t * (other.x - self.x),
+            y: self.y + t * (other.y - self.y),
+        }
+    }
+}
+
+#[inline]
+fn cross(p1: Point, p2: Point) -> f32 {
+    p1.x * p2.y - p2.x * p1.y
+}
+
+
+/// An `OutlineBuilder` adapter that computes signed contour areas.
+///
+/// All segments are passed to the underlying builder as is.
+/// Areas of quadratic and cubic curves are calculated exactly and not via flattening.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{OutlineBuilder, Winding, WindingBuilder};
+///
+/// struct Dummy;
+/// impl OutlineBuilder for Dummy {
+///     fn move_to(&mut self, _: f32, _: f32) {}
+///     fn line_to(&mut self, _: f32, _: f32) {}
+///     fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+///     fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+///     fn close(&mut self) {}
+/// }
+///
+/// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+/// let face = ttf_parser::Face::from_slice(&data, 0).unwrap();
+/// let mut dummy = Dummy;
+/// let mut builder = WindingBuilder::new(&mut dummy);
+/// face.outline_glyph(ttf_parser::GlyphId(1), &mut builder).unwrap();
+/// assert_eq!(builder.winding(), Some(Winding::Clockwise));
+/// assert_eq!(builder.clockwise_contours(), 1);
+/// assert_eq!(builder.counter_clockwise_contours(), 1);
+/// ```
+pub struct WindingBuilder<'a> {
+    builder: &'a mut dyn OutlineBuilder,
+    start: Point,
+    last: Point,
+    is_open: bool,
+    contour_area: f32,
+    last_contour_area: f32,
+    area: f32,
+    clockwise: u16,
+    counter_clockwise: u16,
+}
+
+impl<'a> WindingBuilder<'a> {
+    /// Creates a new `WindingBuilder` that forwards segments to `builder`.
+    #[inline]
+    pub fn new(builder: &'a mut dyn OutlineBuilder) -> Self {
+        WindingBuilder {
+            builder,
+            start: Point::default(),
+            last: Point::default(),
+            is_open: false,
+            contour_area: 0.0,
+            last_contour_area: 0.0,
+            area: 0.0,
+            clockwise: 0,
+            counter_clockwise: 0,
+        }
+    }
+
+    /// Returns a signed area of the whole outline.
+    ///
+    /// This is a sum of all contour areas, so the overlapping parts
+    /// and holes are not handled in any special way.
+    #[inline]
+    pub fn area(&self) -> f32 {
+        self.area
+    }
+
+    /// Returns a winding direction of the whole outline.
+    ///
+    /// Since holes are wound in the opposite direction, this is effectively
+    /// a winding direction of the outer contours.
+    ///
+    /// Returns `None` when the outline is empty or has a zero area.
+    #[inline]
+    pub fn winding(&self) -> Option<Winding> {
+        Winding::from_area(self.area)
+    }
+
+    /// Returns a signed area of the last closed contour.
+    #[inline]
+    pub fn last_contour_area(&self) -> f32 {
+        self.last_contour_area
+    }
+
+    /// Returns the number of clockwise contours.
+    #[inline]
+    pub fn clockwise_contours(&self) -> u16 {
+        self.clockwise
+    }
+
+    /// Returns the number of counter-clockwise contours.
+    #[inline]
+    pub fn counter_clockwise_contours(&self) -> u16 {
+        self.counter_clockwise
+    }
+
+    #[inline]
+    fn finish_contour(&mut self) {
+        if !self.is_open {
+            return;
+        }
+
+        // Implicit closing line.
+        self.contour_area += cross(self.last, self.start) * 0.5;
+
+        match Winding::from_area(self.contour_area) {
+            Some(Winding::Clockwise) => {
+                self.clockwise = self.clockwise.saturating_add(1);
+            }
+            Some(Winding::CounterClockwise) => {
+                self.counter_clockwise = self.counter_clockwise.saturating_add(1);
+            }
+            None => {}
+        }
+
+        self.area += self.contour_area;
+        self.last_contour_area = self.contour_area;
+        self.contour_area = 0.0;
+        self.is_open = false;
+    }
+}
+
+impl OutlineBuilder for WindingBuilder<'_> {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.finish_contour();
+
+        self.start = Point { x, y };
+        self.last = self.start;
+        self.is_open = true;
+        self.builder.move_to(x, y);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = Point { x, y };
+        self.contour_area += cross(self.last, p) * 0.5;
+        self.last = p;
+        self.builder.line_to(x, y);
+    }
+
+    #[inline]
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.last;
+        let p1 = Point { x: x1, y: y1 };
+        let p2 = Point { x, y };
+        self.contour_area += (2.0 * cross(p0, p1) + 2.0 * cross(p1, p2) + cross(p0, p2)) / 6.0;
+        self.last = p2;
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    #[inline]
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.last;
+        let p1 = Point { x: x1, y: y1 };
+        let p2 = Point { x: x2, y: y2 };
+        let p3 = Point { x, y };
+        self.contour_area += (
+              6.0 * cross(p0, p1)
+            + 3.0 * cross(p0, p2)
+            +       cross(p0, p3)
+            + 3.0 * cross(p1, p2)
+            + 3.0 * cross(p1, p3)
+            + 6.0 * cross(p2, p3)
+        ) / 20.0;
+        self.last = p3;
+        self.builder.curve_to(x1, y1, x2, y2, x, y);
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.finish_contour();
+        self.builder.close();
+    }
+}
+
+impl core::fmt::Debug for WindingBuilder<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "WindingBuilder()")
+    }
+}
+
+
+// Limits the number of lines per curve, even for a zero tolerance.
+const MAX_FLATTEN_SEGMENTS: u16 = 100;
+
+// Returns the smallest `n` for which `n^4 * factor >= dev_sq`.
+//
+// We can't use `sqrt()` in `no_std`, so we simply count up.
+fn segments_count(dev_sq: f32, factor: f32) -> u16 {
+    let mut n = 1;
+    while n < MAX_FLATTEN_SEGMENTS {
+        let n2 = f32::from(n * n);
+        if n2 * n2 * factor >= dev_sq {
+            break;
+        }
+
+        n += 1;
+    }
+
+    n
+}
+
+/// Splits a quadratic curve into lines, calling `f` with each end point.
+///
+/// The maximum distance between a curve and its chord is `|p0 - 2p1 + p2| / 4`,
+/// and it decreases quadratically with the number of uniform segments.
+pub(crate) fn flatten_quad(p0: Point, p1: Point, p2: Point, tolerance: f32, f: &mut dyn FnMut(Point)) {
+    let dev_x = p0.x - 2.0 * p1.x + p2.x;
+    let dev_y = p0.y - 2.0 * p1.y + p2.y;
+    let n = segments_count(dev_x * dev_x + dev_y * dev_y, 16.0 * tolerance * tolerance);
+    for i in 1..n {
+        let t = f32::from(i) / f32::from(n);
+        f(p0.lerp(p1, t).lerp(p1.lerp(p2, t), t));
+    }
+
+    f(p2);
+}
+
+/// Splits a cubic curve into lines, calling `f` with each end point.
+///
+/// The maximum distance between a curve and its chord is bounded by
+/// `3/4 * max(|p0 - 2p1 + p2|, |p1 - 2p2 + p3|)`.
+pub(crate) fn flatten_cubic(
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    tolerance: f32,
+    f: &mut dyn FnMut(Point),
+) {
+    let dev_x1 = p0.x - 2.0 * p1.x + p2.x;
+    let dev_y1 = p0.y - 2.0 * p1.y + p2.y;
+    let dev_x2 = p1.x - 2.0 * p2.x + p3.x;
+    let dev_y2 = p1.y - 2.0 * p2.y + p3.y;
+    let dev_sq = (dev_x1 * dev_x1 + dev_y1 * dev_y1).max(dev_x2 * dev_x2 + dev_y2 * dev_y2);
+    let n = segments_count(9.0 * dev_sq, 16.0 * tolerance * tolerance);
+    for i in 1..n {
+        let t = f32::from(i) / f32::from(n);
+        let a = p0.lerp(p1, t);
+        let b = p1.lerp(p2, t);
+        let c = p2.lerp(p3, t);
+        f(a.lerp(b, t).lerp(b.lerp(c, t), t));
+    }
+
+    f(p3);
+}
+
+
+/// An `OutlineBuilder` adapter that converts curves into lines.
+///
+/// Quadratic and cubic curves are split into uniform line segments,
+/// so that the distance between a curve and its approximation doesn't exceed `tolerance`.
+/// The underlying builder will receive only MoveTo, LineTo and ClosePath segments.
+///
+/// `tolerance` is in the same units as the outline, i.e. font units
+/// unless the outline was scaled beforehand.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{FlattenBuilder, OutlineBuilder};
+///
+/// struct Builder(u32);
+/// impl OutlineBuilder for Builder {
+///     fn move_to(&mut self, _: f32, _: f32) {}
+///     fn line_to(&mut self, _: f32, _: f32) { self.0 += 1; }
+///     fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) { unreachable!() }
+///     fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) { unreachable!() }
+///     fn close(&mut self) {}
+/// }
+///
+/// let mut lines = Builder(0);
+/// let mut builder = FlattenBuilder::new(&mut lines, 1.0);
+/// builder.move_to(0.0, 0.0);
+/// builder.quad_to(50.0, 100.0, 100.0, 0.0);
+/// builder.close();
+/// assert_eq!(lines.0, 8);
+/// ```
+pub struct FlattenBuilder<'a> {
+    builder: &'a mut dyn OutlineBuilder,
+    tolerance: f32,
+    last: Point,
+}
+
+impl<'a> FlattenBuilder<'a> {
+    /// Creates a new `FlattenBuilder` that forwards lines to `builder`.
+    #[inline]
+    pub fn new(builder: &'a mut dyn OutlineBuilder, tolerance: f32) -> Self {
+        FlattenBuilder {
+            builder,
+            tolerance,
+            last: Point::default(),
+        }
+    }
+}
+
+impl OutlineBuilder for FlattenBuilder<'_> {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.last = Point { x, y };
+        self.builder.move_to(x, y);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.last = Point { x, y };
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let builder = &mut self.builder;
+        flatten_quad(self.last, Point { x: x1, y: y1 }, Point { x, y }, self.tolerance,
+                     &mut |p| builder.line_to(p.x, p.y));
+        self.last = Point { x, y };
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let builder = &mut self.builder;
+        flatten_cubic(self.last, Point { x: x1, y: y1 }, Point { x: x2, y: y2 }, Point { x, y },
+                      self.tolerance, &mut |p| builder.line_to(p.x, p.y));
+        self.last = Point { x, y };
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+impl core::fmt::Debug for FlattenBuilder<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "FlattenBuilder()")
+    }
+}
+
+
+// An `OutlineBuilder` adapter that scales all coordinates.
+pub(crate) struct ScaleBuilder<'a> {
+    pub builder: &'a mut dyn OutlineBuilder,
+    pub x_scale: f32,
+    pub y_scale: f32,
+}
+
+impl OutlineBuilder for ScaleBuilder<'_> {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.builder.move_to(x * self.x_scale, y * self.y_scale);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.builder.line_to(x * self.x_scale, y * self.y_scale);
+    }
+
+    #[inline]
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.builder.quad_to(x1 * self.x_scale, y1 * self.y_scale, x * self.x_scale, y * self.y_scale);
+    }
+
+    #[inline]
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.builder.curve_to(
+            x1 * self.x_scale, y1 * self.y_scale,
+            x2 * self.x_scale, y2 * self.y_scale,
+            x * self.x_scale, y * self.y_scale,
+        );
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
@@ -0,0 +1,212 @@
+//! An outline cache.
+
+use std::collections::{HashMap, VecDeque};
+use std::vec::Vec;
+
+use crate::{Face, GlyphId, OutlineBuilder, Rect};
+
+
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+impl OutlineBuilder for Vec<Segment> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.push(Segment::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(Segment::LineTo(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.push(Segment::QuadTo(x1, y1, x, y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.push(Segment::CurveTo(x1, y1, x2, y2, x, y));
+    }
+
+    fn close(&mut self) {
+        self.push(Segment::Close);
+    }
+}
+
+
+#[derive(Clone, Debug)]
+struct Outline {
+    segments: Vec<Segment>,
+    bbox: Rect,
+}
+
+impl Outline {
+    fn replay(&self, builder: &mut dyn OutlineBuilder) -> Rect {
+        for segment in &self.segments {
+            match *segment {
+                Segment::MoveTo(x, y) => builder.move_to(x, y),
+                Segment::LineTo(x, y) => builder.line_to(x, y),
+                Segment::QuadTo(x1, y1, x, y) => builder.quad_to(x1, y1, x, y),
+                Segment::CurveTo(x1, y1, x2, y2, x, y) => builder.curve_to(x1, y1, x2, y2, x, y),
+                Segment::Close => builder.close(),
+            }
+        }
+
+        self.bbox
+    }
+}
+
+
+// Glyph ID and normalized variation coordinates.
+type Key = (GlyphId, Vec<i16>);
+
+#[cfg(feature = "variable-fonts")]
+fn coordinates(face: &Face) -> Vec<i16> {
+    face.variation_coordinates().iter().map(|c| c.get()).collect()
+}
+
+#[cfg(not(feature = "variable-fonts"))]
+fn coordinates(_: &Face) -> Vec<i16> {
+    Vec::new()
+}
+
+
+/// A cache of decoded outlines.
+///
+/// Outlines are keyed by a glyph ID and the face's current variation coordinates,
+/// so `gvar` and `CFF2` deltas are applied only once per instance.
+///
+/// A cache must be used with a single face.
+/// When the limit is reached, the oldest outline is evicted.
+///
+/// Available only with the `outline-cache` feature.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{Face, GlyphId, OutlineBuilder, OutlineCache};
+///
+/// struct Dummy;
+/// impl OutlineBuilder for Dummy {
+///     fn move_to(&mut self, _: f32, _: f32) {}
+///     fn line_to(&mut self, _: f32, _: f32) {}
+///     fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+///     fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+///     fn close(&mut self) {}
+/// }
+///
+/// let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+/// let face = Face::from_slice(&data, 0).unwrap();
+/// let mut cache = OutlineCache::new(256);
+/// let bbox = cache.outline_glyph(&face, GlyphId(1), &mut Dummy);
+/// assert_eq!(bbox, face.glyph_bounding_box(GlyphId(1)));
+/// assert_eq!(cache.len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct OutlineCache {
+    outlines: HashMap<Key, Option<Outline>>,
+    order: VecDeque<Key>,
+    limit: usize,
+}
+
+impl OutlineCache {
+    /// Creates a new cache that holds up to `limit` outlines.
+    pub fn new(limit: usize) -> Self {
+        OutlineCache {
+            outlines: HashMap::new(),
+            order: VecDeque::new(),
+            limit,
+        }
+    }
+
+    /// Outlines a glyph, using a cached outline when available.
+    ///
+    /// Has the same semantics as [`Face::outline_glyph`](struct.Face.html#method.outline_glyph).
+    /// Glyphs without an outline are cached too.
+    pub fn outline_glyph(
+        &mut self,
+        face: &Face,
+        glyph_id: GlyphId,
+        builder: &mut dyn OutlineBuilder,
+    ) -> Option<Rect> {
+        let key = (glyph_id, coordinates(face));
+        if let Some(outline) = self.outlines.get(&key) {
+            return outline.as_ref().map(|outline| outline.replay(builder));
+        }
+
+        let mut segments = Vec::new();
+        let outline = face.outline_glyph(glyph_id, &mut segments)
+            .map(|bbox| Outline { segments, bbox });
+        let bbox = outline.as_ref().map(|outline| outline.replay(builder));
+        self.insert(key, outline);
+        bbox
+    }
+
+    fn insert(&mut self, key: Key, outline: Option<Outline>) {
+        if self.limit == 0 {
+            return;
+        }
+
+        while self.order.len() >= self.limit {
+            if let Some(old_key) = self.order.pop_front() {
+                self.outlines.remove(&old_key);
+            }
+        }
+
+        self.order.push_back(key.clone());
+        self.outlines.insert(key, outline);
+    }
+
+    /// Returns the number of cached outlines.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.outlines.len()
+    }
+
+    /// Checks that the cache is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.outlines.is_empty()
+    }
+
+    /// Removes all cached outlines.
+    pub fn clear(&mut self) {
+        self.outlines.clear();
+        self.order.clear();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eviction() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+
+        let mut cache = OutlineCache::new(2);
+        let mut segments = Vec::new();
+        let bbox = cache.outline_glyph(&face, GlyphId(1), &mut segments);
+        assert_eq!(bbox, face.glyph_bounding_box(GlyphId(1)));
+        assert!(!segments.is_empty());
+
+        // A cached outline must be identical.
+        let mut cached = Vec::new();
+        assert_eq!(cache.outline_glyph(&face, GlyphId(1), &mut cached), bbox);
+        assert_eq!(cached.len(), segments.len());
+
+        cache.outline_glyph(&face, GlyphId(2), &mut Vec::new());
+        cache.outline_glyph(&face, GlyphId(3), &mut Vec::new());
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.outlines.contains_key(&(GlyphId(1), coordinates(&face))));
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
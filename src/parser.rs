@@ -489,6 +489,16 @@ pub struct LazyArrayIter32<'a, T> {
     index: u32,
 }
 
+impl<T: FromData> Default for LazyArrayIter32<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        LazyArrayIter32 {
+            data: LazyArray32::new(&[]),
+            index: 0,
+        }
+    }
+}
+
 impl<'a, T: FromData> Iterator for LazyArrayIter32<'a, T> {
     type Item = T;
 
@@ -0,0 +1,383 @@
+//! A minimal scanline rasterizer.
+
+use std::vec::Vec;
+
+use crate::{DummyOutline, FaceTables, GlyphId, OutlineBuilder};
+use crate::outline::{flatten_cubic, flatten_quad, Point};
+
+// Maximum distance between a curve and its approximation in pixels.
+const TOLERANCE: f32 = 0.1;
+
+// Maximum glyph size in ems.
+//
+// Font units are not bounded by the units per em value, so without this limit
+// a malformed font can make us allocate gigabytes for a single mask.
+const MAX_GLYPH_SIZE: i32 = 16;
+
+// Maximum mask width and height in pixels.
+//
+// `pixels_per_em` is caller-controlled, so the mask size has to be limited as well.
+const MAX_MASK_SIZE: f32 = 8192.0;
+
+
+/// A rasterizer that produces an 8-bit coverage mask from outline segments.
+///
+/// Coordinates are in pixels, with the origin at the top-left corner and Y pointing down.
+/// Uses the non-zero fill rule with signed area accumulation and is anti-aliased.
+/// Curves are flattened internally.
+///
+/// Available only with the `rasterizer` feature.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{OutlineBuilder, Rasterizer};
+///
+/// let mut rasterizer = Rasterizer::new(4, 4);
+/// rasterizer.move_to(1.0, 1.0);
+/// rasterizer.line_to(3.0, 1.0);
+/// rasterizer.line_to(3.0, 3.0);
+/// rasterizer.line_to(1.0, 3.0);
+/// rasterizer.close();
+///
+/// let mask = rasterizer.into_coverage();
+/// assert_eq!(mask[0], 0);
+/// assert_eq!(mask[5], 255);
+/// ```
+#[derive(Clone)]
+pub struct Rasterizer {
+    width: usize,
+    height: usize,
+    accumulator: Vec<f32>,
+    start: Point,
+    last: Point,
+    // Applied to all incoming coordinates.
+    scale: f32,
+    x_offset: f32,
+    y_offset: f32,
+    flip_y: bool,
+}
+
+impl Rasterizer {
+    /// Creates a new rasterizer for a mask of the specified size.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the number of pixels doesn't fit into `usize`.
+    pub fn new(width: u32, height: u32) -> Self {
+        let width = width as usize;
+        let height = height as usize;
+        // Additional cells are used by lines touching the right edge.
+        let len = width.checked_mul(height)
+            .and_then(|n| n.checked_add(4))
+            .expect("mask size overflow");
+        Rasterizer {
+            width,
+            height,
+            accumulator: vec![0.0; len],
+            start: Point::default(),
+            last: Point::default(),
+            scale: 1.0,
+            x_offset: 0.0,
+            y_offset: 0.0,
+            flip_y: false,
+        }
+    }
+
+    /// Returns mask's width.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    /// Returns mask's height.
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height as u32
+    }
+
+    #[inline]
+    fn transform(&self, x: f32, y: f32) -> Point {
+        let y = if self.flip_y { -y } else { y };
+        Point {
+            x: x * self.scale + self.x_offset,
+            y: y * self.scale + self.y_offset,
+        }
+    }
+
+    fn draw_line(&mut self, p0: Point, p1: Point) {
+        if p0.y == p1.y {
+            return;
+        }
+
+        // Everything to the left of the mask accumulates in the first column,
+        // and everything to the right is not visible.
+        let max_x = self.width as f32;
+        let p0 = Point { x: p0.x.max(0.0).min(max_x), y: p0.y };
+        let p1 = Point { x: p1.x.max(0.0).min(max_x), y: p1.y };
+
+        let (dir, p0, p1) = if p0.y < p1.y { (1.0, p0, p1) } else { (-1.0, p1, p0) };
+        let dxdy = (p1.x - p0.x) / (p1.y - p0.y);
+        let mut x = p0.x;
+        if p0.y < 0.0 {
+            x -= p0.y * dxdy;
+        }
+
+        let y_start = p0.y.max(0.0) as usize;
+        let y_end = (p1.y.ceil().max(0.0) as usize).min(self.height);
+        for y in y_start..y_end {
+            let line_start = y * self.width;
+            let dy = ((y + 1) as f32).min(p1.y) - (y as f32).max(p0.y);
+            let x_next = x + dxdy * dy;
+            let d = dy * dir;
+            let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            let x0_floor = x0.floor();
+            let x0i = x0_floor as usize;
+            let x1_ceil = x1.ceil();
+            let x1i = x1_ceil as usize;
+            if x1i <= x0i + 1 {
+                let xmf = 0.5 * (x + x_next) - x0_floor;
+                self.accumulator[line_start + x0i] += d - d * xmf;
+                self.accumulator[line_start + x0i + 1] += d * xmf;
+            } else {
+                let s = (x1 - x0).recip();
+                let x0f = x0 - x0_floor;
+                let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1_ceil + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+                self.accumulator[line_start + x0i] += d * a0;
+                if x1i == x0i + 2 {
+                    self.accumulator[line_start + x0i + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.accumulator[line_start + x0i + 1] += d * (a1 - a0);
+                    for xi in x0i + 2..x1i - 1 {
+                        self.accumulator[line_start + xi] += d * s;
+                    }
+
+                    let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                    self.accumulator[line_start + x1i - 1] += d * (1.0 - a2 - am);
+                }
+
+                self.accumulator[line_start + x1i] += d * am;
+            }
+
+            x = x_next;
+        }
+    }
+
+    fn draw_quad(&mut self, p0: Point, p1: Point, p2: Point) {
+        let mut last = p0;
+        flatten_quad(p0, p1, p2, TOLERANCE, &mut |p| {
+            self.draw_line(last, p);
+            last = p;
+        });
+    }
+
+    fn draw_cubic(&mut self, p0: Point, p1: Point, p2: Point, p3: Point) {
+        let mut last = p0;
+        flatten_cubic(p0, p1, p2, p3, TOLERANCE, &mut |p| {
+            self.draw_line(last, p);
+            last = p;
+        });
+    }
+
+    /// Returns a coverage mask, one byte per pixel, row by row.
+    pub fn into_coverage(self) -> Vec<u8> {
+        let mut acc = 0.0;
+        self.accumulator.iter().take(self.width * self.height).map(|c| {
+            acc += c;
+            (acc.abs().min(1.0) * 255.0 + 0.5) as u8
+        }).collect()
+    }
+}
+
+impl OutlineBuilder for Rasterizer {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let p = self.transform(x, y);
+        self.start = p;
+        self.last = p;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.transform(x, y);
+        self.draw_line(self.last, p);
+        self.last = p;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p1 = self.transform(x1, y1);
+        let p = self.transform(x, y);
+        self.draw_quad(self.last, p1, p);
+        self.last = p;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p1 = self.transform(x1, y1);
+        let p2 = self.transform(x2, y2);
+        let p = self.transform(x, y);
+        self.draw_cubic(self.last, p1, p2, p);
+        self.last = p;
+    }
+
+    fn close(&mut self) {
+        if self.last.x != self.start.x || self.last.y != self.start.y {
+            self.draw_line(self.last, self.start);
+        }
+
+        self.last = self.start;
+    }
+}
+
+impl core::fmt::Debug for Rasterizer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Rasterizer")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+
+/// A glyph coverage mask.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GlyphMask {
+    /// Horizontal offset from the glyph origin to the left edge of the mask in pixels.
+    pub left: i32,
+
+    /// Vertical offset from the baseline to the top edge of the mask in pixels.
+    ///
+    /// Positive values are above the baseline.
+    pub top: i32,
+
+    /// Mask width in pixels.
+    pub width: u32,
+
+    /// Mask height in pixels.
+    pub height: u32,
+
+    /// Coverage values, one byte per pixel, row by row.
+    pub data: Vec<u8>,
+}
+
+
+pub(crate) fn rasterize_glyph(
+    face: &FaceTables,
+    glyph_id: GlyphId,
+    pixels_per_em: f32,
+) -> Option<GlyphMask> {
+    if !pixels_per_em.is_finite() || pixels_per_em <= 0.0 {
+        return None;
+    }
+
+    let bbox = face.outline_glyph(glyph_id, &mut DummyOutline)?;
+    let units_per_em = face.units_per_em()?;
+    let max_size = i32::from(units_per_em) * MAX_GLYPH_SIZE;
+    let width = i32::from(bbox.x_max) - i32::from(bbox.x_min);
+    let height = i32::from(bbox.y_max) - i32::from(bbox.y_min);
+    if width > max_size || height > max_size {
+        return None;
+    }
+
+    let scale = pixels_per_em / f32::from(units_per_em);
+
+    let left = (f32::from(bbox.x_min) * scale).floor();
+    let right = (f32::from(bbox.x_max) * scale).ceil();
+    let top = (f32::from(bbox.y_max) * scale).ceil();
+    let bottom = (f32::from(bbox.y_min) * scale).floor();
+    if right - left > MAX_MASK_SIZE || top - bottom > MAX_MASK_SIZE {
+        return None;
+    }
+
+    let mut rasterizer = Rasterizer::new((right - left) as u32, (top - bottom) as u32);
+    rasterizer.scale = scale;
+    rasterizer.x_offset = -left;
+    rasterizer.y_offset = top;
+    rasterizer.flip_y = true;
+    face.outline_glyph(glyph_id, &mut rasterizer)?;
+
+    Some(GlyphMask {
+        left: left as i32,
+        top: top as i32,
+        width: rasterizer.width(),
+        height: rasterizer.height(),
+        data: rasterizer.into_coverage(),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Face;
+
+    #[test]
+    fn square() {
+        let mut rasterizer = Rasterizer::new(4, 4);
+        rasterizer.move_to(1.0, 1.0);
+        rasterizer.line_to(1.0, 3.0);
+        rasterizer.line_to(3.0, 3.0);
+        rasterizer.line_to(3.0, 1.0);
+        rasterizer.close();
+
+        assert_eq!(rasterizer.into_coverage(), vec![
+            0,   0,   0, 0,
+            0, 255, 255, 0,
+            0, 255, 255, 0,
+            0,   0,   0, 0,
+        ]);
+    }
+
+    #[test]
+    fn half_pixel() {
+        let mut rasterizer = Rasterizer::new(2, 1);
+        rasterizer.move_to(0.0, 0.0);
+        rasterizer.line_to(0.5, 0.0);
+        rasterizer.line_to(0.5, 1.0);
+        rasterizer.line_to(0.0, 1.0);
+        rasterizer.close();
+
+        assert_eq!(rasterizer.into_coverage(), vec![128, 0]);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut rasterizer = Rasterizer::new(2, 2);
+        rasterizer.move_to(-10.0, -10.0);
+        rasterizer.line_to(10.0, -10.0);
+        rasterizer.line_to(10.0, 10.0);
+        rasterizer.line_to(-10.0, 10.0);
+        rasterizer.close();
+
+        assert_eq!(rasterizer.into_coverage(), vec![255; 4]);
+    }
+
+    #[test]
+    fn glyph() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        // A glyph with bbox 6,0..541,656 and 1000 units per em.
+        let mask = rasterize_glyph(&face, GlyphId(1), 100.0).unwrap();
+        assert_eq!((mask.left, mask.top, mask.width, mask.height), (0, 66, 55, 66));
+        assert_eq!(mask.data.len(), 55 * 66);
+        assert!(mask.data.iter().any(|c| *c == 255));
+    }
+
+    #[test]
+    fn huge_glyph() {
+        let mut data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        // Set units per em to 16, so the glyph is 41 ems high.
+        data[124 + 18..124 + 20].copy_from_slice(&[0x00, 0x10]);
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(rasterize_glyph(&face, GlyphId(1), 100.0), None);
+    }
+
+    #[test]
+    fn huge_pixels_per_em() {
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        // The glyph is 0.656 ems high, so the mask would be 12464 pixels high.
+        assert_eq!(rasterize_glyph(&face, GlyphId(1), 19_000.0), None);
+        assert_eq!(rasterize_glyph(&face, GlyphId(1), 1e30), None);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{FaceTables, Scripts, Tag};
+
+// Representative code points for each script.
+// All of them must be mapped for a script to be reported as supported by `cmap`.
+const CMAP_SAMPLES: &[(Tag, &[char])] = &[
+    (Tag::from_bytes(b"latn"), &['A', 'Z', 'a', 'z']),
+    (Tag::from_bytes(b"grek"), &['\u{0391}', '\u{03B1}', '\u{03C9}']),
+    (Tag::from_bytes(b"cyrl"), &['\u{0410}', '\u{0430}', '\u{044F}']),
+    (Tag::from_bytes(b"armn"), &['\u{0531}', '\u{0561}']),
+    (Tag::from_bytes(b"hebr"), &['\u{05D0}', '\u{05EA}']),
+    (Tag::from_bytes(b"arab"), &['\u{0627}', '\u{0628}', '\u{064A}']),
+    (Tag::from_bytes(b"syrc"), &['\u{0710}', '\u{0712}']),
+    (Tag::from_bytes(b"thaa"), &['\u{0780}', '\u{0781}']),
+    (Tag::from_bytes(b"nko "), &['\u{07CA}', '\u{07CB}']),
+    (Tag::from_bytes(b"deva"), &['\u{0905}', '\u{0915}']),
+    (Tag::from_bytes(b"beng"), &['\u{0985}', '\u{0995}']),
+    (Tag::from_bytes(b"guru"), &['\u{0A05}', '\u{0A15}']),
+    (Tag::from_bytes(b"gujr"), &['\u{0A85}', '\u{0A95}']),
+    (Tag::from_bytes(b"orya"), &['\u{0B05}', '\u{0B15}']),
+    (Tag::from_bytes(b"taml"), &['\u{0B85}', '\u{0B95}']),
+    (Tag::from_bytes(b"telu"), &['\u{0C05}', '\u{0C15}']),
+    (Tag::from_bytes(b"knda"), &['\u{0C85}', '\u{0C95}']),
+    (Tag::from_bytes(b"mlym"), &['\u{0D05}', '\u{0D15}']),
+    (Tag::from_bytes(b"sinh"), &['\u{0D85}', '\u{0D9A}']),
+    (Tag::from_bytes(b"thai"), &['\u{0E01}', '\u{0E2E}']),
+    (Tag::from_bytes(b"lao "), &['\u{0E81}', '\u{0E82}']),
+    (Tag::from_bytes(b"tibt"), &['\u{0F40}', '\u{0F41}']),
+    (Tag::from_bytes(b"mymr"), &['\u{1000}', '\u{1001}']),
+    (Tag::from_bytes(b"geor"), &['\u{10D0}', '\u{10F0}']),
+    (Tag::from_bytes(b"ethi"), &['\u{1200}', '\u{1208}']),
+    (Tag::from_bytes(b"cher"), &['\u{13A0}', '\u{13A1}']),
+    (Tag::from_bytes(b"khmr"), &['\u{1780}', '\u{1781}']),
+    (Tag::from_bytes(b"mong"), &['\u{1820}', '\u{1821}']),
+    (Tag::from_bytes(b"tfng"), &['\u{2D30}', '\u{2D31}']),
+    (Tag::from_bytes(b"kana"), &['\u{3042}', '\u{30A2}']),
+    (Tag::from_bytes(b"bopo"), &['\u{3105}', '\u{3106}']),
+    (Tag::from_bytes(b"hani"), &['\u{4E00}', '\u{6C34}']),
+    (Tag::from_bytes(b"yi  "), &['\u{A000}', '\u{A001}']),
+    (Tag::from_bytes(b"hang"), &['\u{AC00}', '\u{D7A3}']),
+];
+
+const DEFAULT_SCRIPT: Tag = Tag::from_bytes(b"DFLT");
+
+// Indic scripts have two tags, one per shaping model.
+// We always report the original one.
+fn normalize(tag: Tag) -> Tag {
+    let bytes = match &tag.to_bytes() {
+        b"bng2" => b"beng",
+        b"dev2" => b"deva",
+        b"gjr2" => b"gujr",
+        b"gur2" => b"guru",
+        b"knd2" => b"knda",
+        b"mlm2" => b"mlym",
+        b"mym2" => b"mymr",
+        b"ory2" => b"orya",
+        b"tel2" => b"telu",
+        b"tml2" => b"taml",
+        _ => return tag,
+    };
+
+    Tag::from_bytes(bytes)
+}
+
+fn has_script(scripts: Scripts, tag: Tag) -> bool {
+    let mut scripts = scripts;
+    scripts.any(|script| normalize(script.tag()) == tag)
+}
+
+
+/// An iterator over scripts supported by a face.
+///
+/// Yields [OpenType script tags](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/scripttags).
+/// Indic scripts are always reported using their original tags, like `deva` and not `dev2`.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct SupportedScripts<'a> {
+    face: &'a FaceTables<'a>,
+    gsub_scripts: Scripts<'a>,
+    gpos_scripts: Scripts<'a>,
+    index: usize,
+}
+
+impl<'a> SupportedScripts<'a> {
+    pub(crate) fn new(face: &'a FaceTables<'a>) -> Self {
+        SupportedScripts {
+            face,
+            gsub_scripts: face.substitution_table().map(|t| t.scripts()).unwrap_or_default(),
+            gpos_scripts: face.positioning_table().map(|t| t.scripts()).unwrap_or_default(),
+            index: 0,
+        }
+    }
+
+    fn is_in_layout(&self, tag: Tag) -> bool {
+        // Iterators are copied, so their state is not affected.
+        has_script(self.gsub_scripts, tag) || has_script(self.gpos_scripts, tag)
+    }
+
+    fn is_in_cmap(&self, chars: &[char]) -> bool {
+        chars.iter().all(|c| {
+            self.face.glyph_index(*c).map(|id| id.0 != 0).unwrap_or(false)
+        })
+    }
+}
+
+impl<'a> Iterator for SupportedScripts<'a> {
+    type Item = Tag;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Known scripts first.
+        while let Some(&(tag, chars)) = CMAP_SAMPLES.get(self.index) {
+            self.index += 1;
+            if self.is_in_layout(tag) || self.is_in_cmap(chars) {
+                return Some(tag);
+            }
+        }
+
+        // Then scripts that are present only in GSUB/GPOS.
+        let is_known = |tag: Tag| {
+            tag == DEFAULT_SCRIPT || CMAP_SAMPLES.iter().any(|&(t, _)| t == tag)
+        };
+
+        for script in &mut self.gsub_scripts {
+            let tag = normalize(script.tag());
+            if !is_known(tag) {
+                return Some(tag);
+            }
+        }
+
+        // The GSUB iterator is exhausted at this point,
+        // so we have to check the table once again.
+        let gsub_scripts = self.face.substitution_table().map(|t| t.scripts()).unwrap_or_default();
+        for script in &mut self.gpos_scripts {
+            let tag = normalize(script.tag());
+            if !is_known(tag) && !has_script(gsub_scripts, tag) {
+                return Some(tag);
+            }
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Face;
+
+    // A layout table with the specified script tags.
+    fn layout_table(tags: &[&[u8; 4]]) -> std::vec::Vec<u8> {
+        let count = tags.len() as u16;
+        let script_list_len = 2 + count * 6;
+        let mut data = std::vec::Vec::new();
+        data.extend_from_slice(&[0x00, 0x01, 0x00, 0x00]); // version: 1.0
+        data.extend_from_slice(&10u16.to_be_bytes()); // script list offset
+        data.extend_from_slice(&(10 + script_list_len + 4).to_be_bytes()); // feature list offset
+        data.extend_from_slice(&[0x00, 0x00]); // lookup list offset: 0
+        // Script list
+        data.extend_from_slice(&count.to_be_bytes());
+        for tag in tags {
+            data.extend_from_slice(*tag);
+            data.extend_from_slice(&script_list_len.to_be_bytes()); // script offset
+        }
+        // Script table
+        data.extend_from_slice(&[0x00, 0x00]); // default lang sys offset: 0
+        data.extend_from_slice(&[0x00, 0x00]); // lang sys count: 0
+        // Feature list
+        data.extend_from_slice(&[0x00, 0x00]); // count: 0
+        data
+    }
+
+    #[test]
+    fn cmap_and_layout_scripts() {
+        let cmap = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x01, // number of tables: 1
+            0x00, 0x00, // platform ID: Unicode
+            0x00, 0x03, // encoding ID: Unicode 2.0 BMP
+            0x00, 0x00, 0x00, 0x0C, // offset: 12
+            // Format 4 subtable
+            0x00, 0x04, // format: 4
+            0x00, 0x20, // subtable size: 32
+            0x00, 0x00, // language ID: 0
+            0x00, 0x04, // 2 x segCount: 4
+            0x00, 0x02, // search range: 2
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x02, // range shift: 2
+            // End character codes
+            0x00, 0x7A, // char code [0]: 122
+            0xFF, 0xFF, // char code [1]: 65535
+            0x00, 0x00, // reserved: 0
+            // Start character codes
+            0x00, 0x41, // char code [0]: 65
+            0xFF, 0xFF, // char code [1]: 65535
+            // Deltas
+            0xFF, 0xC0, // delta [0]: -64
+            0x00, 0x01, // delta [1]: 1
+            // Offsets into Glyph index array
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, // offset [1]: 0
+        ];
+        let gsub = layout_table(&[b"DFLT", b"dev2", b"zzzz"]);
+        let gpos = layout_table(&[b"grek", b"zzzz", b"yyyy"]);
+
+        let data = std::fs::read("tests/fonts/demo.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        let tables = [b"head", b"hhea", b"maxp"].iter()
+            .map(|tag| (Tag::from_bytes(tag), face.table_data(Tag::from_bytes(tag))))
+            .chain(core::iter::once((Tag::from_bytes(b"cmap"), Some(&cmap[..]))))
+            .chain(core::iter::once((Tag::from_bytes(b"GSUB"), Some(&gsub[..]))))
+            .chain(core::iter::once((Tag::from_bytes(b"GPOS"), Some(&gpos[..]))))
+            .map(Ok);
+        let face = FaceTables::from_table_provider(tables).unwrap();
+
+        let scripts: std::vec::Vec<Tag> = face.scripts().collect();
+        assert_eq!(scripts, [
+            Tag::from_bytes(b"latn"), // `cmap`
+            Tag::from_bytes(b"grek"), // `GPOS`
+            Tag::from_bytes(b"deva"), // `GSUB`, normalized
+            Tag::from_bytes(b"zzzz"), // unknown, reported once
+            Tag::from_bytes(b"yyyy"), // unknown, `GPOS` only
+        ]);
+    }
+
+    #[test]
+    fn indic_tags() {
+        assert_eq!(normalize(Tag::from_bytes(b"dev2")), Tag::from_bytes(b"deva"));
+        assert_eq!(normalize(Tag::from_bytes(b"deva")), Tag::from_bytes(b"deva"));
+        assert_eq!(normalize(Tag::from_bytes(b"latn")), Tag::from_bytes(b"latn"));
+    }
+}
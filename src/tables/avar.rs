@@ -4,21 +4,26 @@ use core::convert::TryFrom;
 use core::num::NonZeroU16;
 
 use crate::NormalizedCoordinate;
-use crate::parser::{Stream, FromData, LazyArray16};
+use crate::parser::{Stream, FromData, LazyArray16, Offset, Offset32};
+use crate::var_store::{ItemVariationStore, DeltaSetIndexMap};
 
 
 #[derive(Clone, Copy)]
 pub(crate) struct Table<'a> {
     axis_count: NonZeroU16,
     data: &'a [u8],
+    // avar 2.0 only.
+    axis_index_map: Option<DeltaSetIndexMap<'a>>,
+    variation_store: Option<ItemVariationStore<'a>>,
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
-        let mut s = Stream::new(data);
+    pub fn parse(table_data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(table_data);
 
-        let version: u32 = s.read()?;
-        if version != 0x00010000 {
+        let major_version: u16 = s.read()?;
+        s.skip::<u16>(); // minor version
+        if !(major_version == 1 || major_version == 2) {
             return None;
         }
 
@@ -35,24 +40,82 @@ impl<'a> Table<'a> {
             s.advance_checked(AxisValueMapRecord::SIZE * usize::from(count))?;
         }
 
+        let mut axis_index_map = None;
+        let mut variation_store = None;
+        if major_version == 2 {
+            let axis_index_map_offset: Option<Offset32> = s.read()?;
+            let variation_store_offset: Option<Offset32> = s.read()?;
+
+            if let Some(offset) = axis_index_map_offset {
+                axis_index_map = Some(DeltaSetIndexMap::parse(table_data.get(offset.to_usize()..)?)?);
+            }
+
+            if let Some(offset) = variation_store_offset {
+                let s = Stream::new_at(table_data, offset.to_usize())?;
+                variation_store = Some(ItemVariationStore::parse(s)?);
+            }
+        }
+
         Some(Table {
             axis_count,
             data,
+            axis_index_map,
+            variation_store,
         })
     }
 
+    #[inline]
+    pub fn variation_store(&self) -> Option<ItemVariationStore<'a>> {
+        self.variation_store
+    }
+
     pub fn map_coordinates(&self, coordinates: &mut [NormalizedCoordinate]) -> Option<()> {
         if usize::from(self.axis_count.get()) != coordinates.len() {
             return None;
         }
 
         let mut s = Stream::new(self.data);
-        for coord in coordinates {
+        for coord in coordinates.iter_mut() {
             let count: u16 = s.read()?;
             let map = s.read_array16::<AxisValueMapRecord>(count)?;
             *coord = NormalizedCoordinate::from(map_value(&map, coord.0)?);
         }
 
+        if let Some(store) = self.variation_store {
+            self.apply_deltas(store, coordinates)?;
+        }
+
+        Some(())
+    }
+
+    // avar 2.0 adjusts each coordinate by a delta
+    // computed from the segment-mapped coordinates of all axes.
+    fn apply_deltas(
+        &self,
+        store: ItemVariationStore,
+        coordinates: &mut [NormalizedCoordinate],
+    ) -> Option<()> {
+        let mut deltas = [0.0; crate::MAX_VAR_COORDS];
+        for (i, delta) in deltas.iter_mut().enumerate().take(coordinates.len()) {
+            // 'If no mapping is present, the axis index is used directly
+            // as an implicit delta-set index.'
+            let (outer, inner) = match self.axis_index_map {
+                Some(map) => map.map(i as u32)?,
+                None => ((i >> 16) as u16, i as u16),
+            };
+
+            *delta = store.parse_delta(outer, inner, coordinates).unwrap_or(0.0);
+        }
+
+        for (coord, delta) in coordinates.iter_mut().zip(deltas.iter()) {
+            // We can't use `round()` in `no_std`, so this is the next best thing.
+            let delta = (if *delta >= 0.0 { *delta + 0.5 } else { *delta - 0.5 }) as i32;
+            let value = i32::from(coord.0).saturating_add(delta);
+            let value = i16::try_from(value).unwrap_or(if value < 0 { -16384 } else { 16384 });
+            // Clamped to -1..1 on conversion.
+            *coord = NormalizedCoordinate::from(value);
+        }
+
         Some(())
     }
 }
@@ -60,16 +123,22 @@ impl<'a> Table<'a> {
 fn map_value(map: &LazyArray16<AxisValueMapRecord>, value: i16) -> Option<i16> {
     // This code is based on harfbuzz implementation.
 
+    // Shifts a value by a record offset, which can overflow on malformed maps.
+    fn shift(value: i16, from: i16, to: i16) -> i16 {
+        let n = i32::from(value) - i32::from(from) + i32::from(to);
+        i16::try_from(n).unwrap_or(if n < 0 { core::i16::MIN } else { core::i16::MAX })
+    }
+
     if map.len() == 0 {
         return Some(value);
     } else if map.len() == 1 {
         let record = map.get(0)?;
-        return Some(value - record.from_coordinate + record.to_coordinate);
+        return Some(shift(value, record.from_coordinate, record.to_coordinate));
     }
 
     let record_0 = map.get(0)?;
     if value <= record_0.from_coordinate {
-        return Some(value - record_0.from_coordinate + record_0.to_coordinate);
+        return Some(shift(value, record_0.from_coordinate, record_0.to_coordinate));
     }
 
     let mut i = 1;
@@ -85,7 +154,7 @@ fn map_value(map: &LazyArray16<AxisValueMapRecord>, value: i16) -> Option<i16> {
     let curr_from = record_curr.from_coordinate;
     let curr_to = record_curr.to_coordinate;
     if value >= curr_from {
-        return Some(value - curr_from + curr_to);
+        return Some(shift(value, curr_from, curr_to));
     }
 
     let record_prev = map.get(i - 1)?;
@@ -95,13 +164,14 @@ fn map_value(map: &LazyArray16<AxisValueMapRecord>, value: i16) -> Option<i16> {
         return Some(prev_to);
     }
 
-    let curr_from = i32::from(curr_from);
-    let curr_to = i32::from(curr_to);
-    let prev_from = i32::from(prev_from);
-    let prev_to = i32::from(prev_to);
+    // The product below doesn't fit into i32 for large segments.
+    let curr_from = i64::from(curr_from);
+    let curr_to = i64::from(curr_to);
+    let prev_from = i64::from(prev_from);
+    let prev_to = i64::from(prev_to);
 
     let denom = curr_from - prev_from;
-    let k = (curr_to - prev_to) * (i32::from(value) - prev_from) + denom / 2;
+    let k = (curr_to - prev_to) * (i64::from(value) - prev_from) + denom / 2;
     let value = prev_to + k / denom;
     i16::try_from(value).ok()
 }
@@ -125,3 +195,135 @@ impl FromData for AxisValueMapRecord {
         })
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_maps() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // reserved
+            0x00, 0x02, // axis count: 2
+            // Axis 0
+            0x00, 0x04, // map count: 4
+            0xC0, 0x00, 0xC0, 0x00, // map [0]: -1 -> -1
+            0x00, 0x00, 0x00, 0x00, // map [1]: 0 -> 0
+            0x20, 0x00, 0x2C, 0xCD, // map [2]: 0.5 -> 0.7
+            0x40, 0x00, 0x40, 0x00, // map [3]: 1 -> 1
+            // Axis 1
+            0x00, 0x00, // map count: 0
+        ];
+
+        let table = Table::parse(data).unwrap();
+
+        let mut coords = [NormalizedCoordinate::from(0.25), NormalizedCoordinate::from(0.25)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[0].get(), 5735); // ~0.35
+        assert_eq!(coords[1].get(), 4096);
+
+        let mut coords = [NormalizedCoordinate::from(0.5), NormalizedCoordinate::from(0.0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[0].get(), 11469);
+
+        let mut coords = [NormalizedCoordinate::from(0.75), NormalizedCoordinate::from(0.0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[0].get(), 13927); // ~0.85
+
+        let mut coords = [NormalizedCoordinate::from(-0.5), NormalizedCoordinate::from(0.0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[0].get(), -8192);
+
+        // Axis count mismatch.
+        let mut coords = [NormalizedCoordinate::from(0.5)];
+        assert!(table.map_coordinates(&mut coords).is_none());
+    }
+
+    #[test]
+    fn overflowing_map() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // reserved
+            0x00, 0x01, // axis count: 1
+            0x00, 0x01, // map count: 1
+            0x80, 0x00, 0x7F, 0xFF, // map [0]: -32768 -> 32767
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let mut coords = [NormalizedCoordinate::from(1.0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[0].get(), 16384);
+    }
+
+    #[test]
+    fn overflowing_segment() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // reserved
+            0x00, 0x01, // axis count: 1
+            0x00, 0x02, // map count: 2
+            0x80, 0x00, 0x80, 0x00, // map [0]: -32768 -> -32768
+            0x7F, 0xFF, 0x7F, 0xFF, // map [1]: 32767 -> 32767
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let mut coords = [NormalizedCoordinate::from(1.0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[0].get(), 16384);
+    }
+
+    #[test]
+    fn version_2() {
+        let data = &[
+            0x00, 0x02, 0x00, 0x00, // version: 2.0
+            0x00, 0x00, // reserved
+            0x00, 0x02, // axis count: 2
+            0x00, 0x00, // axis 0 map count: 0
+            0x00, 0x00, // axis 1 map count: 0
+            0x00, 0x00, 0x00, 0x00, // axis index map offset: 0
+            0x00, 0x00, 0x00, 0x14, // variation store offset: 20
+            // Item variation store
+            0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0C, // region list offset: 12
+            0x00, 0x01, // data count: 1
+            0x00, 0x00, 0x00, 0x1C, // data offset [0]: 28
+            // Region list
+            0x00, 0x02, // axis count: 2
+            0x00, 0x01, // region count: 1
+            0x00, 0x00, 0x40, 0x00, 0x40, 0x00, // region 0, axis 0: 0, 1, 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // region 0, axis 1: 0, 0, 0
+            // Item variation data
+            0x00, 0x02, // item count: 2
+            0x00, 0x01, // short delta count: 1
+            0x00, 0x01, // region index count: 1
+            0x00, 0x00, // region index [0]: 0
+            0x00, 0x00, // item 0 delta: 0
+            0x10, 0x00, // item 1 delta: 4096
+        ];
+
+        let table = Table::parse(data).unwrap();
+
+        let mut coords = [NormalizedCoordinate::from(0.5), NormalizedCoordinate::from(0.0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[0].get(), 8192);
+        assert_eq!(coords[1].get(), 2048);
+
+        let mut coords = [NormalizedCoordinate::from(0.0), NormalizedCoordinate::from(0.0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords[1].get(), 0);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let data = &[
+            0x00, 0x03, 0x00, 0x00, // version: 3.0
+            0x00, 0x00, // reserved
+            0x00, 0x01, // axis count: 1
+            0x00, 0x00, // axis 0 map count: 0
+        ];
+
+        assert!(Table::parse(data).is_none());
+    }
+}
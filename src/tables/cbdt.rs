@@ -67,3 +67,50 @@ pub fn parse(
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::cblc::Metrics;
+
+    #[test]
+    fn images() {
+        let data = &[
+            // Format 17
+            0x14, // height: 20
+            0x10, // width: 16
+            0x01, // bearing x: 1
+            0x12, // bearing y: 18
+            0x10, // advance: 16
+            0x00, 0x00, 0x00, 0x02, // data length: 2
+            0xAA, 0xBB, // data
+
+            // Format 19
+            0x00, 0x00, 0x00, 0x01, // data length: 1
+            0xCC, // data
+        ];
+
+        let location = Location {
+            format: BitmapFormat::Format17,
+            offset: 0,
+            metrics: Metrics::default(),
+            ppem: 32,
+        };
+        let image = parse(data, location).unwrap();
+        assert_eq!((image.x, image.y, image.width, image.height), (1, -2, 16, 20));
+        assert_eq!(image.pixels_per_em, 32);
+        assert_eq!(image.data, &[0xAA, 0xBB]);
+
+        // Format 19 uses metrics from the index subtable.
+        let location = Location {
+            format: BitmapFormat::Format19,
+            offset: 11,
+            metrics: Metrics { x: -1, y: 10, width: 8, height: 12 },
+            ppem: 32,
+        };
+        let image = parse(data, location).unwrap();
+        assert_eq!((image.x, image.y, image.width, image.height), (-1, -2, 8, 12));
+        assert_eq!(image.data, &[0xCC]);
+    }
+}
@@ -1,6 +1,6 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cblc
 
-use crate::GlyphId;
+use crate::{is_better_strike, GlyphId};
 use crate::parser::{Stream, FromData, Offset, Offset16, Offset32, NumFrom};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -10,7 +10,7 @@ pub enum BitmapFormat {
     Format19,
 }
 
-#[derive(Clone, Copy, Default, Debug)]
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
 pub struct Metrics {
     pub x: i8,
     pub y: i8,
@@ -18,7 +18,7 @@ pub struct Metrics {
     pub height: u8,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Location {
     pub format: BitmapFormat,
     pub offset: usize,
@@ -55,35 +55,35 @@ pub fn find_location(
         _ => return None, // Invalid format.
     };
 
-    // TODO: I wasn't able to find fonts with index 4 and 5, so they are untested.
-
     let glyph_diff = glyph_id.0.checked_sub(info.start_glyph_id.0)?;
-    let metrics = Metrics::default();
+    let mut metrics = Metrics::default();
     match index_format {
         1 => {
             s.advance(usize::from(glyph_diff) * Offset32::SIZE);
             let offset: Offset32 = s.read()?;
-            image_offset += offset.to_usize();
+            image_offset = image_offset.checked_add(offset.to_usize())?;
         }
         2 => {
             let image_size: u32 = s.read()?;
-            image_offset += usize::from(glyph_diff).checked_mul(usize::num_from(image_size))?;
+            metrics = parse_big_metrics(&mut s)?;
+            image_offset = image_offset
+                .checked_add(usize::from(glyph_diff).checked_mul(usize::num_from(image_size))?)?;
         }
         3 => {
             s.advance(usize::from(glyph_diff) * Offset16::SIZE);
             let offset: Offset16 = s.read()?;
-            image_offset += offset.to_usize();
+            image_offset = image_offset.checked_add(offset.to_usize())?;
         }
         4 => {
             let num_glyphs: u32 = s.read()?;
             let num_glyphs = num_glyphs.checked_add(1)?;
             let pairs = s.read_array32::<GlyphIdOffsetPair>(num_glyphs)?;
             let pair = pairs.into_iter().find(|pair| pair.glyph_id == glyph_id)?;
-            image_offset += pair.offset.to_usize();
+            image_offset = image_offset.checked_add(pair.offset.to_usize())?;
         }
         5 => {
             let image_size: u32 = s.read()?;
-            s.advance(8); // big metrics
+            metrics = parse_big_metrics(&mut s)?;
             let num_glyphs: u32 = s.read()?;
             let glyphs = s.read_array32::<GlyphId>(num_glyphs)?;
             let (index, _) = glyphs.binary_search(&glyph_id)?;
@@ -110,18 +110,34 @@ struct BitmapSizeTable {
     // Many fields are omitted.
 }
 
+// Only horizontal metrics are used by the bitmap formats with shared metrics.
+fn parse_big_metrics(s: &mut Stream) -> Option<Metrics> {
+    let height: u8 = s.read()?;
+    let width: u8 = s.read()?;
+    let x: i8 = s.read()?;
+    let y: i8 = s.read()?;
+    s.skip::<u8>(); // hor_advance
+    s.skip::<i8>(); // ver_bearing_x
+    s.skip::<i8>(); // ver_bearing_y
+    s.skip::<u8>(); // ver_advance
+    Some(Metrics { x, y, width, height })
+}
+
 fn select_bitmap_size_table(
     glyph_id: GlyphId,
     pixels_per_em: u16,
     mut s: Stream,
 ) -> Option<BitmapSizeTable> {
+    // The BitmapSize Table is larger than 32 bytes, so we cannot use scripts/gen-tables.py
+    const BITMAP_SIZE_TABLE_SIZE: usize = 48;
+
     let subtable_count: u32 = s.read()?;
-    let orig_s = s.clone();
+    let tables = s.tail()?;
 
-    let mut idx = None;
-    let mut max_ppem = 0;
-    for i in 0..subtable_count {
-        // The BitmapSize Table is larger than 32 bytes, so we cannot use scripts/gen-tables.py
+    // Select a best matching subtable based on `pixels_per_em`.
+    let mut best: Option<(usize, u16)> = None;
+    for i in 0..usize::num_from(subtable_count) {
+        let mut s = Stream::new_at(tables, i.checked_mul(BITMAP_SIZE_TABLE_SIZE)?)?;
 
         // Check that the current subtable contains a provided glyph id.
         s.advance(40); // Jump to `start_glyph_index`.
@@ -130,20 +146,16 @@ fn select_bitmap_size_table(
         let ppem = u16::from(s.read::<u8>()?);
 
         if !(start_glyph_id..=end_glyph_id).contains(&glyph_id) {
-            s.advance(4); // Jump to the end of the subtable.
             continue;
         }
 
-        // Select a best matching subtable based on `pixels_per_em`.
-        if (pixels_per_em <= ppem && ppem < max_ppem) || (pixels_per_em > max_ppem && ppem > max_ppem) {
-            idx = Some(usize::num_from(i));
-            max_ppem = ppem;
+        if is_better_strike(best.map(|(_, best_ppem)| best_ppem), ppem, pixels_per_em) {
+            best = Some((i, ppem));
         }
     }
 
-    let mut s = orig_s;
-    s.advance(idx? * 48); // 48 is BitmapSize Table size
-
+    let (idx, ppem) = best?;
+    let mut s = Stream::new_at(tables, idx * BITMAP_SIZE_TABLE_SIZE)?;
     let subtable_array_offset: Offset32 = s.read()?;
     s.skip::<u32>(); // index_tables_size
     let number_of_subtables: u32 = s.read()?;
@@ -151,7 +163,7 @@ fn select_bitmap_size_table(
     Some(BitmapSizeTable {
         subtable_array_offset,
         number_of_subtables,
-        ppem: max_ppem,
+        ppem,
     })
 }
 
@@ -174,7 +186,7 @@ fn select_index_subtable(
         let offset: Offset32 = s.read()?;
 
         if (start_glyph_id..=end_glyph_id).contains(&glyph_id) {
-            let offset = size_table.subtable_array_offset.to_usize() + offset.to_usize();
+            let offset = size_table.subtable_array_offset.to_usize().checked_add(offset.to_usize())?;
             return Some(IndexSubtableInfo {
                 start_glyph_id,
                 offset,
@@ -204,3 +216,154 @@ impl FromData for GlyphIdOffsetPair {
         })
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locations() {
+        let data = &[
+            0x00, 0x03, 0x00, 0x00, // version: 3.0
+            0x00, 0x00, 0x00, 0x02, // number of sizes: 2
+            // Bitmap size [0]
+            0x00, 0x00, 0x00, 0x68, // index subtable array offset: 104
+            0x00, 0x00, 0x00, 0x00, // index tables size: 0
+            0x00, 0x00, 0x00, 0x01, // number of index subtables: 1
+            0x00, 0x00, 0x00, 0x00, // color ref: 0
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // horizontal line metrics
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // vertical line metrics
+            0x00, 0x01, // start glyph ID: 1
+            0x00, 0x02, // end glyph ID: 2
+            0x20, // ppem x: 32
+            0x20, // ppem y: 32
+            0x20, // bit depth: 32
+            0x01, // flags: HORIZONTAL_METRICS
+            // Bitmap size [1]
+            0x00, 0x00, 0x00, 0x84, // index subtable array offset: 132
+            0x00, 0x00, 0x00, 0x00, // index tables size: 0
+            0x00, 0x00, 0x00, 0x05, // number of index subtables: 5
+            0x00, 0x00, 0x00, 0x00, // color ref: 0
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // horizontal line metrics
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // vertical line metrics
+            0x00, 0x01, // start glyph ID: 1
+            0x00, 0x0C, // end glyph ID: 12
+            0x40, // ppem x: 64
+            0x40, // ppem y: 64
+            0x20, // bit depth: 32
+            0x01, // flags: HORIZONTAL_METRICS
+
+            // Index subtable array [0]
+            0x00, 0x01, // first glyph ID: 1
+            0x00, 0x02, // last glyph ID: 2
+            0x00, 0x00, 0x00, 0x08, // additional offset: 8
+            // Index subtable (format 1)
+            0x00, 0x01, // index format: 1
+            0x00, 0x11, // image format: 17
+            0x00, 0x00, 0x03, 0xE8, // image data offset: 1000
+            0x00, 0x00, 0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, 0x00, 0x04, // offset [1]: 4
+            0x00, 0x00, 0x00, 0x08, // offset [2]: 8
+
+            // Index subtable array [1]
+            0x00, 0x01, // first glyph ID: 1
+            0x00, 0x02, // last glyph ID: 2
+            0x00, 0x00, 0x00, 0x28, // additional offset: 40
+            0x00, 0x03, // first glyph ID: 3
+            0x00, 0x04, // last glyph ID: 4
+            0x00, 0x00, 0x00, 0x3C, // additional offset: 60
+            0x00, 0x05, // first glyph ID: 5
+            0x00, 0x06, // last glyph ID: 6
+            0x00, 0x00, 0x00, 0x50, // additional offset: 80
+            0x00, 0x07, // first glyph ID: 7
+            0x00, 0x08, // last glyph ID: 8
+            0x00, 0x00, 0x00, 0x5E, // additional offset: 94
+            0x00, 0x09, // first glyph ID: 9
+            0x00, 0x0C, // last glyph ID: 12
+            0x00, 0x00, 0x00, 0x76, // additional offset: 118
+            // Index subtable (format 1)
+            0x00, 0x01, // index format: 1
+            0x00, 0x11, // image format: 17
+            0x00, 0x00, 0x00, 0x64, // image data offset: 100
+            0x00, 0x00, 0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, 0x00, 0x0A, // offset [1]: 10
+            0x00, 0x00, 0x00, 0x14, // offset [2]: 20
+            // Index subtable (format 2)
+            0x00, 0x02, // index format: 2
+            0x00, 0x13, // image format: 19
+            0x00, 0x00, 0x00, 0xC8, // image data offset: 200
+            0x00, 0x00, 0x00, 0x1E, // image size: 30
+            0x14, // height: 20
+            0x10, // width: 16
+            0x01, // horizontal bearing x: 1
+            0x12, // horizontal bearing y: 18
+            0x10, // horizontal advance: 16
+            0x00, // vertical bearing x: 0
+            0x00, // vertical bearing y: 0
+            0x14, // vertical advance: 20
+            // Index subtable (format 3)
+            0x00, 0x03, // index format: 3
+            0x00, 0x11, // image format: 17
+            0x00, 0x00, 0x01, 0x2C, // image data offset: 300
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x05, // offset [1]: 5
+            0x00, 0x0C, // offset [2]: 12
+            // Index subtable (format 4)
+            0x00, 0x04, // index format: 4
+            0x00, 0x12, // image format: 18
+            0x00, 0x00, 0x01, 0x90, // image data offset: 400
+            0x00, 0x00, 0x00, 0x02, // number of glyphs: 2
+            0x00, 0x07, // glyph ID: 7
+            0x00, 0x00, // offset: 0
+            0x00, 0x08, // glyph ID: 8
+            0x00, 0x07, // offset: 7
+            0x00, 0x00, // glyph ID: 0
+            0x00, 0x0F, // offset: 15
+            // Index subtable (format 5)
+            0x00, 0x05, // index format: 5
+            0x00, 0x13, // image format: 19
+            0x00, 0x00, 0x01, 0xF4, // image data offset: 500
+            0x00, 0x00, 0x00, 0x28, // image size: 40
+            0x14, // height: 20
+            0x10, // width: 16
+            0x01, // horizontal bearing x: 1
+            0x12, // horizontal bearing y: 18
+            0x10, // horizontal advance: 16
+            0x00, // vertical bearing x: 0
+            0x00, // vertical bearing y: 0
+            0x14, // vertical advance: 20
+            0x00, 0x00, 0x00, 0x02, // number of glyphs: 2
+            0x00, 0x09, // glyph ID [0]: 9
+            0x00, 0x0B, // glyph ID [1]: 11
+        ];
+
+        let location = |glyph_id, pixels_per_em| find_location(data, GlyphId(glyph_id), pixels_per_em);
+        let metrics = Metrics { x: 1, y: 18, width: 16, height: 20 };
+
+        // The 32px strike is the closest one.
+        let loc = location(2, 20).unwrap();
+        assert_eq!(loc.ppem, 32);
+        assert_eq!(loc.format, BitmapFormat::Format17);
+        assert_eq!(loc.offset, 1004);
+        assert_eq!(location(2, 0).unwrap().ppem, 32);
+
+        // The 64px strike is the closest one.
+        assert_eq!(location(2, 48).unwrap().offset, 110);
+        assert_eq!(location(2, core::u16::MAX).unwrap().ppem, 64);
+
+        // Only the 64px strike contains the rest of the glyphs.
+        assert_eq!(location(4, 20), Some(Location {
+            format: BitmapFormat::Format19, offset: 230, metrics, ppem: 64,
+        }));
+        assert_eq!(location(6, 20).unwrap().offset, 305);
+        let loc = location(8, 20).unwrap();
+        assert_eq!(loc.format, BitmapFormat::Format18);
+        assert_eq!(loc.offset, 407);
+        assert_eq!(location(11, 20), Some(Location {
+            format: BitmapFormat::Format19, offset: 540, metrics, ppem: 64,
+        }));
+        assert_eq!(location(10, 20), None);
+        assert_eq!(location(13, 20), None);
+    }
+}
@@ -51,6 +51,7 @@ mod operator {
     pub const CALL_GLOBAL_SUBROUTINE: u8    = 29;
     pub const VH_CURVE_TO: u8               = 30;
     pub const HV_CURVE_TO: u8               = 31;
+    pub const DOTSECTION: u8                = 0;
     pub const HFLEX: u8                     = 34;
     pub const FLEX: u8                      = 35;
     pub const HFLEX1: u8                    = 36;
@@ -527,6 +528,10 @@ fn _parse_char_string(
                 // flex
                 let op2: u8 = s.read().ok_or(CFFError::ReadOutOfBounds)?;
                 match op2 {
+                    operator::DOTSECTION => {
+                        // A deprecated hint operator, which should be ignored.
+                        p.stack.clear();
+                    }
                     operator::HFLEX => p.parse_hflex()?,
                     operator::FLEX => p.parse_flex()?,
                     operator::HFLEX1 => p.parse_hflex1()?,
@@ -551,15 +556,21 @@ fn _parse_char_string(
 
                     ctx.has_seac = true;
 
+                    // Components are regular glyphs, so they can have their own width and hints.
                     let base_char_string = ctx.metadata.char_strings.get(u32::from(base_char.0))
                         .ok_or(CFFError::InvalidSeacCode)?;
+                    ctx.width_parsed = false;
+                    ctx.stems_len = 0;
                     _parse_char_string(ctx, base_char_string, depth + 1, p)?;
                     p.x = dx;
                     p.y = dy;
 
                     let accent_char_string = ctx.metadata.char_strings.get(u32::from(accent_char.0))
                         .ok_or(CFFError::InvalidSeacCode)?;
+                    ctx.width_parsed = false;
+                    ctx.stems_len = 0;
                     _parse_char_string(ctx, accent_char_string, depth + 1, p)?;
+                    ctx.width_parsed = true;
                 } else if p.stack.len() == 1 && !ctx.width_parsed {
                     p.stack.pop();
                     ctx.width_parsed = true;
@@ -972,6 +983,118 @@ mod tests {
         assert_eq!(rect, Rect { x_min: 10, y_min: 0, x_max: 10, y_max: 0 });
     }
 
+    #[test]
+    fn fd_select_format0() {
+        let data = &[
+            0x00, // format: 0
+            0x00, // font DICT index [0]: 0
+            0x02, // font DICT index [1]: 2
+            0x01, // font DICT index [2]: 1
+        ];
+
+        let fd_select = parse_fd_select(3, &mut Stream::new(data)).unwrap();
+        assert_eq!(fd_select.font_dict_index(GlyphId(0)), Some(0));
+        assert_eq!(fd_select.font_dict_index(GlyphId(1)), Some(2));
+        assert_eq!(fd_select.font_dict_index(GlyphId(2)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(3)), None);
+
+        assert!(parse_fd_select(4, &mut Stream::new(data)).is_none());
+    }
+
+    #[test]
+    fn fd_select_format3() {
+        let data = &[
+            0x03, // format: 3
+            0x00, 0x02, // number of ranges: 2
+            0x00, 0x00, // first glyph [0]: 0
+            0x01, // font DICT index [0]: 1
+            0x00, 0x05, // first glyph [1]: 5
+            0x00, // font DICT index [1]: 0
+            0x00, 0x08, // sentinel: 8
+        ];
+
+        let fd_select = parse_fd_select(8, &mut Stream::new(data)).unwrap();
+        assert_eq!(fd_select.font_dict_index(GlyphId(0)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(4)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(5)), Some(0));
+        assert_eq!(fd_select.font_dict_index(GlyphId(7)), Some(0));
+        assert_eq!(fd_select.font_dict_index(GlyphId(8)), None);
+    }
+
+    #[test]
+    fn seac() {
+        let char_strings = [
+            // A composite glyph.
+            writer::convert(&[
+                CFFInt(100), // width
+                CFFInt(50), CFFInt(60), // accent offset
+                CFFInt(33), CFFInt(34), // base and accent codes: exclam and quotedbl
+                UInt8(operator::ENDCHAR),
+            ]),
+            writer::convert(&[UInt8(operator::ENDCHAR)]),
+            // A base glyph.
+            writer::convert(&[
+                CFFInt(200), CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+                CFFInt(30), UInt8(operator::HORIZONTAL_LINE_TO),
+                UInt8(operator::ENDCHAR),
+            ]),
+            // An accent glyph.
+            writer::convert(&[
+                CFFInt(300), CFFInt(5), UInt8(operator::HORIZONTAL_MOVE_TO),
+                CFFInt(10), UInt8(operator::VERTICAL_LINE_TO),
+                UInt8(operator::ENDCHAR),
+            ]),
+        ];
+
+        let mut data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+
+            // Name INDEX
+            UInt16(0), // count
+
+            // Top DICT
+            // INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(3), // index[1]
+            // Data
+            CFFInt(17),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+
+            // String INDEX
+            UInt16(0), // count
+
+            // Global Subroutines INDEX
+            UInt16(0), // count
+
+            // CharString INDEX
+            UInt16(4), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+        ]);
+
+        let mut offset = 1;
+        for char_string in &char_strings {
+            offset += char_string.len();
+            data.push(offset as u8);
+        }
+
+        for char_string in &char_strings {
+            data.extend_from_slice(char_string);
+        }
+
+        let metadata = parse_metadata(&data).unwrap();
+        let mut builder = Builder(String::new());
+        let rect = outline(&metadata, GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 20 Z M 55 60 L 55 70 Z ");
+        assert_eq!(rect, Rect { x_min: 10, y_min: 20, x_max: 55, y_max: 70 });
+    }
+
     fn rect(x_min: i16, y_min: i16, x_max: i16, y_max: i16) -> Rect {
         Rect { x_min, y_min, x_max, y_max }
     }
@@ -1027,6 +1150,25 @@ mod tests {
         rect(10, 20, 10, 20)
     );
 
+    test_cs!(hint_mask, &[
+        CFFInt(0), CFFInt(10), UInt8(operator::HORIZONTAL_STEM_HINT_MASK),
+        CFFInt(0), CFFInt(10), UInt8(operator::HINT_MASK), UInt8(0xC0),
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        UInt8(operator::ENDCHAR),
+    ], "M 10 20 Z ",
+        rect(10, 20, 10, 20)
+    );
+
+    test_cs!(dotsection, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::DOTSECTION),
+        CFFInt(30), UInt8(operator::HORIZONTAL_LINE_TO),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::DOTSECTION),
+        UInt8(operator::ENDCHAR),
+    ], "M 10 20 L 40 20 Z ",
+        rect(10, 20, 40, 20)
+    );
+
     test_cs!(hmove_to, &[
         CFFInt(10), UInt8(operator::HORIZONTAL_MOVE_TO),
         UInt8(operator::ENDCHAR),
@@ -1592,7 +1734,6 @@ mod tests {
     // TODO: HORIZONTAL_STEM
     // TODO: VERTICAL_STEM
     // TODO: HORIZONTAL_STEM_HINT_MASK
-    // TODO: HINT_MASK
     // TODO: COUNTER_MASK
     // TODO: VERTICAL_STEM_HINT_MASK
     // TODO: CURVE_LINE
@@ -1665,4 +1806,66 @@ mod tests {
         let top_dict = parse_top_dict(&mut Stream::new(data)).unwrap();
         assert!(top_dict.private_dict_range.is_none());
     }
+
+    #[test]
+    fn glyph_names() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT
+            // INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index [0]
+            UInt8(5), // index [1]
+            // Data
+            CFFInt(28),
+            UInt8(top_dict_operator::CHARSET_OFFSET as u8),
+            CFFInt(33),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            // String INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index [0]
+            UInt8(7), // index [1]
+            Raw(b"custom"),
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // Charset
+            UInt8(0), // format
+            UInt16(1), // SID [1]: space
+            UInt16(391), // SID [2]: the first custom string
+            // CharString INDEX
+            UInt16(3), // count
+            UInt8(1), // offset size
+            UInt8(1), // index [0]
+            UInt8(2), // index [1]
+            UInt8(3), // index [2]
+            UInt8(4), // index [3]
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(glyph_name(&metadata, GlyphId(0)), Some(".notdef"));
+        assert_eq!(glyph_name(&metadata, GlyphId(1)), Some("space"));
+        assert_eq!(glyph_name(&metadata, GlyphId(2)), Some("custom"));
+        assert_eq!(glyph_name(&metadata, GlyphId(3)), None);
+    }
+
+    #[test]
+    fn glyph_names_iso_adobe() {
+        // Without a charset offset, the ISOAdobe charset is used.
+        let data = gen_cff(&[], &[], &[UInt8(operator::ENDCHAR)]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(glyph_name(&metadata, GlyphId(0)), Some(".notdef"));
+        assert_eq!(glyph_name(&metadata, GlyphId(34)), Some("A"));
+        assert_eq!(glyph_name(&metadata, GlyphId(229)), None);
+    }
 }
@@ -82,6 +82,13 @@ pub struct Metadata<'a> {
     item_variation_store: ItemVariationStore<'a>,
 }
 
+impl<'a> Metadata<'a> {
+    #[inline]
+    pub fn variation_store(&self) -> ItemVariationStore<'a> {
+        self.item_variation_store
+    }
+}
+
 pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
     let mut s = Stream::new(data);
 
@@ -317,7 +324,10 @@ fn parse_char_string(
     };
 
     // Load scalars at default index.
-    ctx.update_scalars(0)?;
+    // The VariationStore is optional and a non-variable CFF2 font doesn't have one.
+    if ctx.metadata.item_variation_store.variation_data_count() != 0 {
+        ctx.update_scalars(0)?;
+    }
 
     let mut inner_builder = Builder {
         builder,
@@ -340,6 +350,12 @@ fn parse_char_string(
     _parse_char_string(&mut ctx, data, 0, &mut parser)?;
     // let _ = _parse_char_string(&mut ctx, data, 0.0, 0.0, &mut stack, 0, &mut inner_builder)?;
 
+    // CFF2 charstrings don't have an `endchar` operator,
+    // so the last contour has to be closed manually.
+    if parser.has_move_to {
+        parser.builder.close();
+    }
+
     let bbox = parser.builder.bbox;
 
     // Check that bbox was changed.
@@ -531,3 +547,168 @@ fn _parse_char_string(
 
     Ok(())
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+    use std::string::String;
+    use std::fmt::Write;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    struct Builder(String);
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    // A CFF2 table with a single glyph and an optional VariationStore
+    // with a single region, that peaks at the end of the first axis.
+    fn gen_cff2(chars: &[writer::TtfType], variable: bool) -> Vec<u8> {
+        const HEADER_SIZE: usize = 5;
+        const GLOBAL_SUBRS_SIZE: usize = 4;
+        const VARIATION_STORE_SIZE: usize = 32;
+
+        let chars_data = writer::convert(chars);
+        assert!(chars_data.len() < 255);
+
+        let top_dict_len = if variable { 4 } else { 2 };
+        let mut char_strings_offset = HEADER_SIZE + top_dict_len + GLOBAL_SUBRS_SIZE;
+        let variation_store_offset = char_strings_offset;
+        if variable {
+            char_strings_offset += VARIATION_STORE_SIZE;
+        }
+
+        let mut w = writer::Writer::new();
+        // Header
+        w.write(UInt8(2)); // major version
+        w.write(UInt8(0)); // minor version
+        w.write(UInt8(HEADER_SIZE as u8)); // header size
+        w.write(UInt16(top_dict_len as u16)); // top dict length
+
+        // Top DICT
+        w.write(CFFInt(char_strings_offset as i32));
+        w.write(UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8));
+        if variable {
+            w.write(CFFInt(variation_store_offset as i32));
+            w.write(UInt8(top_dict_operator::VARIATION_STORE_OFFSET as u8));
+        }
+
+        // Global Subroutines INDEX
+        w.write(UInt32(0)); // count
+
+        if variable {
+            // VariationStore
+            w.write(UInt16(30)); // length
+            w.write(UInt16(1)); // format
+            w.write(UInt32(12)); // region list offset
+            w.write(UInt16(1)); // item variation data count
+            w.write(UInt32(22)); // item variation data offset [0]
+
+            // VariationRegionList
+            w.write(UInt16(1)); // axis count
+            w.write(UInt16(1)); // region count
+            w.write(Int16(0)); // start coord: 0
+            w.write(Int16(0x4000)); // peak coord: 1
+            w.write(Int16(0x4000)); // end coord: 1
+
+            // ItemVariationData
+            w.write(UInt16(0)); // item count
+            w.write(UInt16(0)); // word delta count
+            w.write(UInt16(1)); // region index count
+            w.write(UInt16(0)); // region index [0]
+        }
+
+        // CharString INDEX
+        w.write(UInt32(1)); // count
+        w.write(UInt8(1)); // offset size
+        w.write(UInt8(1)); // index[0]
+        w.write(UInt8(chars_data.len() as u8 + 1)); // index[1]
+        w.data.extend_from_slice(&chars_data);
+
+        w.data
+    }
+
+    fn outline_at(data: &[u8], coordinates: &[NormalizedCoordinate]) -> (String, Option<Rect>) {
+        let metadata = parse_metadata(data).unwrap();
+        let mut builder = Builder(String::new());
+        let rect = outline(&metadata, coordinates, GlyphId(0), &mut builder);
+        (builder.0, rect)
+    }
+
+    #[test]
+    fn closes_last_contour() {
+        let data = gen_cff2(&[
+            CFFInt(10), CFFInt(10), UInt8(operator::MOVE_TO),
+            CFFInt(20), CFFInt(0), UInt8(operator::LINE_TO),
+            CFFInt(0), CFFInt(20), UInt8(operator::LINE_TO),
+        ], false);
+
+        let (path, rect) = outline_at(&data, &[]);
+        assert_eq!(path, "M 10 10 L 30 10 L 30 30 Z ");
+        assert_eq!(rect, Some(Rect { x_min: 10, y_min: 10, x_max: 30, y_max: 30 }));
+    }
+
+    #[test]
+    fn blend() {
+        let data = gen_cff2(&[
+            CFFInt(10), CFFInt(10), UInt8(operator::MOVE_TO),
+            CFFInt(20), CFFInt(40), CFFInt(1), UInt8(operator::BLEND),
+            CFFInt(0), UInt8(operator::LINE_TO),
+            CFFInt(0), CFFInt(20), UInt8(operator::LINE_TO),
+        ], true);
+
+        let (path, _) = outline_at(&data, &[NormalizedCoordinate::from(0)]);
+        assert_eq!(path, "M 10 10 L 30 10 L 30 30 Z ");
+
+        let (path, _) = outline_at(&data, &[NormalizedCoordinate::from(0.5)]);
+        assert_eq!(path, "M 10 10 L 50 10 L 50 30 Z ");
+
+        let (path, _) = outline_at(&data, &[NormalizedCoordinate::from(1.0)]);
+        assert_eq!(path, "M 10 10 L 70 10 L 70 30 Z ");
+    }
+
+    #[test]
+    fn blend_without_variation_store() {
+        // Without regions, `blend` leaves the default values as is.
+        let data = gen_cff2(&[
+            CFFInt(10), CFFInt(10), UInt8(operator::MOVE_TO),
+            CFFInt(20), CFFInt(1), UInt8(operator::BLEND),
+            CFFInt(0), UInt8(operator::LINE_TO),
+            CFFInt(0), CFFInt(20), UInt8(operator::LINE_TO),
+        ], false);
+
+        let (path, _) = outline_at(&data, &[]);
+        assert_eq!(path, "M 10 10 L 30 10 L 30 30 Z ");
+    }
+
+    #[test]
+    fn vsindex_after_blend() {
+        let data = gen_cff2(&[
+            CFFInt(10), CFFInt(10), UInt8(operator::MOVE_TO),
+            CFFInt(20), CFFInt(40), CFFInt(1), UInt8(operator::BLEND),
+            CFFInt(0), UInt8(operator::VS_INDEX),
+        ], true);
+
+        assert_eq!(outline_at(&data, &[NormalizedCoordinate::from(0)]), (String::from("M 10 10 "), None));
+    }
+}
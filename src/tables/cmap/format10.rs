@@ -24,6 +24,8 @@ pub fn codepoints(data: &[u8], mut f: impl FnMut(u32)) -> Option<()> {
     s.skip::<u32>(); // language
     let first_code_point: u32 = s.read()?;
     let count: u32 = s.read()?;
+    // Make sure that the count is backed by the actual data.
+    let count = s.read_array32::<u16>(count)?.len();
 
     for i in 0..count {
         let code_point = first_code_point.checked_add(i)?;
@@ -32,3 +34,29 @@ pub fn codepoints(data: &[u8], mut f: impl FnMut(u32)) -> Option<()> {
 
     Some(())
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, codepoints};
+
+    #[test]
+    fn count_overflow() {
+        let data = &[
+            0x00, 0x0A, // format: 10
+            0x00, 0x00, // reserved
+            0x00, 0x00, 0x00, 0x18, // length: 24
+            0x00, 0x00, 0x00, 0x00, // language: 0
+            0x00, 0x00, 0x00, 0x10, // first code point: 16
+            0xFF, 0xFF, 0xFF, 0xFF, // count: u32::MAX
+            0x00, 0x01, // glyph [0]: 1
+            0x00, 0x02, // glyph [1]: 2
+        ];
+
+        assert_eq!(parse(data, 16), None);
+
+        let mut vec = vec![];
+        codepoints(data, |c| vec.push(c));
+        assert!(vec.is_empty());
+    }
+}
@@ -4,6 +4,8 @@ use core::convert::TryFrom;
 
 use crate::parser::{Stream, FromData};
 
+const MAX_CODE_POINT: u32 = 0x10FFFF;
+
 #[derive(Clone, Copy)]
 pub struct SequentialMapGroup {
     pub start_char_code: u32,
@@ -52,11 +54,60 @@ pub fn codepoints(data: &[u8], mut f: impl FnMut(u32)) -> Option<()> {
     s.skip::<u32>(); // language
     let count: u32 = s.read()?;
     let groups = s.read_array32::<SequentialMapGroup>(count)?;
+    // Groups must be sorted and must not overlap, but we cannot rely on that.
+    // Otherwise, a malformed font can make us call `f` billions of times.
+    // So code points outside the Unicode range and code points that are less than
+    // or equal to the end of the previous group are ignored.
+    let mut next_code_point = 0;
     for group in groups {
-        for code_point in group.start_char_code..=group.end_char_code {
+        let start = group.start_char_code.max(next_code_point);
+        let end = group.end_char_code.min(MAX_CODE_POINT);
+        if start > end {
+            continue;
+        }
+
+        for code_point in start..=end {
             f(code_point);
         }
+
+        next_code_point = end + 1;
     }
 
     Some(())
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, codepoints};
+
+    #[test]
+    fn overlapping_groups() {
+        let data = &[
+            0x00, 0x0C, // format: 12
+            0x00, 0x00, // reserved
+            0x00, 0x00, 0x00, 0x34, // length: 52
+            0x00, 0x00, 0x00, 0x00, // language: 0
+            0x00, 0x00, 0x00, 0x03, // number of groups: 3
+            // Group [0]
+            0x00, 0x00, 0x00, 0x10, // start char code: 16
+            0x00, 0x00, 0x00, 0x12, // end char code: 18
+            0x00, 0x00, 0x00, 0x01, // start glyph ID: 1
+            // Group [1]
+            0x00, 0x00, 0x00, 0x11, // start char code: 17
+            0x00, 0x00, 0x00, 0x13, // end char code: 19
+            0x00, 0x00, 0x00, 0x05, // start glyph ID: 5
+            // Group [2]
+            0x00, 0x10, 0xFF, 0xFF, // start char code: 0x10FFFF
+            0xFF, 0xFF, 0xFF, 0xFF, // end char code: u32::MAX
+            0x00, 0x00, 0x00, 0x07, // start glyph ID: 7
+        ];
+
+        assert_eq!(parse(data, 17), Some(2));
+        assert_eq!(parse(data, 19), Some(7));
+
+        let mut vec = vec![];
+        codepoints(data, |c| vec.push(c));
+        assert_eq!(vec, [16, 17, 18, 19, 0x10FFFF]);
+    }
+}
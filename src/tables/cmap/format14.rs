@@ -118,3 +118,41 @@ pub fn parse(data: &[u8], c: u32, variation: u32) -> Option<GlyphVariationResult
 
     None
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_and_non_default_uvs() {
+        let data = &[
+            0x00, 0x0E, // format: 14
+            0x00, 0x00, 0x00, 0x31, // length: 49
+            0x00, 0x00, 0x00, 0x02, // number of records: 2
+            // Variation selector record [0]
+            0x00, 0xFE, 0x00, // variation selector: VS1
+            0x00, 0x00, 0x00, 0x20, // default UVS offset: 32
+            0x00, 0x00, 0x00, 0x00, // non-default UVS offset: 0
+            // Variation selector record [1]
+            0x00, 0xFE, 0x01, // variation selector: VS2
+            0x00, 0x00, 0x00, 0x00, // default UVS offset: 0
+            0x00, 0x00, 0x00, 0x28, // non-default UVS offset: 40
+            // Default UVS table
+            0x00, 0x00, 0x00, 0x01, // number of ranges: 1
+            0x00, 0x00, 0x41, // start unicode value: 65
+            0x02, // additional count: 2
+            // Non-default UVS table
+            0x00, 0x00, 0x00, 0x01, // number of mappings: 1
+            0x00, 0x00, 0x42, // unicode value: 66
+            0x00, 0x05, // glyph ID: 5
+        ];
+
+        assert_eq!(parse(data, 0x41, 0xFE00), Some(GlyphVariationResult::UseDefault));
+        assert_eq!(parse(data, 0x43, 0xFE00), Some(GlyphVariationResult::UseDefault));
+        assert_eq!(parse(data, 0x44, 0xFE00), None);
+        assert_eq!(parse(data, 0x42, 0xFE01), Some(GlyphVariationResult::Found(GlyphId(5))));
+        assert_eq!(parse(data, 0x41, 0xFE01), None);
+        assert_eq!(parse(data, 0x42, 0xFE02), None);
+    }
+}
@@ -35,7 +35,6 @@ pub fn parse(data: &[u8], code_point: u32) -> Option<u16> {
     // This subtable supports code points only in a u16 range.
     let code_point = u16::try_from(code_point).ok()?;
 
-    let code_point = code_point;
     let high_byte = code_point >> 8;
     let low_byte = code_point & 0x00FF;
 
@@ -51,12 +50,23 @@ pub fn parse(data: &[u8], code_point: u32) -> Option<u16> {
     let sub_headers_offset = s.offset();
     let sub_headers = s.read_array16::<SubHeaderRecord>(sub_headers_count)?;
 
-    let i = if code_point < 0xff {
+    let i = if code_point <= 0xFF {
         // 'SubHeader 0 is special: it is used for single-byte character codes.'
+        // But only bytes that are not the first byte of a two-byte code.
+        if sub_header_keys.get(low_byte)? != 0 {
+            return None;
+        }
+
         0
     } else {
         // 'Array that maps high bytes to subHeaders: value is subHeader index × 8.'
-        sub_header_keys.get(high_byte)? / 8
+        let i = sub_header_keys.get(high_byte)? / 8;
+        // A single-byte high byte cannot start a two-byte code.
+        if i == 0 {
+            return None;
+        }
+
+        i
     };
 
     let sub_header = sub_headers.get(i)?;
@@ -89,7 +99,8 @@ pub fn parse(data: &[u8], code_point: u32) -> Option<u16> {
         return None;
     }
 
-    u16::try_from((i32::from(glyph) + i32::from(sub_header.id_delta)) % 65536).ok()
+    // 'idDelta arithmetic is modulo 65536.'
+    Some(glyph.wrapping_add(sub_header.id_delta as u16))
 }
 
 pub fn codepoints(data: &[u8], mut f: impl FnMut(u32)) -> Option<()> {
@@ -191,4 +202,44 @@ mod tests {
         assert_eq!(parse(&data, 41), Some(1000));
         assert_eq!(parse(&data, 42), None);
     }
+
+    #[test]
+    fn two_byte_codes() {
+        let mut data = vec![
+            0x00, 0x02, // format: 2
+            0x02, 0x1E, // subtable size: 542
+            0x00, 0x00, // language ID: 0
+        ];
+
+        // Make only high byte 0x81 multi-byte.
+        data.extend(std::iter::repeat(0x00).take(256 * u16::SIZE));
+        data[6 + 0x81 * u16::SIZE + 1] = 0x08;
+
+        data.extend(&[
+            // First sub header (for single byte mapping)
+            0x00, 0x80, // first code: 128
+            0x00, 0x02, // entry count: 2
+            0x00, 0x00, // id delta: 0
+            0x00, 0x0A, // id range offset: 10
+            // Second sub header (for high byte 0x81)
+            0x00, 0x40, // first code: 64
+            0x00, 0x02, // entry count: 2
+            0xFF, 0xF6, // id delta: -10
+            0x00, 0x06, // id range offset: 6
+            // Glyph index
+            0x00, 0x0A, // glyph ID [0]: 10
+            0x00, 0x0B, // glyph ID [1]: 11
+            0x00, 0x05, // glyph ID [2]: 5
+            0x00, 0x15, // glyph ID [3]: 21
+        ]);
+
+        assert_eq!(parse(&data, 0x80), Some(10));
+        // 0x81 is the first byte of a two-byte code.
+        assert_eq!(parse(&data, 0x81), None);
+        assert_eq!(parse(&data, 0x8140), Some(65531));
+        assert_eq!(parse(&data, 0x8141), Some(11));
+        assert_eq!(parse(&data, 0x8142), None);
+        // 0x02 is a single-byte code.
+        assert_eq!(parse(&data, 0x0280), None);
+    }
 }
@@ -1,5 +1,6 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-4-segment-mapping-to-delta-values
 
+use core::cmp::Ordering;
 use core::convert::TryFrom;
 
 use crate::parser::Stream;
@@ -25,45 +26,39 @@ pub fn parse(data: &[u8], code_point: u32) -> Option<u16> {
     let id_range_offset_pos = s.offset();
     let id_range_offsets = s.read_array16::<u16>(seg_count)?;
 
-    // A custom binary search.
-    let mut start = 0;
-    let mut end = seg_count;
-    while end > start {
-        let index = (start + end) / 2;
-        let end_value = end_codes.get(index)?;
-        if end_value >= code_point {
-            let start_value = start_codes.get(index)?;
-            if start_value > code_point {
-                end = index;
-            } else {
-                let id_range_offset = id_range_offsets.get(index)?;
-                let id_delta = id_deltas.get(index)?;
-                if id_range_offset == 0 {
-                    return Some(code_point.wrapping_add(id_delta as u16));
-                }
-
-                let delta = (u32::from(code_point) - u32::from(start_value)) * 2;
-                let delta = u16::try_from(delta).ok()?;
-
-                let id_range_offset_pos = (id_range_offset_pos + usize::from(index) * 2) as u16;
-                let pos = id_range_offset_pos.wrapping_add(delta);
-                let pos = pos.wrapping_add(id_range_offset);
-                let glyph_array_value: u16 = Stream::read_at(data, usize::from(pos))?;
-
-                // 0 indicates missing glyph.
-                if glyph_array_value == 0 {
-                    return None;
-                }
-
-                let glyph_id = (glyph_array_value as i16).wrapping_add(id_delta);
-                return u16::try_from(glyph_id).ok();
-            }
-        } else {
-            start = index + 1;
-        }
+    // Segments are sorted and don't overlap, so we are looking for
+    // the last segment that starts before the code point.
+    let (index, start_value) = start_codes.binary_search_by(|start| {
+        if *start > code_point { Ordering::Greater } else { Ordering::Equal }
+    })?;
+
+    if end_codes.get(index)? < code_point {
+        return None;
+    }
+
+    let id_range_offset = id_range_offsets.get(index)?;
+    let id_delta = id_deltas.get(index)?;
+    if id_range_offset == 0 {
+        let glyph_id = code_point.wrapping_add(id_delta as u16);
+        // 0 indicates missing glyph.
+        return if glyph_id != 0 { Some(glyph_id) } else { None };
+    }
+
+    let delta = (u32::from(code_point) - u32::from(start_value)) * 2;
+    let delta = u16::try_from(delta).ok()?;
+
+    let id_range_offset_pos = (id_range_offset_pos + usize::from(index) * 2) as u16;
+    let pos = id_range_offset_pos.wrapping_add(delta);
+    let pos = pos.wrapping_add(id_range_offset);
+    let glyph_array_value: u16 = Stream::read_at(data, usize::from(pos))?;
+
+    // 0 indicates missing glyph.
+    if glyph_array_value == 0 {
+        return None;
     }
 
-    None
+    let glyph_id = (glyph_array_value as i16).wrapping_add(id_delta);
+    u16::try_from(glyph_id).ok()
 }
 
 pub fn codepoints(data: &[u8], mut f: impl FnMut(u32)) -> Option<()> {
@@ -482,6 +477,38 @@ mod tests {
         assert_eq!(parse(data, 0x41), None);
     }
 
+    #[test]
+    fn zero_glyph_id_via_delta() {
+        let data = &[
+            0x00, 0x04, // format: 4
+            0x00, 0x20, // subtable size: 32
+            0x00, 0x00, // language ID: 0
+            0x00, 0x04, // 2 x segCount: 4
+            0x00, 0x02, // search range: 2
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x02, // range shift: 2
+            // End character codes
+            0x00, 0x42, // char code [0]: 66
+            0xFF, 0xFF, // char code [1]: 65535
+            0x00, 0x00, // reserved: 0
+            // Start character codes
+            0x00, 0x41, // char code [0]: 65
+            0xFF, 0xFF, // char code [1]: 65535
+            // Deltas
+            0xFF, 0xBF, // delta [0]: -65
+            0x00, 0x01, // delta [1]: 1
+            // Offsets into Glyph index array
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, // offset [1]: 0
+        ];
+
+        // 65 - 65 = 0, which indicates a missing glyph.
+        assert_eq!(parse(data, 0x41), None);
+        assert_eq!(parse(data, 0x42), Some(1));
+        // The required last segment maps 0xFFFF to 0 too.
+        assert_eq!(parse(data, 0xFFFF), None);
+    }
+
     #[test]
     fn collect_codepoints() {
         let data = &[
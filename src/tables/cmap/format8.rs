@@ -0,0 +1,203 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-8-mixed-16-bit-and-32-bit-coverage
+//
+// 32-bit character codes are a concatenation of a high and a low UTF-16 surrogates,
+// so code points outside the BMP are converted to such pairs and back.
+
+use core::convert::TryFrom;
+
+use crate::parser::Stream;
+use super::format12::SequentialMapGroup;
+
+// The `is32` bit array size.
+const IS32_SIZE: usize = 8192;
+
+pub fn parse(data: &[u8], code_point: u32) -> Option<u16> {
+    let mut s = Stream::new(data);
+    s.skip::<u16>(); // format
+    s.skip::<u16>(); // reserved
+    s.skip::<u32>(); // length
+    s.skip::<u32>(); // language
+    let is32 = s.read_bytes(IS32_SIZE)?;
+    let count: u32 = s.read()?;
+    let groups = s.read_array32::<SequentialMapGroup>(count)?;
+
+    let code = if code_point <= 0xFFFF {
+        // A high surrogate of a 32-bit code cannot be used as a 16-bit one.
+        if is_32(is32, code_point) {
+            return None;
+        }
+
+        code_point
+    } else {
+        let code = encode_surrogates(code_point)?;
+        if !is_32(is32, code >> 16) {
+            return None;
+        }
+
+        code
+    };
+
+    for group in groups {
+        let start_char_code = group.start_char_code;
+        if code >= start_char_code && code <= group.end_char_code {
+            let id = group.start_glyph_id.checked_add(code)?.checked_sub(start_char_code)?;
+            return u16::try_from(id).ok();
+        }
+    }
+
+    None
+}
+
+pub fn codepoints(data: &[u8], mut f: impl FnMut(u32)) -> Option<()> {
+    let mut s = Stream::new(data);
+    s.skip::<u16>(); // format
+    s.skip::<u16>(); // reserved
+    s.skip::<u32>(); // length
+    s.skip::<u32>(); // language
+    let is32 = s.read_bytes(IS32_SIZE)?;
+    let count: u32 = s.read()?;
+    let groups = s.read_array32::<SequentialMapGroup>(count)?;
+    // Just like in format 12, we cannot rely on groups being sorted and not overlapping.
+    // So code points that are less than or equal to the end of the previous group
+    // and 32-bit codes that are not surrogate pairs are ignored.
+    let mut next_code_point = 0;
+    for group in groups {
+        let start = group.start_char_code.max(next_code_point);
+        let end = group.end_char_code;
+        if start > end {
+            continue;
+        }
+
+        for code_point in start..=end.min(0xFFFF) {
+            if !is_32(is32, code_point) {
+                f(code_point);
+            }
+        }
+
+        for high in (start >> 16).max(0xD800)..=(end >> 16).min(0xDBFF) {
+            if !is_32(is32, high) {
+                continue;
+            }
+
+            let low_start = if high == start >> 16 { start & 0xFFFF } else { 0 };
+            let low_end = if high == end >> 16 { end & 0xFFFF } else { 0xFFFF };
+            for low in low_start.max(0xDC00)..=low_end.min(0xDFFF) {
+                f(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00));
+            }
+        }
+
+        next_code_point = match end.checked_add(1) {
+            Some(n) => n,
+            None => break,
+        };
+    }
+
+    Some(())
+}
+
+// Checks that a 16-bit value is the first half of a 32-bit code.
+fn is_32(is32: &[u8], code: u32) -> bool {
+    match is32.get(code as usize / 8) {
+        Some(byte) => byte & (0x80 >> (code % 8)) != 0,
+        None => false,
+    }
+}
+
+fn encode_surrogates(code_point: u32) -> Option<u32> {
+    let n = code_point.checked_sub(0x10000)?;
+    if n > 0xFFFFF {
+        return None;
+    }
+
+    let high = 0xD800 + (n >> 10);
+    let low = 0xDC00 + (n & 0x3FF);
+    Some(high << 16 | low)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Makes only 0xD800 the first half of a 32-bit code.
+    fn subtable(groups: &[u8]) -> std::vec::Vec<u8> {
+        let mut data = vec![
+            0x00, 0x08, // format: 8
+            0x00, 0x00, // reserved
+            0x00, 0x00, 0x00, 0x00, // length: ignored
+            0x00, 0x00, 0x00, 0x00, // language: 0
+        ];
+
+        data.extend(std::iter::repeat(0x00).take(IS32_SIZE));
+        data[12 + 0xD800 / 8] = 0x80;
+        data.extend(groups);
+        data
+    }
+
+    #[test]
+    fn mixed_coverage() {
+        let data = subtable(&[
+            0x00, 0x00, 0x00, 0x02, // number of groups: 2
+            // Group [0]
+            0x00, 0x00, 0x00, 0x41, // start char code: 65
+            0x00, 0x00, 0x00, 0x42, // end char code: 66
+            0x00, 0x00, 0x00, 0x01, // start glyph ID: 1
+            // Group [1]
+            0xD8, 0x00, 0xDF, 0xFE, // start char code: 0xD800DFFE
+            0xD8, 0x01, 0xDC, 0x00, // end char code: 0xD801DC00
+            0x00, 0x00, 0x00, 0x0A, // start glyph ID: 10
+        ]);
+
+        assert_eq!(parse(&data, 0x40), None);
+        assert_eq!(parse(&data, 0x41), Some(1));
+        assert_eq!(parse(&data, 0x42), Some(2));
+        // 0xD800DFFE
+        assert_eq!(parse(&data, 0x103FE), Some(10));
+        // 0xD800DFFF
+        assert_eq!(parse(&data, 0x103FF), Some(11));
+        // Surrogate pairs are not accepted directly.
+        assert_eq!(parse(&data, 0xD800DFFE), None);
+
+        // 0xD801 is not marked as a 32-bit code.
+        let mut vec = vec![];
+        codepoints(&data, |c| vec.push(c));
+        assert_eq!(vec, [0x41, 0x42, 0x103FE, 0x103FF]);
+    }
+
+    #[test]
+    fn high_surrogate_as_16_bit_code() {
+        let data = subtable(&[
+            0x00, 0x00, 0x00, 0x01, // number of groups: 1
+            // Group [0]
+            0x00, 0x00, 0xD7, 0xFF, // start char code: 0xD7FF
+            0x00, 0x00, 0xD8, 0x01, // end char code: 0xD801
+            0x00, 0x00, 0x00, 0x01, // start glyph ID: 1
+        ]);
+
+        assert_eq!(parse(&data, 0xD7FF), Some(1));
+        // The first half of a 32-bit code.
+        assert_eq!(parse(&data, 0xD800), None);
+        assert_eq!(parse(&data, 0xD801), Some(3));
+
+        let mut vec = vec![];
+        codepoints(&data, |c| vec.push(c));
+        assert_eq!(vec, [0xD7FF, 0xD801]);
+    }
+
+    #[test]
+    fn out_of_range_code_point() {
+        let mut data = subtable(&[
+            0x00, 0x00, 0x00, 0x01, // number of groups: 1
+            // Group [0]
+            0xDB, 0xFF, 0xDF, 0xFF, // start char code: 0xDBFFDFFF
+            0xFF, 0xFF, 0xFF, 0xFF, // end char code: 0xFFFFFFFF
+            0x00, 0x00, 0x00, 0x05, // start glyph ID: 5
+        ]);
+
+        // 0xDBFFDFFF
+        assert_eq!(parse(&data, 0x10FFFF), None);
+        data[12 + 0xDBFF / 8] = 0x01;
+        assert_eq!(parse(&data, 0x10FFFF), Some(5));
+        assert_eq!(parse(&data, 0x110000), None);
+    }
+}
@@ -19,12 +19,15 @@ mod format0;
 mod format2;
 mod format4;
 mod format6;
+mod format8;
 mod format10;
 mod format12;
 mod format13;
 mod format14;
+mod mac_roman;
 
 pub use format14::GlyphVariationResult;
+pub(crate) use mac_roman::{decode as decode_mac_roman, encode as encode_mac_roman};
 
 
 /// An iterator over
@@ -69,6 +72,7 @@ impl<'a> Iterator for Subtables<'a> {
 
 
 /// A character encoding subtable.
+#[derive(Clone, Copy)]
 pub struct Subtable<'a> {
     platform_id: PlatformId,
     encoding_id: u16,
@@ -95,6 +99,31 @@ impl<'a> Subtable<'a> {
         self.format
     }
 
+    /// Returns subtable's language.
+    ///
+    /// Used only by subtables with the Macintosh platform, where it stores
+    /// a Macintosh language ID plus one. `0` indicates a language-independent subtable.
+    ///
+    /// Returns `None` for `UnicodeVariationSequences`, which doesn't have a language field.
+    #[inline]
+    pub fn language(&self) -> Option<u32> {
+        match self.format {
+            Format::ByteEncodingTable |
+            Format::HighByteMappingThroughTable |
+            Format::SegmentMappingToDeltaValues |
+            Format::TrimmedTableMapping => {
+                Stream::read_at::<u16>(self.subtable_data, 4).map(u32::from)
+            }
+            Format::MixedCoverage |
+            Format::TrimmedArray |
+            Format::SegmentedCoverage |
+            Format::ManyToOneRangeMappings => {
+                Stream::read_at::<u32>(self.subtable_data, 8)
+            }
+            Format::UnicodeVariationSequences => None,
+        }
+    }
+
     /// Checks that the current encoding is Unicode compatible.
     #[inline]
     pub fn is_unicode(&self) -> bool {
@@ -121,6 +150,29 @@ impl<'a> Subtable<'a> {
         }
     }
 
+    /// Checks that the current encoding is Windows Symbol.
+    ///
+    /// Symbol subtables map the `0xF020..=0xF0FF` range.
+    /// Since they are not Unicode ones, `Face::glyph_index` ignores them.
+    #[inline]
+    pub fn is_symbol(&self) -> bool {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
+        const WINDOWS_SYMBOL_ENCODING_ID: u16 = 0;
+
+        self.platform_id == PlatformId::Windows
+        && self.encoding_id == WINDOWS_SYMBOL_ENCODING_ID
+    }
+
+    /// Checks that the current encoding is Macintosh Roman.
+    #[inline]
+    pub(crate) fn is_mac_roman(&self) -> bool {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-encoding-ids-script-manager-codes
+        const MACINTOSH_ROMAN_ENCODING_ID: u16 = 0;
+
+        self.platform_id == PlatformId::Macintosh
+        && self.encoding_id == MACINTOSH_ROMAN_ENCODING_ID
+    }
+
     /// Maps a character to a glyph ID.
     ///
     /// This is a low-level method and unlike `Face::glyph_index` it doesn't
@@ -129,7 +181,6 @@ impl<'a> Subtable<'a> {
     ///
     /// Returns `None`:
     /// - when glyph ID is `0`.
-    /// - when format is `MixedCoverage`, since it's not supported.
     /// - when format is `UnicodeVariationSequences`. Use `glyph_variation_index` instead.
     #[inline]
     pub fn glyph_index(&self, c: u32) -> Option<GlyphId> {
@@ -147,8 +198,7 @@ impl<'a> Subtable<'a> {
                 format6::parse(self.subtable_data, c)
             }
             Format::MixedCoverage => {
-                // Unsupported.
-                None
+                format8::parse(self.subtable_data, c)
             }
             Format::TrimmedArray => {
                 format10::parse(self.subtable_data, c)
@@ -192,9 +242,8 @@ impl<'a> Subtable<'a> {
     /// `None` because this method finds all codepoints which were _defined_ in
     /// this subtable. The subtable may still map them to glyph ID `0`.
     ///
-    /// Returns without doing anything:
-    /// - when format is `MixedCoverage`, since it's not supported.
-    /// - when format is `UnicodeVariationSequences`, since it's not supported.
+    /// Returns without doing anything when format is `UnicodeVariationSequences`,
+    /// since it's not supported.
     pub fn codepoints<F: FnMut(u32)>(&self, f: F) {
         let _ = match self.format {
             Format::ByteEncodingTable => {
@@ -210,8 +259,7 @@ impl<'a> Subtable<'a> {
                 format6::codepoints(self.subtable_data, f)
             },
             Format::MixedCoverage => {
-                // Unsupported
-                None
+                format8::codepoints(self.subtable_data, f)
             },
             Format::TrimmedArray => {
                 format10::codepoints(self.subtable_data, f)
@@ -232,7 +280,7 @@ impl<'a> Subtable<'a> {
 
 impl<'a> core::fmt::Debug for Subtable<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        f.debug_struct("Encoding")
+        f.debug_struct("Subtable")
             .field("platform_id", &self.platform_id)
             .field("encoding_id", &self.encoding_id)
             .field("format", &self.format)
@@ -310,3 +358,73 @@ pub(crate) fn parse(data: &[u8]) -> Option<Subtables> {
         index: 0,
     })
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x03, // number of tables: 3
+            0x00, 0x01, // platform ID: Macintosh
+            0x00, 0x01, // encoding ID: Japanese
+            0x00, 0x00, 0x00, 0x1C, // offset: 28
+            0x00, 0x01, // platform ID: Macintosh
+            0x00, 0x01, // encoding ID: Japanese
+            0x00, 0x00, 0x00, 0x22, // offset: 34
+            0x00, 0x00, // platform ID: Unicode
+            0x00, 0x05, // encoding ID: Variation Sequences
+            0x00, 0x00, 0x00, 0x32, // offset: 50
+            // Format 6 subtable
+            0x00, 0x06, // format: 6
+            0x00, 0x0A, // length: 10
+            0x00, 0x0C, // language: Japanese + 1
+            // Format 12 subtable
+            0x00, 0x0C, // format: 12
+            0x00, 0x00, // reserved
+            0x00, 0x00, 0x00, 0x10, // length: 16
+            0x00, 0x00, 0x00, 0x00, // language: independent
+            0x00, 0x00, 0x00, 0x00, // number of groups: 0
+            // Format 14 subtable
+            0x00, 0x0E, // format: 14
+            0x00, 0x00, 0x00, 0x0A, // length: 10
+            0x00, 0x00, 0x00, 0x00, // number of records: 0
+        ];
+
+        let mut subtables = parse(data).unwrap();
+        assert_eq!(subtables.next().unwrap().language(), Some(12));
+        assert_eq!(subtables.next().unwrap().language(), Some(0));
+        assert_eq!(subtables.next().unwrap().language(), None);
+    }
+
+    #[test]
+    fn symbol() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x02, // number of tables: 2
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x00, // encoding ID: Symbol
+            0x00, 0x00, 0x00, 0x14, // offset: 20
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x00, 0x00, 0x00, 0x14, // offset: 20
+            // Format 6 subtable
+            0x00, 0x06, // format: 6
+            0x00, 0x0C, // length: 12
+            0x00, 0x00, // language: 0
+            0xF0, 0x41, // first code: 0xF041
+            0x00, 0x01, // count: 1
+            0x00, 0x07, // glyph ID [0]: 7
+        ];
+
+        let mut subtables = parse(data).unwrap();
+        let subtable = subtables.next().unwrap();
+        assert!(subtable.is_symbol());
+        assert!(!subtable.is_unicode());
+        assert_eq!(subtable.glyph_index(0xF041), Some(GlyphId(7)));
+        assert!(!subtables.next().unwrap().is_symbol());
+    }
+}
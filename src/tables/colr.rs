@@ -0,0 +1,1243 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/colr
+
+use crate::{GlyphId, Rect};
+use crate::cpal::{self, RgbaColor};
+use crate::parser::{Stream, FromData, Offset, Offset32, LazyArray16, LazyArrayIter16, LazyArray32};
+use crate::parser::{F2DOT14, Fixed, U24, NumFrom};
+
+// Paint graph limits. Protect against cyclic, overly deep and exponentially large graphs.
+const MAX_PAINT_DEPTH: u8 = 64;
+const MAX_PAINTS: u32 = 10_000;
+
+
+#[derive(Clone, Copy)]
+struct BaseGlyphRecord {
+    glyph_id: GlyphId,
+    first_layer_index: u16,
+    num_layers: u16,
+}
+
+impl FromData for BaseGlyphRecord {
+    const SIZE: usize = 6;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(BaseGlyphRecord {
+            glyph_id: s.read::<GlyphId>()?,
+            first_layer_index: s.read::<u16>()?,
+            num_layers: s.read::<u16>()?,
+        })
+    }
+}
+
+
+/// A [color glyph layer](https://docs.microsoft.com/en-us/typography/opentype/spec/colr#baseglyph-and-layer-records).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ColorGlyphLayer {
+    /// A glyph which outline should be filled with a layer color.
+    pub glyph_id: GlyphId,
+    /// A `CPAL` palette entry index.
+    ///
+    /// `0xFFFF` indicates that the text foreground color should be used.
+    pub palette_index: u16,
+}
+
+impl ColorGlyphLayer {
+    /// Checks that the layer should be filled with the text foreground color.
+    #[inline]
+    pub fn is_foreground(&self) -> bool {
+        self.palette_index == 0xFFFF
+    }
+}
+
+impl FromData for ColorGlyphLayer {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(ColorGlyphLayer {
+            glyph_id: s.read::<GlyphId>()?,
+            palette_index: s.read::<u16>()?,
+        })
+    }
+}
+
+
+/// An iterator over color glyph layers, from bottom to top.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy, Default)]
+pub struct ColorGlyphLayers<'a> {
+    iter: LazyArrayIter16<'a, ColorGlyphLayer>,
+}
+
+impl<'a> Iterator for ColorGlyphLayers<'a> {
+    type Item = ColorGlyphLayer;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct BaseGlyphPaintRecord {
+    glyph_id: GlyphId,
+    paint_offset: Offset32,
+}
+
+impl FromData for BaseGlyphPaintRecord {
+    const SIZE: usize = 6;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(BaseGlyphPaintRecord {
+            glyph_id: s.read::<GlyphId>()?,
+            paint_offset: s.read::<Offset32>()?,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct ClipRecord {
+    start_glyph_id: GlyphId,
+    end_glyph_id: GlyphId,
+    clip_box_offset: u32,
+}
+
+impl FromData for ClipRecord {
+    const SIZE: usize = 7;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(ClipRecord {
+            start_glyph_id: s.read::<GlyphId>()?,
+            end_glyph_id: s.read::<GlyphId>()?,
+            clip_box_offset: s.read::<U24>()?.0,
+        })
+    }
+}
+
+
+/// A [composite mode](https://docs.microsoft.com/en-us/typography/opentype/spec/colr#format-32-paintcomposite).
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CompositeMode {
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+    Plus,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Multiply,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl CompositeMode {
+    fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(CompositeMode::Clear),
+            1 => Some(CompositeMode::Source),
+            2 => Some(CompositeMode::Destination),
+            3 => Some(CompositeMode::SourceOver),
+            4 => Some(CompositeMode::DestinationOver),
+            5 => Some(CompositeMode::SourceIn),
+            6 => Some(CompositeMode::DestinationIn),
+            7 => Some(CompositeMode::SourceOut),
+            8 => Some(CompositeMode::DestinationOut),
+            9 => Some(CompositeMode::SourceAtop),
+            10 => Some(CompositeMode::DestinationAtop),
+            11 => Some(CompositeMode::Xor),
+            12 => Some(CompositeMode::Plus),
+            13 => Some(CompositeMode::Screen),
+            14 => Some(CompositeMode::Overlay),
+            15 => Some(CompositeMode::Darken),
+            16 => Some(CompositeMode::Lighten),
+            17 => Some(CompositeMode::ColorDodge),
+            18 => Some(CompositeMode::ColorBurn),
+            19 => Some(CompositeMode::HardLight),
+            20 => Some(CompositeMode::SoftLight),
+            21 => Some(CompositeMode::Difference),
+            22 => Some(CompositeMode::Exclusion),
+            23 => Some(CompositeMode::Multiply),
+            24 => Some(CompositeMode::Hue),
+            25 => Some(CompositeMode::Saturation),
+            26 => Some(CompositeMode::Color),
+            27 => Some(CompositeMode::Luminosity),
+            _ => None,
+        }
+    }
+}
+
+
+/// A gradient [extend mode](https://docs.microsoft.com/en-us/typography/opentype/spec/colr#color-references-colorstop-and-colorline).
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum GradientExtend {
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+
+/// A paint transform.
+///
+/// Angles are in degrees, counter-clockwise.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Transform {
+    Matrix { xx: f32, yx: f32, xy: f32, yy: f32, dx: f32, dy: f32 },
+    Translate { dx: f32, dy: f32 },
+    Scale { sx: f32, sy: f32, center_x: f32, center_y: f32 },
+    Rotate { angle: f32, center_x: f32, center_y: f32 },
+    Skew { x_angle: f32, y_angle: f32, center_x: f32, center_y: f32 },
+}
+
+impl Transform {
+    /// Converts a transform into a `[xx, yx, xy, yy, dx, dy]` affine matrix.
+    #[cfg(feature = "std")]
+    pub fn to_matrix(&self) -> [f32; 6] {
+        // Applies a linear transform around the center point.
+        fn around(m: [f32; 4], cx: f32, cy: f32) -> [f32; 6] {
+            let dx = cx - (m[0] * cx + m[2] * cy);
+            let dy = cy - (m[1] * cx + m[3] * cy);
+            [m[0], m[1], m[2], m[3], dx, dy]
+        }
+
+        match *self {
+            Transform::Matrix { xx, yx, xy, yy, dx, dy } => [xx, yx, xy, yy, dx, dy],
+            Transform::Translate { dx, dy } => [1.0, 0.0, 0.0, 1.0, dx, dy],
+            Transform::Scale { sx, sy, center_x, center_y } => {
+                around([sx, 0.0, 0.0, sy], center_x, center_y)
+            }
+            Transform::Rotate { angle, center_x, center_y } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                around([cos, sin, -sin, cos], center_x, center_y)
+            }
+            Transform::Skew { x_angle, y_angle, center_x, center_y } => {
+                let x = (-x_angle).to_radians().tan();
+                let y = y_angle.to_radians().tan();
+                around([1.0, y, x, 1.0], center_x, center_y)
+            }
+        }
+    }
+}
+
+
+/// A gradient color stop.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ColorStop {
+    /// A stop position on the color line.
+    pub offset: f32,
+    /// A stop color, with the stop alpha already applied.
+    pub color: RgbaColor,
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Palette<'a> {
+    pub table: Option<cpal::Table<'a>>,
+    pub index: u16,
+    pub foreground: RgbaColor,
+}
+
+impl<'a> Palette<'a> {
+    fn get(&self, entry: u16, alpha: F2DOT14) -> Option<RgbaColor> {
+        let mut color = if entry == 0xFFFF {
+            self.foreground
+        } else {
+            self.table?.get(self.index, entry)?
+        };
+
+        let alpha = alpha.to_f32();
+        let alpha = if alpha < 0.0 { 0.0 } else if alpha > 1.0 { 1.0 } else { alpha };
+        color.alpha = (f32::from(color.alpha) * alpha + 0.5) as u8;
+        Some(color)
+    }
+}
+
+
+/// An iterator over gradient color stops.
+///
+/// Stops with colors that cannot be resolved are skipped.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct ColorStops<'a> {
+    stream: Stream<'a>,
+    count: u16,
+    is_variable: bool,
+    palette: Palette<'a>,
+}
+
+impl<'a> Iterator for ColorStops<'a> {
+    type Item = ColorStop;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.count != 0 {
+            self.count -= 1;
+            let offset: F2DOT14 = self.stream.read()?;
+            let palette_index: u16 = self.stream.read()?;
+            let alpha: F2DOT14 = self.stream.read()?;
+            if self.is_variable {
+                self.stream.skip::<u32>(); // varIndexBase
+            }
+
+            if let Some(color) = self.palette.get(palette_index, alpha) {
+                return Some(ColorStop { offset: offset.to_f32(), color });
+            }
+        }
+
+        None
+    }
+}
+
+
+/// A gradient color line.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct ColorLine<'a> {
+    /// An extend mode.
+    pub extend: GradientExtend,
+    stops: ColorStops<'a>,
+}
+
+impl<'a> ColorLine<'a> {
+    fn parse(data: &'a [u8], is_variable: bool, palette: Palette<'a>) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let extend = match s.read::<u8>()? {
+            1 => GradientExtend::Repeat,
+            2 => GradientExtend::Reflect,
+            // Unknown modes must be treated as `Pad`.
+            _ => GradientExtend::Pad,
+        };
+
+        let count: u16 = s.read()?;
+        let record_size = if is_variable { 10 } else { 6 };
+        let stops = Stream::new(s.read_bytes(usize::from(count) * record_size)?);
+        Some(ColorLine {
+            extend,
+            stops: ColorStops { stream: stops, count, is_variable, palette },
+        })
+    }
+
+    /// Returns an iterator over color stops.
+    #[inline]
+    pub fn stops(&self) -> ColorStops<'a> {
+        self.stops
+    }
+}
+
+
+/// A linear gradient.
+#[allow(missing_docs)]
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct LinearGradient<'a> {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+    /// A rotation point.
+    pub x2: f32,
+    /// A rotation point.
+    pub y2: f32,
+    pub color_line: ColorLine<'a>,
+}
+
+
+/// A two-circle radial gradient.
+#[allow(missing_docs)]
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct RadialGradient<'a> {
+    pub x0: f32,
+    pub y0: f32,
+    pub r0: f32,
+    pub x1: f32,
+    pub y1: f32,
+    pub r1: f32,
+    pub color_line: ColorLine<'a>,
+}
+
+
+/// A sweep gradient.
+///
+/// Angles are in degrees, counter-clockwise.
+#[allow(missing_docs)]
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct SweepGradient<'a> {
+    pub center_x: f32,
+    pub center_y: f32,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub color_line: ColorLine<'a>,
+}
+
+
+/// A fill paint.
+#[allow(missing_docs)]
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub enum Paint<'a> {
+    Solid(RgbaColor),
+    LinearGradient(LinearGradient<'a>),
+    RadialGradient(RadialGradient<'a>),
+    SweepGradient(SweepGradient<'a>),
+}
+
+
+/// A trait for color glyph painting.
+///
+/// All `push_*` calls are balanced by the matching `pop_*` ones.
+pub trait Painter {
+    /// Restricts painting to the glyph outline.
+    fn push_clip_glyph(&mut self, glyph_id: GlyphId);
+
+    /// Restricts painting to the rectangle.
+    fn push_clip_box(&mut self, clip_box: Rect);
+
+    /// Removes the last clip.
+    fn pop_clip(&mut self);
+
+    /// Fills the current clip area with the paint.
+    fn paint(&mut self, paint: Paint);
+
+    /// Applies the transform to all following paints and clips.
+    fn push_transform(&mut self, transform: Transform);
+
+    /// Removes the last transform.
+    fn pop_transform(&mut self);
+
+    /// Starts a new layer, which will be composed using the composite mode.
+    fn push_layer(&mut self, mode: CompositeMode);
+
+    /// Composes the last layer.
+    fn pop_layer(&mut self);
+}
+
+
+struct PaintContext<'a, 'p> {
+    palette: Palette<'a>,
+    painter: &'p mut dyn Painter,
+    paints_left: u32,
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    base_glyphs: LazyArray16<'a, BaseGlyphRecord>,
+    layers: LazyArray16<'a, ColorGlyphLayer>,
+    base_glyph_list: &'a [u8],
+    base_glyph_paints: LazyArray32<'a, BaseGlyphPaintRecord>,
+    layer_list: &'a [u8],
+    layer_paints: LazyArray32<'a, Offset32>,
+    clip_list: &'a [u8],
+    clips: LazyArray32<'a, ClipRecord>,
+}
+
+fn parse_list<T: FromData>(
+    data: &[u8],
+    offset: Option<Offset32>,
+    header_size: usize,
+) -> Option<(&[u8], LazyArray32<'_, T>)> {
+    let list = data.get(offset?.to_usize()..)?;
+    let mut s = Stream::new(list);
+    s.advance(header_size);
+    let count: u32 = s.read()?;
+    Some((list, s.read_array32::<T>(count)?))
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let version: u16 = s.read()?;
+        // Version 1 is a superset of version 0.
+        if version > 1 {
+            return None;
+        }
+
+        let num_base_glyphs: u16 = s.read()?;
+        let base_glyphs_offset: Offset32 = s.read()?;
+        let layers_offset: Offset32 = s.read()?;
+        let num_layers: u16 = s.read()?;
+
+        let base_glyphs = Stream::new_at(data, base_glyphs_offset.to_usize())?
+            .read_array16::<BaseGlyphRecord>(num_base_glyphs)?;
+        let layers = Stream::new_at(data, layers_offset.to_usize())?
+            .read_array16::<ColorGlyphLayer>(num_layers)?;
+
+        let mut table = Table {
+            base_glyphs,
+            layers,
+            base_glyph_list: &[],
+            base_glyph_paints: LazyArray32::default(),
+            layer_list: &[],
+            layer_paints: LazyArray32::default(),
+            clip_list: &[],
+            clips: LazyArray32::default(),
+        };
+
+        if version == 0 {
+            return Some(table);
+        }
+
+        // Malformed version 1 lists are ignored, so version 0 glyphs are still available.
+        let base_glyph_list_offset = s.read::<Option<Offset32>>().and_then(|v| v);
+        let layer_list_offset = s.read::<Option<Offset32>>().and_then(|v| v);
+        let clip_list_offset = s.read::<Option<Offset32>>().and_then(|v| v);
+        // Variations are not supported yet, so `varIndexMapOffset`
+        // and `itemVariationStoreOffset` are ignored.
+
+        if let Some((list, records)) = parse_list(data, base_glyph_list_offset, 0) {
+            table.base_glyph_list = list;
+            table.base_glyph_paints = records;
+        }
+
+        if let Some((list, records)) = parse_list(data, layer_list_offset, 0) {
+            table.layer_list = list;
+            table.layer_paints = records;
+        }
+
+        // A clip list starts with a format byte.
+        if let Some((list, records)) = parse_list(data, clip_list_offset, 1) {
+            table.clip_list = list;
+            table.clips = records;
+        }
+
+        Some(table)
+    }
+
+    pub fn layers(&self, glyph_id: GlyphId) -> Option<ColorGlyphLayers<'a>> {
+        let (_, base) = self.base_glyphs.binary_search_by(|r| r.glyph_id.cmp(&glyph_id))?;
+        let start = base.first_layer_index;
+        let end = start.checked_add(base.num_layers)?;
+        let layers = self.layers.slice(start..end)?;
+        Some(ColorGlyphLayers { iter: layers.into_iter() })
+    }
+
+    pub fn contains(&self, glyph_id: GlyphId) -> bool {
+        self.base_glyph_paint(glyph_id).is_some()
+            || self.base_glyphs.binary_search_by(|r| r.glyph_id.cmp(&glyph_id)).is_some()
+    }
+
+    fn base_glyph_paint(&self, glyph_id: GlyphId) -> Option<BaseGlyphPaintRecord> {
+        self.base_glyph_paints.binary_search_by(|r| r.glyph_id.cmp(&glyph_id)).map(|v| v.1)
+    }
+
+    fn clip_box(&self, glyph_id: GlyphId) -> Option<Rect> {
+        use core::cmp::Ordering;
+
+        let (_, record) = self.clips.binary_search_by(|r| {
+            if r.end_glyph_id < glyph_id {
+                Ordering::Less
+            } else if r.start_glyph_id > glyph_id {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })?;
+
+        let mut s = Stream::new_at(self.clip_list, usize::num_from(record.clip_box_offset))?;
+        let format: u8 = s.read()?;
+        // Format 2 is a variable clip box.
+        if format != 1 && format != 2 {
+            return None;
+        }
+
+        Some(Rect {
+            x_min: s.read::<i16>()?,
+            y_min: s.read::<i16>()?,
+            x_max: s.read::<i16>()?,
+            y_max: s.read::<i16>()?,
+        })
+    }
+
+    /// Paints a color glyph.
+    ///
+    /// `COLR` version 1 glyphs take precedence over version 0 ones.
+    pub fn paint(
+        &self,
+        glyph_id: GlyphId,
+        palette: Palette<'a>,
+        painter: &mut dyn Painter,
+    ) -> Option<()> {
+        if self.base_glyph_paint(glyph_id).is_some() {
+            let mut ctx = PaintContext { palette, painter, paints_left: MAX_PAINTS };
+            return self.paint_base_glyph(glyph_id, &mut ctx, 0);
+        }
+
+        for layer in self.layers(glyph_id)? {
+            painter.push_clip_glyph(layer.glyph_id);
+            // Layers with unresolved colors are still balanced, but not painted.
+            if let Some(color) = palette.get(layer.palette_index, F2DOT14(1 << 14)) {
+                painter.paint(Paint::Solid(color));
+            }
+            painter.pop_clip();
+        }
+
+        Some(())
+    }
+
+    fn paint_base_glyph(
+        &self,
+        glyph_id: GlyphId,
+        ctx: &mut PaintContext<'a, '_>,
+        depth: u8,
+    ) -> Option<()> {
+        let record = self.base_glyph_paint(glyph_id)?;
+        let offset = record.paint_offset.to_usize();
+        match self.clip_box(glyph_id) {
+            Some(clip_box) => {
+                ctx.painter.push_clip_box(clip_box);
+                let res = self.paint_impl(self.base_glyph_list, offset, ctx, depth);
+                ctx.painter.pop_clip();
+                res
+            }
+            None => self.paint_impl(self.base_glyph_list, offset, ctx, depth),
+        }
+    }
+
+    // `offset` is relative to `data`. All nested offsets are relative to the current paint.
+    fn paint_impl(
+        &self,
+        data: &'a [u8],
+        offset: usize,
+        ctx: &mut PaintContext<'a, '_>,
+        depth: u8,
+    ) -> Option<()> {
+        if depth == MAX_PAINT_DEPTH || ctx.paints_left == 0 {
+            return None;
+        }
+
+        let depth = depth + 1;
+        ctx.paints_left -= 1;
+        let data = data.get(offset..)?;
+        let mut s = Stream::new(data);
+        let format: u8 = s.read()?;
+        // All variable formats are odd and have the same layout as the preceding format,
+        // followed by a variation index, which is ignored.
+        let is_variable = format % 2 == 1;
+        match format {
+            1 => {
+                // PaintColrLayers
+                let count: u8 = s.read()?;
+                let first: u32 = s.read()?;
+                for i in 0..u32::from(count) {
+                    let offset = self.layer_paints.get(first.checked_add(i)?)?;
+                    self.paint_impl(self.layer_list, offset.to_usize(), ctx, depth)?;
+                }
+            }
+            2 | 3 => {
+                // PaintSolid
+                let palette_index: u16 = s.read()?;
+                let alpha: F2DOT14 = s.read()?;
+                ctx.painter.paint(Paint::Solid(ctx.palette.get(palette_index, alpha)?));
+            }
+            4 | 5 => {
+                // PaintLinearGradient
+                let color_line = parse_color_line(data, &mut s, is_variable, ctx.palette)?;
+                ctx.painter.paint(Paint::LinearGradient(LinearGradient {
+                    x0: read_fword(&mut s)?,
+                    y0: read_fword(&mut s)?,
+                    x1: read_fword(&mut s)?,
+                    y1: read_fword(&mut s)?,
+                    x2: read_fword(&mut s)?,
+                    y2: read_fword(&mut s)?,
+                    color_line,
+                }));
+            }
+            6 | 7 => {
+                // PaintRadialGradient
+                let color_line = parse_color_line(data, &mut s, is_variable, ctx.palette)?;
+                ctx.painter.paint(Paint::RadialGradient(RadialGradient {
+                    x0: read_fword(&mut s)?,
+                    y0: read_fword(&mut s)?,
+                    r0: f32::from(s.read::<u16>()?),
+                    x1: read_fword(&mut s)?,
+                    y1: read_fword(&mut s)?,
+                    r1: f32::from(s.read::<u16>()?),
+                    color_line,
+                }));
+            }
+            8 | 9 => {
+                // PaintSweepGradient
+                let color_line = parse_color_line(data, &mut s, is_variable, ctx.palette)?;
+                ctx.painter.paint(Paint::SweepGradient(SweepGradient {
+                    center_x: read_fword(&mut s)?,
+                    center_y: read_fword(&mut s)?,
+                    start_angle: read_angle(&mut s)?,
+                    end_angle: read_angle(&mut s)?,
+                    color_line,
+                }));
+            }
+            10 => {
+                // PaintGlyph
+                let paint_offset = read_offset24(&mut s)?;
+                let glyph_id: GlyphId = s.read()?;
+                ctx.painter.push_clip_glyph(glyph_id);
+                let res = self.paint_impl(data, paint_offset, ctx, depth);
+                ctx.painter.pop_clip();
+                res?;
+            }
+            11 => {
+                // PaintColrGlyph
+                let glyph_id: GlyphId = s.read()?;
+                self.paint_base_glyph(glyph_id, ctx, depth)?;
+            }
+            12..=31 => {
+                let paint_offset = read_offset24(&mut s)?;
+                let transform = parse_transform(data, format, &mut s)?;
+                ctx.painter.push_transform(transform);
+                let res = self.paint_impl(data, paint_offset, ctx, depth);
+                ctx.painter.pop_transform();
+                res?;
+            }
+            32 => {
+                // PaintComposite
+                let source_offset = read_offset24(&mut s)?;
+                let mode = CompositeMode::from_u8(s.read()?)?;
+                let backdrop_offset = read_offset24(&mut s)?;
+
+                ctx.painter.push_layer(CompositeMode::SourceOver);
+                let res = self.paint_impl(data, backdrop_offset, ctx, depth);
+                let res = res.and_then(|_| {
+                    ctx.painter.push_layer(mode);
+                    let res = self.paint_impl(data, source_offset, ctx, depth);
+                    ctx.painter.pop_layer();
+                    res
+                });
+                ctx.painter.pop_layer();
+                res?;
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
+
+#[inline]
+fn read_offset24(s: &mut Stream) -> Option<usize> {
+    s.read::<U24>().map(|n| usize::num_from(n.0))
+}
+
+#[inline]
+fn read_fword(s: &mut Stream) -> Option<f32> {
+    s.read::<i16>().map(f32::from)
+}
+
+#[inline]
+fn read_f2dot14(s: &mut Stream) -> Option<f32> {
+    s.read::<F2DOT14>().map(|n| n.to_f32())
+}
+
+// Angles are stored in multiples of 180 degrees.
+#[inline]
+fn read_angle(s: &mut Stream) -> Option<f32> {
+    read_f2dot14(s).map(|n| n * 180.0)
+}
+
+fn parse_color_line<'a>(
+    data: &'a [u8],
+    s: &mut Stream,
+    is_variable: bool,
+    palette: Palette<'a>,
+) -> Option<ColorLine<'a>> {
+    let offset = read_offset24(s)?;
+    ColorLine::parse(data.get(offset..)?, is_variable, palette)
+}
+
+fn parse_transform(data: &[u8], format: u8, s: &mut Stream) -> Option<Transform> {
+    // Formats without a center point are transformed around the origin.
+    let transform = match format {
+        12 | 13 => {
+            // PaintTransform
+            let offset = read_offset24(s)?;
+            let mut s = Stream::new_at(data, offset)?;
+            Transform::Matrix {
+                xx: s.read::<Fixed>()?.0,
+                yx: s.read::<Fixed>()?.0,
+                xy: s.read::<Fixed>()?.0,
+                yy: s.read::<Fixed>()?.0,
+                dx: s.read::<Fixed>()?.0,
+                dy: s.read::<Fixed>()?.0,
+            }
+        }
+        14 | 15 => Transform::Translate {
+            dx: read_fword(s)?,
+            dy: read_fword(s)?,
+        },
+        16..=23 => {
+            let (sx, sy) = if format < 20 {
+                (read_f2dot14(s)?, read_f2dot14(s)?)
+            } else {
+                let scale = read_f2dot14(s)?;
+                (scale, scale)
+            };
+
+            let (center_x, center_y) = parse_center(format, s)?;
+            Transform::Scale { sx, sy, center_x, center_y }
+        }
+        24..=27 => {
+            let angle = read_angle(s)?;
+            let (center_x, center_y) = parse_center(format, s)?;
+            Transform::Rotate { angle, center_x, center_y }
+        }
+        28..=31 => {
+            let x_angle = read_angle(s)?;
+            let y_angle = read_angle(s)?;
+            let (center_x, center_y) = parse_center(format, s)?;
+            Transform::Skew { x_angle, y_angle, center_x, center_y }
+        }
+        _ => return None,
+    };
+
+    Some(transform)
+}
+
+// `*AroundCenter` formats are the second pair in each transform group,
+// i.e. 18/19, 22/23, 26/27 and 30/31.
+fn parse_center(format: u8, s: &mut Stream) -> Option<(f32, f32)> {
+    if (format - 16) % 4 >= 2 {
+        Some((read_fword(s)?, read_fword(s)?))
+    } else {
+        Some((0.0, 0.0))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::String;
+    use std::vec::Vec;
+
+    #[test]
+    fn layers() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x02, // number of base glyphs: 2
+            0x00, 0x00, 0x00, 0x0E, // base glyphs offset: 14
+            0x00, 0x00, 0x00, 0x1A, // layers offset: 26
+            0x00, 0x03, // number of layers: 3
+
+            // Base glyph [0]
+            0x00, 0x02, // glyph ID: 2
+            0x00, 0x00, // first layer index: 0
+            0x00, 0x02, // number of layers: 2
+            // Base glyph [1]
+            0x00, 0x05, // glyph ID: 5
+            0x00, 0x02, // first layer index: 2
+            0x00, 0x02, // number of layers: 2 (malformed)
+
+            // Layer [0]
+            0x00, 0x0A, // glyph ID: 10
+            0x00, 0x01, // palette index: 1
+            // Layer [1]
+            0x00, 0x0B, // glyph ID: 11
+            0xFF, 0xFF, // palette index: foreground
+            // Layer [2]
+            0x00, 0x0C, // glyph ID: 12
+            0x00, 0x00, // palette index: 0
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let mut layers = table.layers(GlyphId(2)).unwrap();
+        assert_eq!(layers.next(), Some(ColorGlyphLayer { glyph_id: GlyphId(10), palette_index: 1 }));
+        let layer = layers.next().unwrap();
+        assert_eq!(layer.glyph_id, GlyphId(11));
+        assert!(layer.is_foreground());
+        assert!(layers.next().is_none());
+
+        assert!(table.layers(GlyphId(3)).is_none());
+        // Layers are out of bounds.
+        assert!(table.layers(GlyphId(5)).is_none());
+    }
+    #[test]
+    fn malformed_v1_lists() {
+        let data = &[
+            0x00, 0x01, // version: 1
+            0x00, 0x01, // number of base glyphs: 1
+            0x00, 0x00, 0x00, 0x1A, // base glyphs offset: 26
+            0x00, 0x00, 0x00, 0x20, // layers offset: 32
+            0x00, 0x01, // number of layers: 1
+            0x00, 0x00, 0xFF, 0x00, // base glyph list offset: 65280 (out of bounds)
+            0x00, 0x00, 0x00, 0x24, // layer list offset: 36 (truncated)
+            0x00, 0x00, 0x00, 0x00, // clip list offset: 0
+
+            // Base glyph [0]
+            0x00, 0x02, // glyph ID: 2
+            0x00, 0x00, // first layer index: 0
+            0x00, 0x01, // number of layers: 1
+
+            // Layer [0]
+            0x00, 0x0A, // glyph ID: 10
+            0x00, 0x01, // palette index: 1
+
+            // Layer list
+            0x00, 0x00, 0x00, 0x10, // count: 16
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let mut layers = table.layers(GlyphId(2)).unwrap();
+        assert_eq!(layers.next(), Some(ColorGlyphLayer { glyph_id: GlyphId(10), palette_index: 1 }));
+        assert!(layers.next().is_none());
+        assert!(table.contains(GlyphId(2)));
+        assert!(table.base_glyph_paint(GlyphId(2)).is_none());
+    }
+
+    #[test]
+    fn huge_counts() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0xFF, 0xFF, // number of base glyphs: 65535
+            0x00, 0x00, 0x00, 0x0E, // base glyphs offset: 14
+            0x00, 0x00, 0x00, 0x14, // layers offset: 20
+            0xFF, 0xFF, // number of layers: 65535
+
+            // Base glyph [0]
+            0x00, 0x02, // glyph ID: 2
+            0x00, 0x00, // first layer index: 0
+            0x00, 0x01, // number of layers: 1
+
+            // Layer [0]
+            0x00, 0x0A, // glyph ID: 10
+            0x00, 0x01, // palette index: 1
+        ];
+
+        // Counts that don't fit the data are rejected without reading the records.
+        assert!(Table::parse(data).is_none());
+    }
+
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl Painter for Recorder {
+        fn push_clip_glyph(&mut self, glyph_id: GlyphId) {
+            self.0.push(format!("push_clip_glyph {}", glyph_id.0));
+        }
+
+        fn push_clip_box(&mut self, clip_box: Rect) {
+            self.0.push(format!("push_clip_box {:?}", clip_box));
+        }
+
+        fn pop_clip(&mut self) {
+            self.0.push("pop_clip".into());
+        }
+
+        fn paint(&mut self, paint: Paint) {
+            match paint {
+                Paint::Solid(color) => self.0.push(format!("solid {:?}", color)),
+                Paint::LinearGradient(g) => {
+                    self.0.push(format!(
+                        "linear {} {} {} {} {} {} {:?}",
+                        g.x0, g.y0, g.x1, g.y1, g.x2, g.y2, g.color_line.extend,
+                    ));
+                    for stop in g.color_line.stops() {
+                        self.0.push(format!("stop {} {:?}", stop.offset, stop.color));
+                    }
+                }
+                _ => self.0.push("gradient".into()),
+            }
+        }
+
+        fn push_transform(&mut self, transform: Transform) {
+            self.0.push(format!("push_transform {:?}", transform));
+        }
+
+        fn pop_transform(&mut self) {
+            self.0.push("pop_transform".into());
+        }
+
+        fn push_layer(&mut self, mode: CompositeMode) {
+            self.0.push(format!("push_layer {:?}", mode));
+        }
+
+        fn pop_layer(&mut self) {
+            self.0.push("pop_layer".into());
+        }
+    }
+
+    fn cpal_table() -> cpal::Table<'static> {
+        cpal::Table::parse(&[
+            0x00, 0x00, // version: 0
+            0x00, 0x02, // number of palette entries: 2
+            0x00, 0x01, // number of palettes: 1
+            0x00, 0x02, // number of color records: 2
+            0x00, 0x00, 0x00, 0x0E, // color records offset: 14
+            0x00, 0x00, // color record index [0]: 0
+            0x00, 0x00, 0xFF, 0xFF, // color [0]: red
+            0xFF, 0x00, 0x00, 0xFF, // color [1]: blue
+        ]).unwrap()
+    }
+
+    #[test]
+    fn paint_v0() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x01, // number of base glyphs: 1
+            0x00, 0x00, 0x00, 0x0E, // base glyphs offset: 14
+            0x00, 0x00, 0x00, 0x14, // layers offset: 20
+            0x00, 0x02, // number of layers: 2
+
+            // Base glyph [0]
+            0x00, 0x02, // glyph ID: 2
+            0x00, 0x00, // first layer index: 0
+            0x00, 0x02, // number of layers: 2
+
+            // Layer [0]
+            0x00, 0x0A, // glyph ID: 10
+            0x00, 0x01, // palette index: 1
+            // Layer [1]
+            0x00, 0x0B, // glyph ID: 11
+            0xFF, 0xFF, // palette index: foreground
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let palette = Palette {
+            table: Some(cpal_table()),
+            index: 0,
+            foreground: RgbaColor { red: 0, green: 0, blue: 0, alpha: 255 },
+        };
+
+        let mut painter = Recorder::default();
+        assert!(table.paint(GlyphId(2), palette, &mut painter).is_some());
+        assert_eq!(painter.0, &[
+            "push_clip_glyph 10",
+            "solid RgbaColor { red: 0, green: 0, blue: 255, alpha: 255 }",
+            "pop_clip",
+            "push_clip_glyph 11",
+            "solid RgbaColor { red: 0, green: 0, blue: 0, alpha: 255 }",
+            "pop_clip",
+        ]);
+
+        assert!(table.contains(GlyphId(2)));
+        assert!(!table.contains(GlyphId(3)));
+        assert!(table.paint(GlyphId(3), palette, &mut painter).is_none());
+    }
+
+    #[test]
+    fn paint_v1() {
+        let data = &[
+            0x00, 0x01, // version: 1
+            0x00, 0x00, // number of base glyphs: 0
+            0x00, 0x00, 0x00, 0x00, // base glyphs offset: 0
+            0x00, 0x00, 0x00, 0x00, // layers offset: 0
+            0x00, 0x00, // number of layers: 0
+            0x00, 0x00, 0x00, 0x22, // base glyph list offset: 34
+            0x00, 0x00, 0x00, 0x32, // layer list offset: 50
+            0x00, 0x00, 0x00, 0x3E, // clip list offset: 62
+            0x00, 0x00, 0x00, 0x00, // var index map offset: 0
+            0x00, 0x00, 0x00, 0x00, // item variation store offset: 0
+
+            // Base glyph list
+            0x00, 0x00, 0x00, 0x02, // count: 2
+            // Base glyph paint record [0]
+            0x00, 0x02, // glyph ID: 2
+            0x00, 0x00, 0x00, 0x31, // paint offset: 49
+            // Base glyph paint record [1]
+            0x00, 0x03, // glyph ID: 3
+            0x00, 0x00, 0x00, 0x6F, // paint offset: 111
+
+            // Layer list
+            0x00, 0x00, 0x00, 0x02, // count: 2
+            0x00, 0x00, 0x00, 0x27, // paint offset [0]: 39
+            0x00, 0x00, 0x00, 0x32, // paint offset [1]: 50
+
+            // Clip list
+            0x01, // format: 1
+            0x00, 0x00, 0x00, 0x01, // count: 1
+            // Clip record [0]
+            0x00, 0x02, // start glyph ID: 2
+            0x00, 0x02, // end glyph ID: 2
+            0x00, 0x00, 0x0C, // clip box offset: 12
+            // Clip box
+            0x01, // format: 1
+            0x00, 0x00, // x min: 0
+            0x00, 0x00, // y min: 0
+            0x00, 0x64, // x max: 100
+            0x00, 0x64, // y max: 100
+
+            // PaintColrLayers
+            0x01, // format: 1
+            0x02, // number of layers: 2
+            0x00, 0x00, 0x00, 0x00, // first layer index: 0
+
+            // PaintGlyph
+            0x0A, // format: 10
+            0x00, 0x00, 0x06, // paint offset: 6
+            0x00, 0x0A, // glyph ID: 10
+            // PaintSolid
+            0x02, // format: 2
+            0x00, 0x00, // palette index: 0
+            0x20, 0x00, // alpha: 0.5
+
+            // PaintTranslate
+            0x0E, // format: 14
+            0x00, 0x00, 0x08, // paint offset: 8
+            0x00, 0x64, // dx: 100
+            0xFF, 0xCE, // dy: -50
+            // PaintGlyph
+            0x0A, // format: 10
+            0x00, 0x00, 0x06, // paint offset: 6
+            0x00, 0x0B, // glyph ID: 11
+            // PaintLinearGradient
+            0x04, // format: 4
+            0x00, 0x00, 0x10, // color line offset: 16
+            0x00, 0x00, // x0: 0
+            0x00, 0x00, // y0: 0
+            0x00, 0x64, // x1: 100
+            0x00, 0x00, // y1: 0
+            0x00, 0x00, // x2: 0
+            0x00, 0x64, // y2: 100
+            // Color line
+            0x01, // extend: repeat
+            0x00, 0x02, // number of stops: 2
+            0x00, 0x00, // stop offset [0]: 0.0
+            0xFF, 0xFF, // palette index [0]: foreground
+            0x40, 0x00, // alpha [0]: 1.0
+            0x40, 0x00, // stop offset [1]: 1.0
+            0x00, 0x01, // palette index [1]: 1
+            0x40, 0x00, // alpha [1]: 1.0
+
+            // PaintColrGlyph
+            0x0B, // format: 11
+            0x00, 0x03, // glyph ID: 3 (cyclic)
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let palette = Palette {
+            table: Some(cpal_table()),
+            index: 0,
+            foreground: RgbaColor { red: 0, green: 255, blue: 0, alpha: 255 },
+        };
+
+        let mut painter = Recorder::default();
+        assert!(table.paint(GlyphId(2), palette, &mut painter).is_some());
+        assert_eq!(painter.0, &[
+            "push_clip_box Rect { x_min: 0, y_min: 0, x_max: 100, y_max: 100 }",
+            "push_clip_glyph 10",
+            "solid RgbaColor { red: 255, green: 0, blue: 0, alpha: 128 }",
+            "pop_clip",
+            "push_transform Translate { dx: 100.0, dy: -50.0 }",
+            "push_clip_glyph 11",
+            "linear 0 0 100 0 0 100 Repeat",
+            "stop 0 RgbaColor { red: 0, green: 255, blue: 0, alpha: 255 }",
+            "stop 1 RgbaColor { red: 0, green: 0, blue: 255, alpha: 255 }",
+            "pop_clip",
+            "pop_transform",
+            "pop_clip",
+        ]);
+
+        assert!(table.contains(GlyphId(3)));
+        let mut painter = Recorder::default();
+        assert!(table.paint(GlyphId(3), palette, &mut painter).is_none());
+        assert!(painter.0.is_empty());
+    }
+
+    #[test]
+    fn paint_composite() {
+        let data = &[
+            0x00, 0x01, // version: 1
+            0x00, 0x00, // number of base glyphs: 0
+            0x00, 0x00, 0x00, 0x00, // base glyphs offset: 0
+            0x00, 0x00, 0x00, 0x00, // layers offset: 0
+            0x00, 0x00, // number of layers: 0
+            0x00, 0x00, 0x00, 0x22, // base glyph list offset: 34
+            0x00, 0x00, 0x00, 0x00, // layer list offset: 0
+            0x00, 0x00, 0x00, 0x00, // clip list offset: 0
+            0x00, 0x00, 0x00, 0x00, // var index map offset: 0
+            0x00, 0x00, 0x00, 0x00, // item variation store offset: 0
+
+            // Base glyph list
+            0x00, 0x00, 0x00, 0x01, // count: 1
+            // Base glyph paint record [0]
+            0x00, 0x02, // glyph ID: 2
+            0x00, 0x00, 0x00, 0x0A, // paint offset: 10
+
+            // PaintComposite
+            0x20, // format: 32
+            0x00, 0x00, 0x08, // source paint offset: 8
+            0x17, // composite mode: multiply
+            0x00, 0x00, 0x12, // backdrop paint offset: 18
+            // PaintRotateAroundCenter (source)
+            0x1A, // format: 26
+            0x00, 0x00, 0x00, // paint offset: 0 (cyclic)
+            0x20, 0x00, // angle: 90
+            0x00, 0x0A, // center x: 10
+            0x00, 0x00, // center y: 0
+            // PaintVarSolid (backdrop)
+            0x03, // format: 3
+            0xFF, 0xFF, // palette index: foreground
+            0x40, 0x00, // alpha: 1.0
+            0x00, 0x00, 0x00, 0x00, // var index base: 0
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let palette = Palette {
+            table: None,
+            index: 0,
+            foreground: RgbaColor { red: 0, green: 0, blue: 0, alpha: 255 },
+        };
+
+        let mut painter = Recorder::default();
+        assert!(table.paint(GlyphId(2), palette, &mut painter).is_none());
+        assert_eq!(&painter.0[..4], &[
+            "push_layer SourceOver",
+            "solid RgbaColor { red: 0, green: 0, blue: 0, alpha: 255 }",
+            "push_layer Multiply",
+            "push_transform Rotate { angle: 90.0, center_x: 10.0, center_y: 0.0 }",
+        ]);
+        assert_eq!(&painter.0[painter.0.len() - 3..], &["pop_transform", "pop_layer", "pop_layer"]);
+
+        // The cycle is stopped, but all calls are balanced.
+        let pushes = painter.0.iter().filter(|s| s.starts_with("push_transform")).count();
+        let pops = painter.0.iter().filter(|s| s.starts_with("pop_transform")).count();
+        assert_eq!(pushes, pops);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn transform_matrix() {
+        let ts = Transform::Rotate { angle: 90.0, center_x: 10.0, center_y: 0.0 };
+        let m = ts.to_matrix();
+        let expected = [0.0, 1.0, -1.0, 0.0, 10.0, -10.0];
+        for (a, b) in m.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 0.0001);
+        }
+
+        let ts = Transform::Scale { sx: 2.0, sy: 0.5, center_x: 10.0, center_y: 10.0 };
+        assert_eq!(ts.to_matrix(), [2.0, 0.0, 0.0, 0.5, -10.0, 5.0]);
+    }
+}
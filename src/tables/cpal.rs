@@ -0,0 +1,231 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cpal
+
+use crate::parser::{Stream, FromData, Offset, Offset32, LazyArray16};
+
+
+/// A BGRA color in sRGB.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct RgbaColor {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl FromData for RgbaColor {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        // Colors are stored in the BGRA order.
+        let blue: u8 = s.read()?;
+        let green: u8 = s.read()?;
+        let red: u8 = s.read()?;
+        let alpha: u8 = s.read()?;
+        Some(RgbaColor { red, green, blue, alpha })
+    }
+}
+
+
+/// [Palette type](https://docs.microsoft.com/en-us/typography/opentype/spec/cpal#palette-type-array) flags.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PaletteFlags(pub u32);
+
+#[allow(missing_docs)]
+impl PaletteFlags {
+    #[inline] pub fn usable_with_light_background(self) -> bool { self.0 & 0x0001 != 0 }
+    #[inline] pub fn usable_with_dark_background(self) -> bool { self.0 & 0x0002 != 0 }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    palette_entries: u16,
+    color_record_indices: LazyArray16<'a, u16>,
+    colors: LazyArray16<'a, RgbaColor>,
+    palette_types: LazyArray16<'a, u32>,
+    palette_labels: LazyArray16<'a, u16>,
+    palette_entry_labels: LazyArray16<'a, u16>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let version: u16 = s.read()?;
+        if version > 1 {
+            return None;
+        }
+
+        let palette_entries: u16 = s.read()?;
+        let palettes: u16 = s.read()?;
+        let color_records: u16 = s.read()?;
+        let color_records_offset: Offset32 = s.read()?;
+        let color_record_indices = s.read_array16::<u16>(palettes)?;
+
+        let colors = Stream::new_at(data, color_records_offset.to_usize())?
+            .read_array16::<RgbaColor>(color_records)?;
+
+        let mut table = Table {
+            palette_entries,
+            color_record_indices,
+            colors,
+            palette_types: LazyArray16::default(),
+            palette_labels: LazyArray16::default(),
+            palette_entry_labels: LazyArray16::default(),
+        };
+
+        if version == 0 {
+            return Some(table);
+        }
+
+        // All version 1 arrays are optional.
+        if let Some(offset) = s.read::<Option<Offset32>>()? {
+            table.palette_types = Stream::new_at(data, offset.to_usize())?
+                .read_array16::<u32>(palettes)?;
+        }
+
+        if let Some(offset) = s.read::<Option<Offset32>>()? {
+            table.palette_labels = Stream::new_at(data, offset.to_usize())?
+                .read_array16::<u16>(palettes)?;
+        }
+
+        if let Some(offset) = s.read::<Option<Offset32>>()? {
+            table.palette_entry_labels = Stream::new_at(data, offset.to_usize())?
+                .read_array16::<u16>(palette_entries)?;
+        }
+
+        Some(table)
+    }
+
+    #[inline]
+    pub fn palettes(&self) -> u16 {
+        self.color_record_indices.len()
+    }
+
+    #[inline]
+    pub fn palette_entries(&self) -> u16 {
+        self.palette_entries
+    }
+
+    /// Returns all colors of the palette at `palette_index`.
+    pub fn palette(&self, palette_index: u16) -> Option<LazyArray16<'a, RgbaColor>> {
+        let first = self.color_record_indices.get(palette_index)?;
+        self.colors.slice(first..first.checked_add(self.palette_entries)?)
+    }
+
+    /// Returns palette flags. Always empty for version 0 tables.
+    pub fn palette_flags(&self, palette_index: u16) -> Option<PaletteFlags> {
+        if palette_index >= self.palettes() {
+            return None;
+        }
+
+        Some(PaletteFlags(self.palette_types.get(palette_index).unwrap_or(0)))
+    }
+
+    pub fn palette_name_id(&self, palette_index: u16) -> Option<u16> {
+        self.palette_labels.get(palette_index).filter(|id| *id != 0xFFFF)
+    }
+
+    pub fn palette_entry_name_id(&self, palette_entry: u16) -> Option<u16> {
+        self.palette_entry_labels.get(palette_entry).filter(|id| *id != 0xFFFF)
+    }
+
+    /// Returns a color of `palette_entry` in the palette at `palette_index`.
+    pub fn get(&self, palette_index: u16, palette_entry: u16) -> Option<RgbaColor> {
+        if palette_entry >= self.palette_entries {
+            return None;
+        }
+
+        let first = self.color_record_indices.get(palette_index)?;
+        self.colors.get(first.checked_add(palette_entry)?)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x02, // number of palette entries: 2
+            0x00, 0x02, // number of palettes: 2
+            0x00, 0x03, // number of color records: 3
+            0x00, 0x00, 0x00, 0x10, // color records offset: 16
+            0x00, 0x00, // color record index [0]: 0
+            0x00, 0x01, // color record index [1]: 1
+
+            // Color records
+            0x10, 0x20, 0x30, 0xFF, // [0]: blue 16, green 32, red 48, alpha 255
+            0x00, 0x00, 0xFF, 0x80, // [1]: red, alpha 128
+            0xFF, 0x00, 0x00, 0xFF, // [2]: blue
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.get(0, 0), Some(RgbaColor { red: 48, green: 32, blue: 16, alpha: 255 }));
+        assert_eq!(table.get(0, 1), Some(RgbaColor { red: 255, green: 0, blue: 0, alpha: 128 }));
+        // Palettes can overlap.
+        assert_eq!(table.get(1, 0), Some(RgbaColor { red: 255, green: 0, blue: 0, alpha: 128 }));
+        assert_eq!(table.get(1, 1), Some(RgbaColor { red: 0, green: 0, blue: 255, alpha: 255 }));
+        // Out of bounds.
+        assert_eq!(table.get(0, 2), None);
+        assert_eq!(table.get(2, 0), None);
+
+        assert_eq!(table.palettes(), 2);
+        assert_eq!(table.palette_entries(), 2);
+        let palette = table.palette(1).unwrap();
+        assert_eq!(palette.len(), 2);
+        assert_eq!(palette.get(1), Some(RgbaColor { red: 0, green: 0, blue: 255, alpha: 255 }));
+        assert!(table.palette(2).is_none());
+        assert_eq!(table.palette_flags(1), Some(PaletteFlags(0)));
+        assert_eq!(table.palette_flags(2), None);
+        assert_eq!(table.palette_name_id(0), None);
+    }
+
+    #[test]
+    fn version_1() {
+        let data = &[
+            0x00, 0x01, // version: 1
+            0x00, 0x01, // number of palette entries: 1
+            0x00, 0x02, // number of palettes: 2
+            0x00, 0x02, // number of color records: 2
+            0x00, 0x00, 0x00, 0x1C, // color records offset: 28
+            0x00, 0x00, // color record index [0]: 0
+            0x00, 0x01, // color record index [1]: 1
+            0x00, 0x00, 0x00, 0x24, // palette types offset: 36
+            0x00, 0x00, 0x00, 0x2C, // palette labels offset: 44
+            0x00, 0x00, 0x00, 0x00, // palette entry labels offset: none
+
+            // Color records
+            0xFF, 0xFF, 0xFF, 0xFF, // [0]: white
+            0x00, 0x00, 0x00, 0xFF, // [1]: black
+
+            // Palette types
+            0x00, 0x00, 0x00, 0x02, // [0]: USABLE_WITH_DARK_BACKGROUND
+            0x00, 0x00, 0x00, 0x01, // [1]: USABLE_WITH_LIGHT_BACKGROUND
+
+            // Palette labels
+            0x01, 0x00, // [0]: 256
+            0xFF, 0xFF, // [1]: none
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.palettes(), 2);
+
+        let flags = table.palette_flags(0).unwrap();
+        assert!(flags.usable_with_dark_background());
+        assert!(!flags.usable_with_light_background());
+        assert!(table.palette_flags(1).unwrap().usable_with_light_background());
+
+        assert_eq!(table.palette_name_id(0), Some(256));
+        assert_eq!(table.palette_name_id(1), None);
+        assert_eq!(table.palette_entry_name_id(0), None);
+
+        let palette = table.palette(1).unwrap();
+        assert_eq!(palette.get(0), Some(RgbaColor { red: 0, green: 0, blue: 0, alpha: 255 }));
+    }
+}
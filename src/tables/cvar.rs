@@ -0,0 +1,145 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cvar
+// https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#tuple-variation-store
+
+use crate::NormalizedCoordinate;
+use crate::parser::{Stream, Offset, Offset16, LazyArray16};
+use crate::gvar::parse_tuple_variation_header;
+use crate::gvar::packed_points::PackedPointsIter;
+use crate::gvar::packed_deltas::PackedValuesIter;
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let version: u32 = s.read()?;
+        if version != 0x00010000 {
+            return None;
+        }
+
+        // Check that the header is present. Tuples are parsed on demand.
+        s.skip::<u16>(); // tuple variation count
+        s.read::<Offset16>()?;
+
+        Some(Table { data })
+    }
+
+    /// Returns an accumulated delta for a CVT value at `index`.
+    pub fn value_delta(&self, coordinates: &[NormalizedCoordinate], index: u16) -> Option<f32> {
+        const SHARED_POINT_NUMBERS_FLAG: u16 = 0x8000;
+        const COUNT_MASK: u16 = 0x0FFF;
+
+        let mut main_s = Stream::new_at(self.data, 4)?;
+        let tuple_variation_count: u16 = main_s.read()?;
+        let data_offset: Offset16 = main_s.read()?;
+
+        let has_shared_point_numbers = tuple_variation_count & SHARED_POINT_NUMBERS_FLAG != 0;
+        let tuple_variation_count = tuple_variation_count & COUNT_MASK;
+
+        let mut serialized_s = Stream::new_at(self.data, data_offset.to_usize())?;
+        let mut shared_point_numbers = None;
+        if has_shared_point_numbers {
+            shared_point_numbers = PackedPointsIter::new(&mut serialized_s)?;
+        }
+
+        // `cvar` has no shared tuples, so all tuples must be embedded.
+        let shared_tuple_records = LazyArray16::default();
+
+        let mut delta = 0.0;
+        for _ in 0..tuple_variation_count {
+            let header = parse_tuple_variation_header(coordinates, &shared_tuple_records, &mut main_s)?;
+            let start = serialized_s.offset();
+            let end = start.checked_add(usize::from(header.serialized_data_len))?;
+
+            if header.scalar > 0.0 {
+                let point_numbers = if header.has_private_point_numbers {
+                    PackedPointsIter::new(&mut serialized_s)?
+                } else {
+                    shared_point_numbers
+                };
+
+                let data = self.data.get(serialized_s.offset()..end)?;
+                let mut deltas = PackedValuesIter::new(header.scalar, data);
+                delta += match point_numbers {
+                    Some(point_numbers) => {
+                        // Point numbers are stored as differences from the previous one.
+                        // A point number can be repeated, in which case all its deltas are applied.
+                        let mut point = 0u16;
+                        point_numbers.zip(deltas).filter_map(|(n, d)| {
+                            point = point.checked_add(n)?;
+                            if point == index { Some(d) } else { None }
+                        }).sum()
+                    }
+                    // 'No point numbers' means that all CVT values have a delta.
+                    None => deltas.nth(usize::from(index)).unwrap_or(0.0),
+                };
+            }
+
+            serialized_s = Stream::new_at(self.data, end)?;
+        }
+
+        Some(delta)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_deltas() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x80, 0x02, // tuple variation count: 2 | SHARED_POINT_NUMBERS
+            0x00, 0x18, // data offset: 24
+
+            // Tuple variation header [0]
+            0x00, 0x03, // variation data size: 3
+            0x80, 0x00, // tuple index: EMBEDDED_PEAK_TUPLE
+            0x40, 0x00, // peak tuple: 1.0
+
+            // Tuple variation header [1]
+            0x00, 0x06, // variation data size: 6
+            0xA0, 0x00, // tuple index: EMBEDDED_PEAK_TUPLE | PRIVATE_POINT_NUMBERS
+            0xC0, 0x00, // peak tuple: -1.0
+
+            // Padding
+            0x00, 0x00, 0x00, 0x00,
+
+            // Shared point numbers
+            0x02, // count: 2
+            0x01, // control: 2 bytes
+            0x01, 0x02, // points: 1, 3
+
+            // Tuple [0] deltas
+            0x01, // control: 2 bytes
+            0x0A, 0xEC, // deltas: 10, -20
+
+            // Tuple [1] point numbers
+            0x00, // count: 0 (all points)
+            // Tuple [1] deltas
+            0x03, // control: 4 bytes
+            0x01, 0x02, 0x03, 0x04, // deltas: 1, 2, 3, 4
+        ];
+
+        let table = Table::parse(data).unwrap();
+
+        let coords = [NormalizedCoordinate::from(0.5)];
+        assert_eq!(table.value_delta(&coords, 0), Some(0.0));
+        assert_eq!(table.value_delta(&coords, 1), Some(5.0));
+        assert_eq!(table.value_delta(&coords, 2), Some(0.0));
+        assert_eq!(table.value_delta(&coords, 3), Some(-10.0));
+
+        let coords = [NormalizedCoordinate::from(-1.0)];
+        assert_eq!(table.value_delta(&coords, 0), Some(1.0));
+        assert_eq!(table.value_delta(&coords, 3), Some(4.0));
+        assert_eq!(table.value_delta(&coords, 4), Some(0.0));
+
+        let coords = [NormalizedCoordinate::from(0.0)];
+        assert_eq!(table.value_delta(&coords, 1), Some(0.0));
+    }
+}
@@ -0,0 +1,11 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cvt
+
+use crate::parser::{Stream, LazyArray16};
+
+
+pub fn parse(data: &[u8]) -> Option<LazyArray16<'_, i16>> {
+    // The number of values is implied by the table length.
+    // Values after `u16::MAX` are unaddressable anyway.
+    let count = core::cmp::min(data.len() / 2, 0xFFFF) as u16;
+    Stream::new(data).read_array16::<i16>(count)
+}
@@ -0,0 +1,207 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/dsig
+
+use crate::parser::{Stream, FromData, LazyArray16, NumFrom};
+
+
+#[derive(Clone, Copy)]
+struct SignatureRecord {
+    format: u32,
+    length: u32,
+    offset: u32,
+}
+
+impl FromData for SignatureRecord {
+    const SIZE: usize = 12;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(SignatureRecord {
+            format: s.read::<u32>()?,
+            length: s.read::<u32>()?,
+            offset: s.read::<u32>()?,
+        })
+    }
+}
+
+
+/// A [digital signature](https://docs.microsoft.com/en-us/typography/opentype/spec/dsig#table-structure).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Signature<'a> {
+    /// Signature format.
+    ///
+    /// Only format 1 is defined by the spec.
+    pub format: u32,
+
+    /// Signature data.
+    ///
+    /// For format 1 this is a PKCS#7 packet.
+    /// For unknown formats this is the whole signature block.
+    pub data: &'a [u8],
+}
+
+
+/// An iterator over face's digital signatures.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Signatures<'a> {
+    data: &'a [u8],
+    records: LazyArray16<'a, SignatureRecord>,
+    index: u16,
+}
+
+impl<'a> Iterator for Signatures<'a> {
+    type Item = Signature<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Malformed signatures are skipped.
+        while self.index < self.records.len() {
+            let record = self.records.get(self.index)?;
+            self.index += 1;
+
+            if let Some(signature) = parse_signature(self.data, record) {
+                return Some(signature);
+            }
+        }
+
+        None
+    }
+}
+
+fn parse_signature(data: &[u8], record: SignatureRecord) -> Option<Signature<'_>> {
+    let start = usize::num_from(record.offset);
+    let end = start.checked_add(usize::num_from(record.length))?;
+    let block = data.get(start..end)?;
+    let data = if record.format == 1 {
+        let mut s = Stream::new(block);
+        s.skip::<u16>(); // reserved1
+        s.skip::<u16>(); // reserved2
+        let len: u32 = s.read()?;
+        s.read_bytes(usize::num_from(len))?
+    } else {
+        block
+    };
+
+    Some(Signature {
+        format: record.format,
+        data,
+    })
+}
+
+
+#[derive(Clone, Copy)]
+pub struct Table<'a> {
+    pub flags: u16,
+    pub signatures: Signatures<'a>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+
+        let version: u32 = s.read()?;
+        if version != 1 {
+            return None;
+        }
+
+        let count: u16 = s.read()?;
+        let flags: u16 = s.read()?;
+        let records = s.read_array16::<SignatureRecord>(count)?;
+
+        Some(Table {
+            flags,
+            signatures: Signatures {
+                data,
+                records,
+                index: 0,
+            },
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_1() {
+        let data = &[
+            0x00, 0x00, 0x00, 0x01, // version: 1
+            0x00, 0x02, // number of signatures: 2
+            0x00, 0x01, // flags: 1
+            // Signature record 1
+            0x00, 0x00, 0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0B, // length: 11
+            0x00, 0x00, 0x00, 0x20, // offset: 32
+            // Signature record 2
+            0x00, 0x00, 0x00, 0x02, // format: 2
+            0x00, 0x00, 0x00, 0x02, // length: 2
+            0x00, 0x00, 0x00, 0x2B, // offset: 43
+            // Signature block 1
+            0x00, 0x00, // reserved1
+            0x00, 0x00, // reserved2
+            0x00, 0x00, 0x00, 0x03, // signature length: 3
+            0x30, 0x82, 0x01, // signature
+            // Signature block 2
+            0xAA, 0xBB,
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.flags, 1);
+
+        let mut signatures = table.signatures;
+        assert_eq!(signatures.count(), 2);
+        assert_eq!(signatures.next(), Some(Signature { format: 1, data: &[0x30, 0x82, 0x01] }));
+        assert_eq!(signatures.next(), Some(Signature { format: 2, data: &[0xAA, 0xBB] }));
+        assert_eq!(signatures.next(), None);
+    }
+
+    #[test]
+    fn malformed_block() {
+        let data = &[
+            0x00, 0x00, 0x00, 0x01, // version: 1
+            0x00, 0x01, // number of signatures: 1
+            0x00, 0x00, // flags: 0
+            // Signature record
+            0x00, 0x00, 0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0B, // length: 11
+            0x00, 0x00, 0x00, 0x14, // offset: 20
+            // Truncated signature block
+            0x00, 0x00,
+        ];
+
+        let mut signatures = Table::parse(data).unwrap().signatures;
+        assert_eq!(signatures.count(), 0);
+        assert_eq!(signatures.next(), None);
+    }
+
+    #[test]
+    fn malformed_record_in_the_middle() {
+        let data = &[
+            0x00, 0x00, 0x00, 0x01, // version: 1
+            0x00, 0x03, // number of signatures: 3
+            0x00, 0x00, // flags: 0
+            // Signature record 1
+            0x00, 0x00, 0x00, 0x02, // format: 2
+            0x00, 0x00, 0x00, 0x01, // length: 1
+            0x00, 0x00, 0x00, 0x2C, // offset: 44
+            // Signature record 2
+            0x00, 0x00, 0x00, 0x02, // format: 2
+            0x00, 0x00, 0x00, 0x10, // length: 16
+            0x00, 0x00, 0x00, 0x2C, // offset: 44 (out of bounds)
+            // Signature record 3
+            0x00, 0x00, 0x00, 0x02, // format: 2
+            0x00, 0x00, 0x00, 0x01, // length: 1
+            0x00, 0x00, 0x00, 0x2D, // offset: 45
+            // Signature blocks
+            0xAA, 0xBB,
+        ];
+
+        let mut signatures = Table::parse(data).unwrap().signatures;
+        assert_eq!(signatures.count(), 2);
+        assert_eq!(signatures.next(), Some(Signature { format: 2, data: &[0xAA] }));
+        assert_eq!(signatures.next(), Some(Signature { format: 2, data: &[0xBB] }));
+        assert_eq!(signatures.next(), None);
+    }
+}
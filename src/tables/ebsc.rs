@@ -0,0 +1,145 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/ebsc
+
+use crate::parser::{Stream, FromData, LazyArray32, LazyArrayIter32};
+
+
+/// A [bitmap scale](https://docs.microsoft.com/en-us/typography/opentype/spec/ebsc#bitmapscale-record).
+///
+/// Indicates that glyphs for `ppem_x`/`ppem_y` sizes should be produced
+/// by scaling bitmaps from the `substitute_ppem_x`/`substitute_ppem_y` strike.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BitmapScale {
+    /// A line ascender for the target size.
+    pub hor_ascender: i8,
+    /// A line descender for the target size.
+    pub hor_descender: i8,
+    pub ppem_x: u8,
+    pub ppem_y: u8,
+    pub substitute_ppem_x: u8,
+    pub substitute_ppem_y: u8,
+}
+
+impl FromData for BitmapScale {
+    const SIZE: usize = 28;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let hor_ascender: i8 = s.read()?;
+        let hor_descender: i8 = s.read()?;
+        s.advance(10); // the rest of the horizontal line metrics
+        s.advance(12); // vertical line metrics
+        Some(BitmapScale {
+            hor_ascender,
+            hor_descender,
+            ppem_x: s.read::<u8>()?,
+            ppem_y: s.read::<u8>()?,
+            substitute_ppem_x: s.read::<u8>()?,
+            substitute_ppem_y: s.read::<u8>()?,
+        })
+    }
+}
+
+
+/// An iterator over bitmap scales.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct BitmapScales<'a> {
+    iter: LazyArrayIter32<'a, BitmapScale>,
+}
+
+impl<'a> Iterator for BitmapScales<'a> {
+    type Item = BitmapScale;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    scales: LazyArray32<'a, BitmapScale>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let major_version: u16 = s.read()?;
+        if major_version != 2 {
+            return None;
+        }
+
+        s.skip::<u16>(); // minor version
+        let count: u32 = s.read()?;
+        let scales = s.read_array32::<BitmapScale>(count)?;
+        Some(Table { scales })
+    }
+
+    #[inline]
+    pub fn scales(&self) -> BitmapScales<'a> {
+        BitmapScales { iter: self.scales.into_iter() }
+    }
+
+    pub fn find(&self, ppem_x: u8, ppem_y: u8) -> Option<BitmapScale> {
+        self.scales().find(|scale| scale.ppem_x == ppem_x && scale.ppem_y == ppem_y)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales() {
+        let data = &[
+            0x00, 0x02, // major version: 2
+            0x00, 0x00, // minor version: 0
+            0x00, 0x00, 0x00, 0x02, // number of sizes: 2
+
+            // Bitmap scale [0]
+            0x0A, // horizontal ascender: 10
+            0xFD, // horizontal descender: -3
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // horizontal line metrics
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // vertical line metrics
+            0x0C, // ppem x: 12
+            0x0C, // ppem y: 12
+            0x18, // substitute ppem x: 24
+            0x18, // substitute ppem y: 24
+
+            // Bitmap scale [1]
+            0x0E, // horizontal ascender: 14
+            0xFC, // horizontal descender: -4
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // horizontal line metrics
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // vertical line metrics
+            0x10, // ppem x: 16
+            0x12, // ppem y: 18
+            0x20, // substitute ppem x: 32
+            0x24, // substitute ppem y: 36
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.scales().count(), 2);
+        assert_eq!(table.find(12, 12), Some(BitmapScale {
+            hor_ascender: 10,
+            hor_descender: -3,
+            ppem_x: 12,
+            ppem_y: 12,
+            substitute_ppem_x: 24,
+            substitute_ppem_y: 24,
+        }));
+        assert_eq!(table.find(16, 18).unwrap().substitute_ppem_y, 36);
+        assert!(table.find(16, 16).is_none());
+
+        // A truncated table.
+        assert!(Table::parse(&data[..35]).is_none());
+    }
+}
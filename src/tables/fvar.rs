@@ -17,6 +17,7 @@ pub struct VariationAxis {
     pub max_value: f32,
     /// An axis name in the `name` table.
     pub name_id: u16,
+    /// Indicates that the axis should not be exposed directly in user interfaces.
     pub hidden: bool,
 }
 
@@ -39,10 +40,25 @@ impl VariationAxis {
     }
 }
 
+const HIDDEN_AXIS: u16 = 0x0001;
+
+
+/// A [named instance](https://docs.microsoft.com/en-us/typography/opentype/spec/fvar#instancerecord).
+#[derive(Clone, Copy, Debug)]
+pub struct NamedInstance<'a> {
+    /// An instance subfamily name in the `name` table.
+    pub subfamily_name_id: u16,
+    /// An instance PostScript name in the `name` table.
+    pub post_script_name_id: Option<u16>,
+    /// User-space coordinates in the `fvar` axes order.
+    pub coordinates: LazyArray16<'a, Fixed>,
+}
+
 
 #[derive(Clone, Copy)]
 pub(crate) struct Table<'a> {
     axes: LazyArray16<'a, VariationAxisRecord>,
+    instances: NamedInstances<'a>,
 }
 
 impl<'a> Table<'a> {
@@ -56,6 +72,9 @@ impl<'a> Table<'a> {
         let axes_array_offset: Offset16 = s.read()?;
         s.skip::<u16>(); // reserved
         let axis_count: u16 = s.read()?;
+        let axis_size: u16 = s.read()?;
+        let instance_count: u16 = s.read()?;
+        let instance_size: u16 = s.read()?;
 
         // 'If axisCount is zero, then the font is not functional as a variable font,
         // and must be treated as a non-variable font;
@@ -65,13 +84,24 @@ impl<'a> Table<'a> {
         let mut s = Stream::new_at(data, axes_array_offset.to_usize())?;
         let axes = s.read_array16::<VariationAxisRecord>(axis_count.get())?;
 
-        Some(Table { axes })
+        // Instances are stored right after axes. Malformed instances are simply ignored.
+        let instances_offset = axes_array_offset.to_usize()
+            + usize::from(axis_count.get()) * usize::from(axis_size);
+        let instances = data.get(instances_offset..)
+            .and_then(|data| NamedInstances::parse(data, axis_count.get(), instance_count, instance_size))
+            .unwrap_or_default();
+
+        Some(Table { axes, instances })
     }
 
     pub fn axes(&self) -> VariationAxes<'a> {
         VariationAxes { iter: self.axes.into_iter() }
     }
 
+    pub fn named_instances(&self) -> NamedInstances<'a> {
+        self.instances
+    }
+
     // TODO: add axis_by_tag
 }
 
@@ -100,7 +130,7 @@ impl<'a> Iterator for VariationAxes<'a> {
             def_value,
             max_value,
             name_id: record.axis_name_id,
-            hidden: (record.flags >> 3) & 1 == 1,
+            hidden: record.flags & HIDDEN_AXIS != 0,
         })
     }
 
@@ -111,6 +141,75 @@ impl<'a> Iterator for VariationAxes<'a> {
 }
 
 
+/// An iterator over named instances.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy, Default)]
+pub struct NamedInstances<'a> {
+    data: &'a [u8],
+    axis_count: u16,
+    record_size: u16,
+    has_post_script_name_id: bool,
+}
+
+impl<'a> NamedInstances<'a> {
+    fn parse(data: &'a [u8], axis_count: u16, count: u16, record_size: u16) -> Option<Self> {
+        let coordinates_size = usize::from(axis_count) * Fixed::SIZE;
+        let has_post_script_name_id = if usize::from(record_size) == coordinates_size + 4 {
+            false
+        } else if usize::from(record_size) == coordinates_size + 6 {
+            true
+        } else {
+            return None;
+        };
+
+        let len = usize::from(count) * usize::from(record_size);
+        Some(NamedInstances {
+            data: data.get(0..len)?,
+            axis_count,
+            record_size,
+            has_post_script_name_id,
+        })
+    }
+}
+
+impl<'a> Iterator for NamedInstances<'a> {
+    type Item = NamedInstance<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let record_size = usize::from(self.record_size);
+        let record = self.data.get(..record_size)?;
+        self.data = &self.data[record_size..];
+
+        let mut s = Stream::new(record);
+        let subfamily_name_id: u16 = s.read()?;
+        s.skip::<u16>(); // flags
+        let coordinates = s.read_array16::<Fixed>(self.axis_count)?;
+        let post_script_name_id = if self.has_post_script_name_id {
+            // 'The value 0xFFFF indicates that no PostScript name is provided for the instance.'
+            Some(s.read::<u16>()?).filter(|id| *id != 0xFFFF)
+        } else {
+            None
+        };
+
+        Some(NamedInstance {
+            subfamily_name_id,
+            post_script_name_id,
+            coordinates,
+        })
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        if self.record_size == 0 {
+            0
+        } else {
+            self.data.len() / usize::from(self.record_size)
+        }
+    }
+}
+
+
 #[derive(Clone, Copy)]
 struct VariationAxisRecord {
     axis_tag: Tag,
@@ -137,3 +236,108 @@ impl FromData for VariationAxisRecord {
         })
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axes() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x10, // offset to axes array: 16
+            0x00, 0x02, // reserved
+            0x00, 0x02, // axis count: 2
+            0x00, 0x14, // axis size: 20
+            0x00, 0x02, // instance count: 2
+            0x00, 0x0E, // instance size: 14
+
+            // Axis [0]
+            0x77, 0x67, 0x68, 0x74, // axis tag: wght
+            0x00, 0xC8, 0x00, 0x00, // min value: 200
+            0x01, 0x90, 0x00, 0x00, // default value: 400
+            0x03, 0x84, 0x00, 0x00, // max value: 900
+            0x00, 0x00, // flags: 0
+            0x01, 0x00, // axis name ID: 256
+
+            // Axis [1]
+            0x6F, 0x70, 0x73, 0x7A, // axis tag: opsz
+            0x00, 0x08, 0x00, 0x00, // min value: 8
+            0x00, 0x0C, 0x00, 0x00, // default value: 12
+            0x00, 0x48, 0x00, 0x00, // max value: 72
+            0x00, 0x01, // flags: HIDDEN_AXIS
+            0x01, 0x01, // axis name ID: 257
+
+            // Instance [0]
+            0x01, 0x02, // subfamily name ID: 258
+            0x00, 0x00, // flags: 0
+            0x01, 0x90, 0x00, 0x00, // coordinate [0]: 400
+            0x00, 0x0C, 0x00, 0x00, // coordinate [1]: 12
+            0x01, 0x03, // PostScript name ID: 259
+
+            // Instance [1]
+            0x01, 0x04, // subfamily name ID: 260
+            0x00, 0x00, // flags: 0
+            0x02, 0xBC, 0x80, 0x00, // coordinate [0]: 700.5
+            0x00, 0x0C, 0x00, 0x00, // coordinate [1]: 12
+            0xFF, 0xFF, // PostScript name ID: none
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.axes().count(), 2);
+
+        let mut axes = table.axes();
+        assert_eq!(axes.next(), Some(VariationAxis {
+            tag: Tag::from_bytes(b"wght"),
+            min_value: 200.0,
+            def_value: 400.0,
+            max_value: 900.0,
+            name_id: 256,
+            hidden: false,
+        }));
+        assert_eq!(axes.next(), Some(VariationAxis {
+            tag: Tag::from_bytes(b"opsz"),
+            min_value: 8.0,
+            def_value: 12.0,
+            max_value: 72.0,
+            name_id: 257,
+            hidden: true,
+        }));
+        assert!(axes.next().is_none());
+
+        assert_eq!(table.named_instances().count(), 2);
+        let mut instances = table.named_instances();
+        let instance = instances.next().unwrap();
+        assert_eq!(instance.subfamily_name_id, 258);
+        assert_eq!(instance.post_script_name_id, Some(259));
+        assert_eq!(instance.coordinates.len(), 2);
+        assert_eq!(instance.coordinates.get(0).unwrap().0, 400.0);
+        assert_eq!(instance.coordinates.get(1).unwrap().0, 12.0);
+        let instance = instances.next().unwrap();
+        assert_eq!(instance.subfamily_name_id, 260);
+        assert_eq!(instance.post_script_name_id, None);
+        assert_eq!(instance.coordinates.get(0).unwrap().0, 700.5);
+        assert!(instances.next().is_none());
+
+        // Instances without a PostScript name ID.
+        let mut short = data.to_vec();
+        short[15] = 0x0C;
+        let table = Table::parse(&short).unwrap();
+        let mut instances = table.named_instances();
+        assert_eq!(instances.next().unwrap().post_script_name_id, None);
+        assert_eq!(instances.next().unwrap().subfamily_name_id, 0x0103);
+
+        // Malformed instances are ignored.
+        let mut malformed = data.to_vec();
+        malformed[15] = 0x0F;
+        let table = Table::parse(&malformed).unwrap();
+        assert_eq!(table.axes().count(), 2);
+        assert_eq!(table.named_instances().count(), 0);
+
+        // No axes.
+        let mut data = data.to_vec();
+        data[9] = 0;
+        assert!(Table::parse(&data).is_none());
+    }
+}
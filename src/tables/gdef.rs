@@ -2,7 +2,7 @@
 
 use crate::GlyphId;
 use crate::parser::{Stream, Offset, Offset16, Offset32, LazyArray16};
-use crate::ggg::{Class, ClassDefinitionTable, CoverageTable};
+use crate::ggg::{Class, ClassDefinitionTable, CoverageTable, Device, LookupFlags};
 
 #[cfg(feature = "variable-fonts")] use crate::NormalizedCoordinate;
 #[cfg(feature = "variable-fonts")] use crate::var_store::ItemVariationStore;
@@ -19,10 +19,102 @@ pub enum GlyphClass {
 }
 
 
+/// A [ligature caret](https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#caret-value-tables).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LigatureCaret {
+    /// A caret position in font units along the text direction.
+    ///
+    /// Variation deltas are already applied.
+    Coordinate(f32),
+
+    /// An index of a glyph contour point that defines the caret position.
+    ContourPoint(u16),
+}
+
+
+/// An iterator over ligature carets.
+///
+/// Carets are yielded in the text direction order, one less than the number of components.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct LigatureCarets<'a> {
+    data: &'a [u8],
+    offsets: LazyArray16<'a, Offset16>,
+    index: u16,
+    #[cfg(feature = "variable-fonts")] variation_store: Option<ItemVariationStore<'a>>,
+    #[cfg(feature = "variable-fonts")] coordinates: &'a [NormalizedCoordinate],
+}
+
+impl<'a> LigatureCarets<'a> {
+    #[cfg(feature = "variable-fonts")]
+    #[inline]
+    pub(crate) fn with_coordinates(self, coordinates: &'a [NormalizedCoordinate]) -> Self {
+        LigatureCarets { coordinates, ..self }
+    }
+
+    fn parse_caret(&self, data: &'a [u8]) -> Option<LigatureCaret> {
+        let mut s = Stream::new(data);
+        let format: u16 = s.read()?;
+        match format {
+            1 => Some(LigatureCaret::Coordinate(f32::from(s.read::<i16>()?))),
+            2 => Some(LigatureCaret::ContourPoint(s.read()?)),
+            3 => {
+                let coordinate = f32::from(s.read::<i16>()?);
+                let offset: Option<Offset16> = s.read()?;
+                let device = offset.and_then(|o| data.get(o.to_usize()..)).and_then(Device::parse);
+                Some(LigatureCaret::Coordinate(coordinate + self.device_delta(device)))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "variable-fonts")]
+    fn device_delta(&self, device: Option<Device>) -> f32 {
+        // Default instance.
+        if self.coordinates.is_empty() {
+            return 0.0;
+        }
+
+        // Hinting devices require a ppem and are ignored.
+        let delta = || {
+            let (outer, inner) = device?.variation_index()?;
+            self.variation_store?.parse_delta(outer, inner, self.coordinates)
+        };
+
+        delta().unwrap_or(0.0)
+    }
+
+    #[cfg(not(feature = "variable-fonts"))]
+    fn device_delta(&self, _: Option<Device>) -> f32 {
+        0.0
+    }
+}
+
+impl<'a> Iterator for LigatureCarets<'a> {
+    type Item = LigatureCaret;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Malformed carets are skipped.
+        while self.index < self.offsets.len() {
+            let offset = self.offsets.get(self.index)?;
+            self.index += 1;
+
+            let caret = self.data.get(offset.to_usize()..).and_then(|data| self.parse_caret(data));
+            if caret.is_some() {
+                return caret;
+            }
+        }
+
+        None
+    }
+}
+
+
 #[derive(Clone, Copy, Default)]
 pub struct Table<'a> {
     glyph_classes: Option<ClassDefinitionTable<'a>>,
     mark_attach_classes: Option<ClassDefinitionTable<'a>>,
+    ligature_carets: Option<&'a [u8]>,
     mark_glyph_coverage_offsets: Option<(&'a [u8], LazyArray16<'a, Offset32>)>,
     #[cfg(feature = "variable-fonts")] variation_store: Option<ItemVariationStore<'a>>,
 }
@@ -37,7 +129,7 @@ impl<'a> Table<'a> {
 
         let glyph_class_def_offset: Option<Offset16> = s.read()?;
         s.skip::<Offset16>(); // attachListOffset
-        s.skip::<Offset16>(); // ligCaretListOffset
+        let lig_caret_list_offset: Option<Offset16> = s.read()?;
         let mark_attach_class_def_offset: Option<Offset16> = s.read()?;
 
         let mut mark_glyph_sets_def_offset: Option<Offset16> = None;
@@ -64,6 +156,10 @@ impl<'a> Table<'a> {
             }
         }
 
+        if let Some(offset) = lig_caret_list_offset {
+            table.ligature_carets = data.get(offset.to_usize()..);
+        }
+
         if let Some(offset) = mark_attach_class_def_offset {
             if let Some(subdata) = data.get(offset.to_usize()..) {
                 table.mark_attach_classes = Some(ClassDefinitionTable::new(subdata));
@@ -72,9 +168,9 @@ impl<'a> Table<'a> {
 
         if let Some(offset) = mark_glyph_sets_def_offset {
             if let Some(subdata) = data.get(offset.to_usize()..) {
+                // A malformed mark glyph sets table must not affect the rest of GDEF.
                 let mut s = Stream::new(subdata);
-                let format: u16 = s.read()?;
-                if format == 1 {
+                if s.read::<u16>() == Some(1) { // format
                     if let Some(count) = s.read::<u16>() {
                         if let Some(array) = s.read_array16::<Offset32>(count) {
                             table.mark_glyph_coverage_offsets = Some((subdata, array));
@@ -125,6 +221,68 @@ impl<'a> Table<'a> {
         is_mark_glyph_impl(self, glyph_id, set_index).is_some()
     }
 
+    #[inline]
+    pub fn mark_glyph_sets_count(&self) -> u16 {
+        self.mark_glyph_coverage_offsets.map(|(_, offsets)| offsets.len()).unwrap_or(0)
+    }
+
+    pub fn ignores_glyph(
+        &self,
+        glyph_id: GlyphId,
+        flags: LookupFlags,
+        mark_filtering_set: Option<u16>,
+    ) -> bool {
+        match self.glyph_class(glyph_id) {
+            Some(GlyphClass::Base) => flags.ignore_base_glyphs(),
+            Some(GlyphClass::Ligature) => flags.ignore_ligatures(),
+            Some(GlyphClass::Mark) => {
+                if flags.ignore_marks() {
+                    return true;
+                }
+
+                if flags.use_mark_filtering_set() {
+                    // A set index is required, otherwise all marks are ignored.
+                    return match mark_filtering_set {
+                        Some(index) => !self.is_mark_glyph(glyph_id, Some(index)),
+                        None => true,
+                    };
+                }
+
+                match flags.mark_attachment_type() {
+                    Some(class) => self.glyph_mark_attachment_class(glyph_id).0 != u16::from(class),
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    pub fn ligature_carets(&self, glyph_id: GlyphId) -> Option<LigatureCarets<'a>> {
+        let data = self.ligature_carets?;
+        let mut s = Stream::new(data);
+        let coverage_offset: Offset16 = s.read()?;
+        let count: u16 = s.read()?;
+        let offsets = s.read_array16::<Offset16>(count)?;
+
+        let coverage = CoverageTable::new(data.get(coverage_offset.to_usize()..)?);
+        let data = data.get(offsets.get(coverage.get(glyph_id)?)?.to_usize()..)?;
+        let mut s = Stream::new(data);
+        let count: u16 = s.read()?;
+        Some(LigatureCarets {
+            data,
+            offsets: s.read_array16::<Offset16>(count)?,
+            index: 0,
+            #[cfg(feature = "variable-fonts")] variation_store: self.variation_store,
+            #[cfg(feature = "variable-fonts")] coordinates: &[],
+        })
+    }
+
+    #[cfg(feature = "variable-fonts")]
+    #[inline]
+    pub fn variation_store(&self) -> Option<ItemVariationStore<'a>> {
+        self.variation_store
+    }
+
     #[cfg(feature = "variable-fonts")]
     #[inline]
     pub fn variation_delta(
@@ -164,3 +322,224 @@ fn is_mark_glyph_impl(
 
     None
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_classes() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0C, // glyph class def offset: 12
+            0x00, 0x00, // attach list offset: 0
+            0x00, 0x00, // lig caret list offset: 0
+            0x00, 0x00, // mark attach class def offset: 0
+            // Class definition table
+            0x00, 0x02, // format: 2
+            0x00, 0x03, // range count: 3
+            0x00, 0x01, 0x00, 0x02, 0x00, 0x01, // range [0]: 1..2 -> base
+            0x00, 0x03, 0x00, 0x03, 0x00, 0x02, // range [1]: 3 -> ligature
+            0x00, 0x04, 0x00, 0x05, 0x00, 0x03, // range [2]: 4..5 -> mark
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert!(table.has_glyph_classes());
+        assert_eq!(table.glyph_class(GlyphId(0)), None);
+        assert_eq!(table.glyph_class(GlyphId(2)), Some(GlyphClass::Base));
+        assert_eq!(table.glyph_class(GlyphId(3)), Some(GlyphClass::Ligature));
+        assert_eq!(table.glyph_class(GlyphId(5)), Some(GlyphClass::Mark));
+        assert_eq!(table.glyph_class(GlyphId(6)), None);
+        assert_eq!(table.glyph_mark_attachment_class(GlyphId(5)), Class(0));
+    }
+
+    #[test]
+    fn mark_filtering() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x02, // version: 1.2
+            0x00, 0x0E, // glyph class def offset: 14
+            0x00, 0x00, // attach list offset: 0
+            0x00, 0x00, // lig caret list offset: 0
+            0x00, 0x1E, // mark attach class def offset: 30
+            0x00, 0x2A, // mark glyph sets def offset: 42
+            // Glyph class definition table
+            0x00, 0x02, // format: 2
+            0x00, 0x02, // range count: 2
+            0x00, 0x01, 0x00, 0x01, 0x00, 0x01, // range [0]: 1 -> base
+            0x00, 0x02, 0x00, 0x04, 0x00, 0x03, // range [1]: 2..4 -> mark
+            // Mark attachment class definition table
+            0x00, 0x01, // format: 1
+            0x00, 0x02, // start glyph: 2
+            0x00, 0x03, // glyph count: 3
+            0x00, 0x01, // class [0]: 1
+            0x00, 0x02, // class [1]: 2
+            0x00, 0x02, // class [2]: 2
+            // Mark glyph sets table
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // mark glyph set count: 1
+            0x00, 0x00, 0x00, 0x08, // coverage offset [0]: 8
+            // Coverage table
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // glyph count: 1
+            0x00, 0x03, // glyph [0]: 3
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.glyph_mark_attachment_class(GlyphId(2)), Class(1));
+        assert_eq!(table.glyph_mark_attachment_class(GlyphId(4)), Class(2));
+        assert_eq!(table.mark_glyph_sets_count(), 1);
+        assert!(table.is_mark_glyph(GlyphId(3), Some(0)));
+        assert!(!table.is_mark_glyph(GlyphId(2), None));
+        assert!(!table.is_mark_glyph(GlyphId(3), Some(1)));
+
+        // Ignore marks.
+        let flags = LookupFlags(0x0008);
+        assert!(table.ignores_glyph(GlyphId(2), flags, None));
+        assert!(!table.ignores_glyph(GlyphId(1), flags, None));
+
+        // Ignore base glyphs.
+        assert!(table.ignores_glyph(GlyphId(1), LookupFlags(0x0002), None));
+
+        // Use mark filtering set.
+        let flags = LookupFlags(0x0010);
+        assert!(table.ignores_glyph(GlyphId(2), flags, Some(0)));
+        assert!(!table.ignores_glyph(GlyphId(3), flags, Some(0)));
+        assert!(table.ignores_glyph(GlyphId(3), flags, None));
+
+        // Mark attachment type 2.
+        let flags = LookupFlags(0x0200);
+        assert!(table.ignores_glyph(GlyphId(2), flags, None));
+        assert!(!table.ignores_glyph(GlyphId(3), flags, None));
+        assert!(!table.ignores_glyph(GlyphId(1), flags, None));
+    }
+
+    #[test]
+    fn malformed_mark_glyph_sets() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x02, // version: 1.2
+            0x00, 0x00, // glyph class def offset: 0
+            0x00, 0x00, // attach list offset: 0
+            0x00, 0x00, // lig caret list offset: 0
+            0x00, 0x00, // mark attach class def offset: 0
+            0x00, 0x0E, // mark glyph sets def offset: 14
+            // Mark glyph sets table
+            0x00, // truncated format
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.mark_glyph_sets_count(), 0);
+    }
+
+    #[test]
+    fn ligature_carets() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x03, // version: 1.3
+            0x00, 0x00, // glyph class def offset: 0
+            0x00, 0x00, // attach list offset: 0
+            0x00, 0x12, // lig caret list offset: 18
+            0x00, 0x00, // mark attach class def offset: 0
+            0x00, 0x00, // mark glyph sets def offset: 0
+            0x00, 0x00, 0x00, 0x34, // item variation store offset: 52
+            // Ligature caret list
+            0x00, 0x06, // coverage offset: 6
+            0x00, 0x01, // lig glyph count: 1
+            0x00, 0x0C, // lig glyph offset [0]: 12
+            // Coverage table
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // glyph [0]: 5
+            // Ligature glyph
+            0x00, 0x02, // caret count: 2
+            0x00, 0x06, // caret value offset [0]: 6
+            0x00, 0x0A, // caret value offset [1]: 10
+            // Caret value 1
+            0x00, 0x01, // format: 1
+            0x01, 0x2C, // coordinate: 300
+            // Caret value 2
+            0x00, 0x03, // format: 3
+            0x02, 0x58, // coordinate: 600
+            0x00, 0x06, // device offset: 6
+            // Device
+            0x00, 0x00, // outer index: 0
+            0x00, 0x00, // inner index: 0
+            0x80, 0x00, // format: variation index
+            // Item variation store
+            0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0C, // region list offset: 12
+            0x00, 0x01, // data count: 1
+            0x00, 0x00, 0x00, 0x16, // data offset [0]: 22
+            // Region list
+            0x00, 0x01, // axis count: 1
+            0x00, 0x01, // region count: 1
+            0x00, 0x00, 0x40, 0x00, 0x40, 0x00, // region 0: 0, 1, 1
+            // Item variation data
+            0x00, 0x01, // item count: 1
+            0x00, 0x01, // short delta count: 1
+            0x00, 0x01, // region index count: 1
+            0x00, 0x00, // region index [0]: 0
+            0x00, 0x64, // item 0 delta: 100
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert!(table.ligature_carets(GlyphId(4)).is_none());
+
+        let carets = table.ligature_carets(GlyphId(5)).unwrap();
+        assert_eq!(carets.count(), 2);
+
+        let mut iter = carets;
+        assert_eq!(iter.next(), Some(LigatureCaret::Coordinate(300.0)));
+        assert_eq!(iter.next(), Some(LigatureCaret::Coordinate(600.0)));
+        assert_eq!(iter.next(), None);
+
+        #[cfg(feature = "variable-fonts")]
+        {
+            let coords = [NormalizedCoordinate::from(0.5)];
+            let mut iter = carets.with_coordinates(&coords);
+            assert_eq!(iter.next(), Some(LigatureCaret::Coordinate(300.0)));
+            assert_eq!(iter.next(), Some(LigatureCaret::Coordinate(650.0)));
+        }
+    }
+
+    #[test]
+    fn malformed_ligature_caret() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // glyph class def offset: 0
+            0x00, 0x00, // attach list offset: 0
+            0x00, 0x0C, // lig caret list offset: 12
+            0x00, 0x00, // mark attach class def offset: 0
+            // Ligature caret list
+            0x00, 0x06, // coverage offset: 6
+            0x00, 0x01, // lig glyph count: 1
+            0x00, 0x0C, // lig glyph offset [0]: 12
+            // Coverage table
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // glyph [0]: 5
+            // Ligature glyph
+            0x00, 0x03, // caret count: 3
+            0x00, 0x08, // caret value offset [0]: 8
+            0x00, 0x0C, // caret value offset [1]: 12
+            0x00, 0x10, // caret value offset [2]: 16
+            // Caret value 1
+            0x00, 0x01, // format: 1
+            0x01, 0x2C, // coordinate: 300
+            // Caret value 2
+            0x00, 0x09, // format: 9 (invalid)
+            0x00, 0x00,
+            // Caret value 3
+            0x00, 0x02, // format: 2
+            0x00, 0x07, // contour point index: 7
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let carets = table.ligature_carets(GlyphId(5)).unwrap();
+        assert_eq!(carets.count(), 2);
+
+        let mut iter = carets;
+        assert_eq!(iter.next(), Some(LigatureCaret::Coordinate(300.0)));
+        assert_eq!(iter.next(), Some(LigatureCaret::ContourPoint(7)));
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -2,8 +2,8 @@
 
 use core::num::NonZeroU16;
 
-use crate::parser::{Stream, F2DOT14, LazyArray16, NumFrom};
-use crate::{loca, GlyphId, OutlineBuilder, Rect, BBox};
+use crate::parser::{Stream, FromData, F2DOT14, LazyArray16, NumFrom};
+use crate::{loca, DummyOutline, GlyphId, GlyphStats, OutlineBuilder, Rect, BBox};
 
 pub(crate) struct Builder<'a> {
     pub builder: &'a mut dyn OutlineBuilder,
@@ -206,18 +206,38 @@ pub(crate) struct CompositeGlyphInfo {
     pub glyph_id: GlyphId,
     pub transform: Transform,
     pub flags: CompositeGlyphFlags,
+    // Parent and child point numbers, when `ARGS_ARE_XY_VALUES` is not set.
+    #[cfg_attr(not(feature = "hinting"), allow(dead_code))]
+    pub point_numbers: Option<(u16, u16)>,
 }
 
 
 #[derive(Clone)]
 pub(crate) struct CompositeGlyphIter<'a> {
     stream: Stream<'a>,
+    finished: bool,
+    has_instructions: bool,
 }
 
 impl<'a> CompositeGlyphIter<'a> {
     #[inline]
     pub fn new(data: &'a [u8]) -> Self {
-        CompositeGlyphIter { stream: Stream::new(data) }
+        CompositeGlyphIter { stream: Stream::new(data), finished: false, has_instructions: false }
+    }
+
+    /// Returns instructions that follow the last component.
+    ///
+    /// Returns `None` when iterator was not exhausted yet or
+    /// no component has the `WE_HAVE_INSTRUCTIONS` flag set.
+    #[inline]
+    pub fn instructions(&self) -> Option<&'a [u8]> {
+        if !self.finished || !self.has_instructions {
+            return None;
+        }
+
+        let mut s = self.stream;
+        let len: u16 = s.read()?;
+        s.read_bytes(usize::from(len))
     }
 }
 
@@ -226,10 +246,15 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
         let flags = CompositeGlyphFlags(self.stream.read::<u16>()?);
         let glyph_id: GlyphId = self.stream.read()?;
 
         let mut ts = Transform::default();
+        let mut point_numbers = None;
 
         if flags.args_are_xy_values() {
             if flags.arg_1_and_2_are_words() {
@@ -239,6 +264,13 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
                 ts.e = f32::from(self.stream.read::<i8>()?);
                 ts.f = f32::from(self.stream.read::<i8>()?);
             }
+        } else if flags.arg_1_and_2_are_words() {
+            point_numbers = Some((self.stream.read::<u16>()?, self.stream.read::<u16>()?));
+        } else {
+            point_numbers = Some((
+                u16::from(self.stream.read::<u8>()?),
+                u16::from(self.stream.read::<u8>()?),
+            ));
         }
 
         if flags.we_have_a_two_by_two() {
@@ -254,15 +286,20 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
             ts.d = ts.a;
         }
 
+        if flags.we_have_instructions() {
+            self.has_instructions = true;
+        }
+
         if !flags.more_components() {
             // Finish the iterator even if stream still has some data.
-            self.stream.jump_to_end();
+            self.finished = true;
         }
 
         Some(CompositeGlyphInfo {
             glyph_id,
             transform: ts,
             flags,
+            point_numbers,
         })
     }
 }
@@ -472,6 +509,7 @@ impl SimpleGlyphFlags {
     #[inline] fn repeat_flag(self) -> bool { self.0 & 0x08 != 0 }
     #[inline] fn x_is_same_or_positive_short(self) -> bool { self.0 & 0x10 != 0 }
     #[inline] fn y_is_same_or_positive_short(self) -> bool { self.0 & 0x20 != 0 }
+    #[inline] fn overlap_simple(self) -> bool { self.0 & 0x40 != 0 }
 }
 
 
@@ -482,10 +520,16 @@ pub(crate) struct CompositeGlyphFlags(u16);
 impl CompositeGlyphFlags {
     #[inline] pub fn arg_1_and_2_are_words(self) -> bool { self.0 & 0x0001 != 0 }
     #[inline] pub fn args_are_xy_values(self) -> bool { self.0 & 0x0002 != 0 }
+    #[cfg(feature = "hinting")]
+    #[inline] pub fn round_xy_to_grid(self) -> bool { self.0 & 0x0004 != 0 }
     #[inline] pub fn we_have_a_scale(self) -> bool { self.0 & 0x0008 != 0 }
     #[inline] pub fn more_components(self) -> bool { self.0 & 0x0020 != 0 }
     #[inline] pub fn we_have_an_x_and_y_scale(self) -> bool { self.0 & 0x0040 != 0 }
     #[inline] pub fn we_have_a_two_by_two(self) -> bool { self.0 & 0x0080 != 0 }
+    #[inline] pub fn we_have_instructions(self) -> bool { self.0 & 0x0100 != 0 }
+    #[cfg(feature = "hinting")]
+    #[inline] pub fn use_my_metrics(self) -> bool { self.0 & 0x0200 != 0 }
+    #[inline] pub fn overlap_compound(self) -> bool { self.0 & 0x0400 != 0 }
 }
 
 
@@ -516,12 +560,171 @@ pub(crate) fn glyph_bbox(
     let mut s = Stream::new(glyph_data);
     s.skip::<i16>(); // number_of_contours
     // It's faster to parse the rect directly, instead of using `FromData`.
-    Some(Rect {
+    let rect = Rect {
         x_min: s.read::<i16>()?,
         y_min: s.read::<i16>()?,
         x_max: s.read::<i16>()?,
         y_max: s.read::<i16>()?,
-    })
+    };
+
+    // `Rect::width` and `Rect::height` can overflow on malformed values.
+    if rect.x_max > rect.x_min && rect.y_max > rect.y_min {
+        Some(rect)
+    } else {
+        // Fallback to a calculated bbox, just like `outline` does.
+        outline(loca_table, glyf_table, glyph_id, &mut DummyOutline)
+    }
+}
+
+#[inline]
+pub(crate) fn glyph_stats(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    glyph_id: GlyphId,
+) -> Option<GlyphStats> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut stats = GlyphStats {
+        number_of_contours: 0,
+        number_of_points: 0,
+        is_composite: false,
+    };
+    glyph_stats_impl(loca_table, glyf_table, glyph_data, 0, &mut stats)?;
+    Some(stats)
+}
+
+fn glyph_stats_impl(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    data: &[u8],
+    depth: u8,
+    stats: &mut GlyphStats,
+) -> Option<()> {
+    if depth >= MAX_COMPONENTS {
+        return None;
+    }
+
+    let mut s = Stream::new(data);
+    let number_of_contours: i16 = s.read()?;
+    s.advance(8); // bbox
+
+    if number_of_contours > 0 {
+        // Simple glyph.
+
+        // u16 casting is safe, since we already checked that the value is positive.
+        let number_of_contours = number_of_contours as u16;
+        let endpoints = s.read_array16::<u16>(number_of_contours)?;
+        let points_total = endpoints.last()?.checked_add(1)?;
+        stats.number_of_contours = stats.number_of_contours.saturating_add(number_of_contours);
+        stats.number_of_points = stats.number_of_points.saturating_add(points_total);
+    } else if number_of_contours < 0 {
+        // Composite glyph.
+        if depth == 0 {
+            stats.is_composite = true;
+        }
+
+        for comp in CompositeGlyphIter::new(s.tail()?) {
+            if let Some(range) = loca_table.glyph_range(comp.glyph_id) {
+                if let Some(glyph_data) = glyf_table.get(range) {
+                    glyph_stats_impl(loca_table, glyf_table, glyph_data, depth + 1, stats)?;
+                }
+            }
+        }
+    }
+
+    Some(())
+}
+
+#[inline]
+pub(crate) fn glyph_instructions<'a>(
+    loca_table: loca::Table,
+    glyf_table: &'a [u8],
+    glyph_id: GlyphId,
+) -> Option<&'a [u8]> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut s = Stream::new(glyph_data);
+    let number_of_contours: i16 = s.read()?;
+    s.advance(8); // bbox
+
+    let instructions = if number_of_contours > 0 {
+        // u16 casting is safe, since we already checked that the value is positive.
+        s.advance(usize::from(number_of_contours as u16) * u16::SIZE); // endPtsOfContours
+        let len: u16 = s.read()?;
+        s.read_bytes(usize::from(len))?
+    } else if number_of_contours < 0 {
+        let mut iter = CompositeGlyphIter::new(s.tail()?);
+        while iter.next().is_some() {}
+        iter.instructions()?
+    } else {
+        return None;
+    };
+
+    if !instructions.is_empty() {
+        Some(instructions)
+    } else {
+        None
+    }
+}
+
+#[inline]
+pub(crate) fn glyph_has_overlap(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    glyph_id: GlyphId,
+) -> Option<bool> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    glyph_has_overlap_impl(loca_table, glyf_table, glyph_data, 0)
+}
+
+fn glyph_has_overlap_impl(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    data: &[u8],
+    depth: u8,
+) -> Option<bool> {
+    if depth >= MAX_COMPONENTS {
+        return None;
+    }
+
+    let mut s = Stream::new(data);
+    let number_of_contours: i16 = s.read()?;
+    s.advance(8); // bbox
+
+    if number_of_contours > 0 {
+        // Simple glyph.
+        // The flag is defined only for the first point.
+
+        // u16 casting is safe, since we already checked that the value is positive.
+        s.advance(usize::from(number_of_contours as u16) * u16::SIZE); // endPtsOfContours
+        let instructions_len: u16 = s.read()?;
+        s.advance(usize::from(instructions_len));
+        let flags = SimpleGlyphFlags(s.read::<u8>()?);
+        Some(flags.overlap_simple())
+    } else if number_of_contours < 0 {
+        // Composite glyph.
+        // The flag must be set on the first component, but we also check
+        // the remaining ones and the referenced glyphs.
+        for comp in CompositeGlyphIter::new(s.tail()?) {
+            if comp.flags.overlap_compound() {
+                return Some(true);
+            }
+
+            if let Some(range) = loca_table.glyph_range(comp.glyph_id) {
+                if let Some(glyph_data) = glyf_table.get(range) {
+                    if glyph_has_overlap_impl(loca_table, glyf_table, glyph_data, depth + 1)? {
+                        return Some(true);
+                    }
+                }
+            }
+        }
+
+        Some(false)
+    } else {
+        // An empty glyph.
+        Some(false)
+    }
 }
 
 #[inline]
@@ -557,10 +760,18 @@ fn outline_impl(
         }
     } else if number_of_contours < 0 {
         // Composite glyph.
-        for comp in CompositeGlyphIter::new(s.tail()?) {
+        let components = s.tail()?;
+        for (i, comp) in CompositeGlyphIter::new(components).enumerate() {
             if let Some(range) = loca_table.glyph_range(comp.glyph_id) {
                 if let Some(glyph_data) = glyf_table.get(range) {
-                    let transform = Transform::combine(builder.transform, comp.transform);
+                    // An empty component, like a space, has nothing to outline.
+                    if Stream::read_at::<i16>(glyph_data, 0) == Some(0) {
+                        continue;
+                    }
+
+                    let ts = component_transform(loca_table, glyf_table, components, i, comp, depth)
+                        .unwrap_or(comp.transform);
+                    let transform = Transform::combine(builder.transform, ts);
                     let mut b = Builder::new(transform, builder.bbox, builder.builder);
                     outline_impl(loca_table, glyf_table, glyph_data, depth + 1, &mut b)?;
 
@@ -574,7 +785,7 @@ fn outline_impl(
         return None;
     }
 
-    let rect_is_valid = rect.width() > 0 && rect.height() > 0;
+    let rect_is_valid = rect.x_max > rect.x_min && rect.y_max > rect.y_min;
     if rect_is_valid {
         Some(rect)
     } else {
@@ -582,6 +793,99 @@ fn outline_impl(
     }
 }
 
+// Resolves a component transform.
+//
+// When a component is positioned by matching points instead of an offset,
+// the offset is calculated using a point of the already placed components.
+fn component_transform(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    components: &[u8],
+    index: usize,
+    comp: CompositeGlyphInfo,
+    depth: u8,
+) -> Option<Transform> {
+    let mut ts = comp.transform;
+    if let Some((parent_point, child_point)) = comp.point_numbers {
+        let p1 = composite_point(loca_table, glyf_table, components, parent_point, index, depth)?;
+        let glyph_data = glyf_table.get(loca_table.glyph_range(comp.glyph_id)?)?;
+        let mut p2 = glyph_point(loca_table, glyf_table, glyph_data, child_point, depth + 1)?;
+        // Only the linear part of the transform is applied to the child point.
+        Transform { e: 0.0, f: 0.0, ..ts }.apply_to(&mut p2.x, &mut p2.y);
+        ts.e = p1.x - p2.x;
+        ts.f = p1.y - p2.y;
+    }
+
+    Some(ts)
+}
+
+// Returns a composite glyph point by index, with component transforms applied.
+//
+// Only the first `components_limit` components are checked,
+// so a component cannot reference its own points.
+fn composite_point(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    components: &[u8],
+    point: u16,
+    components_limit: usize,
+    depth: u8,
+) -> Option<Point> {
+    let mut first_point = 0u16;
+    for (i, comp) in CompositeGlyphIter::new(components).enumerate().take(components_limit) {
+        let glyph_data = match loca_table.glyph_range(comp.glyph_id) {
+            Some(range) => glyf_table.get(range)?,
+            None => continue,
+        };
+
+        let mut stats = GlyphStats {
+            number_of_contours: 0,
+            number_of_points: 0,
+            is_composite: false,
+        };
+        glyph_stats_impl(loca_table, glyf_table, glyph_data, depth + 1, &mut stats)?;
+
+        if point < first_point.saturating_add(stats.number_of_points) {
+            let ts = component_transform(loca_table, glyf_table, components, i, comp, depth)?;
+            let mut p = glyph_point(loca_table, glyf_table, glyph_data, point - first_point, depth + 1)?;
+            ts.apply_to(&mut p.x, &mut p.y);
+            return Some(p);
+        }
+
+        first_point = first_point.checked_add(stats.number_of_points)?;
+    }
+
+    None
+}
+
+// Returns a glyph point by index, with component transforms applied.
+fn glyph_point(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    data: &[u8],
+    point: u16,
+    depth: u8,
+) -> Option<Point> {
+    if depth >= MAX_COMPONENTS {
+        return None;
+    }
+
+    let mut s = Stream::new(data);
+    let number_of_contours: i16 = s.read()?;
+    s.advance(8); // bbox
+
+    if number_of_contours > 0 {
+        // u16 casting is safe, since we already checked that the value is positive.
+        let number_of_contours = NonZeroU16::new(number_of_contours as u16)?;
+        let p = parse_simple_outline(s.tail()?, number_of_contours)?.nth(usize::from(point))?;
+        Some(Point { x: f32::from(p.x), y: f32::from(p.y) })
+    } else if number_of_contours < 0 {
+        composite_point(loca_table, glyf_table, s.tail()?, point, core::usize::MAX, depth)
+    } else {
+        None
+    }
+}
+
 #[inline]
 pub fn parse_simple_outline(
     glyph_data: &[u8],
@@ -666,3 +970,435 @@ fn resolve_coords_len(
 
     Some((x_coords_len, y_coords_len))
 }
+
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroU16;
+    use std::string::String;
+    use std::fmt::Write;
+    use crate::{loca, GlyphId, IndexToLocationFormat, OutlineBuilder, Rect};
+
+    struct Builder(String);
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    fn outline(loca_data: &[u8], glyf_data: &[u8], glyph_id: u16) -> (String, Option<Rect>) {
+        let number_of_glyphs = NonZeroU16::new((loca_data.len() / 2 - 1) as u16).unwrap();
+        let loca = loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        let mut builder = Builder(String::new());
+        let bbox = super::outline(loca, glyf_data, GlyphId(glyph_id), &mut builder);
+        (builder.0, bbox)
+    }
+
+    fn instructions<'a>(loca_data: &[u8], glyf_data: &'a [u8], glyph_id: u16) -> Option<&'a [u8]> {
+        let number_of_glyphs = NonZeroU16::new((loca_data.len() / 2 - 1) as u16).unwrap();
+        let loca = loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        super::glyph_instructions(loca, glyf_data, GlyphId(glyph_id))
+    }
+
+    fn has_overlap(loca_data: &[u8], glyf_data: &[u8], glyph_id: u16) -> Option<bool> {
+        let number_of_glyphs = NonZeroU16::new((loca_data.len() / 2 - 1) as u16).unwrap();
+        let loca = loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        super::glyph_has_overlap(loca, glyf_data, GlyphId(glyph_id))
+    }
+
+    #[test]
+    fn off_curve_points() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x11, // offset [1]: 34
+            0x00, 0x22, // offset [2]: 68
+        ];
+
+        let glyf_data = &[
+            // Glyph [0]
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x64, // bbox: 0, 0, 100, 100
+            0x00, 0x03, // end point [0]: 3
+            0x00, 0x00, // instructions length: 0
+            0x01, // flags [0]: ON_CURVE_POINT
+            0x00, // flags [1]: 0
+            0x00, // flags [2]: 0
+            0x01, // flags [3]: ON_CURVE_POINT
+            0x00, 0x00, // x [0]: 0
+            0x00, 0x64, // x [1]: +100
+            0x00, 0x00, // x [2]: 0
+            0xFF, 0x9C, // x [3]: -100
+            0x00, 0x00, // y [0]: 0
+            0x00, 0x00, // y [1]: 0
+            0x00, 0x64, // y [2]: +100
+            0x00, 0x00, // y [3]: 0
+            // Glyph [1]
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x64, // bbox: 0, 0, 100, 100
+            0x00, 0x03, // end point [0]: 3
+            0x00, 0x00, // instructions length: 0
+            0x00, // flags [0]: 0
+            0x00, // flags [1]: 0
+            0x00, // flags [2]: 0
+            0x00, // flags [3]: 0
+            0x00, 0x00, // x [0]: 0
+            0x00, 0x64, // x [1]: +100
+            0x00, 0x00, // x [2]: 0
+            0xFF, 0x9C, // x [3]: -100
+            0x00, 0x00, // y [0]: 0
+            0x00, 0x00, // y [1]: 0
+            0x00, 0x64, // y [2]: +100
+            0x00, 0x00, // y [3]: 0
+        ];
+
+        let rect = Rect { x_min: 0, y_min: 0, x_max: 100, y_max: 100 };
+        assert_eq!(outline(loca_data, glyf_data, 0), (
+            "M 0 0 Q 100 0 100 50 Q 100 100 0 100 L 0 0 Z ".into(),
+            Some(rect),
+        ));
+        // A contour without on-curve points starts at the middle of the first two points.
+        assert_eq!(outline(loca_data, glyf_data, 1), (
+            "M 50 0 Q 100 0 100 50 Q 100 100 50 100 Q 0 100 0 50 Q 0 0 50 0 Z ".into(),
+            Some(rect),
+        ));
+    }
+
+    #[test]
+    fn matched_points() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x11, // offset [1]: 34
+            0x00, 0x1C, // offset [2]: 56
+        ];
+
+        let glyf_data = &[
+            // Glyph [0]
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x64, // bbox: 0, 0, 100, 100
+            0x00, 0x03, // end point [0]: 3
+            0x00, 0x00, // instructions length: 0
+            0x01, // flags [0]: ON_CURVE_POINT
+            0x01, // flags [1]: ON_CURVE_POINT
+            0x01, // flags [2]: ON_CURVE_POINT
+            0x01, // flags [3]: ON_CURVE_POINT
+            0x00, 0x00, // x [0]: 0
+            0x00, 0x64, // x [1]: +100
+            0x00, 0x00, // x [2]: 0
+            0xFF, 0x9C, // x [3]: -100
+            0x00, 0x00, // y [0]: 0
+            0x00, 0x00, // y [1]: 0
+            0x00, 0x64, // y [2]: +100
+            0x00, 0x00, // y [3]: 0
+            // Glyph [1]
+            0xFF, 0xFF, // number of contours: -1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0xC8, 0x00, 0xC8, // bbox: 0, 0, 200, 200
+            0x00, 0x22, // flags: MORE_COMPONENTS | ARGS_ARE_XY_VALUES
+            0x00, 0x00, // glyph index: 0
+            0x00, 0x00, // arguments: 0, 0
+            0x00, 0x00, // flags: 0
+            0x00, 0x00, // glyph index: 0
+            0x02, 0x00, // arguments: parent point 2, child point 0
+        ];
+
+        assert_eq!(outline(loca_data, glyf_data, 1), (
+            "M 0 0 L 100 0 L 100 100 L 0 100 L 0 0 Z \
+             M 100 100 L 200 100 L 200 200 L 100 200 L 100 100 Z ".into(),
+            Some(Rect { x_min: 0, y_min: 0, x_max: 200, y_max: 200 }),
+        ));
+    }
+
+    #[test]
+    fn malformed_bbox() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0B, // offset [1]: 22
+        ];
+
+        let glyf_data = &[
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox: 0, 0, 0, 0
+            0x00, 0x02, // end point [0]: 2
+            0x00, 0x00, // instructions length: 0
+            0x37, // flags [0]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x37, // flags [1]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x33, // flags [2]: ON_CURVE_POINT | X_SHORT | X_IS_POSITIVE | Y_IS_SAME
+            0x0A, // x [0]: +10
+            0x14, // x [1]: +20
+            0x1E, // x [2]: +30
+            0x05, // y [0]: +5
+            0x28, // y [1]: +40
+        ];
+
+        let number_of_glyphs = NonZeroU16::new(1).unwrap();
+        let loca = loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        assert_eq!(super::glyph_bbox(loca, glyf_data, GlyphId(0)),
+                   Some(Rect { x_min: 10, y_min: 5, x_max: 60, y_max: 45 }));
+    }
+
+    #[test]
+    fn overflowing_bbox() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0B, // offset [1]: 22
+            0x00, 0x16, // offset [2]: 44
+        ];
+
+        let glyf_data = &[
+            // Glyph [0]
+            0x00, 0x01, // number of contours: 1
+            0xFF, 0xFE, 0xFF, 0xFE, 0x7F, 0xFF, 0x7F, 0xFF, // bbox: -2, -2, 32767, 32767
+            0x00, 0x02, // end point [0]: 2
+            0x00, 0x00, // instructions length: 0
+            0x37, // flags [0]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x37, // flags [1]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x33, // flags [2]: ON_CURVE_POINT | X_SHORT | X_IS_POSITIVE | Y_IS_SAME
+            0x0A, // x [0]: +10
+            0x14, // x [1]: +20
+            0x1E, // x [2]: +30
+            0x05, // y [0]: +5
+            0x28, // y [1]: +40
+
+            // Glyph [1]
+            0x00, 0x01, // number of contours: 1
+            0x7F, 0xFF, 0x7F, 0xFF, 0xFF, 0xFE, 0xFF, 0xFE, // bbox: 32767, 32767, -2, -2
+            0x00, 0x02, // end point [0]: 2
+            0x00, 0x00, // instructions length: 0
+            0x37, // flags [0]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x37, // flags [1]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x33, // flags [2]: ON_CURVE_POINT | X_SHORT | X_IS_POSITIVE | Y_IS_SAME
+            0x0A, // x [0]: +10
+            0x14, // x [1]: +20
+            0x1E, // x [2]: +30
+            0x05, // y [0]: +5
+            0x28, // y [1]: +40
+        ];
+
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let loca = loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        let huge_rect = Rect { x_min: -2, y_min: -2, x_max: 32767, y_max: 32767 };
+        assert_eq!(super::glyph_bbox(loca, glyf_data, GlyphId(0)), Some(huge_rect));
+        assert_eq!(outline(loca_data, glyf_data, 0).1, Some(huge_rect));
+
+        // An inverted bbox is replaced with a calculated one.
+        let rect = Rect { x_min: 10, y_min: 5, x_max: 60, y_max: 45 };
+        assert_eq!(super::glyph_bbox(loca, glyf_data, GlyphId(1)), Some(rect));
+        assert_eq!(outline(loca_data, glyf_data, 1).1, Some(rect));
+    }
+
+    #[test]
+    fn simple_glyph_instructions() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0A, // offset [1]: 20
+        ];
+
+        let glyf_data = &[
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x00, 0x00, // end point [0]: 0
+            0x00, 0x02, // instructions length: 2
+            0xB0, 0x01, // instructions: PUSHB[0] 1
+            0x01, // flags [0]: ON_CURVE_POINT
+            0x00, 0x00, 0x00, // padding
+        ];
+
+        assert_eq!(instructions(loca_data, glyf_data, 0), Some(&[0xB0, 0x01][..]));
+    }
+
+    #[test]
+    fn simple_glyph_without_instructions() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x08, // offset [1]: 16
+        ];
+
+        let glyf_data = &[
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x00, 0x00, // end point [0]: 0
+            0x00, 0x00, // instructions length: 0
+            0x01, // flags [0]: ON_CURVE_POINT
+            0x00, // padding
+        ];
+
+        assert_eq!(instructions(loca_data, glyf_data, 0), None);
+    }
+
+    #[test]
+    fn composite_glyph_with_empty_component() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0B, // offset [1]: 22
+            0x00, 0x10, // offset [2]: 32
+            0x00, 0x1B, // offset [3]: 54
+        ];
+
+        let glyf_data = &[
+            // Glyph [0]
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x0A, 0x00, 0x05, 0x00, 0x3C, 0x00, 0x2D, // bbox: 10, 5, 60, 45
+            0x00, 0x02, // end point [0]: 2
+            0x00, 0x00, // instructions length: 0
+            0x37, // flags [0]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x37, // flags [1]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x33, // flags [2]: ON_CURVE_POINT | X_SHORT | X_IS_POSITIVE | Y_IS_SAME
+            0x0A, // x [0]: +10
+            0x14, // x [1]: +20
+            0x1E, // x [2]: +30
+            0x05, // y [0]: +5
+            0x28, // y [1]: +40
+
+            // Glyph [1]
+            0x00, 0x00, // number of contours: 0
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+
+            // Glyph [2]
+            0xFF, 0xFF, // number of contours: -1
+            0x00, 0x0A, 0x00, 0x05, 0x00, 0x3C, 0x00, 0x2D, // bbox: 10, 5, 60, 45
+            0x00, 0x22, // flags: MORE_COMPONENTS | ARGS_ARE_XY_VALUES
+            0x00, 0x01, // glyph index: 1
+            0x00, 0x00, // arguments: 0, 0
+            0x00, 0x02, // flags: ARGS_ARE_XY_VALUES
+            0x00, 0x00, // glyph index: 0
+            0x00, 0x00, // arguments: 0, 0
+        ];
+
+        let (path, rect) = outline(loca_data, glyf_data, 2);
+        assert_eq!(path, "M 10 5 L 30 45 L 60 45 L 10 5 Z ");
+        assert_eq!(rect, Some(Rect { x_min: 10, y_min: 5, x_max: 60, y_max: 45 }));
+
+        let number_of_glyphs = NonZeroU16::new(3).unwrap();
+        let loca = loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        let stats = super::glyph_stats(loca, glyf_data, GlyphId(2)).unwrap();
+        assert_eq!(stats.number_of_contours, 1);
+        assert_eq!(stats.number_of_points, 3);
+        assert!(stats.is_composite);
+    }
+
+    #[test]
+    fn composite_glyph_instructions() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0D, // offset [1]: 26
+        ];
+
+        let glyf_data = &[
+            0xFF, 0xFF, // number of contours: -1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x01, 0x22, // flags: WE_HAVE_INSTRUCTIONS | MORE_COMPONENTS | ARGS_ARE_XY_VALUES
+            0x00, 0x01, // glyph index: 1
+            0x00, 0x00, // arguments: 0, 0
+            0x00, 0x02, // flags: ARGS_ARE_XY_VALUES
+            0x00, 0x02, // glyph index: 2
+            0x00, 0x00, // arguments: 0, 0
+            0x00, 0x01, // instructions length: 1
+            0x2B, // instructions: CALL
+            0x00, // padding
+        ];
+
+        assert_eq!(instructions(loca_data, glyf_data, 0), Some(&[0x2B][..]));
+    }
+
+    #[test]
+    fn composite_glyph_without_instructions() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0A, // offset [1]: 20
+        ];
+
+        let glyf_data = &[
+            0xFF, 0xFF, // number of contours: -1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x00, 0x02, // flags: ARGS_ARE_XY_VALUES
+            0x00, 0x01, // glyph index: 1
+            0x00, 0x00, // arguments: 0, 0
+            0x00, 0x01, // instructions length: 1
+            0x2B, // instructions: CALL
+            0x00, // padding
+        ];
+
+        assert_eq!(instructions(loca_data, glyf_data, 0), None);
+    }
+
+    #[test]
+    fn simple_glyph_overlap() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x08, // offset [1]: 16
+            0x00, 0x10, // offset [2]: 32
+        ];
+
+        let glyf_data = &[
+            // Glyph 0
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x00, 0x00, // end point [0]: 0
+            0x00, 0x00, // instructions length: 0
+            0x41, // flags [0]: ON_CURVE_POINT | OVERLAP_SIMPLE
+            0x00, // padding
+            // Glyph 1
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x00, 0x00, // end point [0]: 0
+            0x00, 0x00, // instructions length: 0
+            0x01, // flags [0]: ON_CURVE_POINT
+            0x00, // padding
+        ];
+
+        assert_eq!(has_overlap(loca_data, glyf_data, 0), Some(true));
+        assert_eq!(has_overlap(loca_data, glyf_data, 1), Some(false));
+    }
+
+    #[test]
+    fn composite_glyph_overlap() {
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x08, // offset [1]: 16
+            0x00, 0x10, // offset [2]: 32
+            0x00, 0x18, // offset [3]: 48
+            0x00, 0x18, // offset [4]: 48
+        ];
+
+        let glyf_data = &[
+            // Glyph 0
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x00, 0x00, // end point [0]: 0
+            0x00, 0x00, // instructions length: 0
+            0x41, // flags [0]: ON_CURVE_POINT | OVERLAP_SIMPLE
+            0x00, // padding
+            // Glyph 1
+            0xFF, 0xFF, // number of contours: -1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x04, 0x02, // flags: OVERLAP_COMPOUND | ARGS_ARE_XY_VALUES
+            0x00, 0x05, // glyph index: 5 (missing)
+            0x00, 0x00, // arguments: 0, 0
+            // Glyph 2
+            0xFF, 0xFF, // number of contours: -1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+            0x00, 0x02, // flags: ARGS_ARE_XY_VALUES
+            0x00, 0x00, // glyph index: 0
+            0x00, 0x00, // arguments: 0, 0
+        ];
+
+        assert_eq!(has_overlap(loca_data, glyf_data, 1), Some(true));
+        assert_eq!(has_overlap(loca_data, glyf_data, 2), Some(true));
+        assert_eq!(has_overlap(loca_data, glyf_data, 3), None);
+    }
+}
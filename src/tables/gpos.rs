@@ -0,0 +1,668 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos
+
+use crate::{GlyphId, Tag};
+use crate::ggg::{ClassDefinitionTable, CoverageTable, Device, LayoutTable, Lookup, LookupIndexSet};
+use crate::parser::{Stream, FromData, Offset, Offset16};
+
+const PAIR_ADJUSTMENT: u16 = 2;
+const MARK_TO_BASE_ATTACHMENT: u16 = 4;
+
+
+/// An [anchor](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#anchor-tables)
+/// used for mark positioning and cursive attachment.
+#[derive(Clone, Copy, Debug)]
+pub struct Anchor<'a> {
+    /// Horizontal value in font units.
+    pub x: i16,
+
+    /// Vertical value in font units.
+    pub y: i16,
+
+    /// An index to a glyph contour point.
+    ///
+    /// Set only for format 2 anchors.
+    pub contour_point: Option<u16>,
+
+    /// A device table for the X coordinate.
+    ///
+    /// Set only for format 3 anchors.
+    pub x_device: Option<Device<'a>>,
+
+    /// A device table for the Y coordinate.
+    ///
+    /// Set only for format 3 anchors.
+    pub y_device: Option<Device<'a>>,
+}
+
+impl<'a> Anchor<'a> {
+    /// Parses an anchor table.
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let format: u16 = s.read()?;
+        let mut anchor = Anchor {
+            x: s.read()?,
+            y: s.read()?,
+            contour_point: None,
+            x_device: None,
+            y_device: None,
+        };
+
+        match format {
+            1 => {}
+            2 => anchor.contour_point = Some(s.read()?),
+            3 => {
+                let device = |offset: Option<Offset16>| {
+                    offset.and_then(|offset| data.get(offset.to_usize()..)).and_then(Device::parse)
+                };
+
+                anchor.x_device = device(s.read()?);
+                anchor.y_device = device(s.read()?);
+            }
+            _ => return None,
+        }
+
+        Some(anchor)
+    }
+
+    /// Returns anchor coordinates adjusted by hinting devices for the specified size.
+    ///
+    /// Variation devices are ignored.
+    pub fn position(&self, x_ppem: u16, y_ppem: u16, units_per_em: u16) -> (f32, f32) {
+        let delta = |device: Option<Device>, ppem| {
+            device.and_then(|d| d.delta(ppem, units_per_em)).unwrap_or(0.0)
+        };
+
+        (
+            f32::from(self.x) + delta(self.x_device, x_ppem),
+            f32::from(self.y) + delta(self.y_device, y_ppem),
+        )
+    }
+}
+
+
+
+#[derive(Clone, Copy, Debug)]
+struct ValueFormat(u16);
+
+impl ValueFormat {
+    #[inline]
+    fn size(self) -> usize {
+        // Each set bit of the low byte adds a 16-bit value.
+        (self.0 & 0xFF).count_ones() as usize * 2
+    }
+
+    #[inline]
+    fn has(self, flag: u16) -> bool {
+        self.0 & flag != 0
+    }
+}
+
+
+/// A [value record](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#value-record).
+///
+/// Values that are not present in the font are set to zero.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ValueRecord<'a> {
+    /// Horizontal adjustment for placement in font units.
+    pub x_placement: i16,
+
+    /// Vertical adjustment for placement in font units.
+    pub y_placement: i16,
+
+    /// Horizontal adjustment for advance in font units.
+    pub x_advance: i16,
+
+    /// Vertical adjustment for advance in font units.
+    pub y_advance: i16,
+
+    /// A device table for the horizontal placement.
+    pub x_placement_device: Option<Device<'a>>,
+
+    /// A device table for the vertical placement.
+    pub y_placement_device: Option<Device<'a>>,
+
+    /// A device table for the horizontal advance.
+    pub x_advance_device: Option<Device<'a>>,
+
+    /// A device table for the vertical advance.
+    pub y_advance_device: Option<Device<'a>>,
+}
+
+impl<'a> ValueRecord<'a> {
+    // Device offsets are from the beginning of the parent subtable.
+    fn parse(s: &mut Stream, format: ValueFormat, parent: &'a [u8]) -> Option<Self> {
+        let device = |s: &mut Stream| -> Option<Option<Device<'a>>> {
+            let offset: Option<Offset16> = s.read()?;
+            Some(offset.and_then(|offset| parent.get(offset.to_usize()..)).and_then(Device::parse))
+        };
+
+        let mut record = ValueRecord::default();
+        if format.has(0x0001) { record.x_placement = s.read()?; }
+        if format.has(0x0002) { record.y_placement = s.read()?; }
+        if format.has(0x0004) { record.x_advance = s.read()?; }
+        if format.has(0x0008) { record.y_advance = s.read()?; }
+        if format.has(0x0010) { record.x_placement_device = device(s)?; }
+        if format.has(0x0020) { record.y_placement_device = device(s)?; }
+        if format.has(0x0040) { record.x_advance_device = device(s)?; }
+        if format.has(0x0080) { record.y_advance_device = device(s)?; }
+        Some(record)
+    }
+}
+
+
+/// A [pair adjustment positioning subtable](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-2-pair-adjustment-positioning-subtable).
+///
+/// Both pair lists (format 1) and class pairs (format 2) are supported.
+#[derive(Clone, Copy)]
+pub struct PairAdjustment<'a> {
+    data: &'a [u8],
+    format: u16,
+    coverage: CoverageTable<'a>,
+    value_format1: ValueFormat,
+    value_format2: ValueFormat,
+}
+
+impl<'a> PairAdjustment<'a> {
+    /// Parses a pair adjustment subtable.
+    ///
+    /// Accepts data returned by [`Lookup::subtable`](struct.Lookup.html#method.subtable).
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let format: u16 = s.read()?;
+        if format != 1 && format != 2 {
+            return None;
+        }
+
+        let coverage_offset: Offset16 = s.read()?;
+        Some(PairAdjustment {
+            data,
+            format,
+            coverage: CoverageTable::new(data.get(coverage_offset.to_usize()..)?),
+            value_format1: ValueFormat(s.read()?),
+            value_format2: ValueFormat(s.read()?),
+        })
+    }
+
+    /// Returns value records for the first and the second glyphs of a pair.
+    ///
+    /// Returns `None` when the subtable doesn't cover the pair.
+    /// Class pairs cover all pairs with a covered first glyph.
+    pub fn glyphs_adjustment(
+        &self,
+        first: GlyphId,
+        second: GlyphId,
+    ) -> Option<(ValueRecord<'a>, ValueRecord<'a>)> {
+        let index = self.coverage.get(first)?;
+        let mut s = Stream::new_at(self.data, 8)?;
+        let values_size = self.value_format1.size() + self.value_format2.size();
+        let record_offset = if self.format == 1 {
+            let count: u16 = s.read()?;
+            let offsets = s.read_array16::<Offset16>(count)?;
+            let set_offset = offsets.get(index)?.to_usize();
+            let mut s = Stream::new_at(self.data, set_offset)?;
+            let count: u16 = s.read()?;
+
+            // Pair value records are sorted by the second glyph,
+            // but their size depends on the value formats.
+            let record_size = GlyphId::SIZE + values_size;
+            let record_at = |index: u16| set_offset + 2 + usize::from(index) * record_size;
+            let (mut start, mut end) = (0, count);
+            loop {
+                if start >= end {
+                    return None;
+                }
+
+                let mid = start + (end - start) / 2;
+                let glyph_id: GlyphId = Stream::read_at(self.data, record_at(mid))?;
+                match glyph_id.cmp(&second) {
+                    core::cmp::Ordering::Less => start = mid + 1,
+                    core::cmp::Ordering::Greater => end = mid,
+                    core::cmp::Ordering::Equal => break record_at(mid) + GlyphId::SIZE,
+                }
+            }
+        } else {
+            let class_def1_offset: Offset16 = s.read()?;
+            let class_def2_offset: Offset16 = s.read()?;
+            let class1_count: u16 = s.read()?;
+            let class2_count: u16 = s.read()?;
+            let class1 = ClassDefinitionTable::new(self.data.get(class_def1_offset.to_usize()..)?)
+                .get(first).0;
+            let class2 = ClassDefinitionTable::new(self.data.get(class_def2_offset.to_usize()..)?)
+                .get(second).0;
+            if class1 >= class1_count || class2 >= class2_count {
+                return None;
+            }
+
+            let index = usize::from(class1) * usize::from(class2_count) + usize::from(class2);
+            16 + index * values_size
+        };
+
+        let mut s = Stream::new_at(self.data, record_offset)?;
+        Some((
+            ValueRecord::parse(&mut s, self.value_format1, self.data)?,
+            ValueRecord::parse(&mut s, self.value_format2, self.data)?,
+        ))
+    }
+}
+
+impl core::fmt::Debug for PairAdjustment<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("PairAdjustment")
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+
+/// A [mark-to-base attachment positioning subtable](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-4-mark-to-base-attachment-positioning-subtable).
+#[derive(Clone, Copy)]
+pub struct MarkToBaseAttachment<'a> {
+    mark_coverage: CoverageTable<'a>,
+    base_coverage: CoverageTable<'a>,
+    mark_class_count: u16,
+    mark_array: &'a [u8],
+    base_array: &'a [u8],
+}
+
+impl<'a> MarkToBaseAttachment<'a> {
+    /// Parses a mark-to-base attachment subtable.
+    ///
+    /// Accepts data returned by [`Lookup::subtable`](struct.Lookup.html#method.subtable).
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let format: u16 = s.read()?;
+        if format != 1 {
+            return None;
+        }
+
+        let mark_coverage_offset: Offset16 = s.read()?;
+        let base_coverage_offset: Offset16 = s.read()?;
+        let mark_class_count: u16 = s.read()?;
+        let mark_array_offset: Offset16 = s.read()?;
+        let base_array_offset: Offset16 = s.read()?;
+        Some(MarkToBaseAttachment {
+            mark_coverage: CoverageTable::new(data.get(mark_coverage_offset.to_usize()..)?),
+            base_coverage: CoverageTable::new(data.get(base_coverage_offset.to_usize()..)?),
+            mark_class_count,
+            mark_array: data.get(mark_array_offset.to_usize()..)?,
+            base_array: data.get(base_array_offset.to_usize()..)?,
+        })
+    }
+
+    /// Returns the number of mark classes.
+    #[inline]
+    pub fn mark_class_count(&self) -> u16 {
+        self.mark_class_count
+    }
+
+    /// Returns mark's class and anchor.
+    ///
+    /// Returns `None` when the mark is not covered by the subtable.
+    pub fn mark_anchor(&self, glyph_id: GlyphId) -> Option<(u16, Anchor<'a>)> {
+        let index = self.mark_coverage.get(glyph_id)?;
+        let mut s = Stream::new(self.mark_array);
+        let count: u16 = s.read()?;
+        if index >= count {
+            return None;
+        }
+
+        // Mark record: markClass (u16) + markAnchorOffset (Offset16).
+        s.advance(usize::from(index) * 4);
+        let class: u16 = s.read()?;
+        let offset: Offset16 = s.read()?;
+        if class >= self.mark_class_count {
+            return None;
+        }
+
+        let anchor = Anchor::parse(self.mark_array.get(offset.to_usize()..)?)?;
+        Some((class, anchor))
+    }
+
+    /// Returns base's anchor for the specified mark class.
+    ///
+    /// Returns `None` when the base is not covered by the subtable
+    /// or has no anchor for this class.
+    pub fn base_anchor(&self, glyph_id: GlyphId, class: u16) -> Option<Anchor<'a>> {
+        if class >= self.mark_class_count {
+            return None;
+        }
+
+        let index = self.base_coverage.get(glyph_id)?;
+        let count: u16 = Stream::read_at(self.base_array, 0)?;
+        if index >= count {
+            return None;
+        }
+
+        // Each base record has an anchor offset per mark class.
+        let record_size = usize::from(self.mark_class_count).checked_mul(Offset16::SIZE)?;
+        let pos = usize::from(index).checked_mul(record_size)?
+            .checked_add(usize::from(class) * Offset16::SIZE)?
+            .checked_add(2)?; // count
+        let offset: Option<Offset16> = Stream::read_at(self.base_array, pos)?;
+        Anchor::parse(self.base_array.get(offset?.to_usize()..)?)
+    }
+
+    /// Returns base and mark anchors for a pair of glyphs.
+    ///
+    /// The mark should be positioned so its anchor matches the base one.
+    pub fn glyphs_anchors(&self, base: GlyphId, mark: GlyphId) -> Option<(Anchor<'a>, Anchor<'a>)> {
+        let (class, mark_anchor) = self.mark_anchor(mark)?;
+        let base_anchor = self.base_anchor(base, class)?;
+        Some((base_anchor, mark_anchor))
+    }
+}
+
+impl core::fmt::Debug for MarkToBaseAttachment<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("MarkToBaseAttachment")
+            .field("mark_class_count", &self.mark_class_count)
+            .finish()
+    }
+}
+
+
+/// Returns lookups of a specified kind referenced by features with a specified tag.
+///
+/// Lookups are yielded in index order and only once.
+fn feature_lookups<'a>(
+    table: Option<LayoutTable<'a>>,
+    tag: Tag,
+    kind: u16,
+) -> impl Iterator<Item = Lookup<'a>> + 'a {
+    let mut indices = LookupIndexSet::new();
+    if let Some(table) = table {
+        for feature in table.features().filter(|feature| feature.tag() == tag) {
+            for index in feature.lookup_indices() {
+                indices.insert(index);
+            }
+        }
+    }
+
+    indices.into_iter().filter_map(move |index| {
+        table?.lookup_at(index).filter(|lookup| lookup.kind() == kind)
+    })
+}
+
+/// Returns pair adjustment lookups referenced by `kern` features.
+pub(crate) fn kerning_lookups<'a>(
+    table: Option<LayoutTable<'a>>,
+) -> impl Iterator<Item = Lookup<'a>> + 'a {
+    feature_lookups(table, Tag::from_bytes(b"kern"), PAIR_ADJUSTMENT)
+}
+
+/// Returns a horizontal kerning value for a pair of glyphs.
+///
+/// Lookups are applied in order. The first subtable that covers the pair
+/// within a lookup is used.
+pub(crate) fn glyphs_kerning(table: Option<LayoutTable>, left: GlyphId, right: GlyphId) -> Option<i16> {
+    let mut kerning = None;
+    for lookup in kerning_lookups(table) {
+        let value = lookup.subtables()
+            .filter_map(PairAdjustment::parse)
+            .find_map(|subtable| subtable.glyphs_adjustment(left, right));
+
+        if let Some((value, _)) = value {
+            kerning = Some(kerning.unwrap_or(0i16).saturating_add(value.x_advance));
+        }
+    }
+
+    kerning
+}
+
+/// Returns base and mark anchors from the first `mark` feature
+/// mark-to-base attachment subtable that covers both glyphs.
+pub(crate) fn mark_to_base_anchors<'a>(
+    table: Option<LayoutTable<'a>>,
+    base: GlyphId,
+    mark: GlyphId,
+) -> Option<(Anchor<'a>, Anchor<'a>)> {
+    feature_lookups(table, Tag::from_bytes(b"mark"), MARK_TO_BASE_ATTACHMENT)
+        .flat_map(|lookup| lookup.subtables())
+        .filter_map(MarkToBaseAttachment::parse)
+        .find_map(|subtable| subtable.glyphs_anchors(base, mark))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_3() {
+        let data = &[
+            0x00, 0x03, // format: 3
+            0x01, 0x2C, // x: 300
+            0xFF, 0x9C, // y: -100
+            0x00, 0x0A, // x device offset: 10
+            0x00, 0x12, // y device offset: 18
+            // X device
+            0x00, 0x0C, // start size: 12
+            0x00, 0x0C, // end size: 12
+            0x00, 0x03, // delta format: 8-bit
+            0x02, 0x00, // deltas: 2
+            // Y device
+            0x00, 0x00, // outer index: 0
+            0x00, 0x05, // inner index: 5
+            0x80, 0x00, // format: variation index
+        ];
+
+        let anchor = Anchor::parse(data).unwrap();
+        assert_eq!(anchor.x, 300);
+        assert_eq!(anchor.y, -100);
+        assert_eq!(anchor.contour_point, None);
+        assert_eq!(anchor.x_device.unwrap().pixel_delta(12), Some(2));
+        assert_eq!(anchor.y_device.unwrap().variation_index(), Some((0, 5)));
+        assert_eq!(anchor.position(12, 12, 1200), (500.0, -100.0));
+        assert_eq!(anchor.position(13, 13, 1200), (300.0, -100.0));
+    }
+
+    #[test]
+    fn format_2() {
+        let data = &[
+            0x00, 0x02, // format: 2
+            0x00, 0x0A, // x: 10
+            0x00, 0x14, // y: 20
+            0x00, 0x03, // anchor point: 3
+        ];
+
+        let anchor = Anchor::parse(data).unwrap();
+        assert_eq!(anchor.contour_point, Some(3));
+        assert!(anchor.x_device.is_none());
+    }
+
+    #[test]
+    fn pair_adjustment_format_1() {
+        let data = &[
+            0x00, 0x01, // format: 1
+            0x00, 0x0E, // coverage offset: 14
+            0x00, 0x04, // value format 1: x advance
+            0x00, 0x00, // value format 2: none
+            0x00, 0x02, // pair set count: 2
+            0x00, 0x16, // pair set offset [0]: 22
+            0x00, 0x20, // pair set offset [1]: 32
+            // Coverage
+            0x00, 0x01, // format: 1
+            0x00, 0x02, // count: 2
+            0x00, 0x01, // glyph [0]: 1
+            0x00, 0x02, // glyph [1]: 2
+            // Pair set [0]
+            0x00, 0x02, // count: 2
+            0x00, 0x02, // second glyph [0]: 2
+            0xFF, 0xCE, // x advance [0]: -50
+            0x00, 0x03, // second glyph [1]: 3
+            0xFF, 0xF6, // x advance [1]: -10
+            // Pair set [1]
+            0x00, 0x01, // count: 1
+            0x00, 0x01, // second glyph [0]: 1
+            0x00, 0x14, // x advance [0]: 20
+        ];
+
+        let subtable = PairAdjustment::parse(data).unwrap();
+        let x_advance = |first, second| {
+            subtable.glyphs_adjustment(GlyphId(first), GlyphId(second)).map(|(v, _)| v.x_advance)
+        };
+
+        assert_eq!(x_advance(1, 2), Some(-50));
+        assert_eq!(x_advance(1, 3), Some(-10));
+        assert_eq!(x_advance(1, 4), None);
+        assert_eq!(x_advance(2, 1), Some(20));
+        assert_eq!(x_advance(3, 1), None);
+    }
+
+    #[test]
+    fn pair_adjustment_format_2() {
+        let data = &[
+            0x00, 0x02, // format: 2
+            0x00, 0x28, // coverage offset: 40
+            0x00, 0x05, // value format 1: x placement | x advance
+            0x00, 0x04, // value format 2: x advance
+            0x00, 0x32, // class def 1 offset: 50
+            0x00, 0x3E, // class def 2 offset: 62
+            0x00, 0x02, // class 1 count: 2
+            0x00, 0x02, // class 2 count: 2
+            // Class 1 record [0]
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // class 2 record [0]: 0, 0, 0
+            0x00, 0x00, 0xFF, 0xF6, 0x00, 0x00, // class 2 record [1]: 0, -10, 0
+            // Class 1 record [1]
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // class 2 record [0]: 0, 0, 0
+            0x00, 0x05, 0xFF, 0xE2, 0x00, 0x07, // class 2 record [1]: 5, -30, 7
+            // Coverage
+            0x00, 0x02, // format: 2
+            0x00, 0x01, // count: 1
+            0x00, 0x01, // start glyph [0]: 1
+            0x00, 0x03, // end glyph [0]: 3
+            0x00, 0x00, // start coverage index [0]: 0
+            // Class definition 1
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // start glyph: 1
+            0x00, 0x03, // count: 3
+            0x00, 0x00, // class [0]: 0
+            0x00, 0x01, // class [1]: 1
+            0x00, 0x01, // class [2]: 1
+            // Class definition 2
+            0x00, 0x02, // format: 2
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // start glyph [0]: 5
+            0x00, 0x06, // end glyph [0]: 6
+            0x00, 0x01, // class [0]: 1
+        ];
+
+        let subtable = PairAdjustment::parse(data).unwrap();
+
+        let (first, second) = subtable.glyphs_adjustment(GlyphId(1), GlyphId(5)).unwrap();
+        assert_eq!((first.x_placement, first.x_advance, second.x_advance), (0, -10, 0));
+
+        let (first, second) = subtable.glyphs_adjustment(GlyphId(2), GlyphId(6)).unwrap();
+        assert_eq!((first.x_placement, first.x_advance, second.x_advance), (5, -30, 7));
+        assert!(first.x_advance_device.is_none());
+
+        // Glyphs without a class belong to the class 0.
+        let (first, _) = subtable.glyphs_adjustment(GlyphId(2), GlyphId(4)).unwrap();
+        assert_eq!(first.x_advance, 0);
+
+        // Not covered.
+        assert!(subtable.glyphs_adjustment(GlyphId(4), GlyphId(5)).is_none());
+    }
+
+    #[test]
+    fn mark_to_base_attachment() {
+        let data = &[
+            0x00, 0x01, // format: 1
+            0x00, 0x0C, // mark coverage offset: 12
+            0x00, 0x14, // base coverage offset: 20
+            0x00, 0x02, // mark class count: 2
+            0x00, 0x1A, // mark array offset: 26
+            0x00, 0x32, // base array offset: 50
+            // Mark coverage
+            0x00, 0x01, // format: 1
+            0x00, 0x02, // count: 2
+            0x00, 0x0A, // glyph [0]: 10
+            0x00, 0x0B, // glyph [1]: 11
+            // Base coverage
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x01, // glyph [0]: 1
+            // Mark array
+            0x00, 0x02, // count: 2
+            0x00, 0x00, // class [0]: 0
+            0x00, 0x0A, // anchor offset [0]: 10
+            0x00, 0x01, // class [1]: 1
+            0x00, 0x10, // anchor offset [1]: 16
+            // Mark anchor [0]
+            0x00, 0x01, // format: 1
+            0x00, 0x64, // x: 100
+            0x01, 0xF4, // y: 500
+            // Mark anchor [1]
+            0x00, 0x02, // format: 2
+            0x00, 0xC8, // x: 200
+            0xFF, 0xCE, // y: -50
+            0x00, 0x03, // anchor point: 3
+            // Base array
+            0x00, 0x01, // count: 1
+            0x00, 0x06, // anchor offset [0, 0]: 6
+            0x00, 0x00, // anchor offset [0, 1]: none
+            // Base anchor [0, 0]
+            0x00, 0x01, // format: 1
+            0x00, 0xFA, // x: 250
+            0x02, 0xBC, // y: 700
+        ];
+
+        let subtable = MarkToBaseAttachment::parse(data).unwrap();
+        assert_eq!(subtable.mark_class_count(), 2);
+
+        let (base, mark) = subtable.glyphs_anchors(GlyphId(1), GlyphId(10)).unwrap();
+        assert_eq!((base.x, base.y), (250, 700));
+        assert_eq!((mark.x, mark.y), (100, 500));
+
+        let (class, mark) = subtable.mark_anchor(GlyphId(11)).unwrap();
+        assert_eq!((class, mark.x, mark.y, mark.contour_point), (1, 200, -50, Some(3)));
+
+        // The base has no anchor for the second class.
+        assert!(subtable.glyphs_anchors(GlyphId(1), GlyphId(11)).is_none());
+        // Not a base.
+        assert!(subtable.glyphs_anchors(GlyphId(2), GlyphId(10)).is_none());
+        // Not a mark.
+        assert!(subtable.mark_anchor(GlyphId(1)).is_none());
+    }
+
+    #[test]
+    fn huge_mark_class_count() {
+        let data = &[
+            0x00, 0x01, // format: 1
+            0x00, 0x0C, // mark coverage offset: 12
+            0x00, 0x12, // base coverage offset: 18
+            0xFF, 0xFF, // mark class count: 65535
+            0x00, 0x18, // mark array offset: 24
+            0x00, 0x20, // base array offset: 32
+            // Mark coverage
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x0A, // glyph [0]: 10
+            // Base coverage
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x01, // glyph [0]: 1
+            // Mark array
+            0x00, 0x01, // count: 1
+            0xFF, 0xFE, // class [0]: 65534
+            0x00, 0x06, // anchor offset [0]: 6
+            0x00, 0x00, // padding
+            // Base array
+            0x00, 0x01, // count: 1
+            0x00, 0x04, // anchor offset [0, 0]: 4
+            // Base anchor [0, 0]
+            0x00, 0x01, // format: 1
+            0x00, 0xFA, // x: 250
+            0x02, 0xBC, // y: 700
+        ];
+
+        let subtable = MarkToBaseAttachment::parse(data).unwrap();
+        let anchor = subtable.base_anchor(GlyphId(1), 0).unwrap();
+        assert_eq!((anchor.x, anchor.y), (250, 700));
+        // The record for the last classes is out of bounds.
+        assert!(subtable.base_anchor(GlyphId(1), 0xFFFE).is_none());
+        assert!(subtable.glyphs_anchors(GlyphId(1), GlyphId(10)).is_none());
+    }
+}
@@ -0,0 +1,94 @@
+// https://graphite.sil.org/graphite_techAbout#graphite-font-tables
+
+use crate::parser::{Stream, FromData, Fixed};
+
+
+/// Raw [Graphite](https://graphite.sil.org/) tables.
+///
+/// Only table headers are validated. It's up to the caller to parse the rest.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GraphiteTables<'a> {
+    /// Glyph attributes (`Glat`) table data.
+    pub glat: &'a [u8],
+
+    /// Glyph attributes locations (`Gloc`) table data.
+    pub gloc: &'a [u8],
+
+    /// Rules and settings (`Silf`) table data.
+    pub silf: &'a [u8],
+
+    /// Features (`Feat`) table data.
+    ///
+    /// A face without user-controllable features may not have one.
+    pub feat: Option<&'a [u8]>,
+}
+
+
+// Checks that the table is long enough and that its major version is supported.
+fn parse_header(data: &[u8], min_size: usize, max_version: u16) -> Option<&[u8]> {
+    if data.len() < min_size {
+        return None;
+    }
+
+    // All Graphite tables start with a 16.16 fixed version.
+    let version: u16 = Stream::read_at(data, 0)?;
+    if version >= 1 && version <= max_version {
+        Some(data)
+    } else {
+        None
+    }
+}
+
+#[inline]
+pub fn parse_feat(data: &[u8]) -> Option<&[u8]> {
+    // version + numFeat + reserved + reserved
+    parse_header(data, Fixed::SIZE + 8, 2)
+}
+
+#[inline]
+pub fn parse_glat(data: &[u8]) -> Option<&[u8]> {
+    parse_header(data, Fixed::SIZE, 3)
+}
+
+#[inline]
+pub fn parse_gloc(data: &[u8]) -> Option<&[u8]> {
+    // version + flags + numAttribs
+    parse_header(data, Fixed::SIZE + 4, 1)
+}
+
+#[inline]
+pub fn parse_silf(data: &[u8]) -> Option<&[u8]> {
+    // version + numSub
+    parse_header(data, Fixed::SIZE + 2, 5)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // flags: 0
+            0x00, 0x02, // number of attributes: 2
+        ];
+        assert!(parse_gloc(data).is_some());
+        assert!(parse_glat(data).is_some());
+        assert!(parse_feat(data).is_none());
+
+        let data = &[
+            0x00, 0x02, 0x00, 0x00, // version: 2.0
+            0x00, 0x00, // flags: 0
+            0x00, 0x02, // number of attributes: 2
+        ];
+        assert!(parse_gloc(data).is_none());
+
+        let data = &[
+            0x00, 0x00, 0x00, 0x00, // version: 0.0
+            0x00, 0x01, // number of sub-tables: 1
+        ];
+        assert!(parse_silf(data).is_none());
+    }
+}
@@ -0,0 +1,296 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gsub
+
+use crate::{GlyphId, Tag};
+use crate::ggg::{CoverageTable, LayoutTable, Lookup};
+use crate::parser::{Stream, Offset, Offset16, LazyArray16};
+
+const SINGLE_SUBSTITUTION: u16 = 1;
+const ALTERNATE_SUBSTITUTION: u16 = 3;
+
+
+enum Alternates<'a> {
+    Single(GlyphId),
+    Set(LazyArray16<'a, GlyphId>),
+}
+
+fn alternates<'a>(kind: u16, data: &'a [u8], glyph_id: GlyphId) -> Option<Alternates<'a>> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    let coverage_offset: Offset16 = s.read()?;
+    let coverage = CoverageTable::new(data.get(coverage_offset.to_usize()..)?);
+    let index = coverage.get(glyph_id)?;
+
+    match (kind, format) {
+        (SINGLE_SUBSTITUTION, 1) => {
+            let delta: i16 = s.read()?;
+            // Addition is modulo 65536.
+            Some(Alternates::Single(GlyphId(glyph_id.0.wrapping_add(delta as u16))))
+        }
+        (SINGLE_SUBSTITUTION, 2) => {
+            let count: u16 = s.read()?;
+            let substitutes = s.read_array16::<GlyphId>(count)?;
+            substitutes.get(index).map(Alternates::Single)
+        }
+        (ALTERNATE_SUBSTITUTION, 1) => {
+            let count: u16 = s.read()?;
+            let offsets = s.read_array16::<Offset16>(count)?;
+            let mut s = Stream::new_at(data, offsets.get(index)?.to_usize())?;
+            let count: u16 = s.read()?;
+            s.read_array16::<GlyphId>(count).map(Alternates::Set)
+        }
+        _ => None,
+    }
+}
+
+
+/// An iterator over glyph's alternates from the `aalt` feature.
+///
+/// Alternates from different lookups are not deduplicated.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphAlternates<'a> {
+    table: Option<LayoutTable<'a>>,
+    glyph_id: GlyphId,
+    feature_index: u16,
+    lookup_index: u16,
+    lookup: Option<Lookup<'a>>,
+    subtable_index: u16,
+    alternates: LazyArray16<'a, GlyphId>,
+    alternate_index: u16,
+    // A bitset of already visited lookups with an index below `VISITED_LOOKUPS_COUNT`.
+    visited_lookups: [u64; 4],
+}
+
+// Fonts rarely have more `GSUB` lookups than this, so the bitset is kept small.
+// Lookups with larger indices are checked against the previous `aalt` features instead.
+const VISITED_LOOKUPS_COUNT: u16 = 256;
+
+impl<'a> GlyphAlternates<'a> {
+    pub(crate) fn new(table: Option<LayoutTable<'a>>, glyph_id: GlyphId) -> Self {
+        GlyphAlternates {
+            table,
+            glyph_id,
+            ..GlyphAlternates::default()
+        }
+    }
+
+    // Returns the next `aalt` lookup that wasn't referenced by a previous `aalt` feature.
+    fn next_lookup(&mut self) -> Option<Lookup<'a>> {
+        const AALT: Tag = Tag::from_bytes(b"aalt");
+
+        let table = self.table?;
+        loop {
+            let feature = table.feature_at(self.feature_index)?;
+            if feature.tag() != AALT {
+                self.feature_index += 1;
+                self.lookup_index = 0;
+                continue;
+            }
+
+            let lookup_index = match feature.lookup_indices().get(self.lookup_index) {
+                Some(idx) => idx,
+                None => {
+                    self.feature_index += 1;
+                    self.lookup_index = 0;
+                    continue;
+                }
+            };
+            self.lookup_index += 1;
+
+            // Multiple `aalt` features usually share the same lookups.
+            let is_duplicate = if lookup_index < VISITED_LOOKUPS_COUNT {
+                let word = usize::from(lookup_index / 64);
+                let mask = 1 << (lookup_index % 64);
+                let is_visited = self.visited_lookups[word] & mask != 0;
+                self.visited_lookups[word] |= mask;
+                is_visited
+            } else {
+                (0..self.feature_index)
+                    .filter_map(|idx| table.feature_at(idx))
+                    .filter(|f| f.tag() == AALT)
+                    .any(|f| f.lookup_indices().into_iter().any(|idx| idx == lookup_index))
+            };
+            if is_duplicate {
+                continue;
+            }
+
+            if let Some(lookup) = table.lookup_at(lookup_index) {
+                return Some(lookup);
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for GlyphAlternates<'a> {
+    type Item = GlyphId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(id) = self.alternates.get(self.alternate_index) {
+                self.alternate_index += 1;
+                return Some(id);
+            }
+
+            let lookup = match self.lookup {
+                Some(lookup) if self.subtable_index < lookup.subtables_count() => lookup,
+                _ => {
+                    self.lookup = Some(self.next_lookup()?);
+                    self.subtable_index = 0;
+                    continue;
+                }
+            };
+
+            let data = lookup.subtable(self.subtable_index);
+            self.subtable_index += 1;
+
+            match data.and_then(|data| alternates(lookup.kind(), data, self.glyph_id)) {
+                Some(Alternates::Single(id)) => return Some(id),
+                Some(Alternates::Set(set)) => {
+                    self.alternates = set;
+                    self.alternate_index = 0;
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ggg::TableKind;
+
+    #[test]
+    fn aalt() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x0C, // feature list offset: 12
+            0x00, 0x28, // lookup list offset: 40
+            // Script list
+            0x00, 0x00, // count: 0
+            // Feature list
+            0x00, 0x02, // count: 2
+            b'a', b'a', b'l', b't', // tag
+            0x00, 0x0E, // offset: 14
+            b'a', b'a', b'l', b't', // tag
+            0x00, 0x16, // offset: 22
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x02, // lookup index count: 2
+            0x00, 0x00, // lookup index [0]: 0
+            0x00, 0x01, // lookup index [1]: 1
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x01, // lookup index count: 1
+            0x00, 0x01, // lookup index [0]: 1
+            // Lookup list
+            0x00, 0x02, // count: 2
+            0x00, 0x06, // offset [0]: 6
+            0x00, 0x1A, // offset [1]: 26
+            // Lookup 0
+            0x00, 0x01, // type: single substitution
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // offset [0]: 8
+            // Single substitution subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x06, // coverage offset: 6
+            0x00, 0x0A, // delta: 10
+            // Coverage table
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // glyph [0]: 5
+            // Lookup 1
+            0x00, 0x07, // type: extension substitution
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // offset [0]: 8
+            // Extension subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x03, // extension lookup type: alternate substitution
+            0x00, 0x00, 0x00, 0x08, // offset: 8
+            // Alternate substitution subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x08, // coverage offset: 8
+            0x00, 0x01, // alternate set count: 1
+            0x00, 0x12, // offset [0]: 18
+            // Coverage table
+            0x00, 0x02, // format: 2
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // start glyph: 5
+            0x00, 0x05, // end glyph: 5
+            0x00, 0x00, // start coverage index: 0
+            // Alternate set
+            0x00, 0x02, // count: 2
+            0x00, 0x14, // glyph [0]: 20
+            0x00, 0x15, // glyph [1]: 21
+        ];
+
+        let table = LayoutTable::parse(data, TableKind::Substitution);
+        assert!(table.is_some());
+
+        let mut iter = GlyphAlternates::new(table, GlyphId(5));
+        assert_eq!(iter.next(), Some(GlyphId(15)));
+        assert_eq!(iter.next(), Some(GlyphId(20)));
+        assert_eq!(iter.next(), Some(GlyphId(21)));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(GlyphAlternates::new(table, GlyphId(6)).count(), 0);
+        assert_eq!(GlyphAlternates::new(None, GlyphId(5)).count(), 0);
+    }
+
+    #[test]
+    fn aalt_with_large_lookup_index() {
+        let mut data = vec![
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x0A, // script list offset: 10
+            0x00, 0x0C, // feature list offset: 12
+            0x00, 0x20, // lookup list offset: 32
+            // Script list
+            0x00, 0x00, // count: 0
+            // Feature list
+            0x00, 0x02, // count: 2
+            b'a', b'a', b'l', b't', // tag
+            0x00, 0x0E, // offset: 14
+            b'a', b'a', b'l', b't', // tag
+            0x00, 0x0E, // offset: 14
+            // Feature table
+            0x00, 0x00, // feature params offset: 0
+            0x00, 0x01, // lookup index count: 1
+            0x01, 0x2C, // lookup index [0]: 300
+            // Lookup list
+            0x01, 0x2D, // count: 301
+        ];
+
+        // All lookups are the same.
+        for _ in 0..301 {
+            data.extend_from_slice(&[0x02, 0x5C]); // offset: 604
+        }
+
+        data.extend_from_slice(&[
+            // Lookup
+            0x00, 0x01, // type: single substitution
+            0x00, 0x00, // flags: 0
+            0x00, 0x01, // subtable count: 1
+            0x00, 0x08, // offset [0]: 8
+            // Single substitution subtable
+            0x00, 0x01, // format: 1
+            0x00, 0x06, // coverage offset: 6
+            0x00, 0x0A, // delta: 10
+            // Coverage table
+            0x00, 0x01, // format: 1
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // glyph [0]: 5
+        ]);
+
+        let table = LayoutTable::parse(&data, TableKind::Substitution);
+        assert!(table.is_some());
+
+        // Both features reference the same lookup.
+        let mut iter = GlyphAlternates::new(table, GlyphId(5));
+        assert_eq!(iter.next(), Some(GlyphId(15)));
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -203,9 +203,14 @@ fn outline_var_impl<'a>(
 
             transform = Transform::combine(transform, component.transform);
 
-            let mut b = glyf::Builder::new(transform, builder.bbox, builder.builder);
+            // An empty component, like a space, has nothing to outline.
             let range = loca_table.glyph_range(component.glyph_id)?;
             let glyph_data = glyf_table.get(range)?;
+            if Stream::read_at::<i16>(glyph_data, 0) == Some(0) {
+                continue;
+            }
+
+            let mut b = glyf::Builder::new(transform, builder.bbox, builder.builder);
             outline_var_impl(
                 loca_table, glyf_table, gvar_table, component.glyph_id,
                 glyph_data, coordinates, depth + 1, &mut b,
@@ -403,10 +408,10 @@ impl<'a> VariationTuples<'a> {
 
 
 #[derive(Clone, Copy, Default, Debug)]
-struct TupleVariationHeaderData {
-    scalar: f32,
-    has_private_point_numbers: bool,
-    serialized_data_len: u16,
+pub(crate) struct TupleVariationHeaderData {
+    pub scalar: f32,
+    pub has_private_point_numbers: bool,
+    pub serialized_data_len: u16,
 }
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#tuplevariationheader
@@ -475,7 +480,7 @@ fn parse_variation_tuples<'a>(
 }
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#tuplevariationheader
-fn parse_tuple_variation_header(
+pub(crate) fn parse_tuple_variation_header(
     coordinates: &[NormalizedCoordinate],
     shared_tuple_records: &LazyArray16<F2DOT14>,
     s: &mut Stream,
@@ -561,7 +566,7 @@ fn parse_tuple_variation_header(
 
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#packed-point-numbers
-mod packed_points {
+pub(crate) mod packed_points {
     use crate::parser::{Stream, FromData};
 
     struct Control(u8);
@@ -967,7 +972,7 @@ use packed_points::*;
 
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#packed-deltas
-mod packed_deltas {
+pub(crate) mod packed_deltas {
     use crate::parser::Stream;
 
     struct Control(u8);
@@ -1114,6 +1119,37 @@ mod packed_deltas {
         }
     }
 
+
+    /// Like `PackedDeltasIter`, but for data with a single delta per point.
+    ///
+    /// Used by `cvar`.
+    #[derive(Clone, Copy, Default)]
+    pub struct PackedValuesIter<'a> {
+        data: &'a [u8],
+        run: RunState,
+        scalar: f32,
+    }
+
+    impl<'a> PackedValuesIter<'a> {
+        #[inline]
+        pub fn new(scalar: f32, data: &'a [u8]) -> Self {
+            PackedValuesIter {
+                data,
+                run: RunState::default(),
+                scalar,
+            }
+        }
+    }
+
+    impl<'a> Iterator for PackedValuesIter<'a> {
+        type Item = f32;
+
+        #[inline]
+        fn next(&mut self) -> Option<Self::Item> {
+            self.run.next(self.data, self.scalar)
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -1561,8 +1597,102 @@ fn infer_delta(
         //
         // 'Target point delta is derived from the adjacent point deltas
         // using linear interpolation.'
-        let d =   f32::from(try_opt_or!(target_point.checked_sub(prev_point), 0.0))
-                / f32::from(try_opt_or!(next_point.checked_sub(prev_point), 0.0));
+        let d =   (i32::from(target_point) - i32::from(prev_point)) as f32
+                / (i32::from(next_point) - i32::from(prev_point)) as f32;
         (1.0 - d) * prev_delta + d * next_delta
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inferred_delta() {
+        // Same adjacent points.
+        assert_eq!(infer_delta(10, 20, 10, 5.0, 5.0), 5.0);
+        assert_eq!(infer_delta(10, 20, 10, 5.0, 6.0), 0.0);
+
+        // Target point is outside the adjacent points range.
+        assert_eq!(infer_delta(10, 0, 20, 5.0, 6.0), 5.0);
+        assert_eq!(infer_delta(20, 0, 10, 5.0, 6.0), 6.0);
+        assert_eq!(infer_delta(10, 30, 20, 5.0, 6.0), 6.0);
+        assert_eq!(infer_delta(20, 30, 10, 5.0, 6.0), 5.0);
+        assert_eq!(infer_delta(10, 10, 20, 5.0, 6.0), 5.0);
+        assert_eq!(infer_delta(10, 20, 20, 5.0, 6.0), 6.0);
+
+        // Target point is between the adjacent points.
+        assert_eq!(infer_delta(0, 25, 100, 0.0, 100.0), 25.0);
+        assert_eq!(infer_delta(100, 25, 0, 100.0, 0.0), 25.0);
+
+        // Coordinate differences that do not fit into i16.
+        assert_eq!(infer_delta(-32768, 0, 32767, 0.0, 65535.0), 32768.0);
+    }
+
+    #[test]
+    fn composite_glyph_with_empty_component() {
+        use core::num::NonZeroU16;
+        use crate::{DummyOutline, Rect};
+        use crate::head::IndexToLocationFormat;
+
+        let loca_data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0B, // offset [1]: 22
+            0x00, 0x10, // offset [2]: 32
+            0x00, 0x1B, // offset [3]: 54
+        ];
+
+        let glyf_data = &[
+            // Glyph [0]
+            0x00, 0x01, // number of contours: 1
+            0x00, 0x0A, 0x00, 0x05, 0x00, 0x3C, 0x00, 0x2D, // bbox: 10, 5, 60, 45
+            0x00, 0x02, // end point [0]: 2
+            0x00, 0x00, // instructions length: 0
+            0x37, // flags [0]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x37, // flags [1]: ON_CURVE_POINT | X_SHORT | Y_SHORT | X_IS_POSITIVE | Y_IS_POSITIVE
+            0x33, // flags [2]: ON_CURVE_POINT | X_SHORT | X_IS_POSITIVE | Y_IS_SAME
+            0x0A, // x [0]: +10
+            0x14, // x [1]: +20
+            0x1E, // x [2]: +30
+            0x05, // y [0]: +5
+            0x28, // y [1]: +40
+
+            // Glyph [1]
+            0x00, 0x00, // number of contours: 0
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bbox
+
+            // Glyph [2]
+            0xFF, 0xFF, // number of contours: -1
+            0x00, 0x0A, 0x00, 0x05, 0x00, 0x3C, 0x00, 0x2D, // bbox: 10, 5, 60, 45
+            0x00, 0x22, // flags: MORE_COMPONENTS | ARGS_ARE_XY_VALUES
+            0x00, 0x01, // glyph index: 1
+            0x00, 0x00, // arguments: 0, 0
+            0x00, 0x02, // flags: ARGS_ARE_XY_VALUES
+            0x00, 0x00, // glyph index: 0
+            0x00, 0x00, // arguments: 0, 0
+        ];
+
+        let gvar_data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x01, // axis count: 1
+            0x00, 0x00, // shared tuple count: 0
+            0x00, 0x00, 0x00, 0x00, // shared tuples offset: 0
+            0x00, 0x03, // glyph count: 3
+            0x00, 0x00, // flags: short offsets
+            0x00, 0x00, 0x00, 0x1C, // glyph variation data array offset: 28
+            // Glyph variation data offsets
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x00, // offset [1]: 0
+            0x00, 0x00, // offset [2]: 0
+            0x00, 0x00, // offset [3]: 0
+        ];
+
+        let number_of_glyphs = NonZeroU16::new(3).unwrap();
+        let loca = loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        let gvar = Table::parse(gvar_data).unwrap();
+        let coordinates = [NormalizedCoordinate::from(0.5)];
+        assert_eq!(outline(loca, glyf_data, &gvar, &coordinates, GlyphId(2), &mut DummyOutline),
+                   Some(Rect { x_min: 10, y_min: 5, x_max: 60, y_max: 45 }));
+    }
+}
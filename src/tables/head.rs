@@ -1,21 +1,47 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/head
 
 use crate::Rect;
-use crate::parser::Stream;
+use crate::parser::{Stream, Fixed};
 
 
 const TABLE_SIZE: usize = 54;
+const FONT_REVISION_OFFSET: usize = 4;
+const FLAGS_OFFSET: usize = 16;
 const UNITS_PER_EM_OFFSET: usize = 18;
 const BBOX_OFFSET: usize = 36;
+const MAC_STYLE_OFFSET: usize = 44;
+const LOWEST_REC_PPEM_OFFSET: usize = 46;
 const INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
 
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub(crate) enum IndexToLocationFormat {
+/// An [index to location format](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/head),
+/// i.e. the size of `loca` table offsets.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub enum IndexToLocationFormat {
+    /// Offsets are `u16` values divided by 2.
     Short,
+    /// Offsets are `u32` values.
     Long,
 }
 
+
+/// [Mac style](https://docs.microsoft.com/en-us/typography/opentype/spec/head) flags.
+#[repr(transparent)]
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Hash)]
+pub struct MacStyle(pub u16);
+
+#[allow(missing_docs)]
+impl MacStyle {
+    #[inline] pub fn bold(self) -> bool { self.0 & (1 << 0) != 0 }
+    #[inline] pub fn italic(self) -> bool { self.0 & (1 << 1) != 0 }
+    #[inline] pub fn underline(self) -> bool { self.0 & (1 << 2) != 0 }
+    #[inline] pub fn outline(self) -> bool { self.0 & (1 << 3) != 0 }
+    #[inline] pub fn shadow(self) -> bool { self.0 & (1 << 4) != 0 }
+    #[inline] pub fn condensed(self) -> bool { self.0 & (1 << 5) != 0 }
+    #[inline] pub fn extended(self) -> bool { self.0 & (1 << 6) != 0 }
+}
+
 #[inline]
 pub fn parse(data: &[u8]) -> Option<&[u8]> {
     if data.len() == TABLE_SIZE {
@@ -25,6 +51,21 @@ pub fn parse(data: &[u8]) -> Option<&[u8]> {
     }
 }
 
+#[inline]
+pub fn font_revision(data: &[u8]) -> Option<f32> {
+    Stream::read_at::<Fixed>(data, FONT_REVISION_OFFSET).map(|n| n.0)
+}
+
+#[inline]
+pub fn mac_style(data: &[u8]) -> Option<MacStyle> {
+    Stream::read_at::<u16>(data, MAC_STYLE_OFFSET).map(MacStyle)
+}
+
+#[inline]
+pub fn flags(data: &[u8]) -> Option<u16> {
+    Stream::read_at(data, FLAGS_OFFSET)
+}
+
 #[inline]
 pub fn units_per_em(data: &[u8]) -> Option<u16> {
     let num: u16 = Stream::read_at(data, UNITS_PER_EM_OFFSET)?;
@@ -47,7 +88,12 @@ pub fn global_bbox(data: &[u8]) -> Option<Rect> {
 }
 
 #[inline]
-pub(crate) fn index_to_loc_format(data: &[u8]) -> Option<IndexToLocationFormat> {
+pub fn lowest_rec_ppem(data: &[u8]) -> Option<u16> {
+    Stream::read_at(data, LOWEST_REC_PPEM_OFFSET)
+}
+
+#[inline]
+pub fn index_to_loc_format(data: &[u8]) -> Option<IndexToLocationFormat> {
     let format: i16 = Stream::read_at(data, INDEX_TO_LOC_FORMAT_OFFSET)?;
     match format {
         0 => Some(IndexToLocationFormat::Short),
@@ -55,3 +101,38 @@ pub(crate) fn index_to_loc_format(data: &[u8]) -> Option<IndexToLocationFormat>
         _ => None,
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_and_ppem() {
+        let mut data = [0; TABLE_SIZE];
+        data[FLAGS_OFFSET..FLAGS_OFFSET + 2].copy_from_slice(&[0x00, 0x13]);
+        data[LOWEST_REC_PPEM_OFFSET..LOWEST_REC_PPEM_OFFSET + 2].copy_from_slice(&[0x00, 0x09]);
+
+        let data = parse(&data).unwrap();
+        assert_eq!(flags(data), Some(0x13));
+        assert_eq!(lowest_rec_ppem(data), Some(9));
+    }
+
+    #[test]
+    fn metadata() {
+        let mut data = [0; TABLE_SIZE];
+        data[FONT_REVISION_OFFSET..FONT_REVISION_OFFSET + 4]
+            .copy_from_slice(&[0x00, 0x02, 0x80, 0x00]);
+        data[MAC_STYLE_OFFSET..MAC_STYLE_OFFSET + 2].copy_from_slice(&[0x00, 0x22]);
+        data[INDEX_TO_LOC_FORMAT_OFFSET..INDEX_TO_LOC_FORMAT_OFFSET + 2]
+            .copy_from_slice(&[0x00, 0x01]);
+
+        let data = parse(&data).unwrap();
+        assert_eq!(font_revision(data), Some(2.5));
+        let style = mac_style(data).unwrap();
+        assert!(!style.bold());
+        assert!(style.italic());
+        assert!(style.condensed());
+        assert_eq!(index_to_loc_format(data), Some(IndexToLocationFormat::Long));
+    }
+}
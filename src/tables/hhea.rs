@@ -9,9 +9,33 @@ const TABLE_SIZE: usize = 36;
 const ASCENDER_OFFSET: usize = 4;
 const DESCENDER_OFFSET: usize = 6;
 const LINE_GAP_OFFSET: usize = 8;
+const ADVANCE_WIDTH_MAX_OFFSET: usize = 10;
+const MIN_LEFT_SIDE_BEARING_OFFSET: usize = 12;
+const MIN_RIGHT_SIDE_BEARING_OFFSET: usize = 14;
+const X_MAX_EXTENT_OFFSET: usize = 16;
+const CARET_SLOPE_RISE_OFFSET: usize = 18;
 const NUMBER_OF_H_METRICS_OFFSET: usize = 34;
 
 
+/// A caret slope.
+///
+/// A vertical caret has a rise of 1 and a run of 0.
+/// For italic faces, the rise-to-run ratio matches the italic angle.
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub struct CaretSlope {
+    /// Slope rise.
+    pub rise: i16,
+
+    /// Slope run.
+    pub run: i16,
+
+    /// A horizontal shift of the slanted caret, which is needed for some faces
+    /// to get the best-looking caret placement.
+    pub offset: i16,
+}
+
+
 #[inline]
 pub fn parse(data: &[u8]) -> Option<&[u8]> {
     if data.len() == TABLE_SIZE {
@@ -36,7 +60,70 @@ pub fn line_gap(data: &[u8]) -> i16 {
     Stream::read_at::<i16>(data, LINE_GAP_OFFSET).unwrap_or(0)
 }
 
+#[inline]
+pub fn advance_width_max(data: &[u8]) -> u16 {
+    Stream::read_at::<u16>(data, ADVANCE_WIDTH_MAX_OFFSET).unwrap_or(0)
+}
+
+#[inline]
+pub fn min_left_side_bearing(data: &[u8]) -> i16 {
+    Stream::read_at::<i16>(data, MIN_LEFT_SIDE_BEARING_OFFSET).unwrap_or(0)
+}
+
+#[inline]
+pub fn min_right_side_bearing(data: &[u8]) -> i16 {
+    Stream::read_at::<i16>(data, MIN_RIGHT_SIDE_BEARING_OFFSET).unwrap_or(0)
+}
+
+#[inline]
+pub fn x_max_extent(data: &[u8]) -> i16 {
+    Stream::read_at::<i16>(data, X_MAX_EXTENT_OFFSET).unwrap_or(0)
+}
+
+#[inline]
+pub fn caret_slope(data: &[u8]) -> CaretSlope {
+    let mut s = Stream::new_at(data, CARET_SLOPE_RISE_OFFSET).unwrap_or_default();
+    CaretSlope {
+        rise: s.read::<i16>().unwrap_or(0),
+        run: s.read::<i16>().unwrap_or(0),
+        offset: s.read::<i16>().unwrap_or(0),
+    }
+}
+
 #[inline]
 pub fn number_of_h_metrics(data: &[u8]) -> Option<NonZeroU16> {
     Stream::read_at::<u16>(data, NUMBER_OF_H_METRICS_OFFSET).and_then(NonZeroU16::new)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x03, 0x20, // ascender: 800
+            0xFF, 0x38, // descender: -200
+            0x00, 0x00, // line gap: 0
+            0x04, 0xB0, // advance width max: 1200
+            0xFF, 0xEC, // min left side bearing: -20
+            0xFF, 0xF6, // min right side bearing: -10
+            0x04, 0x9C, // x max extent: 1180
+            0x00, 0x04, // caret slope rise: 4
+            0x00, 0x01, // caret slope run: 1
+            0xFF, 0xFE, // caret offset: -2
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // reserved
+            0x00, 0x00, // metric data format: 0
+            0x00, 0x01, // number of horizontal metrics: 1
+        ];
+
+        let data = parse(data).unwrap();
+        assert_eq!(advance_width_max(data), 1200);
+        assert_eq!(min_left_side_bearing(data), -20);
+        assert_eq!(min_right_side_bearing(data), -10);
+        assert_eq!(x_max_extent(data), 1180);
+        assert_eq!(caret_slope(data), CaretSlope { rise: 4, run: 1, offset: -2 });
+    }
+}
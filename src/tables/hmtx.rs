@@ -142,6 +142,50 @@ mod tests {
         assert_eq!(table.side_bearing(GlyphId(1)), Some(3));
     }
 
+    #[test]
+    fn trailing_glyphs() {
+        let data = &[
+            0x00, 0x01, // advance width [0]: 1
+            0x00, 0x02, // side bearing [0]: 2
+
+            0x00, 0x03, // advance width [1]: 3
+            0x00, 0x04, // side bearing [1]: 4
+
+            0x00, 0x05, // side bearing [2]: 5
+            0xFF, 0xFA, // side bearing [3]: -6
+        ];
+
+        let table = Table::parse(data, nzu16!(2), nzu16!(4)).unwrap();
+        assert_eq!(table.advance(GlyphId(1)), Some(3));
+        assert_eq!(table.side_bearing(GlyphId(1)), Some(4));
+        assert_eq!(table.advance(GlyphId(2)), Some(3));
+        assert_eq!(table.side_bearing(GlyphId(2)), Some(5));
+        assert_eq!(table.advance(GlyphId(3)), Some(3));
+        assert_eq!(table.side_bearing(GlyphId(3)), Some(-6));
+        assert_eq!(table.advance(GlyphId(4)), None);
+
+        // Advances of trailing glyphs are still known without side bearings.
+        let table = Table::parse(&data[..8], nzu16!(2), nzu16!(4)).unwrap();
+        assert_eq!(table.advance(GlyphId(3)), Some(3));
+        assert_eq!(table.side_bearing(GlyphId(3)), None);
+    }
+
+    #[test]
+    fn huge_counts() {
+        let data = &[
+            0x00, 0x01, // advance width [0]: 1
+            0x00, 0x02, // side bearing [0]: 2
+        ];
+
+        // Counts are checked against the data size without reading the records.
+        assert!(Table::parse(data, nzu16!(0xFFFF), nzu16!(0xFFFF)).is_none());
+
+        let table = Table::parse(data, nzu16!(1), nzu16!(0xFFFF)).unwrap();
+        assert_eq!(table.advance(GlyphId(0xFFFE)), Some(1));
+        assert_eq!(table.side_bearing(GlyphId(0xFFFE)), None);
+        assert_eq!(table.advance(GlyphId(0xFFFF)), None);
+    }
+
     #[test]
     fn less_metrics_than_glyphs() {
         let data = &[
@@ -1,10 +1,8 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/hvar
 
-use core::convert::TryFrom;
-
 use crate::{GlyphId, NormalizedCoordinate};
 use crate::parser::{Stream, Offset, Offset32};
-use crate::var_store::ItemVariationStore;
+use crate::var_store::{ItemVariationStore, DeltaSetIndexMap};
 
 #[derive(Clone, Copy)]
 pub struct Table<'a> {
@@ -34,55 +32,14 @@ impl<'a> Table<'a> {
             lsb_mapping_offset: s.read::<Option<Offset32>>()?,
         })
     }
-}
-
 
-pub struct DeltaSetIndexMap<'a> {
-    data: &'a [u8],
-}
-
-impl<'a> DeltaSetIndexMap<'a> {
     #[inline]
-    pub fn new(data: &'a [u8]) -> Self {
-        DeltaSetIndexMap { data }
-    }
-
-    #[inline]
-    pub fn map(&self, glyph_id: GlyphId) -> Option<(u16, u16)> {
-        let mut idx = glyph_id.0;
-
-        let mut s = Stream::new(self.data);
-        let entry_format: u16 = s.read()?;
-        let map_count: u16 = s.read()?;
-
-        if map_count == 0 {
-            return None;
-        }
-
-        // 'If a given glyph ID is greater than mapCount-1, then the last entry is used.'
-        if idx >= map_count {
-            idx = map_count - 1;
-        }
-
-        let entry_size = ((entry_format >> 4) & 3) + 1;
-        let inner_index_bit_count = u32::from((entry_format & 0xF) + 1);
-
-        s.advance(usize::from(entry_size) * usize::from(idx));
-
-        let mut n = 0u32;
-        for b in s.read_bytes(usize::from(entry_size))? {
-            n = (n << 8) + u32::from(*b);
-        }
-
-        let outer_index = n >> inner_index_bit_count;
-        let inner_index = n & ((1 << inner_index_bit_count) - 1);
-        Some((
-            u16::try_from(outer_index).ok()?,
-            u16::try_from(inner_index).ok()?
-        ))
+    pub fn variation_store(&self) -> ItemVariationStore<'a> {
+        self.variation_store
     }
 }
 
+
 #[inline]
 pub(crate) fn glyph_advance_offset(
     table: Table,
@@ -90,7 +47,7 @@ pub(crate) fn glyph_advance_offset(
     coordinates: &[NormalizedCoordinate],
 ) -> Option<f32> {
     let (outer_idx, inner_idx) = if let Some(offset) = table.advance_width_mapping_offset {
-        DeltaSetIndexMap::new(table.data.get(offset.to_usize()..)?).map(glyph_id)?
+        DeltaSetIndexMap::parse(table.data.get(offset.to_usize()..)?)?.map_glyph(glyph_id)?
     } else {
         // 'If there is no delta-set index mapping table for advance widths,
         // then glyph IDs implicitly provide the indices:
@@ -109,6 +66,124 @@ pub(crate) fn glyph_side_bearing_offset(
     coordinates: &[NormalizedCoordinate],
 ) -> Option<f32> {
     let set_data = table.data.get(table.lsb_mapping_offset?.to_usize()..)?;
-    let (outer_idx, inner_idx) = DeltaSetIndexMap::new(set_data).map(glyph_id)?;
+    let (outer_idx, inner_idx) = DeltaSetIndexMap::parse(set_data)?.map_glyph(glyph_id)?;
     table.variation_store.parse_delta(outer_idx, inner_idx, coordinates)
 }
+
+/// `VVAR` only.
+#[inline]
+pub(crate) fn glyph_y_origin_offset(
+    table: Table,
+    glyph_id: GlyphId,
+    coordinates: &[NormalizedCoordinate],
+) -> Option<f32> {
+    // The vertical origin mapping offset is the last field of the `VVAR` header.
+    let offset = Stream::read_at::<Option<Offset32>>(table.data, 20)??;
+    let set_data = table.data.get(offset.to_usize()..)?;
+    let (outer_idx, inner_idx) = DeltaSetIndexMap::parse(set_data)?.map_glyph(glyph_id)?;
+    table.variation_store.parse_delta(outer_idx, inner_idx, coordinates)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_offsets() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, 0x00, 0x14, // variation store offset: 20
+            0x00, 0x00, 0x00, 0x00, // advance width mapping offset: NULL
+            0x00, 0x00, 0x00, 0x36, // LSB mapping offset: 54
+            0x00, 0x00, 0x00, 0x00, // RSB mapping offset: NULL
+
+            // Item variation store
+            0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0C, // region list offset: 12
+            0x00, 0x01, // data count: 1
+            0x00, 0x00, 0x00, 0x16, // data offset [0]: 22
+            // Region list
+            0x00, 0x01, // axis count: 1
+            0x00, 0x01, // region count: 1
+            0x00, 0x00, 0x40, 0x00, 0x40, 0x00, // region 0, axis 0: 0, 1, 1
+            // Item variation data
+            0x00, 0x02, // item count: 2
+            0x00, 0x01, // short delta count: 1
+            0x00, 0x01, // region index count: 1
+            0x00, 0x00, // region index [0]: 0
+            0x00, 0x64, // item 0 delta: 100
+            0xFF, 0xCE, // item 1 delta: -50
+
+            // LSB mapping
+            0x00, // format: 0
+            0x03, // entry format: 1 byte, 4 inner index bits
+            0x00, 0x02, // map count: 2
+            0x01, // glyph 0: 0, 1
+            0x00, // glyph 1: 0, 0
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let coords = [NormalizedCoordinate::from(0.5)];
+
+        // Glyph IDs are used as inner indices directly.
+        assert_eq!(glyph_advance_offset(table, GlyphId(0), &coords), Some(50.0));
+        assert_eq!(glyph_advance_offset(table, GlyphId(1), &coords), Some(-25.0));
+        assert_eq!(glyph_advance_offset(table, GlyphId(2), &coords), None);
+
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(0), &coords), Some(-25.0));
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(1), &coords), Some(50.0));
+        // Glyphs past the end of the map use the last entry.
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(5), &coords), Some(50.0));
+
+        let coords = [NormalizedCoordinate::from(0.0)];
+        assert_eq!(glyph_advance_offset(table, GlyphId(0), &coords), Some(0.0));
+    }
+
+    #[test]
+    fn vertical_origin_offsets() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, 0x00, 0x18, // variation store offset: 24
+            0x00, 0x00, 0x00, 0x00, // advance height mapping offset: NULL
+            0x00, 0x00, 0x00, 0x00, // TSB mapping offset: NULL
+            0x00, 0x00, 0x00, 0x00, // BSB mapping offset: NULL
+            0x00, 0x00, 0x00, 0x3A, // vertical origin mapping offset: 58
+
+            // Item variation store
+            0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0C, // region list offset: 12
+            0x00, 0x01, // data count: 1
+            0x00, 0x00, 0x00, 0x16, // data offset [0]: 22
+            // Region list
+            0x00, 0x01, // axis count: 1
+            0x00, 0x01, // region count: 1
+            0x00, 0x00, 0x40, 0x00, 0x40, 0x00, // region 0, axis 0: 0, 1, 1
+            // Item variation data
+            0x00, 0x02, // item count: 2
+            0x00, 0x01, // short delta count: 1
+            0x00, 0x01, // region index count: 1
+            0x00, 0x00, // region index [0]: 0
+            0x00, 0x64, // item 0 delta: 100
+            0xFF, 0xCE, // item 1 delta: -50
+
+            // Vertical origin mapping
+            0x00, // format: 0
+            0x03, // entry format: 1 byte, 4 inner index bits
+            0x00, 0x01, // map count: 1
+            0x01, // glyph 0: 0, 1
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let coords = [NormalizedCoordinate::from(1.0)];
+        assert_eq!(glyph_y_origin_offset(table, GlyphId(0), &coords), Some(-50.0));
+        assert_eq!(glyph_y_origin_offset(table, GlyphId(3), &coords), Some(-50.0));
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(0), &coords), None);
+
+        // Without a mapping.
+        let mut data = data.to_vec();
+        data[23] = 0;
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_y_origin_offset(table, GlyphId(0), &coords), None);
+    }
+}
@@ -26,10 +26,20 @@ impl OTCoverage {
         self.0 & (1 << 0) != 0
     }
 
+    #[inline]
+    fn is_minimum(self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+
     #[inline]
     fn has_cross_stream(self) -> bool {
         self.0 & (1 << 2) != 0
     }
+
+    #[inline]
+    fn is_override(self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
 }
 
 impl FromData for OTCoverage {
@@ -73,11 +83,11 @@ impl FromData for AATCoverage {
 
 
 #[derive(Clone, Copy)]
-struct KerningRecord {
+pub(crate) struct KerningRecord {
     // In the kern table spec, a kerning pair is stored as two u16,
     // but we are using one u32, so we can binary search it directly.
-    pair: u32,
-    value: i16,
+    pub pair: u32,
+    pub value: i16,
 }
 
 impl FromData for KerningRecord {
@@ -100,6 +110,8 @@ pub struct Subtable<'a> {
     is_horizontal: bool,
     is_variable: bool,
     has_cross_stream: bool,
+    is_minimum: bool,
+    is_override: bool,
     format: u8,
     header_size: u8,
     data: &'a [u8],
@@ -126,6 +138,23 @@ impl<'a> Subtable<'a> {
         self.has_cross_stream
     }
 
+    /// Checks that subtable contains minimum values instead of kerning values.
+    ///
+    /// Only the OpenType variant has such subtables.
+    #[inline]
+    pub fn is_minimum(&self) -> bool {
+        self.is_minimum
+    }
+
+    /// Checks that subtable values replace the accumulated kerning value
+    /// instead of being added to it.
+    ///
+    /// Only the OpenType variant has such subtables.
+    #[inline]
+    pub fn is_override(&self) -> bool {
+        self.is_override
+    }
+
     /// Checks that subtable uses a state machine.
     ///
     /// In this case `glyphs_kerning()` will return `None`.
@@ -155,6 +184,8 @@ impl core::fmt::Debug for Subtable<'_> {
             .field("is_horizontal", &self.is_horizontal())
             .field("has_state_machine", &self.has_state_machine())
             .field("has_cross_stream", &self.has_cross_stream())
+            .field("is_minimum", &self.is_minimum())
+            .field("is_override", &self.is_override())
             .field("format", &self.format)
             .finish()
     }
@@ -187,6 +218,8 @@ impl<'a> Iterator for Subtables<'a> {
             return None;
         }
 
+        self.table_index += 1;
+
         if self.is_aat {
             const HEADER_SIZE: u8 = 8;
 
@@ -207,6 +240,8 @@ impl<'a> Iterator for Subtables<'a> {
                 is_horizontal: coverage.is_horizontal(),
                 is_variable: coverage.is_variable(),
                 has_cross_stream: coverage.has_cross_stream(),
+                is_minimum: false, // Only OpenType supports it.
+                is_override: false, // Only OpenType supports it.
                 format,
                 header_size: HEADER_SIZE,
                 data: self.stream.read_bytes(data_len)?,
@@ -240,6 +275,8 @@ impl<'a> Iterator for Subtables<'a> {
                 is_horizontal: coverage.is_horizontal(),
                 is_variable: false, // Only AAT supports it.
                 has_cross_stream: coverage.has_cross_stream(),
+                is_minimum: coverage.is_minimum(),
+                is_override: coverage.is_override(),
                 format,
                 header_size: HEADER_SIZE,
                 data: self.stream.read_bytes(data_len)?,
@@ -303,7 +340,7 @@ fn parse_format2(left: GlyphId, right: GlyphId, header_len: u8, data: &[u8]) ->
     let header_len = usize::from(header_len);
     let left_hand_table_offset = s.read::<Offset16>()?.to_usize().checked_sub(header_len)?;
     let right_hand_table_offset = s.read::<Offset16>()?.to_usize().checked_sub(header_len)?;
-    let array_offset = s.read::<Offset16>()?.to_usize().checked_sub(header_len)?;
+    let array_offset = s.read::<Offset16>()?.to_usize();
 
     // 'The array can be indexed by completing the left-hand and right-hand class mappings,
     // adding the class values to the address of the subtable,
@@ -313,6 +350,7 @@ fn parse_format2(left: GlyphId, right: GlyphId, header_len: u8, data: &[u8]) ->
     let right_class = get_format2_class(right.0, right_hand_table_offset, data).unwrap_or(0);
 
     // 'Values within the left-hand offset table should not be less than the kerning array offset.'
+    // Both are relative to the beginning of the subtable.
     if usize::from(left_class) < array_offset {
         return None;
     }
@@ -360,3 +398,84 @@ fn parse_format3(data: &[u8], left: GlyphId, right: GlyphId) -> Option<i16> {
     let index = indices.get(index)?;
     kerning_values.get(u16::from(index))
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format2() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x01, // number of tables: 1
+            // Subtable
+            0x00, 0x00, // version: 0
+            0x00, 0x26, // length: 38
+            0x02, // format: 2
+            0x01, // coverage: horizontal
+            0x00, 0x04, // row width: 4
+            0x00, 0x0E, // left-hand class table offset: 14
+            0x00, 0x16, // right-hand class table offset: 22
+            0x00, 0x1E, // kerning array offset: 30
+            // Left-hand class table
+            0x00, 0x01, // first glyph: 1
+            0x00, 0x02, // number of glyphs: 2
+            0x00, 0x1E, // class [0]: 30
+            0x00, 0x22, // class [1]: 34
+            // Right-hand class table
+            0x00, 0x02, // first glyph: 2
+            0x00, 0x02, // number of glyphs: 2
+            0x00, 0x00, // class [0]: 0
+            0x00, 0x02, // class [1]: 2
+            // Kerning array
+            0x00, 0x00, // value [0, 0]: 0
+            0xFF, 0xF6, // value [0, 1]: -10
+            0xFF, 0xEC, // value [1, 0]: -20
+            0xFF, 0xE2, // value [1, 1]: -30
+        ];
+
+        let subtable = parse(data).unwrap().next().unwrap();
+        assert!(subtable.is_horizontal());
+        assert!(!subtable.has_state_machine());
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(0));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(3)), Some(-10));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(2)), Some(-20));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(3)), Some(-30));
+        // Right-hand glyphs without a class are in the first column.
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(5)), Some(-20));
+        // Left-hand glyphs without a class have no kerning.
+        assert_eq!(subtable.glyphs_kerning(GlyphId(3), GlyphId(2)), None);
+    }
+
+    #[test]
+    fn format2_aat() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, 0x00, 0x01, // number of tables: 1
+            // Subtable
+            0x00, 0x00, 0x00, 0x1E, // length: 30
+            0x00, // coverage: horizontal
+            0x02, // format: 2
+            0x00, 0x00, // tuple index: 0
+            0x00, 0x02, // row width: 2
+            0x00, 0x10, // left-hand class table offset: 16
+            0x00, 0x16, // right-hand class table offset: 22
+            0x00, 0x1C, // kerning array offset: 28
+            // Left-hand class table
+            0x00, 0x01, // first glyph: 1
+            0x00, 0x01, // number of glyphs: 1
+            0x00, 0x1C, // class [0]: 28
+            // Right-hand class table
+            0x00, 0x02, // first glyph: 2
+            0x00, 0x01, // number of glyphs: 1
+            0x00, 0x00, // class [0]: 0
+            // Kerning array
+            0xFF, 0xD8, // value [0, 0]: -40
+        ];
+
+        let subtable = parse(data).unwrap().next().unwrap();
+        assert!(subtable.is_horizontal());
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-40));
+    }
+}
@@ -0,0 +1,539 @@
+/*!
+An [Extended Kerning Table](
+https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html) implementation.
+
+Like with the `kern` table, we provide an access to kerning subtables,
+so a caller can implement a kerning algorithm manually.
+Only formats 0, 2 and 6 are supported. Other formats use state machines or anchor points.
+*/
+
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::num::NonZeroU16;
+
+use crate::GlyphId;
+use crate::parser::{Stream, NumFrom};
+use super::kern::KerningRecord;
+
+// length (u32) + coverage (u32) + tupleCount (u32)
+const HEADER_SIZE: usize = 12;
+
+
+#[derive(Clone, Copy, Debug)]
+struct Coverage(u8);
+
+impl Coverage {
+    #[inline]
+    fn is_horizontal(self) -> bool {
+        self.0 & (1 << 7) == 0
+    }
+
+    #[inline]
+    fn has_cross_stream(self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+
+    #[inline]
+    fn is_variable(self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+}
+
+
+/// An extended kerning subtable.
+#[derive(Clone, Copy, Default)]
+pub struct Subtable<'a> {
+    is_horizontal: bool,
+    is_variable: bool,
+    has_cross_stream: bool,
+    format: u8,
+    number_of_glyphs: u16,
+    // Includes the header, since all offsets are from the beginning of the subtable.
+    data: &'a [u8],
+}
+
+impl<'a> Subtable<'a> {
+    /// Checks that subtable is for horizontal text.
+    #[inline]
+    pub fn is_horizontal(&self) -> bool {
+        self.is_horizontal
+    }
+
+    /// Checks that subtable is variable.
+    #[inline]
+    pub fn is_variable(&self) -> bool {
+        self.is_variable
+    }
+
+    /// Checks that subtable has a cross-stream values.
+    #[inline]
+    pub fn has_cross_stream(&self) -> bool {
+        self.has_cross_stream
+    }
+
+    /// Checks that subtable uses a state machine.
+    ///
+    /// In this case `glyphs_kerning()` will return `None`.
+    #[inline]
+    pub fn has_state_machine(&self) -> bool {
+        self.format == 1 || self.format == 4
+    }
+
+    /// Returns kerning for a pair of glyphs.
+    ///
+    /// Returns `None` in case of a state machine or an anchor points based subtable.
+    #[inline]
+    pub fn glyphs_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        match self.format {
+            0 => parse_format0(self.data, left, right),
+            2 => parse_format2(self.data, self.number_of_glyphs, left, right),
+            6 => parse_format6(self.data, self.number_of_glyphs, left, right),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Debug for Subtable<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Subtable")
+            .field("is_horizontal", &self.is_horizontal())
+            .field("has_state_machine", &self.has_state_machine())
+            .field("has_cross_stream", &self.has_cross_stream())
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+
+/// An iterator over extended kerning subtables.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy, Default)]
+pub struct Subtables<'a> {
+    number_of_glyphs: u16,
+    /// The current table index,
+    table_index: u32,
+    /// The total number of tables.
+    number_of_tables: u32,
+    /// Actual data. Starts right after `kerx` header.
+    stream: Stream<'a>,
+}
+
+impl<'a> Iterator for Subtables<'a> {
+    type Item = Subtable<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.table_index == self.number_of_tables {
+            return None;
+        }
+
+        if self.stream.at_end() {
+            return None;
+        }
+
+        self.table_index += 1;
+
+        let mut s = self.stream;
+        let table_len: u32 = s.read()?;
+        let coverage = Coverage(s.read()?);
+        s.advance(2); // unused
+        let format: u8 = s.read()?;
+
+        let data = self.stream.read_bytes(usize::num_from(table_len))?;
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+
+        Some(Subtable {
+            is_horizontal: coverage.is_horizontal(),
+            is_variable: coverage.is_variable(),
+            has_cross_stream: coverage.has_cross_stream(),
+            format,
+            number_of_glyphs: self.number_of_glyphs,
+            data,
+        })
+    }
+}
+
+pub(crate) fn parse(data: &[u8], number_of_glyphs: NonZeroU16) -> Option<Subtables<'_>> {
+    let mut s = Stream::new(data);
+    let version: u16 = s.read()?;
+    if version < 2 {
+        return None;
+    }
+
+    s.skip::<u16>(); // padding
+    let number_of_tables: u32 = s.read()?;
+    Some(Subtables {
+        number_of_glyphs: number_of_glyphs.get(),
+        table_index: 0,
+        number_of_tables,
+        stream: s,
+    })
+}
+
+fn parse_format0(data: &[u8], left: GlyphId, right: GlyphId) -> Option<i16> {
+    let mut s = Stream::new_at(data, HEADER_SIZE)?;
+    let number_of_pairs: u32 = s.read()?;
+    s.advance(12); // search_range (u32) + entry_selector (u32) + range_shift (u32)
+    let pairs = s.read_array32::<KerningRecord>(number_of_pairs)?;
+
+    let needle = u32::from(left.0) << 16 | u32::from(right.0);
+    pairs.binary_search_by(|v| v.pair.cmp(&needle)).map(|(_, v)| v.value)
+}
+
+fn parse_format2(data: &[u8], number_of_glyphs: u16, left: GlyphId, right: GlyphId) -> Option<i16> {
+    let mut s = Stream::new_at(data, HEADER_SIZE)?;
+    s.skip::<u32>(); // row_width
+    let left_hand_table_offset: u32 = s.read()?;
+    let right_hand_table_offset: u32 = s.read()?;
+    let array_offset: u32 = s.read()?;
+
+    // Glyphs without a class use the class 0.
+    let left_class = data.get(usize::num_from(left_hand_table_offset)..)
+        .and_then(|data| lookup(data, number_of_glyphs, left, 2))
+        .unwrap_or(0);
+    let right_class = data.get(usize::num_from(right_hand_table_offset)..)
+        .and_then(|data| lookup(data, number_of_glyphs, right, 2))
+        .unwrap_or(0);
+
+    // Unlike in the `kern` table, the left-hand classes are not offsets,
+    // but indices premultiplied by the number of values in a row.
+    let index = usize::num_from(left_class.checked_add(right_class)?);
+    let offset = usize::num_from(array_offset).checked_add(index.checked_mul(2)?)?;
+    Stream::read_at::<i16>(data, offset)
+}
+
+fn parse_format6(data: &[u8], number_of_glyphs: u16, left: GlyphId, right: GlyphId) -> Option<i16> {
+    let mut s = Stream::new_at(data, HEADER_SIZE)?;
+    let flags: u32 = s.read()?;
+    s.skip::<u16>(); // row_count
+    s.skip::<u16>(); // column_count
+    let row_index_table_offset: u32 = s.read()?;
+    let column_index_table_offset: u32 = s.read()?;
+    let array_offset: u32 = s.read()?;
+
+    let is_long = flags & 1 != 0;
+    let value_size = if is_long { 4 } else { 2 };
+
+    let row = data.get(usize::num_from(row_index_table_offset)..)
+        .and_then(|data| lookup(data, number_of_glyphs, left, value_size))
+        .unwrap_or(0);
+    let column = data.get(usize::num_from(column_index_table_offset)..)
+        .and_then(|data| lookup(data, number_of_glyphs, right, value_size))
+        .unwrap_or(0);
+
+    let index = usize::num_from(row.checked_add(column)?);
+    let offset = usize::num_from(array_offset)
+        .checked_add(index.checked_mul(usize::from(value_size))?)?;
+    if is_long {
+        i16::try_from(Stream::read_at::<i32>(data, offset)?).ok()
+    } else {
+        Stream::read_at::<i16>(data, offset)
+    }
+}
+
+
+/// Returns a value from an AAT lookup table.
+///
+/// `value_size` is in bytes and can be 2 or 4.
+/// The format 10 uses its own value size.
+fn lookup(data: &[u8], number_of_glyphs: u16, glyph_id: GlyphId, value_size: u8) -> Option<u32> {
+    // Format + BinSrchHeader: unitSize, nUnits, searchRange, entrySelector, rangeShift.
+    const BIN_SEARCH_HEADER_SIZE: usize = 12;
+
+    let glyph = glyph_id.0;
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    match format {
+        0 => {
+            // Simple array, one value per glyph.
+            if glyph >= number_of_glyphs {
+                return None;
+            }
+
+            read_value(data, 2 + usize::from(glyph) * usize::from(value_size), value_size)
+        }
+        2 | 4 => {
+            // Segment single or segment array.
+            let unit_size: u16 = s.read()?;
+            let number_of_units: u16 = s.read()?;
+            let offset = binary_search_units(
+                data, BIN_SEARCH_HEADER_SIZE, unit_size, number_of_units,
+                |s| {
+                    let last: u16 = s.read()?;
+                    let first: u16 = s.read()?;
+                    Some(if glyph > last {
+                        Ordering::Less
+                    } else if glyph < first {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Equal
+                    })
+                },
+            )?;
+
+            if format == 2 {
+                read_value(data, offset + 4, value_size)
+            } else {
+                let first: u16 = Stream::read_at(data, offset + 2)?;
+                // An offset from the beginning of the lookup table to the values array.
+                let array_offset: u16 = Stream::read_at(data, offset + 4)?;
+                let index = usize::from(glyph - first);
+                read_value(data, usize::from(array_offset) + index * usize::from(value_size), value_size)
+            }
+        }
+        6 => {
+            // Single table.
+            let unit_size: u16 = s.read()?;
+            let number_of_units: u16 = s.read()?;
+            let offset = binary_search_units(
+                data, BIN_SEARCH_HEADER_SIZE, unit_size, number_of_units,
+                |s| s.read::<u16>().map(|g| g.cmp(&glyph)),
+            )?;
+
+            read_value(data, offset + 2, value_size)
+        }
+        8 => {
+            // Trimmed array.
+            let first_glyph: u16 = s.read()?;
+            let glyph_count: u16 = s.read()?;
+            let index = glyph.checked_sub(first_glyph)?;
+            if index >= glyph_count {
+                return None;
+            }
+
+            read_value(data, 6 + usize::from(index) * usize::from(value_size), value_size)
+        }
+        10 => {
+            // Extended trimmed array.
+            let unit_size: u16 = s.read()?;
+            let first_glyph: u16 = s.read()?;
+            let glyph_count: u16 = s.read()?;
+            let index = glyph.checked_sub(first_glyph)?;
+            if index >= glyph_count {
+                return None;
+            }
+
+            let unit_size = u8::try_from(unit_size).ok()?;
+            read_value(data, 8 + usize::from(index) * usize::from(unit_size), unit_size)
+        }
+        _ => None,
+    }
+}
+
+fn read_value(data: &[u8], offset: usize, size: u8) -> Option<u32> {
+    match size {
+        1 => Stream::read_at::<u8>(data, offset).map(u32::from),
+        2 => Stream::read_at::<u16>(data, offset).map(u32::from),
+        4 => Stream::read_at::<u32>(data, offset),
+        _ => None,
+    }
+}
+
+// Returns an offset to a unit found by `f`.
+//
+// `f` should return the ordering of a unit relative to the needle.
+fn binary_search_units<F>(
+    data: &[u8],
+    start: usize,
+    unit_size: u16,
+    number_of_units: u16,
+    mut f: F,
+) -> Option<usize>
+    where F: FnMut(&mut Stream) -> Option<Ordering>
+{
+    let mut size = number_of_units;
+    if size == 0 {
+        return None;
+    }
+
+    let mut base = 0;
+    while size > 1 {
+        let half = size / 2;
+        let mid = base + half;
+        let offset = start + usize::from(mid) * usize::from(unit_size);
+        let cmp = f(&mut Stream::new_at(data, offset)?)?;
+        base = if cmp == Ordering::Greater { base } else { mid };
+        size -= half;
+    }
+
+    let offset = start + usize::from(base) * usize::from(unit_size);
+    let cmp = f(&mut Stream::new_at(data, offset)?)?;
+    if cmp == Ordering::Equal {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_subtable(data: &[u8]) -> Subtable<'_> {
+        parse(data, NonZeroU16::new(4).unwrap()).unwrap().next().unwrap()
+    }
+
+    #[test]
+    fn format0() {
+        let data = &[
+            0x00, 0x02, // version: 2
+            0x00, 0x00, // padding
+            0x00, 0x00, 0x00, 0x01, // number of tables: 1
+            // Subtable
+            0x00, 0x00, 0x00, 0x28, // length: 40
+            0x00, 0x00, 0x00, 0x00, // coverage: horizontal, format 0
+            0x00, 0x00, 0x00, 0x00, // tuple count: 0
+            0x00, 0x00, 0x00, 0x02, // number of pairs: 2
+            0x00, 0x00, 0x00, 0x0C, // search range: 12
+            0x00, 0x00, 0x00, 0x01, // entry selector: 1
+            0x00, 0x00, 0x00, 0x00, // range shift: 0
+            0x00, 0x01, 0x00, 0x02, // pair [0]: 1, 2
+            0xFF, 0xCE, // value [0]: -50
+            0x00, 0x01, 0x00, 0x03, // pair [1]: 1, 3
+            0xFF, 0xF6, // value [1]: -10
+        ];
+
+        let subtable = first_subtable(data);
+        assert!(subtable.is_horizontal());
+        assert!(!subtable.has_state_machine());
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-50));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(3)), Some(-10));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(1)), None);
+    }
+
+    #[test]
+    fn format2() {
+        let data = &[
+            0x00, 0x02, // version: 2
+            0x00, 0x00, // padding
+            0x00, 0x00, 0x00, 0x01, // number of tables: 1
+            // Subtable
+            0x00, 0x00, 0x00, 0x40, // length: 64
+            0x00, 0x00, 0x00, 0x02, // coverage: horizontal, format 2
+            0x00, 0x00, 0x00, 0x00, // tuple count: 0
+            0x00, 0x00, 0x00, 0x04, // row width: 4
+            0x00, 0x00, 0x00, 0x1C, // left-hand class table offset: 28
+            0x00, 0x00, 0x00, 0x26, // right-hand class table offset: 38
+            0x00, 0x00, 0x00, 0x38, // kerning array offset: 56
+            // Left-hand class table
+            0x00, 0x08, // format: 8
+            0x00, 0x01, // first glyph: 1
+            0x00, 0x02, // glyph count: 2
+            0x00, 0x00, // class [0]: 0
+            0x00, 0x02, // class [1]: 2
+            // Right-hand class table
+            0x00, 0x02, // format: 2
+            0x00, 0x06, // unit size: 6
+            0x00, 0x01, // number of units: 1
+            0x00, 0x06, // search range: 6
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x00, // range shift: 0
+            0x00, 0x03, // last glyph [0]: 3
+            0x00, 0x03, // first glyph [0]: 3
+            0x00, 0x01, // class [0]: 1
+            // Kerning array
+            0x00, 0x00, // value [0, 0]: 0
+            0xFF, 0xF6, // value [0, 1]: -10
+            0xFF, 0xEC, // value [1, 0]: -20
+            0xFF, 0xE2, // value [1, 1]: -30
+        ];
+
+        let subtable = first_subtable(data);
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(0));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(3)), Some(-10));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(2)), Some(-20));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(3)), Some(-30));
+        // Glyphs without a class are in the first row.
+        assert_eq!(subtable.glyphs_kerning(GlyphId(5), GlyphId(3)), Some(-10));
+    }
+
+    #[test]
+    fn format6() {
+        let data = &[
+            0x00, 0x02, // version: 2
+            0x00, 0x00, // padding
+            0x00, 0x00, 0x00, 0x01, // number of tables: 1
+            // Subtable
+            0x00, 0x00, 0x00, 0x4A, // length: 74
+            0x00, 0x00, 0x00, 0x06, // coverage: horizontal, format 6
+            0x00, 0x00, 0x00, 0x00, // tuple count: 0
+            0x00, 0x00, 0x00, 0x00, // flags: short values
+            0x00, 0x02, // row count: 2
+            0x00, 0x02, // column count: 2
+            0x00, 0x00, 0x00, 0x24, // row index table offset: 36
+            0x00, 0x00, 0x00, 0x38, // column index table offset: 56
+            0x00, 0x00, 0x00, 0x42, // kerning array offset: 66
+            0x00, 0x00, 0x00, 0x00, // kerning vector offset: 0
+            // Row index table
+            0x00, 0x06, // format: 6
+            0x00, 0x04, // unit size: 4
+            0x00, 0x02, // number of units: 2
+            0x00, 0x08, // search range: 8
+            0x00, 0x01, // entry selector: 1
+            0x00, 0x00, // range shift: 0
+            0x00, 0x01, // glyph [0]: 1
+            0x00, 0x00, // row [0]: 0
+            0x00, 0x02, // glyph [1]: 2
+            0x00, 0x02, // row [1]: 2
+            // Column index table
+            0x00, 0x00, // format: 0
+            0x00, 0x00, // column [0]: 0
+            0x00, 0x00, // column [1]: 0
+            0x00, 0x01, // column [2]: 1
+            0x00, 0x00, // column [3]: 0
+            // Kerning array
+            0x00, 0x00, // value [0, 0]: 0
+            0xFF, 0xF6, // value [0, 1]: -10
+            0xFF, 0xEC, // value [1, 0]: -20
+            0xFF, 0xE2, // value [1, 1]: -30
+        ];
+
+        let subtable = first_subtable(data);
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-10));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(1)), Some(-20));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(2)), Some(-30));
+        // Glyph 7 is out of the number of glyphs.
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(7)), Some(-20));
+    }
+
+    #[test]
+    fn segment_array_lookup() {
+        let data = &[
+            0x00, 0x04, // format: 4
+            0x00, 0x06, // unit size: 6
+            0x00, 0x01, // number of units: 1
+            0x00, 0x06, // search range: 6
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x00, // range shift: 0
+            0x00, 0x0B, // last glyph [0]: 11
+            0x00, 0x0A, // first glyph [0]: 10
+            0x00, 0x12, // values offset [0]: 18
+            0x00, 0x05, // value [0]: 5
+            0x00, 0x07, // value [1]: 7
+        ];
+
+        assert_eq!(lookup(data, 20, GlyphId(9), 2), None);
+        assert_eq!(lookup(data, 20, GlyphId(10), 2), Some(5));
+        assert_eq!(lookup(data, 20, GlyphId(11), 2), Some(7));
+        assert_eq!(lookup(data, 20, GlyphId(12), 2), None);
+    }
+
+    #[test]
+    fn extended_trimmed_array_lookup() {
+        let data = &[
+            0x00, 0x0A, // format: 10
+            0x00, 0x01, // unit size: 1
+            0x00, 0x03, // first glyph: 3
+            0x00, 0x02, // glyph count: 2
+            0x09, // value [0]: 9
+            0x08, // value [1]: 8
+        ];
+
+        assert_eq!(lookup(data, 20, GlyphId(2), 2), None);
+        assert_eq!(lookup(data, 20, GlyphId(3), 2), Some(9));
+        assert_eq!(lookup(data, 20, GlyphId(4), 2), Some(8));
+        assert_eq!(lookup(data, 20, GlyphId(5), 2), None);
+    }
+}
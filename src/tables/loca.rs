@@ -77,3 +77,50 @@ impl<'a> Table<'a> {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_format() {
+        let data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0A, // offset [1]: 20
+            0x00, 0x0A, // offset [2]: 20
+            0x00, 0x10, // offset [3]: 32
+        ];
+
+        let table = Table::parse(data, NonZeroU16::new(3).unwrap(), IndexToLocationFormat::Short).unwrap();
+        assert_eq!(table.glyph_range(GlyphId(0)), Some(0..20));
+        // An empty glyph.
+        assert_eq!(table.glyph_range(GlyphId(1)), None);
+        assert_eq!(table.glyph_range(GlyphId(2)), Some(20..32));
+        assert_eq!(table.glyph_range(GlyphId(3)), None);
+    }
+
+    #[test]
+    fn long_format() {
+        let data = &[
+            0x00, 0x00, 0x00, 0x00, // offset [0]: 0
+            0x00, 0x01, 0x00, 0x00, // offset [1]: 65536
+            0x00, 0x00, 0x00, 0x10, // offset [2]: 16
+        ];
+
+        let table = Table::parse(data, NonZeroU16::new(2).unwrap(), IndexToLocationFormat::Long).unwrap();
+        assert_eq!(table.glyph_range(GlyphId(0)), Some(0..65536));
+        // Offsets are not in ascending order.
+        assert_eq!(table.glyph_range(GlyphId(1)), None);
+    }
+
+    #[test]
+    fn not_enough_offsets() {
+        let data = &[
+            0x00, 0x00, // offset [0]: 0
+            0x00, 0x0A, // offset [1]: 20
+        ];
+
+        assert!(Table::parse(data, NonZeroU16::new(2).unwrap(), IndexToLocationFormat::Short).is_none());
+    }
+}
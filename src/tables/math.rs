@@ -0,0 +1,889 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/math
+
+use crate::GlyphId;
+use crate::ggg::{CoverageTable, Device};
+use crate::parser::{Stream, FromData, Offset, Offset16, LazyArray16};
+
+
+/// A [Math Value](https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathvaluerecord)
+/// with an optional device table.
+#[derive(Clone, Copy, Debug)]
+pub struct MathValue<'a> {
+    /// A value in font design units.
+    pub value: i16,
+    /// A device table for the value.
+    ///
+    /// Contains either per-ppem adjustments or a variation index.
+    pub device: Option<Device<'a>>,
+}
+
+impl<'a> MathValue<'a> {
+    // Device offsets are relative to the parent table.
+    fn parse(data: &'a [u8], parent: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let value: i16 = s.read()?;
+        let device = s.read::<Option<Offset16>>()?
+            .and_then(|offset| parent.get(offset.to_usize()..))
+            .and_then(Device::parse);
+        Some(MathValue { value, device })
+    }
+}
+
+
+/// A [Math Constants Table](https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathconstants-table).
+///
+/// Constants are parsed on access.
+#[derive(Clone, Copy)]
+pub struct MathConstants<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> MathConstants<'a> {
+    // 4 plain values, 51 value records and 1 more plain value.
+    const SIZE: usize = 214;
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if data.len() < Self::SIZE {
+            return None;
+        }
+
+        Some(MathConstants { data })
+    }
+
+    // The table size is checked during parsing, so reading cannot fail.
+    #[inline]
+    fn read<T: FromData + Default>(&self, offset: usize) -> T {
+        Stream::read_at(self.data, offset).unwrap_or_default()
+    }
+
+    #[inline]
+    fn read_record(&self, offset: usize) -> MathValue<'a> {
+        let data = self.data.get(offset..).unwrap_or_default();
+        MathValue::parse(data, self.data).unwrap_or(MathValue { value: 0, device: None })
+    }
+
+    /// Percentage of scaling down for level 1 superscripts and subscripts.
+    #[inline]
+    pub fn script_percent_scale_down(&self) -> i16 {
+        self.read(0)
+    }
+
+    /// Percentage of scaling down for level 2 (scriptScript) superscripts and subscripts.
+    #[inline]
+    pub fn script_script_percent_scale_down(&self) -> i16 {
+        self.read(2)
+    }
+
+    /// Minimum height required for a delimited expression (contained within parentheses, etc.)
+    /// to be treated as a sub-formula.
+    #[inline]
+    pub fn delimited_sub_formula_min_height(&self) -> u16 {
+        self.read(4)
+    }
+
+    /// Minimum height of n-ary operators (such as integral and summation)
+    /// for formulas in display mode.
+    #[inline]
+    pub fn display_operator_min_height(&self) -> u16 {
+        self.read(6)
+    }
+
+    /// White space to be left between math formulas to ensure proper line spacing.
+    #[inline]
+    pub fn math_leading(&self) -> MathValue<'a> {
+        self.read_record(8)
+    }
+
+    /// Axis height of the font.
+    #[inline]
+    pub fn axis_height(&self) -> MathValue<'a> {
+        self.read_record(12)
+    }
+
+    /// Maximum (ink) height of accent base that does not require raising the accents.
+    #[inline]
+    pub fn accent_base_height(&self) -> MathValue<'a> {
+        self.read_record(16)
+    }
+
+    /// Maximum (ink) height of accent base that does not require flattening the accents.
+    #[inline]
+    pub fn flattened_accent_base_height(&self) -> MathValue<'a> {
+        self.read_record(20)
+    }
+
+    /// The standard shift down applied to subscript elements.
+    #[inline]
+    pub fn subscript_shift_down(&self) -> MathValue<'a> {
+        self.read_record(24)
+    }
+
+    /// Maximum allowed height of the (ink) top of subscripts that does not require moving subscripts further down.
+    #[inline]
+    pub fn subscript_top_max(&self) -> MathValue<'a> {
+        self.read_record(28)
+    }
+
+    /// Minimum allowed drop of the baseline of subscripts relative to the (ink) bottom of the base.
+    #[inline]
+    pub fn subscript_baseline_drop_min(&self) -> MathValue<'a> {
+        self.read_record(32)
+    }
+
+    /// Standard shift up applied to superscript elements.
+    #[inline]
+    pub fn superscript_shift_up(&self) -> MathValue<'a> {
+        self.read_record(36)
+    }
+
+    /// Standard shift of superscripts relative to the base, in cramped style.
+    #[inline]
+    pub fn superscript_shift_up_cramped(&self) -> MathValue<'a> {
+        self.read_record(40)
+    }
+
+    /// Minimum allowed height of the (ink) bottom of superscripts that does not require moving subscripts further up.
+    #[inline]
+    pub fn superscript_bottom_min(&self) -> MathValue<'a> {
+        self.read_record(44)
+    }
+
+    /// Maximum allowed drop of the baseline of superscripts relative to the (ink) top of the base.
+    #[inline]
+    pub fn superscript_baseline_drop_max(&self) -> MathValue<'a> {
+        self.read_record(48)
+    }
+
+    /// Minimum gap between the superscript and subscript ink.
+    #[inline]
+    pub fn sub_superscript_gap_min(&self) -> MathValue<'a> {
+        self.read_record(52)
+    }
+
+    /// The maximum level to which the (ink) bottom of superscript can be pushed to increase the gap between superscript and subscript.
+    #[inline]
+    pub fn superscript_bottom_max_with_subscript(&self) -> MathValue<'a> {
+        self.read_record(56)
+    }
+
+    /// Extra white space to be added after each subscript and superscript.
+    #[inline]
+    pub fn space_after_script(&self) -> MathValue<'a> {
+        self.read_record(60)
+    }
+
+    /// Minimum gap between the (ink) bottom of the upper limit, and the (ink) top of the base operator.
+    #[inline]
+    pub fn upper_limit_gap_min(&self) -> MathValue<'a> {
+        self.read_record(64)
+    }
+
+    /// Minimum distance between baseline of upper limit and (ink) top of the base operator.
+    #[inline]
+    pub fn upper_limit_baseline_rise_min(&self) -> MathValue<'a> {
+        self.read_record(68)
+    }
+
+    /// Minimum gap between (ink) top of the lower limit, and (ink) bottom of the base operator.
+    #[inline]
+    pub fn lower_limit_gap_min(&self) -> MathValue<'a> {
+        self.read_record(72)
+    }
+
+    /// Minimum distance between baseline of the lower limit and (ink) bottom of the base operator.
+    #[inline]
+    pub fn lower_limit_baseline_drop_min(&self) -> MathValue<'a> {
+        self.read_record(76)
+    }
+
+    /// Standard shift up applied to the top element of a stack.
+    #[inline]
+    pub fn stack_top_shift_up(&self) -> MathValue<'a> {
+        self.read_record(80)
+    }
+
+    /// Standard shift up applied to the top element of a stack in display style.
+    #[inline]
+    pub fn stack_top_display_style_shift_up(&self) -> MathValue<'a> {
+        self.read_record(84)
+    }
+
+    /// Standard shift down applied to the bottom element of a stack.
+    #[inline]
+    pub fn stack_bottom_shift_down(&self) -> MathValue<'a> {
+        self.read_record(88)
+    }
+
+    /// Standard shift down applied to the bottom element of a stack in display style.
+    #[inline]
+    pub fn stack_bottom_display_style_shift_down(&self) -> MathValue<'a> {
+        self.read_record(92)
+    }
+
+    /// Minimum gap between (ink) bottom of the top element of a stack, and the (ink) top of the bottom element.
+    #[inline]
+    pub fn stack_gap_min(&self) -> MathValue<'a> {
+        self.read_record(96)
+    }
+
+    /// Minimum gap between (ink) bottom of the top element of a stack, and the (ink) top of the bottom element in display style.
+    #[inline]
+    pub fn stack_display_style_gap_min(&self) -> MathValue<'a> {
+        self.read_record(100)
+    }
+
+    /// Standard shift up applied to the top element of the stretch stack.
+    #[inline]
+    pub fn stretch_stack_top_shift_up(&self) -> MathValue<'a> {
+        self.read_record(104)
+    }
+
+    /// Standard shift down applied to the bottom element of the stretch stack.
+    #[inline]
+    pub fn stretch_stack_bottom_shift_down(&self) -> MathValue<'a> {
+        self.read_record(108)
+    }
+
+    /// Minimum gap between the ink of the stretched element, and the (ink) bottom of the element above.
+    #[inline]
+    pub fn stretch_stack_gap_above_min(&self) -> MathValue<'a> {
+        self.read_record(112)
+    }
+
+    /// Minimum gap between the ink of the stretched element, and the (ink) top of the element below.
+    #[inline]
+    pub fn stretch_stack_gap_below_min(&self) -> MathValue<'a> {
+        self.read_record(116)
+    }
+
+    /// Standard shift up applied to the numerator.
+    #[inline]
+    pub fn fraction_numerator_shift_up(&self) -> MathValue<'a> {
+        self.read_record(120)
+    }
+
+    /// Standard shift up applied to the numerator in display style.
+    #[inline]
+    pub fn fraction_numerator_display_style_shift_up(&self) -> MathValue<'a> {
+        self.read_record(124)
+    }
+
+    /// Standard shift down applied to the denominator.
+    #[inline]
+    pub fn fraction_denominator_shift_down(&self) -> MathValue<'a> {
+        self.read_record(128)
+    }
+
+    /// Standard shift down applied to the denominator in display style.
+    #[inline]
+    pub fn fraction_denominator_display_style_shift_down(&self) -> MathValue<'a> {
+        self.read_record(132)
+    }
+
+    /// Minimum tolerated gap between the (ink) bottom of the numerator and the ink of the fraction bar.
+    #[inline]
+    pub fn fraction_numerator_gap_min(&self) -> MathValue<'a> {
+        self.read_record(136)
+    }
+
+    /// Minimum tolerated gap between the (ink) bottom of the numerator and the ink of the fraction bar in display style.
+    #[inline]
+    pub fn fraction_num_display_style_gap_min(&self) -> MathValue<'a> {
+        self.read_record(140)
+    }
+
+    /// Thickness of the fraction bar.
+    #[inline]
+    pub fn fraction_rule_thickness(&self) -> MathValue<'a> {
+        self.read_record(144)
+    }
+
+    /// Minimum tolerated gap between the (ink) top of the denominator and the ink of the fraction bar.
+    #[inline]
+    pub fn fraction_denominator_gap_min(&self) -> MathValue<'a> {
+        self.read_record(148)
+    }
+
+    /// Minimum tolerated gap between the (ink) top of the denominator and the ink of the fraction bar in display style.
+    #[inline]
+    pub fn fraction_denom_display_style_gap_min(&self) -> MathValue<'a> {
+        self.read_record(152)
+    }
+
+    /// Horizontal distance between the top and bottom elements of a skewed fraction.
+    #[inline]
+    pub fn skewed_fraction_horizontal_gap(&self) -> MathValue<'a> {
+        self.read_record(156)
+    }
+
+    /// Vertical distance between the ink of the top and bottom elements of a skewed fraction.
+    #[inline]
+    pub fn skewed_fraction_vertical_gap(&self) -> MathValue<'a> {
+        self.read_record(160)
+    }
+
+    /// Distance between the overbar and the (ink) top of the base.
+    #[inline]
+    pub fn overbar_vertical_gap(&self) -> MathValue<'a> {
+        self.read_record(164)
+    }
+
+    /// Thickness of overbar.
+    #[inline]
+    pub fn overbar_rule_thickness(&self) -> MathValue<'a> {
+        self.read_record(168)
+    }
+
+    /// Extra white space reserved above the overbar.
+    #[inline]
+    pub fn overbar_extra_ascender(&self) -> MathValue<'a> {
+        self.read_record(172)
+    }
+
+    /// Distance between underbar and (ink) bottom of the base.
+    #[inline]
+    pub fn underbar_vertical_gap(&self) -> MathValue<'a> {
+        self.read_record(176)
+    }
+
+    /// Thickness of underbar.
+    #[inline]
+    pub fn underbar_rule_thickness(&self) -> MathValue<'a> {
+        self.read_record(180)
+    }
+
+    /// Extra white space reserved below the underbar.
+    #[inline]
+    pub fn underbar_extra_descender(&self) -> MathValue<'a> {
+        self.read_record(184)
+    }
+
+    /// Space between the (ink) top of the expression and the bar over it.
+    #[inline]
+    pub fn radical_vertical_gap(&self) -> MathValue<'a> {
+        self.read_record(188)
+    }
+
+    /// Space between the (ink) top of the expression and the bar over it in display style.
+    #[inline]
+    pub fn radical_display_style_vertical_gap(&self) -> MathValue<'a> {
+        self.read_record(192)
+    }
+
+    /// Thickness of the radical rule.
+    #[inline]
+    pub fn radical_rule_thickness(&self) -> MathValue<'a> {
+        self.read_record(196)
+    }
+
+    /// Extra white space reserved above the radical.
+    #[inline]
+    pub fn radical_extra_ascender(&self) -> MathValue<'a> {
+        self.read_record(200)
+    }
+
+    /// Extra horizontal kern before the degree of a radical, if such is present.
+    #[inline]
+    pub fn radical_kern_before_degree(&self) -> MathValue<'a> {
+        self.read_record(204)
+    }
+
+    /// Negative kern after the degree of a radical, if such is present.
+    #[inline]
+    pub fn radical_kern_after_degree(&self) -> MathValue<'a> {
+        self.read_record(208)
+    }
+
+    /// Height of the bottom of the radical degree, if such is present,
+    /// in proportion to the ascender of the radical sign.
+    #[inline]
+    pub fn radical_degree_bottom_raise_percent(&self) -> i16 {
+        self.read(212)
+    }
+}
+
+impl core::fmt::Debug for MathConstants<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "MathConstants {{ ... }}")
+    }
+}
+
+
+// A coverage table followed by per-glyph value records.
+// Used by both italics correction and top accent attachment tables.
+#[derive(Clone, Copy)]
+struct GlyphValues<'a> {
+    data: &'a [u8],
+    coverage: CoverageTable<'a>,
+    records: &'a [u8],
+}
+
+impl<'a> GlyphValues<'a> {
+    const RECORD_SIZE: usize = 4;
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let coverage_offset: Offset16 = s.read()?;
+        let count: u16 = s.read()?;
+        let records = s.read_bytes(usize::from(count) * Self::RECORD_SIZE)?;
+        Some(GlyphValues {
+            data,
+            coverage: CoverageTable::new(data.get(coverage_offset.to_usize()..)?),
+            records,
+        })
+    }
+
+    fn get(&self, glyph_id: GlyphId) -> Option<MathValue<'a>> {
+        let index = usize::from(self.coverage.get(glyph_id)?);
+        let record = self.records.get(index * Self::RECORD_SIZE..)?;
+        MathValue::parse(record, self.data)
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct GlyphInfo<'a> {
+    italic_corrections: Option<GlyphValues<'a>>,
+    top_accent_attachments: Option<GlyphValues<'a>>,
+    extended_shapes: Option<CoverageTable<'a>>,
+}
+
+impl<'a> GlyphInfo<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let subtable = |offset: Option<Offset16>| offset.and_then(|o| data.get(o.to_usize()..));
+        let italic_corrections = subtable(s.read()?).and_then(GlyphValues::parse);
+        let top_accent_attachments = subtable(s.read()?).and_then(GlyphValues::parse);
+        let extended_shapes = subtable(s.read()?).map(CoverageTable::new);
+        // `mathKernInfoOffset` is not supported yet.
+        Some(GlyphInfo {
+            italic_corrections,
+            top_accent_attachments,
+            extended_shapes,
+        })
+    }
+}
+
+
+/// A [glyph variant](https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathglyphvariantrecord).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MathGlyphVariant {
+    /// A variant glyph.
+    pub glyph_id: GlyphId,
+    /// An advance height or width of the variant, in the direction of the requested size.
+    pub advance_measurement: u16,
+}
+
+impl FromData for MathGlyphVariant {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(MathGlyphVariant {
+            glyph_id: s.read::<GlyphId>()?,
+            advance_measurement: s.read::<u16>()?,
+        })
+    }
+}
+
+
+/// [Glyph part](https://docs.microsoft.com/en-us/typography/opentype/spec/math#glyphpart-record) flags.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MathGlyphPartFlags(pub u16);
+
+#[allow(missing_docs)]
+impl MathGlyphPartFlags {
+    #[inline] pub fn extender(self) -> bool { self.0 & 0x0001 != 0 }
+}
+
+
+/// A [glyph assembly part](https://docs.microsoft.com/en-us/typography/opentype/spec/math#glyphpart-record).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MathGlyphPart {
+    /// A part glyph.
+    pub glyph_id: GlyphId,
+    /// A connector length at the start of the part, in the direction of growth.
+    pub start_connector_length: u16,
+    /// A connector length at the end of the part, in the direction of growth.
+    pub end_connector_length: u16,
+    /// A full advance of the part, in the direction of growth.
+    pub full_advance: u16,
+    /// Part flags.
+    ///
+    /// Extender parts can be skipped or repeated.
+    pub flags: MathGlyphPartFlags,
+}
+
+impl FromData for MathGlyphPart {
+    const SIZE: usize = 10;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(MathGlyphPart {
+            glyph_id: s.read::<GlyphId>()?,
+            start_connector_length: s.read::<u16>()?,
+            end_connector_length: s.read::<u16>()?,
+            full_advance: s.read::<u16>()?,
+            flags: MathGlyphPartFlags(s.read::<u16>()?),
+        })
+    }
+}
+
+
+/// A [glyph assembly](https://docs.microsoft.com/en-us/typography/opentype/spec/math#glyphassembly-table).
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct MathGlyphAssembly<'a> {
+    /// An italics correction of the whole assembly.
+    pub italics_correction: MathValue<'a>,
+    /// Assembly parts, from bottom to top or from left to right.
+    pub parts: LazyArray16<'a, MathGlyphPart>,
+}
+
+impl<'a> MathGlyphAssembly<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let italics_correction = MathValue::parse(data, data)?;
+        let mut s = Stream::new_at(data, 4)?;
+        let count: u16 = s.read()?;
+        let parts = s.read_array16::<MathGlyphPart>(count)?;
+        Some(MathGlyphAssembly { italics_correction, parts })
+    }
+}
+
+
+/// A [glyph construction](https://docs.microsoft.com/en-us/typography/opentype/spec/math#mathglyphconstruction-table).
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct MathGlyphConstruction<'a> {
+    /// A glyph assembly for sizes larger than any variant.
+    pub assembly: Option<MathGlyphAssembly<'a>>,
+    /// Pre-made glyph variants, in increasing size order.
+    ///
+    /// The first variant is usually the glyph itself.
+    pub variants: LazyArray16<'a, MathGlyphVariant>,
+}
+
+impl<'a> MathGlyphConstruction<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let assembly = s.read::<Option<Offset16>>()?
+            .and_then(|offset| data.get(offset.to_usize()..))
+            .and_then(MathGlyphAssembly::parse);
+        let count: u16 = s.read()?;
+        let variants = s.read_array16::<MathGlyphVariant>(count)?;
+        Some(MathGlyphConstruction { assembly, variants })
+    }
+}
+
+
+// Vertical or horizontal glyph constructions.
+#[derive(Clone, Copy)]
+struct Constructions<'a> {
+    data: &'a [u8],
+    coverage: CoverageTable<'a>,
+    offsets: LazyArray16<'a, Offset16>,
+}
+
+impl<'a> Constructions<'a> {
+    fn get(&self, glyph_id: GlyphId) -> Option<MathGlyphConstruction<'a>> {
+        let index = self.coverage.get(glyph_id)?;
+        let offset = self.offsets.get(index)?;
+        MathGlyphConstruction::parse(self.data.get(offset.to_usize()..)?)
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct Variants<'a> {
+    min_connector_overlap: u16,
+    vertical: Option<Constructions<'a>>,
+    horizontal: Option<Constructions<'a>>,
+}
+
+impl<'a> Variants<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let min_connector_overlap: u16 = s.read()?;
+        let vertical_coverage: Option<Offset16> = s.read()?;
+        let horizontal_coverage: Option<Offset16> = s.read()?;
+        let vertical_count: u16 = s.read()?;
+        let horizontal_count: u16 = s.read()?;
+        let vertical_offsets = s.read_array16::<Offset16>(vertical_count)?;
+        let horizontal_offsets = s.read_array16::<Offset16>(horizontal_count)?;
+
+        let constructions = |coverage: Option<Offset16>, offsets| {
+            Some(Constructions {
+                data,
+                coverage: CoverageTable::new(data.get(coverage?.to_usize()..)?),
+                offsets,
+            })
+        };
+
+        Some(Variants {
+            min_connector_overlap,
+            vertical: constructions(vertical_coverage, vertical_offsets),
+            horizontal: constructions(horizontal_coverage, horizontal_offsets),
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    pub constants: Option<MathConstants<'a>>,
+    glyph_info: Option<GlyphInfo<'a>>,
+    variants: Option<Variants<'a>>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let major_version: u16 = s.read()?;
+        if major_version != 1 {
+            return None;
+        }
+
+        s.skip::<u16>(); // minor version
+        let constants = s.read::<Option<Offset16>>()?
+            .and_then(|offset| data.get(offset.to_usize()..))
+            .and_then(MathConstants::parse);
+        let glyph_info = s.read::<Option<Offset16>>()?
+            .and_then(|offset| data.get(offset.to_usize()..))
+            .and_then(GlyphInfo::parse);
+        let variants = s.read::<Option<Offset16>>()?
+            .and_then(|offset| data.get(offset.to_usize()..))
+            .and_then(Variants::parse);
+
+        Some(Table { constants, glyph_info, variants })
+    }
+
+    pub fn min_connector_overlap(&self) -> Option<u16> {
+        self.variants.map(|v| v.min_connector_overlap)
+    }
+
+    pub fn vertical_construction(&self, glyph_id: GlyphId) -> Option<MathGlyphConstruction<'a>> {
+        self.variants?.vertical?.get(glyph_id)
+    }
+
+    pub fn horizontal_construction(&self, glyph_id: GlyphId) -> Option<MathGlyphConstruction<'a>> {
+        self.variants?.horizontal?.get(glyph_id)
+    }
+
+    pub fn italic_correction(&self, glyph_id: GlyphId) -> Option<MathValue<'a>> {
+        self.glyph_info?.italic_corrections?.get(glyph_id)
+    }
+
+    pub fn top_accent_attachment(&self, glyph_id: GlyphId) -> Option<MathValue<'a>> {
+        self.glyph_info?.top_accent_attachments?.get(glyph_id)
+    }
+
+    pub fn is_extended_shape(&self, glyph_id: GlyphId) -> bool {
+        self.glyph_info
+            .and_then(|info| info.extended_shapes)
+            .map(|coverage| coverage.contains(glyph_id))
+            .unwrap_or(false)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn constants() {
+        let mut values = vec![
+            UInt16(1), // major version
+            UInt16(0), // minor version
+            UInt16(10), // constants offset
+            UInt16(0), // glyph info offset
+            UInt16(0), // variants offset
+
+            // Constants
+            Int16(80), // script percent scale down
+            Int16(60), // script script percent scale down
+            UInt16(1500), // delimited sub formula min height
+            UInt16(1300), // display operator min height
+        ];
+
+        // Value records.
+        for i in 0..51 {
+            values.push(Int16(i * 10));
+            // Axis height has a device table.
+            values.push(UInt16(if i == 1 { 214 } else { 0 }));
+        }
+
+        values.extend_from_slice(&[
+            Int16(55), // radical degree bottom raise percent
+
+            // Device table
+            UInt16(12), // start size
+            UInt16(12), // end size
+            UInt16(1), // format: 2-bit values
+            UInt16(0x4000), // delta: 1
+        ]);
+
+        let data = writer::convert(&values);
+        let constants = Table::parse(&data).unwrap().constants.unwrap();
+        assert_eq!(constants.script_percent_scale_down(), 80);
+        assert_eq!(constants.script_script_percent_scale_down(), 60);
+        assert_eq!(constants.delimited_sub_formula_min_height(), 1500);
+        assert_eq!(constants.display_operator_min_height(), 1300);
+        assert_eq!(constants.math_leading().value, 0);
+        assert!(constants.math_leading().device.is_none());
+        assert_eq!(constants.axis_height().value, 10);
+        assert_eq!(constants.axis_height().device.unwrap().pixel_delta(12), Some(1));
+        assert_eq!(constants.fraction_rule_thickness().value, 340);
+        assert_eq!(constants.radical_kern_after_degree().value, 500);
+        assert_eq!(constants.radical_degree_bottom_raise_percent(), 55);
+
+        // A truncated constants table.
+        assert!(Table::parse(&data[..100]).unwrap().constants.is_none());
+    }
+
+    #[test]
+    fn glyph_info() {
+        let data = writer::convert(&[
+            UInt16(1), // major version
+            UInt16(0), // minor version
+            UInt16(0), // constants offset
+            UInt16(10), // glyph info offset
+            UInt16(0), // variants offset
+
+            // Glyph info
+            UInt16(8), // italics correction info offset
+            UInt16(36), // top accent attachment offset
+            UInt16(54), // extended shape coverage offset
+            UInt16(0), // kern info offset
+
+            // Italics correction info
+            UInt16(12), // coverage offset
+            UInt16(2), // count
+            Int16(50), // value [0]
+            UInt16(20), // device offset [0]
+            Int16(-20), // value [1]
+            UInt16(0), // device offset [1]
+            // Coverage
+            UInt16(1), // format
+            UInt16(2), // count
+            UInt16(3), // glyph [0]
+            UInt16(7), // glyph [1]
+            // Device
+            UInt16(12), // start size
+            UInt16(12), // end size
+            UInt16(1), // format: 2-bit values
+            UInt16(0xC000), // delta: -1
+
+            // Top accent attachment
+            UInt16(8), // coverage offset
+            UInt16(1), // count
+            Int16(250), // value [0]
+            UInt16(0), // device offset [0]
+            // Coverage
+            UInt16(2), // format
+            UInt16(1), // count
+            UInt16(5), // start glyph
+            UInt16(5), // end glyph
+            UInt16(0), // start coverage index
+
+            // Extended shape coverage
+            UInt16(1), // format
+            UInt16(1), // count
+            UInt16(7), // glyph [0]
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert!(table.constants.is_none());
+
+        let value = table.italic_correction(GlyphId(3)).unwrap();
+        assert_eq!(value.value, 50);
+        assert_eq!(value.device.unwrap().pixel_delta(12), Some(-1));
+        let value = table.italic_correction(GlyphId(7)).unwrap();
+        assert_eq!(value.value, -20);
+        assert!(value.device.is_none());
+        assert!(table.italic_correction(GlyphId(5)).is_none());
+
+        assert_eq!(table.top_accent_attachment(GlyphId(5)).unwrap().value, 250);
+        assert!(table.top_accent_attachment(GlyphId(3)).is_none());
+
+        assert!(table.is_extended_shape(GlyphId(7)));
+        assert!(!table.is_extended_shape(GlyphId(3)));
+    }
+
+    #[test]
+    fn variants() {
+        let data = writer::convert(&[
+            UInt16(1), // major version
+            UInt16(0), // minor version
+            UInt16(0), // constants offset
+            UInt16(0), // glyph info offset
+            UInt16(10), // variants offset
+
+            // Variants
+            UInt16(20), // min connector overlap
+            UInt16(12), // vertical coverage offset
+            UInt16(0), // horizontal coverage offset
+            UInt16(1), // vertical glyph count
+            UInt16(0), // horizontal glyph count
+            UInt16(18), // vertical construction offset [0]
+            // Vertical coverage
+            UInt16(1), // format
+            UInt16(1), // count
+            UInt16(4), // glyph [0]
+
+            // Glyph construction
+            UInt16(12), // assembly offset
+            UInt16(2), // variant count
+            UInt16(4), // variant glyph [0]
+            UInt16(1000), // advance measurement [0]
+            UInt16(40), // variant glyph [1]
+            UInt16(1500), // advance measurement [1]
+
+            // Glyph assembly
+            Int16(30), // italics correction
+            UInt16(0), // italics correction device offset
+            UInt16(2), // part count
+            // Part [0]
+            UInt16(41), // glyph
+            UInt16(0), // start connector length
+            UInt16(150), // end connector length
+            UInt16(600), // full advance
+            UInt16(0), // flags
+            // Part [1]
+            UInt16(42), // glyph
+            UInt16(150), // start connector length
+            UInt16(150), // end connector length
+            UInt16(300), // full advance
+            UInt16(1), // flags: EXTENDER
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.min_connector_overlap(), Some(20));
+        assert!(table.vertical_construction(GlyphId(5)).is_none());
+        assert!(table.horizontal_construction(GlyphId(4)).is_none());
+
+        let construction = table.vertical_construction(GlyphId(4)).unwrap();
+        assert_eq!(construction.variants.len(), 2);
+        assert_eq!(construction.variants.get(1), Some(MathGlyphVariant {
+            glyph_id: GlyphId(40),
+            advance_measurement: 1500,
+        }));
+
+        let assembly = construction.assembly.unwrap();
+        assert_eq!(assembly.italics_correction.value, 30);
+        assert_eq!(assembly.parts.len(), 2);
+        assert!(!assembly.parts.get(0).unwrap().flags.extender());
+        assert_eq!(assembly.parts.get(1), Some(MathGlyphPart {
+            glyph_id: GlyphId(42),
+            start_connector_length: 150,
+            end_connector_length: 150,
+            full_advance: 300,
+            flags: MathGlyphPartFlags(1),
+        }));
+        assert!(assembly.parts.get(1).unwrap().flags.extender());
+    }
+}
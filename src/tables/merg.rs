@@ -0,0 +1,121 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/merg
+
+use crate::GlyphId;
+use crate::ggg::{Class, ClassDefinitionTable};
+use crate::parser::{Stream, Offset, Offset16, LazyArray16};
+
+
+/// A [merge entry](https://docs.microsoft.com/en-us/typography/opentype/spec/merg#merge-entry-flags)
+/// describing how two adjacent glyphs should be merged during anti-aliasing.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct MergeEntry(pub u8);
+
+#[allow(missing_docs)]
+impl MergeEntry {
+    #[inline] pub fn merge_ltr(self) -> bool { self.0 & 0x01 != 0 }
+    #[inline] pub fn group_ltr(self) -> bool { self.0 & 0x02 != 0 }
+    #[inline] pub fn second_is_subordinate_ltr(self) -> bool { self.0 & 0x04 != 0 }
+    #[inline] pub fn merge_rtl(self) -> bool { self.0 & 0x10 != 0 }
+    #[inline] pub fn group_rtl(self) -> bool { self.0 & 0x20 != 0 }
+    #[inline] pub fn second_is_subordinate_rtl(self) -> bool { self.0 & 0x40 != 0 }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    data: &'a [u8],
+    class_count: u16,
+    entries: &'a [u8],
+    class_def_offsets: LazyArray16<'a, Offset16>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let version: u16 = s.read()?;
+        if version != 0 {
+            return None;
+        }
+
+        let class_count: u16 = s.read()?;
+        let entries_offset: Offset16 = s.read()?;
+        let class_def_count: u16 = s.read()?;
+        let class_def_offsets_offset: Offset16 = s.read()?;
+
+        let entries_len = usize::from(class_count) * usize::from(class_count);
+        let entries = Stream::new_at(data, entries_offset.to_usize())?.read_bytes(entries_len)?;
+
+        let mut s = Stream::new_at(data, class_def_offsets_offset.to_usize())?;
+        let class_def_offsets = s.read_array16::<Offset16>(class_def_count)?;
+
+        Some(Table {
+            data,
+            class_count,
+            entries,
+            class_def_offsets,
+        })
+    }
+
+    /// Glyphs not present in any class definition belong to class 0.
+    pub fn glyph_class(&self, glyph_id: GlyphId) -> Class {
+        for offset in self.class_def_offsets {
+            if let Some(data) = self.data.get(offset.to_usize()..) {
+                let class = ClassDefinitionTable::new(data).get(glyph_id);
+                if class.0 != 0 {
+                    return class;
+                }
+            }
+        }
+
+        Class(0)
+    }
+
+    pub fn entry(&self, first: Class, second: Class) -> Option<MergeEntry> {
+        if first.0 >= self.class_count || second.0 >= self.class_count {
+            return None;
+        }
+
+        let index = usize::from(first.0) * usize::from(self.class_count) + usize::from(second.0);
+        self.entries.get(index).map(|flags| MergeEntry(*flags))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x02, // merge class count: 2
+            0x00, 0x0A, // merge data offset: 10
+            0x00, 0x01, // class def count: 1
+            0x00, 0x0E, // class def offsets offset: 14
+            // Merge data
+            0x00, 0x00, // class 0: 0, 0
+            0x00, 0x13, // class 1: 0, merge and group LTR + merge RTL
+            // Class def offsets
+            0x00, 0x10, // offset [0]: 16
+            // Class def table
+            0x00, 0x01, // format: 1
+            0x00, 0x05, // start glyph: 5
+            0x00, 0x02, // count: 2
+            0x00, 0x01, // class [0]: 1
+            0x00, 0x01, // class [1]: 1
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.glyph_class(GlyphId(4)), Class(0));
+        assert_eq!(table.glyph_class(GlyphId(6)), Class(1));
+
+        let entry = table.entry(Class(1), Class(1)).unwrap();
+        assert!(entry.merge_ltr());
+        assert!(entry.group_ltr());
+        assert!(!entry.second_is_subordinate_ltr());
+        assert!(entry.merge_rtl());
+        assert_eq!(table.entry(Class(0), Class(1)), Some(MergeEntry(0)));
+        assert!(table.entry(Class(2), Class(0)).is_none());
+    }
+}
@@ -2,23 +2,37 @@ pub mod cbdt;
 pub mod cblc;
 mod cff;
 pub mod cmap;
+pub mod colr;
+pub mod cpal;
+pub mod cvt;
+pub mod dsig;
+pub mod ebsc;
 pub mod gdef;
 pub mod glyf;
+pub mod graphite;
+pub mod gpos;
+pub mod gsub;
 pub mod head;
 pub mod hhea;
 pub mod hmtx;
 pub mod kern;
+pub mod kerx;
 pub mod loca;
+pub mod math;
 pub mod maxp;
+pub mod merg;
 pub mod name;
 pub mod os2;
+pub mod pclt;
 pub mod post;
 pub mod sbix;
+pub mod stat;
 pub mod svg;
 pub mod vhea;
 pub mod vorg;
 
 #[cfg(feature = "variable-fonts")] pub mod avar;
+#[cfg(feature = "variable-fonts")] pub mod cvar;
 #[cfg(feature = "variable-fonts")] pub mod fvar;
 #[cfg(feature = "variable-fonts")] pub mod gvar;
 #[cfg(feature = "variable-fonts")] pub mod hvar;
@@ -64,6 +64,11 @@ impl<'a> Table<'a> {
         })
     }
 
+    #[inline]
+    pub fn variation_store(&self) -> ItemVariationStore<'a> {
+        self.variation_store
+    }
+
     pub fn metrics_offset(&self, tag: Tag, coordinates: &[NormalizedCoordinate]) -> Option<f32> {
         let (_, record) = self.records.binary_search_by(|r| r.value_tag.cmp(&tag))?;
         self.variation_store.parse_delta(
@@ -73,3 +78,56 @@ impl<'a> Table<'a> {
         )
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_offsets() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // reserved
+            0x00, 0x08, // value record size: 8
+            0x00, 0x02, // value record count: 2
+            0x00, 0x1C, // item variation store offset: 28
+            // Value record [0]
+            0x75, 0x6E, 0x64, 0x6F, // value tag: undo
+            0x00, 0x00, // delta-set outer index: 0
+            0x00, 0x01, // delta-set inner index: 1
+            // Value record [1]
+            0x78, 0x68, 0x67, 0x74, // value tag: xhgt
+            0x00, 0x00, // delta-set outer index: 0
+            0x00, 0x00, // delta-set inner index: 0
+
+            // Item variation store
+            0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0C, // region list offset: 12
+            0x00, 0x01, // data count: 1
+            0x00, 0x00, 0x00, 0x16, // data offset [0]: 22
+            // Region list
+            0x00, 0x01, // axis count: 1
+            0x00, 0x01, // region count: 1
+            0x00, 0x00, 0x40, 0x00, 0x40, 0x00, // region 0, axis 0: 0, 1, 1
+            // Item variation data
+            0x00, 0x02, // item count: 2
+            0x00, 0x01, // short delta count: 1
+            0x00, 0x01, // region index count: 1
+            0x00, 0x00, // region index [0]: 0
+            0x00, 0x14, // item 0 delta: 20
+            0xFF, 0xF6, // item 1 delta: -10
+        ];
+
+        let table = Table::parse(data).unwrap();
+        let coords = [NormalizedCoordinate::from(0.5)];
+        assert_eq!(table.metrics_offset(Tag::from_bytes(b"xhgt"), &coords), Some(10.0));
+        assert_eq!(table.metrics_offset(Tag::from_bytes(b"undo"), &coords), Some(-5.0));
+        assert_eq!(table.metrics_offset(Tag::from_bytes(b"cpht"), &coords), None);
+
+        // Unsupported value record size.
+        let mut data = data.to_vec();
+        data[7] = 10;
+        assert!(Table::parse(&data).is_none());
+    }
+}
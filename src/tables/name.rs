@@ -5,10 +5,7 @@ use std::vec::Vec;
 #[cfg(feature = "std")]
 use std::string::String;
 
-#[cfg(feature = "std")]
-use crate::parser::LazyArray16;
-
-use crate::parser::{Stream, FromData};
+use crate::parser::{Stream, FromData, LazyArray16, Offset, Offset16};
 
 
 /// A list of [name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids)'s.
@@ -90,6 +87,83 @@ fn is_unicode_encoding(platform_id: PlatformId, encoding_id: u16) -> bool {
 }
 
 
+// Unicode code points of the Mac OS Roman characters 0x80..=0xFF.
+//
+// https://www.unicode.org/Public/MAPPINGS/VENDORS/APPLE/ROMAN.TXT
+#[cfg(feature = "std")]
+const MAC_ROMAN: [u16; 128] = [
+    0x00C4, 0x00C5, 0x00C7, 0x00C9, 0x00D1, 0x00D6, 0x00DC, 0x00E1,
+    0x00E0, 0x00E2, 0x00E4, 0x00E3, 0x00E5, 0x00E7, 0x00E9, 0x00E8,
+    0x00EA, 0x00EB, 0x00ED, 0x00EC, 0x00EE, 0x00EF, 0x00F1, 0x00F3,
+    0x00F2, 0x00F4, 0x00F6, 0x00F5, 0x00FA, 0x00F9, 0x00FB, 0x00FC,
+    0x2020, 0x00B0, 0x00A2, 0x00A3, 0x00A7, 0x2022, 0x00B6, 0x00DF,
+    0x00AE, 0x00A9, 0x2122, 0x00B4, 0x00A8, 0x2260, 0x00C6, 0x00D8,
+    0x221E, 0x00B1, 0x2264, 0x2265, 0x00A5, 0x00B5, 0x2202, 0x2211,
+    0x220F, 0x03C0, 0x222B, 0x00AA, 0x00BA, 0x03A9, 0x00E6, 0x00F8,
+    0x00BF, 0x00A1, 0x00AC, 0x221A, 0x0192, 0x2248, 0x2206, 0x00AB,
+    0x00BB, 0x2026, 0x00A0, 0x00C0, 0x00C3, 0x00D5, 0x0152, 0x0153,
+    0x2013, 0x2014, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x25CA,
+    0x00FF, 0x0178, 0x2044, 0x20AC, 0x2039, 0x203A, 0xFB01, 0xFB02,
+    0x2021, 0x00B7, 0x201A, 0x201E, 0x2030, 0x00C2, 0x00CA, 0x00C1,
+    0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x00D3, 0x00D4,
+    0xF8FF, 0x00D2, 0x00DA, 0x00DB, 0x00D9, 0x0131, 0x02C6, 0x02DC,
+    0x00AF, 0x02D8, 0x02D9, 0x02DA, 0x00B8, 0x02DD, 0x02DB, 0x02C7,
+];
+
+
+// Windows language IDs and corresponding BCP 47 tags, sorted by ID.
+//
+// Only the most common languages are listed.
+// https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-language-ids
+#[cfg(feature = "std")]
+const WINDOWS_LANGUAGES: &[(u16, &str)] = &[
+    (0x0401, "ar-SA"), (0x0402, "bg-BG"), (0x0403, "ca-ES"), (0x0404, "zh-TW"),
+    (0x0405, "cs-CZ"), (0x0406, "da-DK"), (0x0407, "de-DE"), (0x0408, "el-GR"),
+    (0x0409, "en-US"), (0x040A, "es-ES"), (0x040B, "fi-FI"), (0x040C, "fr-FR"),
+    (0x040D, "he-IL"), (0x040E, "hu-HU"), (0x040F, "is-IS"), (0x0410, "it-IT"),
+    (0x0411, "ja-JP"), (0x0412, "ko-KR"), (0x0413, "nl-NL"), (0x0414, "nb-NO"),
+    (0x0415, "pl-PL"), (0x0416, "pt-BR"), (0x0418, "ro-RO"), (0x0419, "ru-RU"),
+    (0x041A, "hr-HR"), (0x041B, "sk-SK"), (0x041D, "sv-SE"), (0x041E, "th-TH"),
+    (0x041F, "tr-TR"), (0x0421, "id-ID"), (0x0422, "uk-UA"), (0x0424, "sl-SI"),
+    (0x0425, "et-EE"), (0x0426, "lv-LV"), (0x0427, "lt-LT"), (0x042A, "vi-VN"),
+    (0x0439, "hi-IN"), (0x0804, "zh-CN"), (0x0807, "de-CH"), (0x0809, "en-GB"),
+    (0x080A, "es-MX"), (0x080C, "fr-BE"), (0x0816, "pt-PT"), (0x0C04, "zh-HK"),
+    (0x0C07, "de-AT"), (0x0C09, "en-AU"), (0x0C0A, "es-ES"), (0x0C0C, "fr-CA"),
+    (0x1004, "zh-SG"), (0x1009, "en-CA"), (0x1404, "zh-MO"),
+];
+
+// Macintosh language IDs 0..=33 and corresponding BCP 47 tags.
+//
+// https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-language-ids
+#[cfg(feature = "std")]
+const MACINTOSH_LANGUAGES: [&str; 34] = [
+    "en", "fr", "de", "it", "nl", "sv", "es", "da", "pt", "nb", "he", "ja",
+    "ar", "fi", "el", "is", "mt", "tr", "hr", "zh-Hant", "ur", "hi", "th", "ko",
+    "lt", "pl", "hu", "et", "lv", "se", "fo", "fa", "ru", "zh-Hans",
+];
+
+
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+struct LangTagRecord {
+    length: u16,
+    offset: u16,
+}
+
+impl FromData for LangTagRecord {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(LangTagRecord {
+            length: s.read::<u16>()?,
+            offset: s.read::<u16>()?,
+        })
+    }
+}
+
+
 #[derive(Clone, Copy)]
 struct NameRecord {
     platform_id: PlatformId,
@@ -123,6 +197,8 @@ impl FromData for NameRecord {
 pub struct Name<'a> {
     data: NameRecord,
     strings: &'a [u8],
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    lang_tags: LazyArray16<'a, LangTagRecord>,
 }
 
 impl<'a> Name<'a> {
@@ -141,6 +217,33 @@ impl<'a> Name<'a> {
         self.data.language_id
     }
 
+    /// Returns the language as a [BCP 47](https://tools.ietf.org/html/bcp47) tag.
+    ///
+    /// Language IDs starting from `0x8000` reference language-tag records
+    /// of the naming table format 1. Otherwise, a tag is derived from the platform's
+    /// language ID. Only the most common Windows languages are supported.
+    #[cfg(feature = "std")]
+    pub fn language_tag(&self) -> Option<String> {
+        let language_id = self.language_id();
+        if language_id >= 0x8000 {
+            let record = self.lang_tags.get(language_id - 0x8000)?;
+            let start = usize::from(record.offset);
+            let end = start + usize::from(record.length);
+            return utf16_be_to_string(self.strings.get(start..end)?);
+        }
+
+        let tag = match self.platform_id() {
+            PlatformId::Windows => {
+                let index = WINDOWS_LANGUAGES.binary_search_by_key(&language_id, |v| v.0).ok()?;
+                WINDOWS_LANGUAGES[index].1
+            }
+            PlatformId::Macintosh => MACINTOSH_LANGUAGES.get(usize::from(language_id))?,
+            _ => return None,
+        };
+
+        Some(String::from(tag))
+    }
+
     /// Returns the [Name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids).
     ///
     /// A predefined list of ID's can be found in the [`name_id`](name_id/index.html) module.
@@ -159,18 +262,21 @@ impl<'a> Name<'a> {
 
     /// Returns the Name's data as a UTF-8 string.
     ///
-    /// Only Unicode names are supported. And since they are stored as UTF-16BE,
-    /// we can't return `&str` and have to allocate a `String`.
+    /// Unicode names are stored as UTF-16BE and Macintosh ones use a single-byte encoding,
+    /// so we can't return `&str` and have to allocate a `String`.
     ///
     /// Supports:
     /// - Unicode Platform ID
     /// - Windows Platform ID + Symbol
     /// - Windows Platform ID + Unicode BMP
+    /// - Macintosh Platform ID + Roman
     #[cfg(feature = "std")]
     #[inline(never)]
     pub fn to_string(&self) -> Option<String> {
         if self.is_unicode() {
             self.name_from_utf16_be()
+        } else if self.is_mac_roman() {
+            Some(self.name_from_mac_roman())
         } else {
             None
         }
@@ -182,16 +288,44 @@ impl<'a> Name<'a> {
         is_unicode_encoding(self.platform_id(), self.encoding_id())
     }
 
+    #[cfg(feature = "std")]
+    #[inline]
+    fn is_mac_roman(&self) -> bool {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-encoding-ids-script-manager-codes
+        const MACINTOSH_ROMAN_ENCODING_ID: u16 = 0;
+
+        self.platform_id() == PlatformId::Macintosh
+            && self.encoding_id() == MACINTOSH_ROMAN_ENCODING_ID
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(never)]
+    fn name_from_mac_roman(&self) -> String {
+        self.name().iter().map(|b| {
+            if *b < 0x80 {
+                char::from(*b)
+            } else {
+                let c = u32::from(MAC_ROMAN[usize::from(*b - 0x80)]);
+                core::char::from_u32(c).unwrap_or(core::char::REPLACEMENT_CHARACTER)
+            }
+        }).collect()
+    }
+
     #[cfg(feature = "std")]
     #[inline(never)]
     fn name_from_utf16_be(&self) -> Option<String> {
-        let mut name: Vec<u16> = Vec::new();
-        for c in LazyArray16::<u16>::new(self.name()) {
-            name.push(c);
-        }
+        utf16_be_to_string(self.name())
+    }
+}
 
-        String::from_utf16(&name).ok()
+#[cfg(feature = "std")]
+fn utf16_be_to_string(data: &[u8]) -> Option<String> {
+    let mut name: Vec<u16> = Vec::new();
+    for c in LazyArray16::<u16>::new(data) {
+        name.push(c);
     }
+
+    String::from_utf16(&name).ok()
 }
 
 #[cfg(feature = "std")]
@@ -231,15 +365,22 @@ impl<'a> core::fmt::Debug for Name<'a> {
 pub struct Names<'a> {
     names: &'a [u8],
     storage: &'a [u8],
+    lang_tags: LazyArray16<'a, LangTagRecord>,
     index: u16,
     total: u16,
 }
 
 impl<'a> Names<'a> {
-    fn new(names: &'a [u8], storage: &'a [u8], total: u16) -> Self {
+    fn new(
+        names: &'a [u8],
+        storage: &'a [u8],
+        lang_tags: LazyArray16<'a, LangTagRecord>,
+        total: u16,
+    ) -> Self {
         Names {
             names,
             storage,
+            lang_tags,
             index: 0,
             total,
         }
@@ -256,6 +397,7 @@ impl<'a> Iterator for Names<'a> {
             Some(Name {
                 data: Stream::read_at::<NameRecord>(self.names, NameRecord::SIZE * index)?,
                 strings: self.storage,
+                lang_tags: self.lang_tags,
             })
         } else {
             None
@@ -270,25 +412,112 @@ impl<'a> Iterator for Names<'a> {
 
 #[inline(never)]
 pub(crate) fn parse(data: &[u8]) -> Option<Names> {
-    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
-    const LANG_TAG_RECORD_SIZE: u16 = 4;
-
     let mut s = Stream::new(data);
     let format: u16 = s.read()?;
     let count: u16 = s.read()?;
-    s.skip::<u16>(); // offset
+    let storage_offset: Offset16 = s.read()?;
+    let storage = data.get(storage_offset.to_usize()..)?;
 
     if format == 0 {
         let names_data = s.read_bytes(NameRecord::SIZE * usize::from(count))?;
-        Some(Names::new(names_data, s.tail()?, count))
+        Some(Names::new(names_data, storage, LazyArray16::default(), count))
     } else if format == 1 {
-        let lang_tag_count: u16 = s.read()?;
-        let lang_tag_len = lang_tag_count.checked_mul(LANG_TAG_RECORD_SIZE)?;
-
-        s.advance(usize::from(lang_tag_len)); // langTagRecords
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+        // Language-tag records follow the name records.
         let names_data = s.read_bytes(NameRecord::SIZE * usize::from(count))?;
-        Some(Names::new(names_data, s.tail()?, count))
+        let lang_tag_count: u16 = s.read()?;
+        let lang_tags = s.read_array16::<LangTagRecord>(lang_tag_count)?;
+        Some(Names::new(names_data, storage, lang_tags, count))
     } else {
         None
     }
 }
+
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding() {
+        let data = &[
+            0x00, 0x00, // format: 0
+            0x00, 0x03, // count: 3
+            0x00, 0x2A, // storage offset: 42
+            // Name record [0]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x04, 0x09, // language ID: English (US)
+            0x00, 0x01, // name ID: family
+            0x00, 0x04, // length: 4
+            0x00, 0x00, // offset: 0
+            // Name record [1]
+            0x00, 0x01, // platform ID: Macintosh
+            0x00, 0x00, // encoding ID: Roman
+            0x00, 0x00, // language ID: English
+            0x00, 0x01, // name ID: family
+            0x00, 0x03, // length: 3
+            0x00, 0x04, // offset: 4
+            // Name record [2]
+            0x00, 0x01, // platform ID: Macintosh
+            0x00, 0x01, // encoding ID: Japanese
+            0x00, 0x0B, // language ID: Japanese
+            0x00, 0x01, // name ID: family
+            0x00, 0x02, // length: 2
+            0x00, 0x07, // offset: 7
+            // Storage
+            0x00, b'A', 0x00, 0xE9, // "Aé" in UTF-16BE
+            b'A', 0x8E, 0xAA, // "Aé™" in Mac Roman
+            0x82, 0xA0, // "あ" in Shift JIS
+        ];
+
+        let mut names = parse(data).unwrap();
+        assert_eq!(names.next().unwrap().to_string(), Some(String::from("Aé")));
+        assert_eq!(names.next().unwrap().to_string(), Some(String::from("Aé™")));
+
+        let name = names.next().unwrap();
+        assert_eq!(name.name(), &[0x82, 0xA0]);
+        assert_eq!(name.to_string(), None);
+        assert!(names.next().is_none());
+    }
+
+    #[test]
+    fn language_tags() {
+        let data = &[
+            0x00, 0x01, // format: 1
+            0x00, 0x02, // count: 2
+            0x00, 0x24, // storage offset: 36
+            // Name record [0]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x04, 0x11, // language ID: Japanese (Japan)
+            0x00, 0x01, // name ID: family
+            0x00, 0x02, // length: 2
+            0x00, 0x00, // offset: 0
+            // Name record [1]
+            0x00, 0x03, // platform ID: Windows
+            0x00, 0x01, // encoding ID: Unicode BMP
+            0x80, 0x00, // language ID: language-tag record 0
+            0x00, 0x01, // name ID: family
+            0x00, 0x02, // length: 2
+            0x00, 0x02, // offset: 2
+            // Language-tag records
+            0x00, 0x01, // count: 1
+            0x00, 0x0A, // length: 10
+            0x00, 0x04, // offset: 4
+            // Storage
+            0x30, 0x42, // "あ"
+            0x00, b'A', // "A"
+            0x00, b'e', 0x00, b'n', 0x00, b'-', 0x00, b'G', 0x00, b'B', // "en-GB"
+        ];
+
+        let mut names = parse(data).unwrap();
+        let name = names.next().unwrap();
+        assert_eq!(name.language_tag(), Some(String::from("ja-JP")));
+        assert_eq!(name.to_string(), Some(String::from("あ")));
+
+        let name = names.next().unwrap();
+        assert_eq!(name.language_tag(), Some(String::from("en-GB")));
+        assert_eq!(name.to_string(), Some(String::from("A")));
+    }
+}
@@ -1,23 +1,36 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/os2
 
+use core::ops::Range;
+
 use crate::LineMetrics;
 use crate::parser::Stream;
 
 
 const WEIGHT_CLASS_OFFSET: usize = 4;
 const WIDTH_CLASS_OFFSET: usize = 6;
+const TYPE_OFFSET: usize = 8;
 const Y_SUBSCRIPT_X_SIZE_OFFSET: usize = 10;
 const Y_SUPERSCRIPT_X_SIZE_OFFSET: usize = 18;
 const Y_STRIKEOUT_SIZE_OFFSET: usize = 26;
 const Y_STRIKEOUT_POSITION_OFFSET: usize = 28;
+const UNICODE_RANGE_OFFSET: usize = 42;
 const FS_SELECTION_OFFSET: usize = 62;
 const TYPO_ASCENDER_OFFSET: usize = 68;
 const TYPO_DESCENDER_OFFSET: usize = 70;
 const TYPO_LINE_GAP_OFFSET: usize = 72;
 const WIN_ASCENT: usize = 74;
 const WIN_DESCENT: usize = 76;
+const CODE_PAGE_RANGE_OFFSET: usize = 78;
 const X_HEIGHT_OFFSET: usize = 86;
 const CAP_HEIGHT_OFFSET: usize = 88;
+const DEFAULT_CHAR_OFFSET: usize = 90;
+const BREAK_CHAR_OFFSET: usize = 92;
+const MAX_CONTEXT_OFFSET: usize = 94;
+const LOWER_OPTICAL_POINT_SIZE_OFFSET: usize = 96;
+const UPPER_OPTICAL_POINT_SIZE_OFFSET: usize = 98;
+
+// The original Apple version 0 table ends before `sTypoAscender`.
+const APPLE_VERSION_0_TABLE_LEN: usize = 68;
 
 
 /// A font [weight](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#usweightclass).
@@ -122,6 +135,110 @@ impl Default for Width {
 }
 
 
+/// A font [embedding licensing level](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fstype).
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub enum EmbeddingLevel {
+    /// The font may be embedded and permanently installed on the remote system.
+    Installable,
+
+    /// The font must not be embedded without an explicit permission from the legal owner.
+    Restricted,
+
+    /// The font may be embedded in documents that are opened read-only.
+    PreviewAndPrint,
+
+    /// The font may be embedded in documents that can be edited.
+    Editable,
+}
+
+
+/// Font [embedding permissions](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fstype).
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub struct EmbeddingPermissions {
+    /// Embedding licensing level.
+    ///
+    /// When multiple levels are set, which is allowed before the `OS/2` version 3,
+    /// the least restrictive one is used.
+    pub level: EmbeddingLevel,
+
+    /// The font must not be subsetted before embedding.
+    pub no_subsetting: bool,
+
+    /// Only bitmaps contained in the font may be embedded.
+    pub bitmap_only: bool,
+}
+
+impl EmbeddingPermissions {
+    fn from_bits(bits: u16) -> Self {
+        let level = if bits & 0x0008 != 0 {
+            EmbeddingLevel::Editable
+        } else if bits & 0x0004 != 0 {
+            EmbeddingLevel::PreviewAndPrint
+        } else if bits & 0x0002 != 0 {
+            EmbeddingLevel::Restricted
+        } else {
+            EmbeddingLevel::Installable
+        };
+
+        EmbeddingPermissions {
+            level,
+            no_subsetting: bits & 0x0100 != 0,
+            bitmap_only: bits & 0x0200 != 0,
+        }
+    }
+}
+
+
+/// [Unicode ranges](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#ur)
+/// considered functional by the face.
+///
+/// Each bit represents one or more Unicode blocks, as defined by the specification.
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Hash)]
+pub struct UnicodeRanges(pub u128);
+
+impl UnicodeRanges {
+    /// Checks that a range bit is set.
+    ///
+    /// Bits 123..=127 are reserved.
+    #[inline]
+    pub fn contains(&self, bit: u8) -> bool {
+        bit < 128 && self.0 & (1 << bit) != 0
+    }
+
+    /// Returns an iterator over set range bits in ascending order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = u8> {
+        let ranges = *self;
+        (0..128).filter(move |bit| ranges.contains(*bit))
+    }
+}
+
+
+/// [Code page character ranges](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#cpr)
+/// considered functional by the face.
+///
+/// For example, bit 0 is *Latin 1* (code page 1252) and bit 17 is *Japanese* (code page 932).
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Hash)]
+pub struct CodePageRanges(pub u64);
+
+impl CodePageRanges {
+    /// Checks that a code page bit is set.
+    #[inline]
+    pub fn contains(&self, bit: u8) -> bool {
+        bit < 64 && self.0 & (1 << bit) != 0
+    }
+
+    /// Returns an iterator over set code page bits in ascending order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = u8> {
+        let ranges = *self;
+        (0..64).filter(move |bit| ranges.contains(*bit))
+    }
+}
+
+
 /// A script metrics used by subscript and superscript.
 #[repr(C)]
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
@@ -149,6 +266,8 @@ impl SelectionFlags {
     #[inline] fn bold(self) -> bool { self.0 & (1 << 5) != 0 }
     #[inline] fn regular(self) -> bool { self.0 & (1 << 6) != 0 }
     #[inline] fn use_typo_metrics(self) -> bool { self.0 & (1 << 7) != 0 }
+    #[cfg(feature = "std")]
+    #[inline] fn wws(self) -> bool { self.0 & (1 << 8) != 0 }
     #[inline] fn oblique(self) -> bool { self.0 & (1 << 9) != 0 }
 }
 
@@ -165,7 +284,7 @@ impl<'a> Table<'a> {
         let version: u16 = s.read()?;
 
         let table_len = match version {
-            0 => 78,
+            0 => APPLE_VERSION_0_TABLE_LEN,
             1 => 86,
             2 => 96,
             3 => 96,
@@ -174,7 +293,9 @@ impl<'a> Table<'a> {
             _ => return None,
         };
 
-        if data.len() != table_len {
+        // Fields that are not present in the current version must not be read,
+        // even when the table is longer than required.
+        if data.len() < table_len {
             return None;
         }
 
@@ -205,6 +326,11 @@ impl<'a> Table<'a> {
         }
     }
 
+    #[inline]
+    pub fn embedding_permissions(&self) -> EmbeddingPermissions {
+        EmbeddingPermissions::from_bits(Stream::read_at::<u16>(self.data, TYPE_OFFSET).unwrap_or(0))
+    }
+
     #[inline]
     pub fn subscript_metrics(&self) -> ScriptMetrics {
         let mut s = Stream::new_at(self.data, Y_SUBSCRIPT_X_SIZE_OFFSET).unwrap_or_default();
@@ -227,6 +353,30 @@ impl<'a> Table<'a> {
         }
     }
 
+    #[inline]
+    pub fn unicode_ranges(&self) -> UnicodeRanges {
+        // ulUnicodeRange1 contains bits 0..=31 and ulUnicodeRange4 - 96..=127.
+        let mut s = Stream::new_at(self.data, UNICODE_RANGE_OFFSET).unwrap_or_default();
+        let mut n = 0;
+        for i in 0..4 {
+            n |= u128::from(s.read::<u32>().unwrap_or(0)) << (i * 32);
+        }
+
+        UnicodeRanges(n)
+    }
+
+    #[inline]
+    pub fn code_page_ranges(&self) -> Option<CodePageRanges> {
+        if self.version < 1 {
+            None
+        } else {
+            let mut s = Stream::new_at(self.data, CODE_PAGE_RANGE_OFFSET)?;
+            let lower: u32 = s.read()?;
+            let upper: u32 = s.read()?;
+            Some(CodePageRanges(u64::from(upper) << 32 | u64::from(lower)))
+        }
+    }
+
     #[inline]
     pub fn strikeout_metrics(&self) -> LineMetrics {
         LineMetrics {
@@ -264,6 +414,16 @@ impl<'a> Table<'a> {
         }
     }
 
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn is_wws(&self) -> bool {
+        if self.version < 4 {
+            false
+        } else {
+            SelectionFlags(self.fs_selection()).wws()
+        }
+    }
+
     #[inline]
     pub fn is_use_typo_metrics(&self) -> bool {
         if self.version < 4 {
@@ -274,29 +434,29 @@ impl<'a> Table<'a> {
     }
 
     #[inline]
-    pub fn typo_ascender(&self) -> i16 {
-        Stream::read_at::<i16>(self.data, TYPO_ASCENDER_OFFSET).unwrap_or(0)
+    pub fn typo_ascender(&self) -> Option<i16> {
+        Stream::read_at::<i16>(self.data, TYPO_ASCENDER_OFFSET)
     }
 
     #[inline]
-    pub fn typo_descender(&self) -> i16 {
-        Stream::read_at::<i16>(self.data, TYPO_DESCENDER_OFFSET).unwrap_or(0)
+    pub fn typo_descender(&self) -> Option<i16> {
+        Stream::read_at::<i16>(self.data, TYPO_DESCENDER_OFFSET)
     }
 
     #[inline]
-    pub fn typo_line_gap(&self) -> i16 {
-        Stream::read_at::<i16>(self.data, TYPO_LINE_GAP_OFFSET).unwrap_or(0)
+    pub fn typo_line_gap(&self) -> Option<i16> {
+        Stream::read_at::<i16>(self.data, TYPO_LINE_GAP_OFFSET)
     }
 
     #[inline]
-    pub fn windows_ascender(&self) -> i16 {
-        Stream::read_at::<i16>(self.data, WIN_ASCENT).unwrap_or(0)
+    pub fn windows_ascender(&self) -> Option<i16> {
+        Stream::read_at::<i16>(self.data, WIN_ASCENT)
     }
 
     #[inline]
-    pub fn windows_descender(&self) -> i16 {
+    pub fn windows_descender(&self) -> Option<i16> {
         // Should be negated.
-        -Stream::read_at::<i16>(self.data, WIN_DESCENT).unwrap_or(0)
+        Stream::read_at::<i16>(self.data, WIN_DESCENT).map(|n| n.wrapping_neg())
     }
 
     #[inline]
@@ -316,4 +476,145 @@ impl<'a> Table<'a> {
             Stream::read_at::<i16>(self.data, CAP_HEIGHT_OFFSET)
         }
     }
+
+    #[inline]
+    pub fn default_char(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at::<u16>(self.data, DEFAULT_CHAR_OFFSET)
+        }
+    }
+
+    #[inline]
+    pub fn break_char(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at::<u16>(self.data, BREAK_CHAR_OFFSET)
+        }
+    }
+
+    #[inline]
+    pub fn max_context(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at::<u16>(self.data, MAX_CONTEXT_OFFSET)
+        }
+    }
+
+    #[inline]
+    pub fn optical_point_size_range(&self) -> Option<Range<f32>> {
+        if self.version < 5 {
+            None
+        } else {
+            // Values are in TWIPs, which are 1/20 of a point.
+            let lower: u16 = Stream::read_at(self.data, LOWER_OPTICAL_POINT_SIZE_OFFSET)?;
+            let upper: u16 = Stream::read_at(self.data, UPPER_OPTICAL_POINT_SIZE_OFFSET)?;
+            Some(f32::from(lower) / 20.0 .. f32::from(upper) / 20.0)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn apple_version_0() {
+        let mut data = vec![0; APPLE_VERSION_0_TABLE_LEN];
+        data[WEIGHT_CLASS_OFFSET..WEIGHT_CLASS_OFFSET + 2].copy_from_slice(&[0x02, 0xBC]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.weight(), Weight::Bold);
+        assert_eq!(table.typo_ascender(), None);
+        assert_eq!(table.windows_descender(), None);
+        assert_eq!(table.x_height(), None);
+    }
+
+    #[test]
+    fn embedding_permissions() {
+        let mut data = vec![0; 96];
+        data[1] = 4; // version
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.embedding_permissions(), EmbeddingPermissions {
+            level: EmbeddingLevel::Installable,
+            no_subsetting: false,
+            bitmap_only: false,
+        });
+
+        data[TYPE_OFFSET..TYPE_OFFSET + 2].copy_from_slice(&[0x03, 0x02]);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.embedding_permissions(), EmbeddingPermissions {
+            level: EmbeddingLevel::Restricted,
+            no_subsetting: true,
+            bitmap_only: true,
+        });
+
+        // Multiple levels.
+        data[TYPE_OFFSET..TYPE_OFFSET + 2].copy_from_slice(&[0x00, 0x06]);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.embedding_permissions().level, EmbeddingLevel::PreviewAndPrint);
+    }
+
+    #[test]
+    fn ranges() {
+        let mut data = vec![0; 86];
+        data[1] = 1; // version
+        data[UNICODE_RANGE_OFFSET..UNICODE_RANGE_OFFSET + 16].copy_from_slice(&[
+            0x00, 0x00, 0x00, 0x03, // ulUnicodeRange1: Basic Latin, Latin-1 Supplement
+            0x00, 0x00, 0x00, 0x00, // ulUnicodeRange2
+            0x00, 0x00, 0x00, 0x00, // ulUnicodeRange3
+            0x04, 0x00, 0x00, 0x00, // ulUnicodeRange4: bit 122
+        ]);
+        data[CODE_PAGE_RANGE_OFFSET..CODE_PAGE_RANGE_OFFSET + 8].copy_from_slice(&[
+            0x00, 0x02, 0x00, 0x01, // ulCodePageRange1: Latin 1, Japanese
+            0x80, 0x00, 0x00, 0x00, // ulCodePageRange2: US
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let unicode_ranges = table.unicode_ranges();
+        assert!(unicode_ranges.contains(1));
+        assert!(!unicode_ranges.contains(2));
+        assert!(!unicode_ranges.contains(200));
+        assert_eq!(unicode_ranges.iter().collect::<Vec<_>>(), vec![0, 1, 122]);
+
+        let code_pages = table.code_page_ranges().unwrap();
+        assert_eq!(code_pages.iter().collect::<Vec<_>>(), vec![0, 17, 63]);
+
+        data[1] = 0;
+        let table = Table::parse(&data).unwrap();
+        assert!(table.code_page_ranges().is_none());
+    }
+
+    #[test]
+    fn version_5() {
+        let mut data = vec![0; 100];
+        data[1] = 5; // version
+        data[TYPO_ASCENDER_OFFSET..TYPO_ASCENDER_OFFSET + 2].copy_from_slice(&[0x03, 0x20]);
+        data[MAX_CONTEXT_OFFSET..MAX_CONTEXT_OFFSET + 2].copy_from_slice(&[0x00, 0x03]);
+        data[LOWER_OPTICAL_POINT_SIZE_OFFSET..LOWER_OPTICAL_POINT_SIZE_OFFSET + 2]
+            .copy_from_slice(&[0x00, 0xA0]);
+        data[UPPER_OPTICAL_POINT_SIZE_OFFSET..UPPER_OPTICAL_POINT_SIZE_OFFSET + 2]
+            .copy_from_slice(&[0xFF, 0xFF]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.typo_ascender(), Some(800));
+        assert_eq!(table.max_context(), Some(3));
+        assert_eq!(table.optical_point_size_range(), Some(8.0..3276.75));
+
+        // Version 4 with the same data.
+        data[1] = 4;
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.max_context(), Some(3));
+        assert_eq!(table.optical_point_size_range(), None);
+
+        // Too short.
+        data[1] = 5;
+        assert!(Table::parse(&data[..96]).is_none());
+    }
 }
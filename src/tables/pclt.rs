@@ -0,0 +1,164 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/pclt
+
+use crate::parser::Stream;
+
+
+/// A [PCL 5 table](https://docs.microsoft.com/en-us/typography/opentype/spec/pclt).
+///
+/// A legacy table used by HP PCL 5 printer drivers.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub struct PclInfo {
+    /// A unique font identifier.
+    pub font_number: u32,
+
+    /// The width of the space in font units.
+    pub pitch: u16,
+
+    /// The height of the optical line describing the height of the lowercase x
+    /// in font units.
+    pub x_height: u16,
+
+    /// The PCL 5 style word.
+    ///
+    /// Encodes posture, appearance width and structure.
+    pub style: u16,
+
+    /// The PCL 5 typeface family word.
+    pub type_family: u16,
+
+    /// The height of the optical line describing the top of the uppercase H
+    /// in font units.
+    pub cap_height: u16,
+
+    /// The PCL 5 symbol set.
+    pub symbol_set: u16,
+
+    /// The typeface name, padded with spaces or NULs.
+    ///
+    /// Use [`typeface_name()`](#method.typeface_name) to get a trimmed string.
+    pub typeface: [u8; 16],
+
+    /// Supported character sets.
+    pub character_complement: [u8; 8],
+
+    /// The suggested PCL 5 file name.
+    pub file_name: [u8; 6],
+
+    /// The stroke weight in a range from -7 to 7.
+    pub stroke_weight: i8,
+
+    /// The width type in a range from -5 to 5.
+    pub width_type: i8,
+
+    /// The serif style.
+    pub serif_style: u8,
+}
+
+impl PclInfo {
+    /// Returns the typeface name without padding.
+    ///
+    /// Returns `None` when the name is not valid ASCII.
+    pub fn typeface_name(&self) -> Option<&str> {
+        let len = self.typeface.iter()
+            .rposition(|c| *c != 0 && *c != b' ')
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        let name = &self.typeface[..len];
+        if !name.is_ascii() {
+            return None;
+        }
+
+        core::str::from_utf8(name).ok()
+    }
+}
+
+
+pub fn parse(data: &[u8]) -> Option<PclInfo> {
+    let mut s = Stream::new(data);
+    let version: u32 = s.read()?;
+    if version != 0x00010000 {
+        return None;
+    }
+
+    let font_number = s.read()?;
+    let pitch = s.read()?;
+    let x_height = s.read()?;
+    let style = s.read()?;
+    let type_family = s.read()?;
+    let cap_height = s.read()?;
+    let symbol_set = s.read()?;
+
+    let mut typeface = [0; 16];
+    typeface.copy_from_slice(s.read_bytes(16)?);
+
+    let mut character_complement = [0; 8];
+    character_complement.copy_from_slice(s.read_bytes(8)?);
+
+    let mut file_name = [0; 6];
+    file_name.copy_from_slice(s.read_bytes(6)?);
+
+    Some(PclInfo {
+        font_number,
+        pitch,
+        x_height,
+        style,
+        type_family,
+        cap_height,
+        symbol_set,
+        typeface,
+        character_complement,
+        file_name,
+        stroke_weight: s.read()?,
+        width_type: s.read()?,
+        serif_style: s.read()?,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x80, 0x00, 0x00, 0x01, // font number
+            0x01, 0x00, // pitch: 256
+            0x01, 0xF4, // x-height: 500
+            0x00, 0x01, // style: 1
+            0x10, 0x00, // type family
+            0x02, 0xBC, // cap height: 700
+            0x00, 0x0E, // symbol set: 14
+            b'T', b'e', b's', b't', b' ', b'S', b'a', b'n', // typeface
+            b's', b' ', b' ', b' ', 0x00, 0x00, 0x00, 0x00,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, // character complement
+            b'T', b'S', b'R', b'0', b'0', b'0', // file name
+            0xFD, // stroke weight: -3
+            0x00, // width type: 0
+            0x40, // serif style
+            0x00, // reserved
+        ];
+
+        let info = parse(data).unwrap();
+        assert_eq!(info.font_number, 0x80000001);
+        assert_eq!(info.pitch, 256);
+        assert_eq!(info.x_height, 500);
+        assert_eq!(info.cap_height, 700);
+        assert_eq!(info.symbol_set, 14);
+        assert_eq!(info.typeface_name(), Some("Test Sans"));
+        assert_eq!(&info.file_name, b"TSR000");
+        assert_eq!(info.stroke_weight, -3);
+        assert_eq!(info.serif_style, 0x40);
+    }
+
+    #[test]
+    fn truncated() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x80, 0x00, 0x00, 0x01, // font number
+        ];
+
+        assert!(parse(data).is_none());
+    }
+}
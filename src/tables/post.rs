@@ -278,6 +278,8 @@ pub struct Table<'a> {
     italic_angle: f32,
     underline: LineMetrics,
     is_monospaced: bool,
+    // Version 1.0 uses the Macintosh standard order for all glyphs.
+    is_standard_order: bool,
     name_indexes: LazyArray16<'a, u16>,
     names: &'a [u8],
 }
@@ -320,6 +322,7 @@ impl<'a> Table<'a> {
             italic_angle,
             underline,
             is_monospaced,
+            is_standard_order: version == 0x00010000,
             name_indexes,
             names,
         })
@@ -342,6 +345,10 @@ impl<'a> Table<'a> {
 
     #[inline]
     pub fn glyph_name(&self, glyph_id: GlyphId) -> Option<&'a str> {
+        if self.is_standard_order {
+            return MACINTOSH_NAMES.get(usize::from(glyph_id.0)).cloned();
+        }
+
         let mut index = self.name_indexes.get(glyph_id.0)?;
 
         // 'If the name index is between 0 and 257, treat the name index
@@ -376,4 +383,112 @@ impl<'a> Table<'a> {
             None
         }
     }
+
+    pub fn glyph_index_by_name(&self, name: &str) -> Option<GlyphId> {
+        let mac_index = MACINTOSH_NAMES.iter().position(|n| *n == name);
+        if self.is_standard_order {
+            return mac_index.map(|index| GlyphId(index as u16));
+        }
+
+        let mut pool_index = None;
+        let mut s = Stream::new(self.names);
+        let mut i = 0;
+        while !s.at_end() && i < core::u16::MAX - MACINTOSH_NAMES.len() as u16 {
+            let len: u8 = s.read()?;
+            if s.read_bytes(usize::from(len))? == name.as_bytes() {
+                pool_index = Some(i + MACINTOSH_NAMES.len() as u16);
+                break;
+            }
+
+            i += 1;
+        }
+
+        let mac_index = mac_index.map(|index| index as u16);
+        if mac_index.is_none() && pool_index.is_none() {
+            return None;
+        }
+
+        self.name_indexes.into_iter()
+            .position(|index| Some(index) == mac_index || Some(index) == pool_index)
+            .map(|id| GlyphId(id as u16))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics() {
+        let data = &[
+            0x00, 0x03, 0x00, 0x00, // version: 3.0
+            0xFF, 0xF3, 0x80, 0x00, // italic angle: -12.5
+            0xFF, 0x9C, // underline position: -100
+            0x00, 0x32, // underline thickness: 50
+            0x00, 0x00, 0x00, 0x01, // is fixed pitch: true
+            0x00, 0x00, 0x00, 0x00, // min memory Type 42
+            0x00, 0x00, 0x00, 0x00, // max memory Type 42
+            0x00, 0x00, 0x00, 0x00, // min memory Type 1
+            0x00, 0x00, 0x00, 0x00, // max memory Type 1
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.italic_angle(), -12.5);
+        assert_eq!(table.underline_metrics(), LineMetrics { position: -100, thickness: 50 });
+        assert!(table.is_monospaced());
+        assert_eq!(table.glyph_name(GlyphId(0)), None);
+
+        // Too short.
+        assert!(Table::parse(&data[..31]).is_none());
+    }
+
+    #[test]
+    fn glyph_names() {
+        let data = &[
+            0x00, 0x02, 0x00, 0x00, // version: 2.0
+            0x00, 0x00, 0x00, 0x00, // italic angle: 0
+            0x00, 0x00, // underline position: 0
+            0x00, 0x00, // underline thickness: 0
+            0x00, 0x00, 0x00, 0x00, // is fixed pitch: false
+            0x00, 0x00, 0x00, 0x00, // min memory Type 42
+            0x00, 0x00, 0x00, 0x00, // max memory Type 42
+            0x00, 0x00, 0x00, 0x00, // min memory Type 1
+            0x00, 0x00, 0x00, 0x00, // max memory Type 1
+            0x00, 0x04, // number of glyphs: 4
+            0x00, 0x00, // name index [0]: .notdef
+            0x01, 0x03, // name index [1]: 259
+            0x00, 0x24, // name index [2]: A
+            0x01, 0x02, // name index [3]: 258
+            // Names
+            0x03, b'f', b'_', b'f', // f_f
+            0x03, b'u', b'n', b'i', // uni
+        ];
+
+        let table = Table::parse(data).unwrap();
+        assert_eq!(table.glyph_name(GlyphId(0)), Some(".notdef"));
+        assert_eq!(table.glyph_name(GlyphId(1)), Some("uni"));
+        assert_eq!(table.glyph_name(GlyphId(2)), Some("A"));
+        assert_eq!(table.glyph_name(GlyphId(3)), Some("f_f"));
+        assert_eq!(table.glyph_name(GlyphId(4)), None);
+
+        assert_eq!(table.glyph_index_by_name(".notdef"), Some(GlyphId(0)));
+        assert_eq!(table.glyph_index_by_name("uni"), Some(GlyphId(1)));
+        assert_eq!(table.glyph_index_by_name("A"), Some(GlyphId(2)));
+        assert_eq!(table.glyph_index_by_name("f_f"), Some(GlyphId(3)));
+        assert_eq!(table.glyph_index_by_name("B"), None);
+        assert_eq!(table.glyph_index_by_name("f_i"), None);
+    }
+
+    #[test]
+    fn standard_glyph_names() {
+        let mut data = [0; TABLE_SIZE];
+        data[1] = 1; // version: 1.0
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.glyph_name(GlyphId(3)), Some("space"));
+        assert_eq!(table.glyph_name(GlyphId(258)), None);
+        assert_eq!(table.glyph_index_by_name("space"), Some(GlyphId(3)));
+        assert_eq!(table.glyph_index_by_name("f_f"), None);
+    }
 }
@@ -3,7 +3,7 @@
 use core::convert::TryFrom;
 use core::num::NonZeroU16;
 
-use crate::{GlyphId, RasterGlyphImage, RasterImageFormat, Tag};
+use crate::{is_better_strike, GlyphId, RasterGlyphImage, RasterImageFormat, Tag};
 use crate::parser::{Stream, FromData, Offset, Offset32};
 
 pub fn parse(
@@ -35,25 +35,18 @@ pub fn parse(
 
     // Select a best matching strike based on `pixels_per_em`.
     let mut idx = 0;
-    let mut max_ppem = 0;
-    {
-        for (i, offset) in strikes.into_iter().enumerate() {
-            let mut s = Stream::new_at(data, offset.to_usize())?;
-            let ppem: u16 = s.read()?;
-            s.skip::<u16>(); // ppi
-
-            if (pixels_per_em <= ppem && ppem < max_ppem) ||
-                (pixels_per_em > max_ppem && ppem > max_ppem)
-            {
-                idx = i as u32;
-                max_ppem = ppem;
-            }
+    let mut best_ppem = None;
+    for (i, offset) in strikes.into_iter().enumerate() {
+        let ppem: u16 = Stream::read_at(data, offset.to_usize())?;
+        if is_better_strike(best_ppem, ppem, pixels_per_em) {
+            idx = i as u32;
+            best_ppem = Some(ppem);
         }
     }
 
     let offset = strikes.get(idx)?;
     let mut s = Stream::new_at(data, offset.to_usize())?;
-    s.skip::<u16>(); // ppem
+    let ppem: u16 = s.read()?;
     s.skip::<u16>(); // ppi
 
     let glyph_offsets = s.read_array32::<Offset32>(total_glyphs)?;
@@ -67,7 +60,7 @@ pub fn parse(
 
     let data_len = end.checked_sub(start)?.checked_sub(8)?; // 8 is a Glyph data header size.
 
-    let mut s = Stream::new_at(data, offset.to_usize() + start)?;
+    let mut s = Stream::new_at(data, offset.to_usize().checked_add(start)?)?;
     let x: i16 = s.read()?;
     let y: i16 = s.read()?;
     let image_type: Tag = s.read()?;
@@ -98,7 +91,7 @@ pub fn parse(
         y,
         width,
         height,
-        pixels_per_em: max_ppem,
+        pixels_per_em: ppem,
         format,
         data: image_data,
     })
@@ -119,3 +112,62 @@ fn png_size(data: &[u8]) -> Option<(u16, u16)> {
         u16::try_from(height).ok()?,
     ))
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strikes() {
+        let data = &[
+            0x00, 0x01, // version: 1
+            0x00, 0x00, // flags
+            0x00, 0x00, 0x00, 0x02, // number of strikes: 2
+            0x00, 0x00, 0x00, 0x10, // strike offset [0]: 16
+            0x00, 0x00, 0x00, 0x1C, // strike offset [1]: 28
+
+            // Strike [0]
+            0x00, 0x40, // ppem: 64
+            0x00, 0x48, // ppi: 72
+            0x00, 0x00, 0x00, 0x0C, // glyph data offset [0]: 12
+            0x00, 0x00, 0x00, 0x0C, // glyph data offset [1]: 12 (no data)
+
+            // Strike [1]
+            0x00, 0x20, // ppem: 32
+            0x00, 0x48, // ppi: 72
+            0x00, 0x00, 0x00, 0x0C, // glyph data offset [0]: 12
+            0x00, 0x00, 0x00, 0x2C, // glyph data offset [1]: 44
+
+            // Glyph data
+            0x00, 0x01, // origin offset x: 1
+            0xFF, 0xFE, // origin offset y: -2
+            0x70, 0x6E, 0x67, 0x20, // graphic type: png
+            // PNG signature
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A,
+            // IHDR chunk
+            0x00, 0x00, 0x00, 0x0D, // length
+            0x49, 0x48, 0x44, 0x52, // type
+            0x00, 0x00, 0x00, 0x10, // width: 16
+            0x00, 0x00, 0x00, 0x14, // height: 20
+        ];
+
+        let number_of_glyphs = NonZeroU16::new(1).unwrap();
+        // The 32px strike is the closest one.
+        let image = parse(data, number_of_glyphs, GlyphId(0), 20, 0).unwrap();
+        assert_eq!(image.pixels_per_em, 32);
+        assert_eq!(image.x, 1);
+        assert_eq!(image.y, -2);
+        assert_eq!(image.width, 16);
+        assert_eq!(image.height, 20);
+        assert_eq!(image.format, RasterImageFormat::PNG);
+        assert_eq!(image.data.len(), 24);
+
+        let image = parse(data, number_of_glyphs, GlyphId(0), 0, 0).unwrap();
+        assert_eq!(image.pixels_per_em, 32);
+
+        // The 64px strike has no image for this glyph.
+        assert!(parse(data, number_of_glyphs, GlyphId(0), 48, 0).is_none());
+        assert!(parse(data, number_of_glyphs, GlyphId(0), core::u16::MAX, 0).is_none());
+    }
+}
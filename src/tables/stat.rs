@@ -0,0 +1,528 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/stat
+
+use crate::Tag;
+use crate::parser::{Stream, FromData, Fixed, Offset, Offset16, Offset32, LazyArray16};
+
+
+/// A [design axis](https://docs.microsoft.com/en-us/typography/opentype/spec/stat#axis-records).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DesignAxis {
+    /// An axis tag, like `wght`.
+    pub tag: Tag,
+    /// An axis name in the `name` table.
+    pub name_id: u16,
+    /// A value that determines the order of axis values in composed style names.
+    pub ordering: u16,
+}
+
+
+/// An iterator over design axes.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy, Default)]
+pub struct DesignAxes<'a> {
+    data: &'a [u8],
+    record_size: u16,
+}
+
+impl<'a> DesignAxes<'a> {
+    /// Returns an axis at `index`.
+    pub fn get(&self, index: u16) -> Option<DesignAxis> {
+        let offset = usize::from(index) * usize::from(self.record_size);
+        let mut s = Stream::new_at(self.data, offset)?;
+        Some(DesignAxis {
+            tag: s.read::<Tag>()?,
+            name_id: s.read::<u16>()?,
+            ordering: s.read::<u16>()?,
+        })
+    }
+}
+
+impl<'a> Iterator for DesignAxes<'a> {
+    type Item = DesignAxis;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let axis = self.get(0)?;
+        self.data = &self.data[usize::from(self.record_size)..];
+        Some(axis)
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        if self.record_size == 0 {
+            0
+        } else {
+            self.data.len() / usize::from(self.record_size)
+        }
+    }
+}
+
+
+/// [Axis value](https://docs.microsoft.com/en-us/typography/opentype/spec/stat#axis-value-tables) flags.
+#[repr(transparent)]
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Hash)]
+pub struct AxisValueFlags(pub u16);
+
+#[allow(missing_docs)]
+impl AxisValueFlags {
+    #[inline] pub fn older_sibling_font_attribute(self) -> bool { self.0 & 0x0001 != 0 }
+    #[inline] pub fn elidable_axis_value_name(self) -> bool { self.0 & 0x0002 != 0 }
+}
+
+
+/// A single axis value of a format 4 axis value table.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AxisValueRecord {
+    /// An index into design axes.
+    pub axis_index: u16,
+    /// An axis value in user-space coordinates.
+    pub value: f32,
+}
+
+impl FromData for AxisValueRecord {
+    const SIZE: usize = 6;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(AxisValueRecord {
+            axis_index: s.read::<u16>()?,
+            value: s.read::<Fixed>()?.0,
+        })
+    }
+}
+
+
+/// An [axis value table](https://docs.microsoft.com/en-us/typography/opentype/spec/stat#axis-value-tables).
+///
+/// All values are in user-space coordinates.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug)]
+pub enum AxisValue<'a> {
+    /// Format 1. A single value on a single axis.
+    Value {
+        axis_index: u16,
+        flags: AxisValueFlags,
+        value_name_id: u16,
+        value: f32,
+    },
+    /// Format 2. A range of values on a single axis.
+    Range {
+        axis_index: u16,
+        flags: AxisValueFlags,
+        value_name_id: u16,
+        nominal_value: f32,
+        min_value: f32,
+        max_value: f32,
+    },
+    /// Format 3. A single value on a single axis with a style-linked value, like Bold for Regular.
+    LinkedValue {
+        axis_index: u16,
+        flags: AxisValueFlags,
+        value_name_id: u16,
+        value: f32,
+        linked_value: f32,
+    },
+    /// Format 4. A combination of values on multiple axes.
+    Combination {
+        flags: AxisValueFlags,
+        value_name_id: u16,
+        values: LazyArray16<'a, AxisValueRecord>,
+    },
+}
+
+impl<'a> AxisValue<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let format: u16 = s.read()?;
+        match format {
+            1 => Some(AxisValue::Value {
+                axis_index: s.read()?,
+                flags: AxisValueFlags(s.read()?),
+                value_name_id: s.read()?,
+                value: s.read::<Fixed>()?.0,
+            }),
+            2 => Some(AxisValue::Range {
+                axis_index: s.read()?,
+                flags: AxisValueFlags(s.read()?),
+                value_name_id: s.read()?,
+                nominal_value: s.read::<Fixed>()?.0,
+                min_value: s.read::<Fixed>()?.0,
+                max_value: s.read::<Fixed>()?.0,
+            }),
+            3 => Some(AxisValue::LinkedValue {
+                axis_index: s.read()?,
+                flags: AxisValueFlags(s.read()?),
+                value_name_id: s.read()?,
+                value: s.read::<Fixed>()?.0,
+                linked_value: s.read::<Fixed>()?.0,
+            }),
+            4 => {
+                let count: u16 = s.read()?;
+                Some(AxisValue::Combination {
+                    flags: AxisValueFlags(s.read()?),
+                    value_name_id: s.read()?,
+                    values: s.read_array16::<AxisValueRecord>(count)?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns axis value flags.
+    #[inline]
+    pub fn flags(&self) -> AxisValueFlags {
+        match *self {
+            AxisValue::Value { flags, .. } => flags,
+            AxisValue::Range { flags, .. } => flags,
+            AxisValue::LinkedValue { flags, .. } => flags,
+            AxisValue::Combination { flags, .. } => flags,
+        }
+    }
+
+    /// Returns an axis value name in the `name` table.
+    #[inline]
+    pub fn value_name_id(&self) -> u16 {
+        match *self {
+            AxisValue::Value { value_name_id, .. } => value_name_id,
+            AxisValue::Range { value_name_id, .. } => value_name_id,
+            AxisValue::LinkedValue { value_name_id, .. } => value_name_id,
+            AxisValue::Combination { value_name_id, .. } => value_name_id,
+        }
+    }
+
+    /// Returns an axis index and a nominal value of a single axis value table.
+    ///
+    /// Returns `None` for format 4.
+    #[cfg(feature = "std")]
+    fn single_value(&self) -> Option<(u16, f32)> {
+        match *self {
+            AxisValue::Value { axis_index, value, .. } => Some((axis_index, value)),
+            AxisValue::Range { axis_index, nominal_value, .. } => Some((axis_index, nominal_value)),
+            AxisValue::LinkedValue { axis_index, value, .. } => Some((axis_index, value)),
+            AxisValue::Combination { .. } => None,
+        }
+    }
+
+    /// Checks that a single axis value table matches a value on the specified axis.
+    ///
+    /// Ranges are matched only when `exact` is not set.
+    /// Always returns `false` for format 4.
+    #[cfg(feature = "std")]
+    fn matches(&self, index: u16, v: f32, exact: bool) -> bool {
+        match *self {
+            AxisValue::Value { axis_index, value, .. } |
+            AxisValue::LinkedValue { axis_index, value, .. } => {
+                axis_index == index && value == v
+            }
+            AxisValue::Range { axis_index, min_value, max_value, .. } => {
+                !exact && axis_index == index && min_value <= v && v <= max_value
+            }
+            AxisValue::Combination { .. } => false,
+        }
+    }
+}
+
+
+/// An iterator over axis value tables.
+///
+/// Tables with an unknown format are skipped.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy, Default)]
+pub struct AxisValues<'a> {
+    data: &'a [u8],
+    offsets: LazyArray16<'a, Offset16>,
+    index: u16,
+}
+
+impl<'a> Iterator for AxisValues<'a> {
+    type Item = AxisValue<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.offsets.len() {
+            let offset = self.offsets.get(self.index)?;
+            self.index += 1;
+
+            if let Some(value) = self.data.get(offset.to_usize()..).and_then(AxisValue::parse) {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    pub axes: DesignAxes<'a>,
+    pub values: AxisValues<'a>,
+    pub elided_fallback_name_id: Option<u16>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let major_version: u16 = s.read()?;
+        let minor_version: u16 = s.read()?;
+        if major_version != 1 {
+            return None;
+        }
+
+        let design_axis_size: u16 = s.read()?;
+        let design_axis_count: u16 = s.read()?;
+        let design_axes_offset: Offset32 = s.read()?;
+        let axis_value_count: u16 = s.read()?;
+        let axis_value_offsets_offset: Offset32 = s.read()?;
+        // 'In version 1.0, this field is not present',
+        // in which case the *Regular* name ID is used.
+        let elided_fallback_name_id = if minor_version > 0 {
+            Some(s.read::<u16>()?)
+        } else {
+            None
+        };
+
+        // 'The size in bytes of each axis record' must be at least 8.
+        if usize::from(design_axis_size) < 8 {
+            return None;
+        }
+
+        let axes = {
+            let len = usize::from(design_axis_count) * usize::from(design_axis_size);
+            let start = design_axes_offset.to_usize();
+            DesignAxes {
+                data: data.get(start..start.checked_add(len)?)?,
+                record_size: design_axis_size,
+            }
+        };
+
+        let values = {
+            let mut s = Stream::new_at(data, axis_value_offsets_offset.to_usize())?;
+            let offsets = s.read_array16::<Offset16>(axis_value_count)?;
+            AxisValues {
+                // Offsets are from the start of the offsets array.
+                data: data.get(axis_value_offsets_offset.to_usize()..)?,
+                offsets,
+                index: 0,
+            }
+        };
+
+        Some(Table {
+            axes,
+            values,
+            elided_fallback_name_id,
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn find_value(&self, index: u16, v: f32, exact: bool) -> Option<AxisValue<'a>> {
+        let mut values = self.values;
+        values.find(|value| value.matches(index, v, exact))
+    }
+
+    /// Returns a list of name IDs that compose a style name for the specified
+    /// user-space coordinates.
+    ///
+    /// Axes without a coordinate are ignored, unless they have only one axis value,
+    /// like an `ital` axis of a static upright font.
+    #[cfg(feature = "std")]
+    pub fn style_name_ids(&self, coordinates: &[(Tag, f32)]) -> std::vec::Vec<u16> {
+        // An elidable combination still counts as matched.
+        let mut matched: std::vec::Vec<(u16, u16, bool)> = std::vec::Vec::new();
+        let mut covered: std::vec::Vec<u16> = std::vec::Vec::new();
+
+        let coordinate = |index: u16| -> Option<f32> {
+            let axis = self.axes.get(index)?;
+            if let Some(&(_, v)) = coordinates.iter().find(|(tag, _)| *tag == axis.tag) {
+                return Some(v);
+            }
+
+            // Use the only axis value of this axis, if any.
+            let mut values = self.values
+                .filter_map(|value| value.single_value())
+                .filter(|(axis_index, _)| *axis_index == index);
+            let (_, v) = values.next()?;
+            if values.next().is_some() {
+                return None;
+            }
+
+            Some(v)
+        };
+
+        // 'If a font has format 4 axis value tables that match the given coordinates,
+        // they take precedence over tables for individual axes.'
+        for value in self.values {
+            if let AxisValue::Combination { values, flags, value_name_id } = value {
+                let is_match = !values.is_empty() && values.into_iter().all(|r| {
+                    !covered.contains(&r.axis_index) && coordinate(r.axis_index) == Some(r.value)
+                });
+
+                if is_match {
+                    let ordering = values.into_iter()
+                        .filter_map(|r| self.axes.get(r.axis_index))
+                        .map(|a| a.ordering)
+                        .min()
+                        .unwrap_or(0);
+                    matched.push((ordering, value_name_id, flags.elidable_axis_value_name()));
+                    covered.extend(values.into_iter().map(|r| r.axis_index));
+                }
+            }
+        }
+
+        for (index, axis) in self.axes.enumerate() {
+            let index = index as u16;
+            if covered.contains(&index) {
+                continue;
+            }
+
+            let v = match coordinate(index) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            // Exact values are preferred over ranges.
+            let value = self.find_value(index, v, true)
+                .or_else(|| self.find_value(index, v, false));
+
+            if let Some(value) = value {
+                matched.push((axis.ordering, value.value_name_id(),
+                              value.flags().elidable_axis_value_name()));
+            }
+        }
+
+        matched.sort_by_key(|&(ordering, _, _)| ordering);
+
+        let ids: std::vec::Vec<u16> = matched.iter()
+            .filter(|(_, _, elidable)| !elidable)
+            .map(|&(_, id, _)| id)
+            .collect();
+
+        if ids.is_empty() {
+            // 'If all axis values are elided, the elided fallback name is used.'
+            const REGULAR_NAME_ID: u16 = 2;
+            std::vec![self.elided_fallback_name_id.unwrap_or(REGULAR_NAME_ID)]
+        } else {
+            ids
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = &[
+        0x00, 0x01, 0x00, 0x01, // version: 1.1
+        0x00, 0x08, // design axis size: 8
+        0x00, 0x02, // design axis count: 2
+        0x00, 0x00, 0x00, 0x14, // design axes offset: 20
+        0x00, 0x05, // axis value count: 5
+        0x00, 0x00, 0x00, 0x24, // axis value offsets offset: 36
+        0x01, 0x2C, // elided fallback name ID: 300
+
+        // Design axis [0]
+        0x77, 0x67, 0x68, 0x74, // axis tag: wght
+        0x01, 0x00, // axis name ID: 256
+        0x00, 0x01, // axis ordering: 1
+        // Design axis [1]
+        0x77, 0x64, 0x74, 0x68, // axis tag: wdth
+        0x01, 0x01, // axis name ID: 257
+        0x00, 0x00, // axis ordering: 0
+
+        // Axis value offsets
+        0x00, 0x0A, // offset [0]: 10
+        0x00, 0x16, // offset [1]: 22
+        0x00, 0x22, // offset [2]: 34
+        0x00, 0x36, // offset [3]: 54
+        0x00, 0x4A, // offset [4]: 74
+
+        // Axis value [0]
+        0x00, 0x01, // format: 1
+        0x00, 0x00, // axis index: 0
+        0x00, 0x00, // flags: 0
+        0x01, 0x02, // value name ID: 258
+        0x02, 0xBC, 0x00, 0x00, // value: 700
+
+        // Axis value [1]
+        0x00, 0x01, // format: 1
+        0x00, 0x00, // axis index: 0
+        0x00, 0x02, // flags: ELIDABLE_AXIS_VALUE_NAME
+        0x01, 0x03, // value name ID: 259
+        0x01, 0x90, 0x00, 0x00, // value: 400
+
+        // Axis value [2]
+        0x00, 0x02, // format: 2
+        0x00, 0x01, // axis index: 1
+        0x00, 0x00, // flags: 0
+        0x01, 0x04, // value name ID: 260
+        0x00, 0x4B, 0x00, 0x00, // nominal value: 75
+        0x00, 0x32, 0x00, 0x00, // range min value: 50
+        0x00, 0x57, 0x80, 0x00, // range max value: 87.5
+
+        // Axis value [3]
+        0x00, 0x04, // format: 4
+        0x00, 0x02, // axis count: 2
+        0x00, 0x00, // flags: 0
+        0x01, 0x05, // value name ID: 261
+        0x00, 0x00, // axis index [0]: 0
+        0x03, 0x84, 0x00, 0x00, // value [0]: 900
+        0x00, 0x01, // axis index [1]: 1
+        0x00, 0x32, 0x00, 0x00, // value [1]: 50
+
+        // Axis value [4]
+        0x00, 0x05, // format: 5
+    ];
+
+    #[test]
+    fn axes_and_values() {
+        let table = Table::parse(DATA).unwrap();
+        assert_eq!(table.axes.count(), 2);
+        assert_eq!(table.axes.get(1), Some(DesignAxis {
+            tag: Tag::from_bytes(b"wdth"),
+            name_id: 257,
+            ordering: 0,
+        }));
+        assert_eq!(table.axes.get(2), None);
+        assert_eq!(table.elided_fallback_name_id, Some(300));
+
+        // Unknown formats are skipped.
+        assert_eq!(table.values.count(), 4);
+        let mut values = table.values;
+        let value = values.nth(1).unwrap();
+        assert_eq!(value.value_name_id(), 259);
+        assert!(value.flags().elidable_axis_value_name());
+        match values.nth(1).unwrap() {
+            AxisValue::Combination { values, .. } => {
+                assert_eq!(values.get(1), Some(AxisValueRecord { axis_index: 1, value: 50.0 }));
+            }
+            _ => panic!("expected a format 4 table"),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn style_name_ids() {
+        let wght = Tag::from_bytes(b"wght");
+        let wdth = Tag::from_bytes(b"wdth");
+        let table = Table::parse(DATA).unwrap();
+
+        // Names are sorted by axis ordering.
+        assert_eq!(table.style_name_ids(&[(wght, 700.0), (wdth, 75.0)]), vec![260, 258]);
+        assert_eq!(table.style_name_ids(&[(wght, 700.0), (wdth, 60.0)]), vec![260, 258]);
+        // Format 4 tables take precedence.
+        assert_eq!(table.style_name_ids(&[(wght, 900.0), (wdth, 50.0)]), vec![261]);
+        assert_eq!(table.style_name_ids(&[(wght, 900.0), (wdth, 55.0)]), vec![260]);
+        // All names are elided.
+        assert_eq!(table.style_name_ids(&[(wght, 400.0), (wdth, 100.0)]), vec![300]);
+        // An axis with a single value uses it by default.
+        assert_eq!(table.style_name_ids(&[(wght, 700.0)]), vec![260, 258]);
+
+        // Version 1.0 falls back to the subfamily name.
+        let mut data = DATA.to_vec();
+        data[3] = 0;
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.elided_fallback_name_id, None);
+        assert_eq!(table.style_name_ids(&[(wght, 400.0), (wdth, 100.0)]), vec![2]);
+    }
+}
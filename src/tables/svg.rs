@@ -32,19 +32,79 @@ pub fn parse(
     data: &[u8],
     glyph_id: GlyphId,
 ) -> Option<&[u8]> {
+    use core::cmp::Ordering;
+
     let mut s = Stream::new(data);
-    s.skip::<u16>(); // version
+    let version: u16 = s.read()?;
+    if version != 0 {
+        return None;
+    }
+
     let doc_list_offset = s.read::<Option<Offset32>>()??;
 
     let mut s = Stream::new_at(data, doc_list_offset.to_usize())?;
     let count: u16 = s.read()?;
     let records = s.read_array16::<SvgDocumentRecord>(count)?;
-    let record = records.into_iter()
-        .find(|rec| (rec.start_glyph_id..=rec.end_glyph_id).contains(&glyph_id))?;
+    // Records are sorted by glyph ID and must not overlap.
+    let (_, record) = records.binary_search_by(|rec| {
+        if rec.end_glyph_id < glyph_id {
+            Ordering::Less
+        } else if rec.start_glyph_id > glyph_id {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    })?;
 
-    let svg_offset = record.svg_doc_offset?;
-    let mut s = Stream::new_at(data, doc_list_offset.to_usize() + svg_offset.to_usize())?;
+    // Document offsets are relative to the document list.
+    let svg_offset = doc_list_offset.to_usize().checked_add(record.svg_doc_offset?.to_usize())?;
+    let mut s = Stream::new_at(data, svg_offset)?;
     let svg_data = s.read_bytes(usize::num_from(record.svg_doc_length))?;
 
     Some(svg_data)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documents() {
+        let data = &[
+            0x00, 0x00, // version: 0
+            0x00, 0x00, 0x00, 0x0A, // document list offset: 10
+            0x00, 0x00, 0x00, 0x00, // reserved
+
+            // Document list
+            0x00, 0x03, // number of records: 3
+            // Record [0]
+            0x00, 0x01, // start glyph ID: 1
+            0x00, 0x03, // end glyph ID: 3
+            0x00, 0x00, 0x00, 0x26, // document offset: 38
+            0x00, 0x00, 0x00, 0x03, // document length: 3
+            // Record [1]
+            0x00, 0x05, // start glyph ID: 5
+            0x00, 0x05, // end glyph ID: 5
+            0x00, 0x00, 0x00, 0x29, // document offset: 41
+            0x00, 0x00, 0x00, 0x02, // document length: 2
+            // Record [2]
+            0x00, 0x07, // start glyph ID: 7
+            0x00, 0x07, // end glyph ID: 7
+            0x00, 0x00, 0x00, 0x29, // document offset: 41
+            0x00, 0x00, 0x00, 0x05, // document length: 5 (out of bounds)
+
+            // Documents
+            0x61, 0x62, 0x63, // "abc"
+            0x1F, 0x8B, // a gzip header
+        ];
+
+        assert_eq!(parse(data, GlyphId(1)), Some(&b"abc"[..]));
+        assert_eq!(parse(data, GlyphId(3)), Some(&b"abc"[..]));
+        assert_eq!(parse(data, GlyphId(5)), Some(&[0x1F, 0x8B][..]));
+        assert_eq!(parse(data, GlyphId(0)), None);
+        assert_eq!(parse(data, GlyphId(4)), None);
+        assert_eq!(parse(data, GlyphId(7)), None);
+        assert_eq!(parse(data, GlyphId(8)), None);
+    }
+}
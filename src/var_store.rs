@@ -2,15 +2,21 @@
 //!
 //! https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#item-variation-store
 
-use crate::NormalizedCoordinate;
+use core::convert::TryFrom;
+
+use crate::{GlyphId, NormalizedCoordinate};
 use crate::parser::{Stream, FromData, LazyArray16, NumFrom};
 
 
+/// An [Item Variation Store](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#item-variation-store).
+///
+/// Stores variation regions and delta sets shared by multiple items.
 #[derive(Clone, Copy)]
-pub(crate) struct ItemVariationStore<'a> {
+pub struct ItemVariationStore<'a> {
     data: &'a [u8],
     data_offsets: LazyArray16<'a, u32>,
-    pub regions: VariationRegionList<'a>,
+    pub(crate) regions: VariationRegionList<'a>,
 }
 
 impl<'a> Default for ItemVariationStore<'a> {
@@ -29,7 +35,7 @@ impl<'a> Default for ItemVariationStore<'a> {
 
 impl<'a> ItemVariationStore<'a> {
     #[inline]
-    pub fn parse(mut s: Stream) -> Option<ItemVariationStore> {
+    pub(crate) fn parse(mut s: Stream) -> Option<ItemVariationStore> {
         let data = s.tail()?;
 
         let mut regions_s = s.clone();
@@ -50,70 +56,312 @@ impl<'a> ItemVariationStore<'a> {
             let total = count.checked_mul(axis_count)?;
             VariationRegionList {
                 axis_count,
-                regions: regions_s.read_array16::<RegionAxisCoordinatesRecord>(total)?,
+                regions: regions_s.read_array16::<RegionAxisCoordinates>(total)?,
             }
         };
 
         Some(ItemVariationStore { data, data_offsets: offsets, regions })
     }
 
-    pub fn region_indices(&self, index: u16) -> Option<LazyArray16<u16>> {
+    /// Returns a list of variation regions.
+    #[inline]
+    pub fn regions(&self) -> VariationRegionList<'a> {
+        self.regions
+    }
+
+    /// Returns the number of item variation data subtables.
+    #[inline]
+    pub fn variation_data_count(&self) -> u16 {
+        self.data_offsets.len()
+    }
+
+    /// Returns an item variation data subtable at the specified outer index.
+    pub fn variation_data(&self, outer_index: u16) -> Option<ItemVariationData<'a>> {
         // Offsets in bytes from the start of the item variation store
         // to each item variation data subtable.
-        let offset = self.data_offsets.get(index)?;
+        let offset = self.data_offsets.get(outer_index)?;
         let mut s = Stream::new_at(self.data, usize::num_from(offset))?;
-        s.skip::<u16>(); // item_count
-        s.skip::<u16>(); // short_delta_count
-        let count: u16 = s.read()?;
-        s.read_array16::<u16>(count)
+        let item_count: u16 = s.read()?;
+        let word_delta_count: u16 = s.read()?;
+        let region_index_count: u16 = s.read()?;
+        let region_indices = s.read_array16::<u16>(region_index_count)?;
+
+        let data = ItemVariationData {
+            item_count,
+            word_delta_count,
+            region_indices,
+            delta_sets: s.tail()?,
+        };
+
+        // Words must not exceed the number of regions.
+        if data.word_count() > region_index_count {
+            return None;
+        }
+
+        Some(data)
+    }
+
+    pub(crate) fn region_indices(&self, index: u16) -> Option<LazyArray16<u16>> {
+        self.variation_data(index).map(|data| data.region_indices)
     }
 
-    pub fn parse_delta(
+    pub(crate) fn parse_delta(
         &self,
         outer_index: u16,
         inner_index: u16,
         coordinates: &[NormalizedCoordinate],
     ) -> Option<f32> {
-        let offset = self.data_offsets.get(outer_index)?;
-        let mut s = Stream::new_at(self.data, usize::num_from(offset))?;
-        let item_count: u16 = s.read()?;
-        let short_delta_count: u16 = s.read()?;
-        let region_index_count: u16 = s.read()?;
-        let region_indices = s.read_array16::<u16>(region_index_count)?;
+        let data = self.variation_data(outer_index)?;
+        let deltas = data.deltas(inner_index)?;
+
+        let mut delta = 0.0;
+        for (idx, region_delta) in data.region_indices.into_iter().zip(deltas) {
+            delta += region_delta as f32 * self.regions.evaluate_region(idx, coordinates);
+        }
+
+        Some(delta)
+    }
+}
+
+impl core::fmt::Debug for ItemVariationStore<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ItemVariationStore")
+            .field("regions", &self.regions)
+            .field("variation_data_count", &self.variation_data_count())
+            .finish()
+    }
+}
+
+
+/// An [Item Variation Data](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#item-variation-data)
+/// subtable.
+///
+/// Stores delta sets, one per item, with one delta per referenced region.
+#[derive(Clone, Copy)]
+pub struct ItemVariationData<'a> {
+    item_count: u16,
+    word_delta_count: u16,
+    region_indices: LazyArray16<'a, u16>,
+    delta_sets: &'a [u8],
+}
 
-        if inner_index >= item_count {
+impl<'a> ItemVariationData<'a> {
+    /// Returns the number of delta sets, which is the number of inner indices.
+    #[inline]
+    pub fn item_count(&self) -> u16 {
+        self.item_count
+    }
+
+    /// Returns indices into the region list, one per delta in a delta set.
+    #[inline]
+    pub fn region_indices(&self) -> LazyArray16<'a, u16> {
+        self.region_indices
+    }
+
+    /// Checks that deltas are stored as 32/16-bit values instead of 16/8-bit ones.
+    #[inline]
+    pub fn has_long_words(&self) -> bool {
+        self.word_delta_count & 0x8000 != 0
+    }
+
+    /// Returns the number of deltas stored using the larger type.
+    #[inline]
+    pub fn word_count(&self) -> u16 {
+        self.word_delta_count & 0x7FFF
+    }
+
+    /// Returns an iterator over deltas for the specified inner index.
+    ///
+    /// Deltas are in the order of [`region_indices`](#method.region_indices).
+    pub fn deltas(&self, inner_index: u16) -> Option<DeltaSet<'a>> {
+        if inner_index >= self.item_count {
             return None;
         }
 
-        let delta_set_len = usize::from(short_delta_count) + usize::from(region_index_count);
-        s.advance(usize::from(inner_index).checked_mul(delta_set_len)?);
+        let word_size = if self.has_long_words() { 4 } else { 2 };
+        let region_count = usize::from(self.region_indices.len());
+        let word_count = usize::from(self.word_count());
+        let row_size = word_count * word_size + (region_count - word_count) * word_size / 2;
+        let start = usize::from(inner_index).checked_mul(row_size)?;
+        let data = self.delta_sets.get(start..start.checked_add(row_size)?)?;
+        Some(DeltaSet {
+            stream: Stream::new(data),
+            long_words: self.has_long_words(),
+            word_count: self.word_count(),
+            index: 0,
+        })
+    }
+}
 
-        let mut delta = 0.0;
-        let mut i = 0;
-        while i < short_delta_count {
-            let idx = region_indices.get(i)?;
-            delta += f32::from(s.read::<i16>()?) * self.regions.evaluate_region(idx, coordinates);
-            i += 1;
+impl core::fmt::Debug for ItemVariationData<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ItemVariationData")
+            .field("item_count", &self.item_count)
+            .field("region_count", &self.region_indices.len())
+            .finish()
+    }
+}
+
+
+/// An iterator over deltas of a single delta set.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct DeltaSet<'a> {
+    stream: Stream<'a>,
+    long_words: bool,
+    word_count: u16,
+    index: u16,
+}
+
+impl<'a> Iterator for DeltaSet<'a> {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let is_word = self.index < self.word_count;
+        self.index += 1;
+        match (self.long_words, is_word) {
+            (true, true) => self.stream.read::<i32>(),
+            (true, false) | (false, true) => self.stream.read::<i16>().map(i32::from),
+            (false, false) => self.stream.read::<i8>().map(i32::from),
         }
+    }
+}
+
+
+/// A [Delta-Set Index Mapping](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#associating-target-items-to-variation-data).
+///
+/// Maps items, like glyph IDs or axis indices, to outer and inner delta-set indices
+/// of an item variation store. Used by `HVAR`, `VVAR`, `avar` 2.0 and `COLR` 1.
+#[derive(Clone, Copy)]
+pub struct DeltaSetIndexMap<'a> {
+    entry_format: u8,
+    entries: &'a [u8],
+    len: u32,
+}
+
+impl<'a> DeltaSetIndexMap<'a> {
+    /// Parses a delta-set index map.
+    ///
+    /// Supports formats 0 and 1.
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let format: u8 = s.read()?;
+        let entry_format: u8 = s.read()?;
+        let len = match format {
+            0 => u32::from(s.read::<u16>()?),
+            1 => s.read::<u32>()?,
+            _ => return None,
+        };
+
+        let entry_size = usize::from((entry_format >> 4) & 3) + 1;
+        let entries = s.read_bytes(entry_size.checked_mul(usize::num_from(len))?)?;
+        Some(DeltaSetIndexMap { entry_format, entries, len })
+    }
 
-        while i < region_index_count {
-            let idx = region_indices.get(i)?;
-            delta += f32::from(s.read::<i8>()?) * self.regions.evaluate_region(idx, coordinates);
-            i += 1;
+    /// Returns the number of entries.
+    #[inline]
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Checks that the map has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the size of an entry in bytes, from 1 to 4.
+    #[inline]
+    pub fn entry_size(&self) -> u8 {
+        ((self.entry_format >> 4) & 3) + 1
+    }
+
+    /// Returns the number of bits used by the inner index, from 1 to 16.
+    #[inline]
+    pub fn inner_index_bit_count(&self) -> u8 {
+        (self.entry_format & 0xF) + 1
+    }
+
+    /// Maps an item index into outer and inner delta-set indices.
+    ///
+    /// Indices past the end of the map use the last entry.
+    pub fn map(&self, index: u32) -> Option<(u16, u16)> {
+        if self.len == 0 {
+            return None;
         }
 
-        Some(delta)
+        // 'If a given glyph ID is greater than mapCount-1, then the last entry is used.'
+        let index = index.min(self.len - 1);
+
+        let entry_size = usize::from(self.entry_size());
+        let mut s = Stream::new_at(self.entries, entry_size * usize::num_from(index))?;
+        let mut n = 0u32;
+        for b in s.read_bytes(entry_size)? {
+            n = (n << 8) + u32::from(*b);
+        }
+
+        let inner_index_bit_count = u32::from(self.inner_index_bit_count());
+        let outer_index = n >> inner_index_bit_count;
+        let inner_index = n & ((1 << inner_index_bit_count) - 1);
+        Some((
+            u16::try_from(outer_index).ok()?,
+            u16::try_from(inner_index).ok()?
+        ))
+    }
+
+    /// Maps a glyph ID into outer and inner delta-set indices.
+    #[inline]
+    pub fn map_glyph(&self, glyph_id: GlyphId) -> Option<(u16, u16)> {
+        self.map(u32::from(glyph_id.0))
+    }
+}
+
+impl core::fmt::Debug for DeltaSetIndexMap<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeltaSetIndexMap")
+            .field("len", &self.len)
+            .field("entry_size", &self.entry_size())
+            .field("inner_index_bit_count", &self.inner_index_bit_count())
+            .finish()
     }
 }
 
 
+/// A [Variation Region List](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#variation-regions).
 #[derive(Clone, Copy)]
 pub struct VariationRegionList<'a> {
     axis_count: u16,
-    regions: LazyArray16<'a, RegionAxisCoordinatesRecord>,
+    regions: LazyArray16<'a, RegionAxisCoordinates>,
 }
 
 impl<'a> VariationRegionList<'a> {
+    /// Returns the number of axes in each region.
+    #[inline]
+    pub fn axis_count(&self) -> u16 {
+        self.axis_count
+    }
+
+    /// Returns the number of regions.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.regions.len().checked_div(self.axis_count).unwrap_or(0)
+    }
+
+    /// Checks that the list has no regions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns per-axis coordinates of a region at the specified index.
+    pub fn region(&self, index: u16) -> Option<LazyArray16<'a, RegionAxisCoordinates>> {
+        let start = index.checked_mul(self.axis_count)?;
+        self.regions.slice(start..start.checked_add(self.axis_count)?)
+    }
+
     #[inline]
     pub(crate) fn evaluate_region(
         &self,
@@ -139,17 +387,32 @@ impl<'a> VariationRegionList<'a> {
     }
 }
 
+impl core::fmt::Debug for VariationRegionList<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VariationRegionList")
+            .field("axis_count", &self.axis_count)
+            .field("len", &self.len())
+            .finish()
+    }
+}
 
-#[derive(Clone, Copy)]
-struct RegionAxisCoordinatesRecord {
-    start_coord: i16,
-    peak_coord: i16,
-    end_coord: i16,
+
+/// Region coordinates along a single axis.
+///
+/// All values are normalized coordinates in F2DOT14 format.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct RegionAxisCoordinates {
+    /// The region start coordinate.
+    pub start_coord: i16,
+    /// The region peak coordinate.
+    pub peak_coord: i16,
+    /// The region end coordinate.
+    pub end_coord: i16,
 }
 
-impl RegionAxisCoordinatesRecord {
+impl RegionAxisCoordinates {
     #[inline]
-    pub fn evaluate_axis(&self, coord: i16) -> f32 {
+    pub(crate) fn evaluate_axis(&self, coord: i16) -> f32 {
         let start = self.start_coord;
         let peak = self.peak_coord;
         let end = self.end_coord;
@@ -178,16 +441,117 @@ impl RegionAxisCoordinatesRecord {
     }
 }
 
-impl FromData for RegionAxisCoordinatesRecord {
+impl FromData for RegionAxisCoordinates {
     const SIZE: usize = 6;
 
     #[inline]
     fn parse(data: &[u8]) -> Option<Self> {
         let mut s = Stream::new(data);
-        Some(RegionAxisCoordinatesRecord {
+        Some(RegionAxisCoordinates {
             start_coord: s.read::<i16>()?,
             peak_coord: s.read::<i16>()?,
             end_coord: s.read::<i16>()?,
         })
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_variation_store() {
+        let data = &[
+            0x00, 0x01, // format: 1
+            0x00, 0x00, 0x00, 0x0C, // region list offset: 12
+            0x00, 0x01, // data count: 1
+            0x00, 0x00, 0x00, 0x1C, // data offset [0]: 28
+            // Region list
+            0x00, 0x02, // axis count: 2
+            0x00, 0x01, // region count: 1
+            0x00, 0x00, 0x40, 0x00, 0x40, 0x00, // region 0, axis 0: 0, 1, 1
+            0xC0, 0x00, 0xC0, 0x00, 0x00, 0x00, // region 0, axis 1: -1, -1, 0
+            // Item variation data
+            0x00, 0x02, // item count: 2
+            0x80, 0x01, // word delta count: 1, long words
+            0x00, 0x02, // region index count: 2
+            0x00, 0x00, // region index [0]: 0
+            0x00, 0x00, // region index [1]: 0
+            0x00, 0x01, 0x00, 0x00, 0xFF, 0xFE, // item 0 deltas: 65536, -2
+            0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x05, // item 1 deltas: -1, 5
+        ];
+
+        let store = ItemVariationStore::parse(Stream::new(data)).unwrap();
+        let regions = store.regions();
+        assert_eq!(regions.axis_count(), 2);
+        assert_eq!(regions.len(), 1);
+        let region = regions.region(0).unwrap();
+        assert_eq!(region.get(1), Some(RegionAxisCoordinates {
+            start_coord: -16384,
+            peak_coord: -16384,
+            end_coord: 0,
+        }));
+        assert!(regions.region(1).is_none());
+
+        assert_eq!(store.variation_data_count(), 1);
+        let var_data = store.variation_data(0).unwrap();
+        assert_eq!(var_data.item_count(), 2);
+        assert!(var_data.has_long_words());
+        assert_eq!(var_data.word_count(), 1);
+
+        let mut deltas = var_data.deltas(1).unwrap();
+        assert_eq!(deltas.next(), Some(-1));
+        assert_eq!(deltas.next(), Some(5));
+        assert_eq!(deltas.next(), None);
+        assert!(var_data.deltas(2).is_none());
+
+        let coords = [NormalizedCoordinate::from(1.0), NormalizedCoordinate::from(-1.0)];
+        assert_eq!(store.parse_delta(0, 0, &coords), Some(65534.0));
+    }
+
+    #[test]
+    fn delta_set_index_map_format_0() {
+        let data = &[
+            0x00, // format: 0
+            0x13, // entry format: 2 bytes, 4 inner bits
+            0x00, 0x02, // map count: 2
+            0x00, 0x21, // entry [0]: 2, 1
+            0x01, 0x0F, // entry [1]: 16, 15
+        ];
+
+        let map = DeltaSetIndexMap::parse(data).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.entry_size(), 2);
+        assert_eq!(map.inner_index_bit_count(), 4);
+        assert_eq!(map.map_glyph(GlyphId(0)), Some((2, 1)));
+        assert_eq!(map.map_glyph(GlyphId(1)), Some((16, 15)));
+        // The last entry is used for indices past the end.
+        assert_eq!(map.map_glyph(GlyphId(5)), Some((16, 15)));
+    }
+
+    #[test]
+    fn delta_set_index_map_format_1() {
+        let data = &[
+            0x01, // format: 1
+            0x0F, // entry format: 1 byte, 16 inner bits
+            0x00, 0x00, 0x00, 0x01, // map count: 1
+            0x07, // entry [0]: 0, 7
+        ];
+
+        let map = DeltaSetIndexMap::parse(data).unwrap();
+        assert_eq!(map.map(0), Some((0, 7)));
+    }
+
+    #[test]
+    fn delta_set_index_map_truncated() {
+        let data = &[
+            0x00, // format: 0
+            0x10, // entry format: 2 bytes
+            0x00, 0x02, // map count: 2
+            0x00, 0x01, // entry [0]
+        ];
+
+        assert!(DeltaSetIndexMap::parse(data).is_none());
+    }
+}